@@ -1,11 +1,15 @@
 use criterion::{criterion_group, criterion_main, Criterion};
-use rand_core::OsRng;
+use rand_core::{OsRng, RngCore};
 
 use synedrion::{
+    batch_verify_signatures,
     bench_internals::{
-        key_init, key_refresh, presigning, signing, PresigningInputs, SigningInputs,
+        key_init, key_refresh, mul_by_generator, mul_by_generator_naive, presigning, signing,
+        PresigningInputs, SigningInputs,
     },
-    TestParams,
+    ecdsa::{signature::hazmat::PrehashVerifier, SigningKey},
+    k256::NonZeroScalar,
+    RecoverableSignature, Secp256k1, TestParams,
 };
 
 fn bench_happy_paths(c: &mut Criterion) {
@@ -36,6 +40,62 @@ fn bench_happy_paths(c: &mut Criterion) {
     group.finish()
 }
 
-criterion_group!(benches, bench_happy_paths);
+fn bench_batch_verification(c: &mut Criterion) {
+    let mut group = c.benchmark_group("signature verification");
+
+    let sk = SigningKey::random(&mut OsRng);
+    let vkey = *sk.verifying_key();
+    let items = (0..32)
+        .map(|_| {
+            let mut message = [0u8; 32];
+            OsRng.fill_bytes(&mut message);
+            let (signature, recovery_id) = sk.sign_prehash_recoverable(&message).unwrap();
+            let mut bytes = signature.to_bytes().to_vec();
+            bytes.push(recovery_id.to_byte());
+            (
+                message,
+                RecoverableSignature::<Secp256k1>::try_from(&bytes[..]).unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    group.bench_function("One by one, 32 signatures", |b| {
+        b.iter(|| {
+            items.iter().all(|(message, signature)| {
+                vkey.verify_prehash(message, &signature.to_backend().0)
+                    .is_ok()
+            })
+        })
+    });
+
+    group.bench_function("Batch, 32 signatures", |b| {
+        b.iter(|| batch_verify_signatures(&mut OsRng, &vkey, &items))
+    });
+
+    group.finish()
+}
+
+fn bench_generator_multiplication(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generator multiplication");
+
+    let scalar = NonZeroScalar::random(&mut OsRng);
+
+    group.bench_function("Generic multiplication", |b| {
+        b.iter(|| mul_by_generator_naive(&scalar))
+    });
+
+    group.bench_function("Precomputed tables", |b| {
+        b.iter(|| mul_by_generator(&scalar))
+    });
+
+    group.finish()
+}
+
+criterion_group!(
+    benches,
+    bench_happy_paths,
+    bench_batch_verification,
+    bench_generator_multiplication
+);
 
 criterion_main!(benches);
@@ -0,0 +1,94 @@
+//! Runs several parties in the same process through KeyGen, and then signs a message.
+//!
+//! Usage: `cargo run --example threshold_sign --features testing -- [message]`
+//!
+//! All the sessions are driven by `InProcessNetwork`; in a real application each party
+//! runs its own session and exchanges the messages over the network
+//! (see the `README` for the shape of the round loop).
+
+use std::collections::BTreeSet;
+
+use k256::ecdsa::{signature::hazmat::PrehashVerifier, Signature, SigningKey, VerifyingKey};
+use rand_core::OsRng;
+use sha2::{Digest, Sha256};
+
+use synedrion::{
+    make_interactive_signing_session, make_key_gen_session, sessions::InProcessNetwork, SessionId,
+    TestParams,
+};
+
+const NUM_PARTIES: usize = 3;
+
+fn main() {
+    let message = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "Hello, world!".into());
+    let prehashed_message: [u8; 32] = Sha256::digest(message.as_bytes()).into();
+
+    // Each party has a signing key used to sign its protocol messages,
+    // and is identified by the corresponding verifying key.
+    let signers = (0..NUM_PARTIES)
+        .map(|_| SigningKey::random(&mut OsRng))
+        .collect::<Vec<_>>();
+    let verifiers = signers
+        .iter()
+        .map(|signer| *signer.verifying_key())
+        .collect::<BTreeSet<_>>();
+
+    let network = InProcessNetwork::new();
+
+    // KeyGen produces a key share and the auxiliary information for each party.
+    // Note: `TestParams` are used to make the example fast; use `ProductionParams` for real keys.
+    println!("Running KeyGen with {NUM_PARTIES} parties...");
+    let session_id = SessionId::from_seed(b"example key gen");
+    let sessions = signers
+        .iter()
+        .map(|signer| {
+            make_key_gen_session::<TestParams, Signature, SigningKey, VerifyingKey>(
+                &mut OsRng,
+                session_id,
+                signer.clone(),
+                &verifiers,
+            )
+            .unwrap()
+        })
+        .collect();
+    let key_gen_results = network.run(&mut OsRng, sessions).unwrap();
+
+    let verifying_key = key_gen_results[0].0.verifying_key();
+    println!(
+        "Shared verifying key: {}",
+        hex::encode(verifying_key.to_encoded_point(true).as_bytes())
+    );
+
+    // All the parties of the key share take part in signing.
+    println!("Signing {message:?}...");
+    let session_id = SessionId::from_seed(b"example signing");
+    let sessions = signers
+        .iter()
+        .zip(key_gen_results.iter())
+        .map(|(signer, (key_share, aux_info))| {
+            make_interactive_signing_session::<_, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signer.clone(),
+                &verifiers,
+                key_share,
+                aux_info,
+                &prehashed_message,
+            )
+            .unwrap()
+        })
+        .collect();
+    let signatures = network.run(&mut OsRng, sessions).unwrap();
+
+    // Every party ends up with the same signature.
+    let (signature, recovery_id) = signatures[0].to_backend();
+    println!("Signature: {}", hex::encode(signature.to_bytes()));
+    println!("Recovery ID: {}", recovery_id.to_byte());
+
+    verifying_key
+        .verify_prehash(&prehashed_message, &signature)
+        .unwrap();
+    println!("The signature is valid");
+}
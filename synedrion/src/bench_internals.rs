@@ -10,12 +10,26 @@ use rand_core::CryptoRngCore;
 use super::cggmp21::{
     key_init, key_refresh, presigning, signing, AuxInfo, KeyShare, PresigningData, SchemeParams,
 };
-use crate::curve::Scalar;
+use crate::curve::{Scalar, Secp256k1};
 use crate::rounds::{
     test_utils::{step_next_round, step_result, step_round, Id, Without},
     FirstRound,
 };
 
+/// Multiplies the curve generator by `scalar` using the precomputed tables.
+pub fn mul_by_generator(scalar: &k256::NonZeroScalar) -> k256::ProjectivePoint {
+    Scalar::<Secp256k1>::from(scalar)
+        .mul_by_generator()
+        .to_backend()
+}
+
+/// Multiplies the curve generator by `scalar` using a generic point multiplication.
+pub fn mul_by_generator_naive(scalar: &k256::NonZeroScalar) -> k256::ProjectivePoint {
+    Scalar::<Secp256k1>::from(scalar)
+        .mul_by_generator_naive()
+        .to_backend()
+}
+
 /// A sequential execution of the KeyGen protocol for all parties.
 pub fn key_init<P: SchemeParams>(rng: &mut impl CryptoRngCore, num_parties: usize) {
     let mut shared_randomness = [0u8; 32];
@@ -61,7 +75,7 @@ pub fn key_refresh<P: SchemeParams>(rng: &mut impl CryptoRngCore, num_parties: u
                 &shared_randomness,
                 ids.clone().without(id),
                 *id,
-                (),
+                None,
             )
             .unwrap();
             (*id, round)
@@ -112,7 +126,12 @@ pub fn presigning<P: SchemeParams>(rng: &mut impl CryptoRngCore, inputs: &Presig
                 &shared_randomness,
                 inputs.ids.clone().without(id),
                 *id,
-                (inputs.key_shares[id].clone(), inputs.aux_infos[id].clone()),
+                presigning::Inputs {
+                    key_share: inputs.key_shares[id].clone(),
+                    aux_info: inputs.aux_infos[id].clone(),
+                    nonce_only: false,
+                    rng_seed: None,
+                },
             )
             .unwrap();
             (*id, round)
@@ -9,19 +9,24 @@
 
 mod entities;
 mod params;
+mod presignature_pool;
 mod protocols;
 mod sigma;
 
-pub use entities::{AuxInfo, KeyShare, KeyShareChange, PresigningData};
+pub use entities::{
+    AuxInfo, KeyShare, KeyShareBytesError, KeyShareChange, PaillierPrimes, PresigningData,
+};
 pub(crate) use entities::{PublicAuxInfo, SecretAuxInfo};
 pub use params::{ProductionParams, SchemeParams, TestParams};
-pub(crate) use protocols::{aux_gen, interactive_signing, key_gen, key_init, key_refresh};
+#[cfg(feature = "p256")]
+pub use params::{ProductionParamsP256, TestParamsP256};
+pub use presignature_pool::PresignaturePool;
+pub(crate) use protocols::{
+    aux_gen, interactive_signing, key_gen, key_init, key_refresh, presigning, signing,
+};
 pub use protocols::{
     AuxGenError, AuxGenResult, InteractiveSigningError, InteractiveSigningProof,
     InteractiveSigningResult, KeyGenError, KeyGenProof, KeyGenResult, KeyInitError, KeyInitResult,
-    KeyRefreshResult, PresigningError, PresigningProof, PresigningResult, SigningProof,
-    SigningResult,
+    KeyRefreshResult, PresigningError, PresigningProof, PresigningResult, PresigningRetryReason,
+    SigningProof, SigningResult, VerificationFailReason,
 };
-
-#[cfg(feature = "bench-internals")]
-pub(crate) use protocols::{presigning, signing};
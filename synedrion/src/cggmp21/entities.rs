@@ -1,34 +1,62 @@
 use alloc::boxed::Box;
 use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::String;
 use alloc::vec::Vec;
 use core::fmt::Debug;
-use core::marker::PhantomData;
 
-use k256::ecdsa::VerifyingKey;
+use crypto_bigint::BitOps;
+use k256::ecdsa::signature::hazmat::PrehashVerifier;
 use rand_core::CryptoRngCore;
 use secrecy::{ExposeSecret, SecretBox};
 use serde::{Deserialize, Serialize};
 
 use crate::cggmp21::SchemeParams;
-use crate::curve::{Point, Scalar};
+use crate::constructors::PrehashedMessage;
+#[cfg(feature = "dangerous-reconstruct")]
+use crate::curve::Secp256k1;
+use crate::curve::{Curve, Point, RecoverableSignature, Scalar};
 use crate::paillier::{
-    CiphertextMod, PaillierParams, PublicKeyPaillier, PublicKeyPaillierPrecomputed, RPParams,
+    Ciphertext, PaillierParams, PublicKeyPaillier, PublicKeyPaillierPrecomputed, RPParams,
     RPParamsMod, Randomizer, SecretKeyPaillier, SecretKeyPaillierPrecomputed,
 };
-use crate::uint::Signed;
+use crate::sessions::LocalError;
+use crate::tools::hashing::{Chain, FofHasher, HashOutput};
+use crate::uint::{Integer, Signed};
 
 #[cfg(any(test, feature = "bench-internals"))]
-use crate::paillier::RandomizerMod;
+use crate::paillier::{CiphertextMod, RandomizerMod};
+
+/// The magic bytes at the start of the serialized key share (see [`KeyShare::to_bytes`]).
+const KEY_SHARE_MAGIC: &[u8; 4] = b"SYKS";
+
+/// The current version of the key share format (see [`KeyShare::to_bytes`]).
+const KEY_SHARE_VERSION: u16 = 1;
+
+/// The length of the header of the serialized key share:
+/// the magic bytes, the version, and the scheme parameters hash.
+const KEY_SHARE_HEADER_LEN: usize = 4 + 2 + 32;
+
+/// An error returned by [`KeyShare::from_bytes`].
+#[derive(Debug, Clone)]
+pub enum KeyShareBytesError {
+    /// The data does not start with the expected magic bytes.
+    InvalidMagic,
+    /// The format version is not supported by this version of the library.
+    UnsupportedVersion(u16),
+    /// The key share was created with different scheme parameters.
+    MismatchedParams,
+    /// The key share cannot be deserialized.
+    CannotDeserialize(String),
+}
 
 /// The result of the KeyInit protocol.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct KeyShare<P, I: Ord> {
+pub struct KeyShare<P: SchemeParams, I: Ord> {
     pub(crate) owner: I,
     /// Secret key share of this node.
-    pub(crate) secret_share: SecretBox<Scalar>, // `x_i`
-    pub(crate) public_shares: BTreeMap<I, Point>, // `X_j`
-    // TODO (#27): this won't be needed when Scalar/Point are a part of `P`
-    pub(crate) phantom: PhantomData<P>,
+    pub(crate) secret_share: SecretBox<Scalar<P::Curve>>, // `x_i`
+    pub(crate) public_shares: BTreeMap<I, Point<P::Curve>>, // `X_j`
 }
 
 /// The result of the AuxGen protocol.
@@ -44,14 +72,14 @@ pub struct AuxInfo<P: SchemeParams, I: Ord> {
 #[serde(bound(deserialize = "SecretKeyPaillier<P::Paillier>: for <'x> Deserialize<'x>"))]
 pub(crate) struct SecretAuxInfo<P: SchemeParams> {
     pub(crate) paillier_sk: SecretKeyPaillier<P::Paillier>,
-    pub(crate) el_gamal_sk: SecretBox<Scalar>, // `y_i`
+    pub(crate) el_gamal_sk: SecretBox<Scalar<P::Curve>>, // `y_i`
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(bound(serialize = "PublicKeyPaillier<P::Paillier>: Serialize"))]
 #[serde(bound(deserialize = "PublicKeyPaillier<P::Paillier>: for <'x> Deserialize<'x>"))]
 pub(crate) struct PublicAuxInfo<P: SchemeParams> {
-    pub(crate) el_gamal_pk: Point, // `Y_i`
+    pub(crate) el_gamal_pk: Point<P::Curve>, // `Y_i`
     /// The Paillier public key.
     pub(crate) paillier_pk: PublicKeyPaillier<P::Paillier>,
     /// The ring-Pedersen parameters.
@@ -68,60 +96,175 @@ pub(crate) struct AuxInfoPrecomputed<P: SchemeParams, I> {
 pub(crate) struct SecretAuxInfoPrecomputed<P: SchemeParams> {
     pub(crate) paillier_sk: SecretKeyPaillierPrecomputed<P::Paillier>,
     #[allow(dead_code)] // TODO (#36): this will be needed for the 6-round presigning protocol.
-    pub(crate) el_gamal_sk: SecretBox<Scalar>, // `y_i`
+    pub(crate) el_gamal_sk: SecretBox<Scalar<P::Curve>>, // `y_i`
 }
 
 #[derive(Clone)]
 pub(crate) struct PublicAuxInfoPrecomputed<P: SchemeParams> {
     #[allow(dead_code)] // TODO (#36): this will be needed for the 6-round presigning protocol.
-    pub(crate) el_gamal_pk: Point,
+    pub(crate) el_gamal_pk: Point<P::Curve>,
     pub(crate) paillier_pk: PublicKeyPaillierPrecomputed<P::Paillier>,
     pub(crate) rp_params: RPParamsMod<P::Paillier>,
 }
 
+/// A pair of safe primes to be used for the Paillier key of the local party
+/// instead of generating them during the AuxGen or KeyGen protocols.
+///
+/// Since generating safe primes is the most expensive part of these protocols,
+/// they can be generated in advance with [`PaillierPrimes::random`] and persisted,
+/// so that an interrupted ceremony can be restarted without generating them again.
+///
+/// **Warning:** the security of the shared key depends on these primes being secret
+/// and sampled uniformly at random. Whoever generated them is able to decrypt
+/// the values sent to this party during the following protocols,
+/// so they must only come from a trusted (e.g. audited) source, and must not be reused
+/// (in particular, after a successful ceremony the persisted primes must be deleted).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "SecretKeyPaillier<P::Paillier>: Serialize"))]
+#[serde(bound(deserialize = "SecretKeyPaillier<P::Paillier>: for <'x> Deserialize<'x>"))]
+pub struct PaillierPrimes<P: SchemeParams>(pub(crate) SecretKeyPaillier<P::Paillier>);
+
+impl<P: SchemeParams> PaillierPrimes<P> {
+    /// Generates a new pair of safe primes.
+    pub fn random(rng: &mut impl CryptoRngCore) -> Self {
+        Self(SecretKeyPaillier::random(rng))
+    }
+
+    /// Checks that `p` and `q` are distinct safe primes of the size required by `P`.
+    pub fn new(
+        rng: &mut impl CryptoRngCore,
+        p: <P::Paillier as PaillierParams>::HalfUint,
+        q: <P::Paillier as PaillierParams>::HalfUint,
+    ) -> Result<Self, LocalError> {
+        SecretKeyPaillier::from_primes(rng, p, q)
+            .map(Self)
+            .map_err(|err| LocalError(format!("Invalid Paillier primes: {err}")))
+    }
+}
+
 /// The result of the Auxiliary Info & Key Refresh protocol - the update to the key share.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KeyShareChange<P: SchemeParams, I: Ord> {
     pub(crate) owner: I,
     /// The value to be added to the secret share.
-    pub(crate) secret_share_change: SecretBox<Scalar>, // `x_i^* - x_i == \sum_{j} x_j^i`
+    pub(crate) secret_share_change: SecretBox<Scalar<P::Curve>>, // `x_i^* - x_i == \sum_{j} x_j^i`
     /// The values to be added to the public shares of remote nodes.
-    pub(crate) public_share_changes: BTreeMap<I, Point>, // `X_k^* - X_k == \sum_j X_j^k`, for all nodes
-    // TODO (#27): this won't be needed when Scalar/Point are a part of `P`
-    pub(crate) phantom: PhantomData<P>,
+    pub(crate) public_share_changes: BTreeMap<I, Point<P::Curve>>, // `X_k^* - X_k == \sum_j X_j^k`, for all nodes
+}
+
+/// Applies a change obtained from the KeyRefresh protocol to a key share.
+///
+/// Fails if the owners of the key share and the change differ,
+/// if they were created for different sets of parties,
+/// or if the change would alter the verifying key.
+impl<P, I> TryFrom<(KeyShare<P, I>, KeyShareChange<P, I>)> for KeyShare<P, I>
+where
+    P: SchemeParams,
+    I: Clone + Ord + PartialEq + Debug,
+{
+    type Error = LocalError;
+
+    fn try_from(
+        (key_share, change): (KeyShare<P, I>, KeyShareChange<P, I>),
+    ) -> Result<Self, Self::Error> {
+        key_share.validate_change(&change)?;
+        Ok(key_share.update(change))
+    }
 }
 
 /// The result of the Presigning protocol.
-#[derive(Debug, Clone)]
+///
+/// It does not depend on the message, so it can be created in advance and stored
+/// until a signature is needed (see [`make_signing_session`](`crate::make_signing_session`)).
+/// Each presigning result must be used for at most one signature,
+/// since signing two messages with the same nonce reveals the secret key.
+///
+/// If the presigning was performed in the nonce-only mode,
+/// the share of `k * x` is not available, and the data cannot be used for signing;
+/// only the nonce and the ephemeral scalar share can be used.
+///
+/// To prevent accidental reuse, the data can only be moved, and not copied.
+/// Cloning and serialization (e.g. to store the presignatures outside of the process)
+/// are available with the `dangerous-presignature-export` feature;
+/// it is then the user's responsibility to make sure each copy is used at most once.
+#[derive(Debug)]
+#[cfg_attr(
+    any(
+        test,
+        feature = "bench-internals",
+        feature = "dangerous-presignature-export"
+    ),
+    derive(Clone)
+)]
+#[cfg_attr(
+    feature = "dangerous-presignature-export",
+    derive(Serialize, Deserialize),
+    serde(bound(serialize = "I: Ord + Serialize")),
+    serde(bound(deserialize = "I: Ord + for <'x> Deserialize<'x>"))
+)]
 pub struct PresigningData<P: SchemeParams, I> {
-    pub(crate) nonce: Scalar, // x-coordinate of $R$
+    pub(crate) nonce: Scalar<P::Curve>, // x-coordinate of $R$
+    #[cfg_attr(not(feature = "experimental"), allow(dead_code))]
+    pub(crate) nonce_point: Point<P::Curve>, // $R$
+    /// The identifier of the key share used in presigning (see [`KeyShare::key_id`]).
+    pub(crate) key_id: HashOutput,
     /// An additive share of the ephemeral scalar.
-    pub(crate) ephemeral_scalar_share: SecretBox<Scalar>, // $k_i$
+    pub(crate) ephemeral_scalar_share: SecretBox<Scalar<P::Curve>>, // $k_i$
+    /// The share of `k * x` and the associated values.
+    /// `None` if the presigning was performed in the nonce-only mode,
+    /// in which case the data cannot be used for signing.
+    pub(crate) product: Option<PresigningProduct<P, I>>,
+
+    // $K_i$.
+    pub(crate) cap_k: Ciphertext<P::Paillier>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "
+    I: Ord + Serialize,
+    Signed<<P::Paillier as PaillierParams>::Uint>: Serialize,
+    PresigningValues<P>: Serialize,
+"))]
+#[serde(bound(deserialize = "
+    I: Ord + for<'x> Deserialize<'x>,
+    Signed<<P::Paillier as PaillierParams>::Uint>: for<'x> Deserialize<'x>,
+    PresigningValues<P>: for<'x> Deserialize<'x>,
+"))]
+pub(crate) struct PresigningProduct<P: SchemeParams, I> {
     /// An additive share of `k * x` where `x` is the secret key.
-    pub(crate) product_share: SecretBox<Scalar>,
+    pub(crate) product_share: SecretBox<Scalar<P::Curve>>,
 
     // Values generated during presigning,
     // kept in case we need to generate a proof of correctness.
     pub(crate) product_share_nonreduced: Signed<<P::Paillier as PaillierParams>::Uint>,
 
-    // $K_i$.
-    pub(crate) cap_k: CiphertextMod<P::Paillier>,
-
     // The values for $j$, $j != i$.
     pub(crate) values: BTreeMap<I, PresigningValues<P>>,
 }
 
-#[derive(Debug, Clone)]
+// The ciphertexts are stored in the serializable form;
+// they are only needed to construct the correctness proofs when signing fails.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "
+    SecretBox<Signed<<P::Paillier as PaillierParams>::Uint>>: Serialize,
+    Randomizer<P::Paillier>: Serialize,
+    Ciphertext<P::Paillier>: Serialize,
+"))]
+#[serde(bound(deserialize = "
+    SecretBox<Signed<<P::Paillier as PaillierParams>::Uint>>: for<'x> Deserialize<'x>,
+    Randomizer<P::Paillier>: for<'x> Deserialize<'x>,
+    Ciphertext<P::Paillier>: for<'x> Deserialize<'x>,
+"))]
 pub(crate) struct PresigningValues<P: SchemeParams> {
     pub(crate) hat_beta: SecretBox<Signed<<P::Paillier as PaillierParams>::Uint>>,
     pub(crate) hat_r: Randomizer<P::Paillier>,
     pub(crate) hat_s: Randomizer<P::Paillier>,
-    pub(crate) cap_k: CiphertextMod<P::Paillier>,
+    pub(crate) cap_k: Ciphertext<P::Paillier>,
     /// Received $\hat{D}_{i,j}$.
-    pub(crate) hat_cap_d_received: CiphertextMod<P::Paillier>,
+    pub(crate) hat_cap_d_received: Ciphertext<P::Paillier>,
     /// Sent $\hat{D}_{j,i}$.
-    pub(crate) hat_cap_d: CiphertextMod<P::Paillier>,
-    pub(crate) hat_cap_f: CiphertextMod<P::Paillier>,
+    pub(crate) hat_cap_d: Ciphertext<P::Paillier>,
+    pub(crate) hat_cap_f: Ciphertext<P::Paillier>,
 }
 
 impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> KeyShare<P, I> {
@@ -143,23 +286,71 @@ impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> KeyShare<P, I> {
             owner: self.owner,
             secret_share,
             public_shares,
-            phantom: PhantomData,
         }
     }
 
+    fn validate_change(&self, change: &KeyShareChange<P, I>) -> Result<(), LocalError> {
+        if self.owner != change.owner {
+            return Err(LocalError(format!(
+                "The key share owner ({:?}) is different from the change owner ({:?})",
+                self.owner, change.owner
+            )));
+        }
+        if !self
+            .public_shares
+            .keys()
+            .eq(change.public_share_changes.keys())
+        {
+            return Err(LocalError(
+                "The key share and the change have different sets of parties".into(),
+            ));
+        }
+        // The verifying key is the sum of the public shares, so the changes must cancel out.
+        if change
+            .public_share_changes
+            .values()
+            .sum::<Point<P::Curve>>()
+            != Point::<P::Curve>::IDENTITY
+        {
+            return Err(LocalError(
+                "The change does not preserve the verifying key".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks that the secret share matches the public one, and the shared key is valid.
+    pub(crate) fn check_consistency(&self) -> Result<(), String> {
+        let public_share = self
+            .public_shares
+            .get(&self.owner)
+            .ok_or_else(|| String::from("The owner is not one of the parties of the key share"))?;
+        if &self.secret_share.expose_secret().mul_by_generator() != public_share {
+            return Err("The secret share does not match the public share".into());
+        }
+        if self.public_shares.values().sum::<Point<P::Curve>>() == Point::<P::Curve>::IDENTITY {
+            return Err("The shared public key is the point at infinity".into());
+        }
+        Ok(())
+    }
+
     /// Creates a set of random self-consistent key shares
     /// (which in a decentralized case would be the output of KeyInit protocol).
+    ///
+    /// If `signing_key` is given, the shares add up to it (e.g. to import an existing key),
+    /// otherwise a new random key is shared.
+    /// The auxiliary data for the parties can be created with [`AuxInfo::new_centralized`].
     pub fn new_centralized(
         rng: &mut impl CryptoRngCore,
         ids: &BTreeSet<I>,
-        signing_key: Option<&k256::ecdsa::SigningKey>,
+        signing_key: Option<&<P::Curve as Curve>::SigningKey>,
     ) -> BTreeMap<I, Self> {
         let secret = match signing_key {
-            None => Scalar::random(rng),
-            Some(sk) => Scalar::from(sk.as_nonzero_scalar()),
+            None => Scalar::<P::Curve>::random(rng),
+            Some(sk) => Scalar::<P::Curve>::from_signing_key(sk),
         };
 
-        let secret_shares = secret.split(rng, ids.len());
+        let secret_shares = Scalar::split_additive(rng, &secret, ids.len());
         let public_shares = ids
             .iter()
             .zip(secret_shares.iter())
@@ -175,24 +366,65 @@ impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> KeyShare<P, I> {
                         owner: id.clone(),
                         secret_share: SecretBox::new(Box::new(secret_share)),
                         public_shares: public_shares.clone(),
-                        phantom: PhantomData,
                     },
                 )
             })
             .collect()
     }
 
-    pub(crate) fn verifying_key_as_point(&self) -> Point {
+    pub(crate) fn verifying_key_as_point(&self) -> Point<P::Curve> {
         self.public_shares.values().sum()
     }
 
     /// Return the verifying key to which this set of shares corresponds.
-    pub fn verifying_key(&self) -> VerifyingKey {
+    pub fn verifying_key(&self) -> <P::Curve as Curve>::VerifyingKey {
         // TODO (#5): need to ensure on creation of the share that the verifying key actually exists
         // (that is, the sum of public keys does not evaluate to the infinity point)
         self.verifying_key_as_point().to_verifying_key().unwrap()
     }
 
+    /// Returns `true` if `signature` is a valid signature of `message`
+    /// for the verifying key of this set of shares.
+    pub fn verify_signature(
+        &self,
+        signature: &<P::Curve as Curve>::Signature,
+        message: &PrehashedMessage,
+    ) -> bool {
+        match self.verifying_key_as_point().to_verifying_key() {
+            Some(vkey) => vkey.verify_prehash(message, signature).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Signs a message using this key share alone, without running any protocol.
+    ///
+    /// This is only possible in the degenerate case where the share makes up the whole key
+    /// (e.g. a set of shares created for a single party); an error is returned otherwise.
+    /// In all the other cases the interactive signing protocol must be used.
+    pub fn sign_solo(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        prehashed_message: &PrehashedMessage,
+    ) -> Result<RecoverableSignature<P::Curve>, LocalError> {
+        let secret = self.secret_share.expose_secret();
+        let verifying_key = self.verifying_key_as_point();
+        if secret.mul_by_generator() != verifying_key {
+            return Err(LocalError(
+                "The key share does not make up the whole key".into(),
+            ));
+        }
+
+        let message = Scalar::<P::Curve>::from_reduced_bytes(prehashed_message);
+
+        // Same as the distributed signing, with `R = k^{-1} G` and `s = k (m + r x)`.
+        let k = Scalar::<P::Curve>::random_nonzero(rng);
+        let r = k.invert().unwrap().mul_by_generator().x_coordinate();
+        let s = k * (message + r * secret);
+
+        RecoverableSignature::from_scalars(&r, &s, &verifying_key, &message)
+            .ok_or_else(|| LocalError("Failed to create a signature".into()))
+    }
+
     /// Returns the owner of this key share.
     pub fn owner(&self) -> &I {
         &self.owner
@@ -204,6 +436,160 @@ impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> KeyShare<P, I> {
     }
 }
 
+#[cfg(feature = "dangerous-reconstruct")]
+impl<P, I> KeyShare<P, I>
+where
+    P: SchemeParams<Curve = Secp256k1>,
+    I: Clone + Ord + PartialEq + Debug,
+{
+    /// Reconstructs the full secret key from all the shares of a distributed key.
+    ///
+    /// **Warning:** this defeats the whole purpose of distributing the key.
+    /// Whoever holds the result can sign anything without the other parties.
+    /// It is only meant for tests, and for the degenerate case of recovering a key
+    /// when a single operator legitimately holds all the shares.
+    ///
+    /// Returns an error if `shares` does not contain exactly one share for each party,
+    /// if the shares belong to different keys, or if a secret share
+    /// does not match the corresponding public share.
+    pub fn reconstruct_secret_key(shares: &[Self]) -> Result<k256::SecretKey, LocalError> {
+        let first = shares
+            .first()
+            .ok_or_else(|| LocalError("No key shares given".into()))?;
+
+        let owners = shares
+            .iter()
+            .map(|share| &share.owner)
+            .collect::<BTreeSet<_>>();
+        if owners.len() != shares.len() || !owners.iter().copied().eq(first.public_shares.keys()) {
+            return Err(LocalError(
+                "Exactly one share for each party of the key is required".into(),
+            ));
+        }
+
+        for share in shares {
+            if share.public_shares != first.public_shares {
+                return Err(LocalError(format!(
+                    "The share of {:?} belongs to a different key",
+                    share.owner
+                )));
+            }
+            if share.secret_share.expose_secret().mul_by_generator()
+                != first.public_shares[&share.owner]
+            {
+                return Err(LocalError(format!(
+                    "The secret share of {:?} does not match its public share",
+                    share.owner
+                )));
+            }
+        }
+
+        let secret = shares
+            .iter()
+            .map(|share| *share.secret_share.expose_secret())
+            .sum::<Scalar<Secp256k1>>();
+        if secret.mul_by_generator() != first.verifying_key_as_point() {
+            return Err(LocalError(
+                "The reconstructed key does not match the verifying key".into(),
+            ));
+        }
+
+        let signing_key = secret
+            .to_signing_key()
+            .ok_or_else(|| LocalError("The reconstructed key is zero".into()))?;
+        Ok(k256::SecretKey::from(*signing_key.as_nonzero_scalar()))
+    }
+}
+
+impl<P: SchemeParams, I: Ord + Serialize> KeyShare<P, I> {
+    /// Returns the identifier of the set of shares this key share belongs to.
+    ///
+    /// The product share in the presigning data is only valid for the secret share
+    /// it was created with, so this is used to bind the presigning data to the key share.
+    /// Note that it depends on all the public shares and not just the verifying key,
+    /// so it is different for the shares of different quorums and for refreshed shares.
+    pub(crate) fn key_id(&self) -> HashOutput {
+        FofHasher::new_with_dst(b"KeyShareID")
+            .chain(&self.public_shares)
+            .finalize()
+    }
+
+    /// Returns a short human-readable fingerprint of the verifying key and the set of parties
+    /// (e.g. `1a2b-3c4d-5e6f-7a8b-9c0d-1e2f-3a4b-5c6d`).
+    ///
+    /// All the shares of the same distributed key have the same fingerprint,
+    /// so it can be compared by the parties out-of-band to detect a tampered key set.
+    pub fn fingerprint(&self) -> String {
+        let hash = FofHasher::new_with_dst(b"KeyShareFingerprint")
+            .chain(&self.public_shares.values().sum::<Point<P::Curve>>())
+            .chain(&self.public_shares.keys().collect::<Vec<_>>())
+            .finalize();
+        hash.as_ref()[..16]
+            .chunks(2)
+            .map(hex::encode)
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+}
+
+impl<P: SchemeParams, I: Ord + Serialize> KeyShare<P, I> {
+    /// Serializes the key share into a self-describing format suitable for long-term storage.
+    ///
+    /// The data starts with a header consisting of 4 magic bytes, the format version
+    /// (2 bytes, big-endian), and a hash of the scheme parameters (32 bytes),
+    /// so that [`Self::from_bytes`] can reject the data written by an incompatible
+    /// version of the library or for different parameters instead of misinterpreting it.
+    ///
+    /// Note that the result contains the secret share.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, LocalError> {
+        let serialized = bincode::serde::encode_to_vec(self, bincode::config::standard())
+            .map_err(|err| LocalError(format!("Failed to serialize the key share: {err}")))?;
+
+        let mut bytes = Vec::with_capacity(KEY_SHARE_HEADER_LEN + serialized.len());
+        bytes.extend(KEY_SHARE_MAGIC);
+        bytes.extend(KEY_SHARE_VERSION.to_be_bytes());
+        bytes.extend(scheme_params_id::<P>().as_ref());
+        bytes.extend(serialized);
+        Ok(bytes)
+    }
+}
+
+impl<P: SchemeParams, I: Ord + for<'de> Deserialize<'de>> KeyShare<P, I> {
+    /// Deserializes a key share created by [`Self::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, KeyShareBytesError> {
+        if bytes.len() < KEY_SHARE_HEADER_LEN || &bytes[..4] != KEY_SHARE_MAGIC {
+            return Err(KeyShareBytesError::InvalidMagic);
+        }
+
+        let version = u16::from_be_bytes([bytes[4], bytes[5]]);
+        if version != KEY_SHARE_VERSION {
+            return Err(KeyShareBytesError::UnsupportedVersion(version));
+        }
+
+        if bytes[6..KEY_SHARE_HEADER_LEN] != *scheme_params_id::<P>().as_ref() {
+            return Err(KeyShareBytesError::MismatchedParams);
+        }
+
+        bincode::serde::decode_borrowed_from_slice(
+            &bytes[KEY_SHARE_HEADER_LEN..],
+            bincode::config::standard(),
+        )
+        .map_err(|err| KeyShareBytesError::CannotDeserialize(format!("{err}")))
+    }
+}
+
+/// Returns a hash identifying the scheme parameters.
+fn scheme_params_id<P: SchemeParams>() -> HashOutput {
+    FofHasher::new_with_dst(b"SchemeParamsID")
+        .chain_type::<P>()
+        .chain(&(P::SECURITY_PARAMETER as u64))
+        .chain(&(P::L_BOUND as u64))
+        .chain(&(P::LP_BOUND as u64))
+        .chain(&(P::EPS_BOUND as u64))
+        .chain(&(<P::Paillier as PaillierParams>::MODULUS_BITS as u64))
+        .finalize()
+}
+
 impl<P: SchemeParams, I: Ord + Clone> AuxInfo<P, I> {
     /// Returns the owner of this aux data.
     pub fn owner(&self) -> &I {
@@ -251,8 +637,37 @@ impl<P: SchemeParams, I: Ord + Clone> AuxInfo<P, I> {
             .collect()
     }
 
+    /// Restricts the auxiliary data to the parties in `ids`
+    /// (e.g. the quorum chosen for threshold signing),
+    /// so that it can be used in the presigning/signing protocols with these parties.
+    ///
+    /// `ids` must include the owner of the data, and all the parties in it must be known.
+    pub fn to_subset(&self, ids: &BTreeSet<I>) -> Result<Self, LocalError> {
+        if !ids.contains(&self.owner) {
+            return Err(LocalError(
+                "The subset must include the owner of the auxiliary data".into(),
+            ));
+        }
+        if !ids.iter().all(|id| self.public_aux.contains_key(id)) {
+            return Err(LocalError(
+                "The subset contains parties with no auxiliary data".into(),
+            ));
+        }
+
+        Ok(Self {
+            owner: self.owner.clone(),
+            secret_aux: self.secret_aux.clone(),
+            public_aux: self
+                .public_aux
+                .iter()
+                .filter(|(id, _public_aux)| ids.contains(id))
+                .map(|(id, public_aux)| (id.clone(), public_aux.clone()))
+                .collect(),
+        })
+    }
+
     pub(crate) fn to_precomputed(&self) -> AuxInfoPrecomputed<P, I> {
-        AuxInfoPrecomputed {
+        let precomputed = AuxInfoPrecomputed {
             secret_aux: SecretAuxInfoPrecomputed {
                 paillier_sk: self.secret_aux.paillier_sk.to_precomputed(),
                 el_gamal_sk: self.secret_aux.el_gamal_sk.clone(),
@@ -272,14 +687,138 @@ impl<P: SchemeParams, I: Ord + Clone> AuxInfo<P, I> {
                     )
                 })
                 .collect(),
+        };
+        debug_assert!(precomputed.verify_matches(self));
+        precomputed
+    }
+
+    /// Checks that the secret keys of the owner match their public counterparts.
+    pub(crate) fn check_own_keys(&self) -> Result<(), String> {
+        let public_aux = self
+            .public_aux
+            .get(&self.owner)
+            .ok_or_else(|| String::from("The owner is not one of the parties of the aux data"))?;
+        let paillier_sk = self.secret_aux.paillier_sk.to_precomputed();
+        if paillier_sk.public_key().as_minimal() != &public_aux.paillier_pk {
+            return Err("The Paillier secret key does not match the public key".into());
+        }
+        if self
+            .secret_aux
+            .el_gamal_sk
+            .expose_secret()
+            .mul_by_generator()
+            != public_aux.el_gamal_pk
+        {
+            return Err("The El-Gamal secret key does not match the public key".into());
+        }
+        Ok(())
+    }
+
+    /// Performs the cheap sanity checks of the public data of the party `id`.
+    ///
+    /// These do not replace the proofs checked during AuxGen,
+    /// but catch data that was corrupted or mixed up after it.
+    pub(crate) fn check_public_aux(&self, id: &I) -> Result<(), String> {
+        let public_aux = self
+            .public_aux
+            .get(id)
+            .ok_or_else(|| String::from("The party is missing from the aux data"))?;
+
+        // The modulus is a product of two `PRIME_BITS`-sized primes,
+        // so it can be one bit shorter than `MODULUS_BITS`.
+        let modulus = public_aux.paillier_pk.modulus();
+        let modulus_bits = modulus.bits_vartime() as usize;
+        let expected_bits = <P::Paillier as PaillierParams>::MODULUS_BITS;
+        if modulus_bits != expected_bits && modulus_bits != expected_bits - 1 {
+            return Err(format!(
+                "The Paillier modulus has {modulus_bits} bits, expected {expected_bits}"
+            ));
+        }
+        if !bool::from(modulus.is_odd()) {
+            return Err("The Paillier modulus is even".into());
+        }
+        if self
+            .public_aux
+            .iter()
+            .any(|(other_id, other)| other_id != id && other.paillier_pk == public_aux.paillier_pk)
+        {
+            return Err("The Paillier modulus is shared with another party".into());
+        }
+
+        let one = <<P::Paillier as PaillierParams>::Uint as Integer>::one();
+        for (name, value) in [
+            ("base", &public_aux.rp_params.base),
+            ("power", &public_aux.rp_params.power),
+        ] {
+            if value <= &one || value >= modulus {
+                return Err(format!("The ring-Pedersen {name} is out of range"));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<P: SchemeParams, I: Ord> AuxInfoPrecomputed<P, I> {
+    /// Checks that the precomputed values correspond to the ones in `aux_info`.
+    ///
+    /// This is a consistency check for the precomputation itself,
+    /// so it is only run in debug builds.
+    pub(crate) fn verify_matches(&self, aux_info: &AuxInfo<P, I>) -> bool {
+        if self.secret_aux.paillier_sk.to_minimal() != aux_info.secret_aux.paillier_sk
+            || self.secret_aux.el_gamal_sk.expose_secret()
+                != aux_info.secret_aux.el_gamal_sk.expose_secret()
+        {
+            return false;
         }
+
+        if self.public_aux.len() != aux_info.public_aux.len() {
+            return false;
+        }
+        self.public_aux.iter().all(|(id, precomputed)| {
+            let public_aux = match aux_info.public_aux.get(id) {
+                Some(public_aux) => public_aux,
+                None => return false,
+            };
+            let rp_params = precomputed.rp_params.retrieve();
+            precomputed.el_gamal_pk == public_aux.el_gamal_pk
+                && precomputed.paillier_pk.as_minimal() == &public_aux.paillier_pk
+                && precomputed.rp_params.public_key().as_minimal() == &public_aux.paillier_pk
+                && rp_params.base == public_aux.rp_params.base
+                && rp_params.power == public_aux.rp_params.power
+        })
+    }
+}
+
+/// Experimental accessors for building custom signature schemes on top of presigning.
+///
+/// **These are not a part of the supported API.** ECDSA signing (via the interactive signing
+/// protocol) is the only supported use of the presigning data, and these methods may change
+/// or disappear in any release. They are only provided to allow experimenting
+/// with other finalization procedures (e.g. Schnorr-like signatures), and the crate
+/// makes no claims about the security of such uses. In particular, a presigning result
+/// must never be used more than once, whatever the finalization is.
+#[cfg(any(test, feature = "experimental"))]
+impl<P: SchemeParams, I> PresigningData<P, I> {
+    /// Returns the nonce point `R`, which is the same for all the parties.
+    ///
+    /// Note that, as required by ECDSA, `R = k^{-1} G`, where `k` is the sum of
+    /// all the parties' [ephemeral scalar shares](`Self::experimental_ephemeral_scalar_share`).
+    pub fn experimental_nonce_point(&self) -> <P::Curve as Curve>::BackendPoint {
+        self.nonce_point.to_backend()
+    }
+
+    /// Returns this party's additive share of the ephemeral scalar `k`.
+    ///
+    /// This is a secret value and must be handled accordingly.
+    pub fn experimental_ephemeral_scalar_share(&self) -> <P::Curve as Curve>::BackendScalar {
+        self.ephemeral_scalar_share.expose_secret().to_backend()
     }
 }
 
 impl<P, I> PresigningData<P, I>
 where
     P: SchemeParams,
-    I: Ord + Clone + PartialEq,
+    I: Ord + Clone + PartialEq + Serialize,
 {
     /// Creates a consistent set of presigning data for testing purposes.
     #[cfg(any(test, feature = "bench-internals"))]
@@ -290,13 +829,11 @@ where
     ) -> BTreeMap<I, Self> {
         let ids = key_shares.keys().cloned().collect::<BTreeSet<_>>();
 
-        let ephemeral_scalar = Scalar::random(rng);
-        let nonce = ephemeral_scalar
-            .invert()
-            .unwrap()
-            .mul_by_generator()
-            .x_coordinate();
-        let ephemeral_scalar_shares = ephemeral_scalar.split(rng, key_shares.len());
+        let ephemeral_scalar = Scalar::<P::Curve>::random(rng);
+        let nonce_point = ephemeral_scalar.invert().unwrap().mul_by_generator();
+        let nonce = nonce_point.x_coordinate();
+        let ephemeral_scalar_shares =
+            Scalar::split_additive(rng, &ephemeral_scalar, key_shares.len());
 
         let ephemeral_scalar_shares = ids
             .iter()
@@ -375,10 +912,10 @@ where
                         hat_beta: SecretBox::new(Box::new(hat_betas[&id_ij])),
                         hat_r: hat_rs[&id_ij].clone(),
                         hat_s: hat_ss[&id_ij].clone(),
-                        hat_cap_d_received: hat_cap_ds[&id_ij].clone(),
-                        hat_cap_d: hat_cap_ds[&id_ji].clone(),
-                        hat_cap_f: hat_cap_fs[&id_ji].clone(),
-                        cap_k: all_cap_k[id_j].clone(),
+                        hat_cap_d_received: hat_cap_ds[&id_ij].retrieve(),
+                        hat_cap_d: hat_cap_ds[&id_ji].retrieve(),
+                        hat_cap_f: hat_cap_fs[&id_ji].retrieve(),
+                        cap_k: all_cap_k[id_j].retrieve(),
                     },
                 );
             }
@@ -410,13 +947,17 @@ where
                 id_i.clone(),
                 PresigningData {
                     nonce,
+                    nonce_point,
+                    key_id: key_shares[&id_i].key_id(),
                     ephemeral_scalar_share: SecretBox::new(Box::new(k_i)),
-                    product_share: SecretBox::new(Box::new(P::scalar_from_signed(
-                        &product_share_nonreduced,
-                    ))),
-                    product_share_nonreduced,
-                    cap_k: all_cap_k[&id_i].clone(),
-                    values,
+                    product: Some(PresigningProduct {
+                        product_share: SecretBox::new(Box::new(P::scalar_from_signed(
+                            &product_share_nonreduced,
+                        ))),
+                        product_share_nonreduced,
+                        values,
+                    }),
+                    cap_k: all_cap_k[&id_i].retrieve(),
                 },
             );
         }
@@ -429,11 +970,57 @@ where
 mod tests {
     use alloc::collections::BTreeSet;
 
-    use k256::ecdsa::{SigningKey, VerifyingKey};
-    use rand_core::OsRng;
+    use k256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey, VerifyingKey};
+    use rand_core::{OsRng, RngCore};
+
+    use alloc::boxed::Box;
+    use alloc::collections::BTreeMap;
+
+    use secrecy::{ExposeSecret, SecretBox};
 
-    use super::KeyShare;
-    use crate::cggmp21::TestParams;
+    #[cfg(feature = "dangerous-presignature-export")]
+    use super::PresigningData;
+    use super::{AuxInfo, KeyShare, KeyShareBytesError, KeyShareChange};
+    use crate::cggmp21::{ProductionParams, TestParams};
+    use crate::constructors::{presigning_preflight, PreflightError};
+    use crate::curve::Secp256k1;
+    use crate::paillier::PublicKeyPaillier;
+    use crate::uint::U1024;
+
+    type Point = crate::curve::Point<Secp256k1>;
+    type Scalar = crate::curve::Scalar<Secp256k1>;
+
+    fn make_changes(
+        ids: &BTreeSet<VerifyingKey>,
+    ) -> BTreeMap<VerifyingKey, KeyShareChange<TestParams, VerifyingKey>> {
+        // Random secret changes summing up to zero, so that the shared secret stays the same.
+        let mut secret_changes = ids
+            .iter()
+            .map(|id| (*id, Scalar::random(&mut OsRng)))
+            .collect::<BTreeMap<_, _>>();
+        let sum: Scalar = secret_changes.values().sum();
+        *secret_changes.values_mut().next().unwrap() =
+            secret_changes.values().next().unwrap() - &sum;
+
+        let public_share_changes = secret_changes
+            .iter()
+            .map(|(id, change)| (*id, change.mul_by_generator()))
+            .collect::<BTreeMap<_, _>>();
+
+        secret_changes
+            .into_iter()
+            .map(|(id, change)| {
+                (
+                    id,
+                    KeyShareChange {
+                        owner: id,
+                        secret_share_change: SecretBox::new(Box::new(change)),
+                        public_share_changes: public_share_changes.clone(),
+                    },
+                )
+            })
+            .collect()
+    }
 
     #[test]
     fn key_share_centralized() {
@@ -449,4 +1036,352 @@ mod tests {
             .values()
             .all(|share| &share.verifying_key() == sk.verifying_key()));
     }
+
+    #[test]
+    fn fingerprint() {
+        let ids = (0..3)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .collect::<BTreeSet<_>>();
+
+        let shares = KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let fingerprint = shares.values().next().unwrap().fingerprint();
+        assert_eq!(fingerprint.len(), 39);
+        assert!(shares
+            .values()
+            .all(|share| share.fingerprint() == fingerprint));
+
+        // A different key
+        let other_shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        assert_ne!(
+            other_shares.values().next().unwrap().fingerprint(),
+            fingerprint
+        );
+
+        // The same key shared between a different set of parties
+        let sk = SigningKey::random(&mut OsRng);
+        let shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, Some(&sk));
+        let mut other_ids = ids.clone();
+        other_ids.insert(*SigningKey::random(&mut OsRng).verifying_key());
+        let other_shares = KeyShare::<TestParams, VerifyingKey>::new_centralized(
+            &mut OsRng,
+            &other_ids,
+            Some(&sk),
+        );
+        assert_ne!(
+            shares.values().next().unwrap().fingerprint(),
+            other_shares.values().next().unwrap().fingerprint()
+        );
+    }
+
+    #[cfg(feature = "dangerous-reconstruct")]
+    #[test]
+    fn reconstruct_secret_key() {
+        let ids = (0..3)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .collect::<BTreeSet<_>>();
+
+        let shares = KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None)
+            .into_values()
+            .collect::<Vec<_>>();
+        let verifying_key = shares[0].verifying_key();
+
+        let secret_key = KeyShare::reconstruct_secret_key(&shares).unwrap();
+        let signing_key = SigningKey::from(secret_key);
+        assert_eq!(signing_key.verifying_key(), &verifying_key);
+
+        let mut message = [0u8; 32];
+        OsRng.fill_bytes(&mut message);
+        let signature: Signature = signing_key.sign_prehash(&message).unwrap();
+        assert!(shares[0].verify_signature(&signature, &message));
+
+        // Not all the shares
+        assert!(KeyShare::reconstruct_secret_key(&shares[1..]).is_err());
+
+        // The same share twice
+        let duplicated = [shares[0].clone(), shares[0].clone(), shares[1].clone()];
+        assert!(KeyShare::reconstruct_secret_key(&duplicated).is_err());
+    }
+
+    #[cfg(feature = "dangerous-presignature-export")]
+    #[test]
+    fn presigning_data_serialization() {
+        let ids = (0..3)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .collect::<BTreeSet<_>>();
+
+        let key_shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids);
+        let presigning_datas = PresigningData::new_centralized(&mut OsRng, &key_shares, &aux_infos);
+        let data = presigning_datas.values().next().unwrap();
+
+        let config = bincode::config::standard();
+        let serialized = bincode::serde::encode_to_vec(data, config).unwrap();
+        let deserialized: PresigningData<TestParams, VerifyingKey> =
+            bincode::serde::decode_borrowed_from_slice(&serialized, config).unwrap();
+        assert_eq!(deserialized.nonce, data.nonce);
+        assert_eq!(deserialized.key_id, data.key_id);
+    }
+
+    #[test]
+    fn verify_signature() {
+        let sk = SigningKey::random(&mut OsRng);
+
+        let ids = (0..3)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .collect::<BTreeSet<_>>();
+
+        let shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, Some(&sk));
+        let share = shares.values().next().unwrap();
+
+        let mut message = [0u8; 32];
+        OsRng.fill_bytes(&mut message);
+        let signature: Signature = sk.sign_prehash(&message).unwrap();
+        assert!(share.verify_signature(&signature, &message));
+
+        let mut tampered_message = message;
+        tampered_message[0] ^= 1;
+        assert!(!share.verify_signature(&signature, &tampered_message));
+
+        let other_sk = SigningKey::random(&mut OsRng);
+        let other_signature: Signature = other_sk.sign_prehash(&message).unwrap();
+        assert!(!share.verify_signature(&other_signature, &message));
+    }
+
+    #[test]
+    fn sign_solo() {
+        let id = *SigningKey::random(&mut OsRng).verifying_key();
+        let shares = KeyShare::<TestParams, VerifyingKey>::new_centralized(
+            &mut OsRng,
+            &BTreeSet::from([id]),
+            None,
+        );
+        let share = &shares[&id];
+
+        let mut message = [0u8; 32];
+        OsRng.fill_bytes(&mut message);
+        let (signature, _recovery_id) = share.sign_solo(&mut OsRng, &message).unwrap().to_backend();
+        assert!(share.verify_signature(&signature, &message));
+
+        // A share of a key split between several parties cannot sign alone.
+        let ids = (0..2)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .collect::<BTreeSet<_>>();
+        let shares = KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let share = shares.values().next().unwrap();
+        assert!(share.sign_solo(&mut OsRng, &message).is_err());
+    }
+
+    #[test]
+    fn apply_key_share_change() {
+        let ids = (0..3)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .collect::<BTreeSet<_>>();
+
+        let shares = KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let vkey = shares.values().next().unwrap().verifying_key();
+        let changes = make_changes(&ids);
+
+        for (id, share) in shares.iter() {
+            let new_share = KeyShare::try_from((share.clone(), changes[id].clone())).unwrap();
+            assert_eq!(new_share.verifying_key(), vkey);
+            assert_eq!(
+                new_share.secret_share.expose_secret().mul_by_generator(),
+                new_share.public_shares[id]
+            );
+        }
+    }
+
+    #[test]
+    fn apply_mismatched_key_share_change() {
+        let ids = (0..3)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .collect::<BTreeSet<_>>();
+        let shares = KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let changes = make_changes(&ids);
+
+        let mut ids_iter = ids.iter();
+        let id0 = ids_iter.next().unwrap();
+        let id1 = ids_iter.next().unwrap();
+
+        // Wrong owner
+        assert!(KeyShare::try_from((shares[id0].clone(), changes[id1].clone())).is_err());
+
+        // Different set of parties
+        let other_ids = (0..3)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .chain([*id0])
+            .collect::<BTreeSet<_>>();
+        let other_changes = make_changes(&other_ids);
+        assert!(KeyShare::try_from((shares[id0].clone(), other_changes[id0].clone())).is_err());
+
+        // A change that does not preserve the verifying key
+        let mut change = changes[id0].clone();
+        let public_share_change = change.public_share_changes.get_mut(id1).unwrap();
+        *public_share_change = *public_share_change + Point::GENERATOR;
+        assert!(KeyShare::try_from((shares[id0].clone(), change)).is_err());
+    }
+
+    #[test]
+    fn corrupted_precomputed_aux_info_is_detected() {
+        let ids = (0..3)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .collect::<BTreeSet<_>>();
+        let aux_infos = AuxInfo::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids);
+
+        let mut ids_iter = ids.iter();
+        let id0 = ids_iter.next().unwrap();
+        let id1 = ids_iter.next().unwrap();
+
+        let aux_info = &aux_infos[id0];
+        let precomputed = aux_info.to_precomputed();
+        assert!(precomputed.verify_matches(aux_info));
+
+        // The precomputed values of another party
+        let mut corrupted = precomputed.clone();
+        corrupted.public_aux.get_mut(id0).unwrap().rp_params =
+            precomputed.public_aux[id1].rp_params.clone();
+        assert!(!corrupted.verify_matches(aux_info));
+
+        let mut corrupted = precomputed.clone();
+        corrupted.public_aux.get_mut(id1).unwrap().el_gamal_pk = Point::GENERATOR;
+        assert!(!corrupted.verify_matches(aux_info));
+
+        // The precomputed values of another node
+        let corrupted = aux_infos[id1].to_precomputed();
+        assert!(!corrupted.verify_matches(aux_info));
+    }
+
+    #[test]
+    fn aux_info_subset() {
+        let ids = (0..3)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .collect::<BTreeSet<_>>();
+        let aux_infos = AuxInfo::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids);
+
+        let mut ids_iter = ids.iter();
+        let id0 = *ids_iter.next().unwrap();
+        let id1 = *ids_iter.next().unwrap();
+
+        let subset = aux_infos[&id0]
+            .to_subset(&BTreeSet::from([id0, id1]))
+            .unwrap();
+        assert!(subset.public_aux.keys().eq([id0, id1].iter()));
+
+        // The owner must be in the subset
+        let id2 = *ids_iter.next().unwrap();
+        assert!(aux_infos[&id0]
+            .to_subset(&BTreeSet::from([id1, id2]))
+            .is_err());
+
+        // All the parties must be known
+        let unknown = *SigningKey::random(&mut OsRng).verifying_key();
+        assert!(aux_infos[&id0]
+            .to_subset(&BTreeSet::from([id0, unknown]))
+            .is_err());
+    }
+
+    #[test]
+    fn preflight_detects_corrupted_paillier_key() {
+        let ids = (0..3)
+            .map(|_| *SigningKey::random(&mut OsRng).verifying_key())
+            .collect::<BTreeSet<_>>();
+        let key_shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids);
+
+        let mut ids_iter = ids.iter();
+        let id0 = ids_iter.next().unwrap();
+        let id1 = ids_iter.next().unwrap();
+        let id2 = ids_iter.next().unwrap();
+
+        for id in ids.iter() {
+            assert!(presigning_preflight(&key_shares[id], &aux_infos[id]).is_ok());
+        }
+
+        // Another party's Paillier key was replaced by someone else's
+        let mut aux_info = aux_infos[id0].clone();
+        aux_info.public_aux.get_mut(id1).unwrap().paillier_pk =
+            aux_info.public_aux[id2].paillier_pk;
+        assert!(matches!(
+            presigning_preflight(&key_shares[id0], &aux_info),
+            Err(PreflightError::Party { party, .. }) if &party == id1
+        ));
+
+        // Another party's Paillier modulus is too small
+        let mut aux_info = aux_infos[id0].clone();
+        let modulus = *aux_info.public_aux[id1].paillier_pk.modulus();
+        aux_info.public_aux.get_mut(id1).unwrap().paillier_pk =
+            PublicKeyPaillier::from_modulus((modulus >> 8) | U1024::ONE);
+        assert!(matches!(
+            presigning_preflight(&key_shares[id0], &aux_info),
+            Err(PreflightError::Party { party, .. }) if &party == id1
+        ));
+
+        // Another party's Paillier modulus was altered (and is now even)
+        let mut aux_info = aux_infos[id0].clone();
+        aux_info.public_aux.get_mut(id1).unwrap().paillier_pk =
+            PublicKeyPaillier::from_modulus(modulus.wrapping_sub(&U1024::ONE));
+        assert!(matches!(
+            presigning_preflight(&key_shares[id0], &aux_info),
+            Err(PreflightError::Party { party, .. }) if &party == id1
+        ));
+
+        // Our own Paillier key does not match the secret one
+        let mut aux_info = aux_infos[id0].clone();
+        aux_info.secret_aux.paillier_sk = aux_infos[id1].secret_aux.paillier_sk.clone();
+        assert!(matches!(
+            presigning_preflight(&key_shares[id0], &aux_info),
+            Err(PreflightError::AuxInfo(_))
+        ));
+
+        // The data of different parties
+        assert!(matches!(
+            presigning_preflight(&key_shares[id0], &aux_infos[id1]),
+            Err(PreflightError::AuxInfo(_))
+        ));
+    }
+
+    #[test]
+    fn key_share_bytes_round_trip() {
+        let ids = BTreeSet::from([
+            *SigningKey::random(&mut OsRng).verifying_key(),
+            *SigningKey::random(&mut OsRng).verifying_key(),
+        ]);
+        let key_shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let key_share = key_shares.values().next().unwrap();
+
+        let bytes = key_share.to_bytes().unwrap();
+        let restored = KeyShare::<TestParams, VerifyingKey>::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.owner(), key_share.owner());
+        assert_eq!(
+            restored.secret_share.expose_secret(),
+            key_share.secret_share.expose_secret()
+        );
+        assert_eq!(restored.public_shares, key_share.public_shares);
+
+        // A corrupted version is reported as such
+        let mut corrupted = bytes.clone();
+        corrupted[5] ^= 0xff;
+        assert!(matches!(
+            KeyShare::<TestParams, VerifyingKey>::from_bytes(&corrupted),
+            Err(KeyShareBytesError::UnsupportedVersion(version)) if version == 0xfe
+        ));
+
+        // Data that is not a key share
+        assert!(matches!(
+            KeyShare::<TestParams, VerifyingKey>::from_bytes(&bytes[1..]),
+            Err(KeyShareBytesError::InvalidMagic)
+        ));
+
+        // A key share for different parameters
+        assert!(matches!(
+            KeyShare::<ProductionParams, VerifyingKey>::from_bytes(&bytes),
+            Err(KeyShareBytesError::MismatchedParams)
+        ));
+    }
 }
@@ -1,6 +1,10 @@
+use alloc::format;
+use alloc::string::String;
 use core::fmt::Debug;
 
-use crate::curve::{Curve, Scalar, ORDER};
+#[cfg(feature = "p256")]
+use crate::curve::Secp256r1;
+use crate::curve::{Curve, Point, Scalar, Secp256k1};
 use crate::paillier::PaillierParams;
 use crate::tools::hashing::{Chain, HashableType};
 use crate::uint::{
@@ -80,9 +84,9 @@ impl PaillierParams for PaillierProduction {
 }
 
 /// Signing scheme parameters.
-// TODO (#27): this trait can include curve scalar/point types as well,
-// but for now they are hardcoded to `k256`.
 pub trait SchemeParams: Debug + Clone + Send + PartialEq + Eq + Send + Sync + 'static {
+    /// The curve the protocols are run on.
+    type Curve: Curve;
     /// The order of the curve.
     const CURVE_ORDER: NonZero<<Self::Paillier as PaillierParams>::Uint>; // $q$
     /// The order of the curve as a wide integer.
@@ -90,16 +94,29 @@ pub trait SchemeParams: Debug + Clone + Send + PartialEq + Eq + Send + Sync + 's
     /// The scheme's statistical security parameter.
     const SECURITY_PARAMETER: usize; // $\kappa$
     /// The bound for secret values.
+    ///
+    /// Must be at least `log2(q)` (`q` being the curve order), since the secrets are scalars.
+    /// Larger values increase the size of the proofs.
     const L_BOUND: usize; // $\ell$, paper sets it to $\log2(q)$ (see Table 2)
     /// The error bound for secret masks.
+    ///
+    /// The masks are sampled as signed integers and then Paillier-encrypted,
+    /// so `LP_BOUND` plus the slack accumulated in the homomorphic operations
+    /// must stay below `Paillier::MODULUS_BITS - 1` (one bit is taken by the sign),
+    /// otherwise the plaintexts wrap around. See the comment in `PaillierTest` for the details.
+    /// Larger values increase the size of the proofs.
     const LP_BOUND: usize; // $\ell^\prime$, in paper $= 5 \ell$ (see Table 2)
     /// The error bound for range checks (referred to in the paper as the slackness parameter).
+    ///
+    /// An honest party fails a range check with the probability of about `q / 2^EPS_BOUND`,
+    /// so it must be at least `log2(q) + SECURITY_PARAMETER` to keep the rate of benign aborts
+    /// negligible. Larger values increase the size of the proofs.
     const EPS_BOUND: usize; // $\eps$, in paper $= 2 \ell$ (see Table 2)
     /// The parameters of the Paillier encryption.
     type Paillier: PaillierParams;
 
     /// Converts a curve scalar to the associated integer type.
-    fn uint_from_scalar(value: &Scalar) -> <Self::Paillier as PaillierParams>::Uint {
+    fn uint_from_scalar(value: &Scalar<Self::Curve>) -> <Self::Paillier as PaillierParams>::Uint {
         let scalar_bytes = value.to_bytes();
         let mut repr = <Self::Paillier as PaillierParams>::Uint::zero().to_be_bytes();
 
@@ -112,58 +129,117 @@ pub trait SchemeParams: Debug + Clone + Send + PartialEq + Eq + Send + Sync + 's
     }
 
     /// Converts a curve scalar to the associated integer type, wrapped in `Bounded`.
-    fn bounded_from_scalar(value: &Scalar) -> Bounded<<Self::Paillier as PaillierParams>::Uint> {
-        const ORDER_BITS: u32 = ORDER.bits_vartime();
-        Bounded::new(Self::uint_from_scalar(value), ORDER_BITS).unwrap()
+    fn bounded_from_scalar(
+        value: &Scalar<Self::Curve>,
+    ) -> Bounded<<Self::Paillier as PaillierParams>::Uint> {
+        let order_bits = <Self::Curve as Curve>::ORDER.bits_vartime();
+        Bounded::new(Self::uint_from_scalar(value), order_bits).unwrap()
     }
 
     /// Converts a curve scalar to the associated integer type, wrapped in `Signed`.
-    fn signed_from_scalar(value: &Scalar) -> Signed<<Self::Paillier as PaillierParams>::Uint> {
+    fn signed_from_scalar(
+        value: &Scalar<Self::Curve>,
+    ) -> Signed<<Self::Paillier as PaillierParams>::Uint> {
         Self::bounded_from_scalar(value).into_signed().unwrap()
     }
 
     /// Converts an integer to the associated curve scalar type.
-    fn scalar_from_uint(value: &<Self::Paillier as PaillierParams>::Uint) -> Scalar {
+    fn scalar_from_uint(value: &<Self::Paillier as PaillierParams>::Uint) -> Scalar<Self::Curve> {
         let r = *value % Self::CURVE_ORDER;
 
         let repr = r.to_be_bytes();
         let uint_len = repr.as_ref().len();
-        let scalar_len = Scalar::repr_len();
+        let scalar_len = Scalar::<Self::Curve>::repr_len();
 
         // Can unwrap here since the value is within the Scalar range
-        Scalar::try_from_bytes(&repr.as_ref()[uint_len - scalar_len..]).unwrap()
+        Scalar::<Self::Curve>::try_from_bytes(&repr.as_ref()[uint_len - scalar_len..]).unwrap()
     }
 
     /// Converts a `Signed`-wrapped integer to the associated curve scalar type.
-    fn scalar_from_signed(value: &Signed<<Self::Paillier as PaillierParams>::Uint>) -> Scalar {
+    fn scalar_from_signed(
+        value: &Signed<<Self::Paillier as PaillierParams>::Uint>,
+    ) -> Scalar<Self::Curve> {
         let abs_value = Self::scalar_from_uint(&value.abs());
-        Scalar::conditional_select(&abs_value, &-abs_value, value.is_negative())
+        Scalar::<Self::Curve>::conditional_select(&abs_value, &-abs_value, value.is_negative())
     }
 
     /// Converts a wide integer to the associated curve scalar type.
-    fn scalar_from_wide_uint(value: &<Self::Paillier as PaillierParams>::WideUint) -> Scalar {
+    fn scalar_from_wide_uint(
+        value: &<Self::Paillier as PaillierParams>::WideUint,
+    ) -> Scalar<Self::Curve> {
         let r = *value % Self::CURVE_ORDER_WIDE;
 
         let repr = r.to_be_bytes();
         let uint_len = repr.as_ref().len();
-        let scalar_len = Scalar::repr_len();
+        let scalar_len = Scalar::<Self::Curve>::repr_len();
 
         // Can unwrap here since the value is within the Scalar range
-        Scalar::try_from_bytes(&repr.as_ref()[uint_len - scalar_len..]).unwrap()
+        Scalar::<Self::Curve>::try_from_bytes(&repr.as_ref()[uint_len - scalar_len..]).unwrap()
     }
 
     /// Converts a `Signed`-wrapped wide integer to the associated curve scalar type.
     fn scalar_from_wide_signed(
         value: &Signed<<Self::Paillier as PaillierParams>::WideUint>,
-    ) -> Scalar {
+    ) -> Scalar<Self::Curve> {
         let abs_value = Self::scalar_from_wide_uint(&value.abs());
-        Scalar::conditional_select(&abs_value, &-abs_value, value.is_negative())
+        Scalar::<Self::Curve>::conditional_select(&abs_value, &-abs_value, value.is_negative())
     }
 }
 
+/// Checks that the bounds in `P` satisfy the constraints documented in [`SchemeParams`]
+/// for a protocol run with `num_parties` parties.
+pub(crate) fn validate_scheme_params<P: SchemeParams>(num_parties: usize) -> Result<(), String> {
+    let order_bits = <P::Curve as Curve>::ORDER.bits_vartime() as usize;
+    let uint_bits =
+        <<P::Paillier as PaillierParams>::Uint as crypto_bigint::Bounded>::BITS as usize;
+    let parties_bits = num_parties.next_power_of_two().trailing_zeros() as usize;
+
+    if P::L_BOUND < order_bits {
+        return Err(format!(
+            "`L_BOUND` ({}) must be at least the bit size of the curve order ({order_bits})",
+            P::L_BOUND
+        ));
+    }
+
+    if P::EPS_BOUND < order_bits + P::SECURITY_PARAMETER {
+        return Err(format!(
+            "`EPS_BOUND` ({}) must be at least the bit size of the curve order \
+            plus `SECURITY_PARAMETER` ({}), otherwise honest parties will fail range checks",
+            P::EPS_BOUND,
+            order_bits + P::SECURITY_PARAMETER
+        ));
+    }
+
+    // The masked values in the range proofs must fit in a signed `Uint`.
+    let max_masked_bits = core::cmp::max(P::L_BOUND, P::LP_BOUND) + P::EPS_BOUND + 1;
+    if max_masked_bits >= uint_bits - 1 {
+        return Err(format!(
+            "`max(L_BOUND, LP_BOUND) + EPS_BOUND + 1` ({max_masked_bits}) \
+            must be smaller than {}",
+            uint_bits - 1
+        ));
+    }
+
+    // The accumulated plaintexts in the presigning must not wrap around the Paillier modulus
+    // (see the comment in `PaillierTest`).
+    let max_plaintext_bits =
+        core::cmp::max(2 * P::L_BOUND, P::LP_BOUND + 2) + order_bits + parties_bits;
+    let modulus_bits = <P::Paillier as PaillierParams>::MODULUS_BITS;
+    if max_plaintext_bits >= modulus_bits - 2 {
+        return Err(format!(
+            "The accumulated plaintexts ({max_plaintext_bits} bits for {num_parties} parties) \
+            must be smaller than the Paillier modulus ({modulus_bits} bits) minus 2"
+        ));
+    }
+
+    Ok(())
+}
+
 impl<P: SchemeParams> HashableType for P {
     fn chain_type<C: Chain>(digest: C) -> C {
-        digest.chain_type::<Curve>()
+        digest
+            .chain(&<P::Curve as Curve>::ORDER)
+            .chain(&Point::<P::Curve>::GENERATOR)
     }
 }
 
@@ -181,29 +257,292 @@ pub struct TestParams;
 // - Range checks will fail with the probability $q / 2^\eps$, so $\eps$ should be large enough.
 // - P^{fac} assumes $N ~ 2^{4 \ell + 2 \eps}$
 impl SchemeParams for TestParams {
+    type Curve = Secp256k1;
     const SECURITY_PARAMETER: usize = 10;
     const L_BOUND: usize = 256;
     const LP_BOUND: usize = 256;
     const EPS_BOUND: usize = 320;
     type Paillier = PaillierTest;
     const CURVE_ORDER: NonZero<<Self::Paillier as PaillierParams>::Uint> =
-        upcast_uint(ORDER).to_nz().expect("Correct by construction");
+        upcast_uint(<Secp256k1 as Curve>::ORDER)
+            .to_nz()
+            .expect("Correct by construction");
     const CURVE_ORDER_WIDE: NonZero<<Self::Paillier as PaillierParams>::WideUint> =
-        upcast_uint(ORDER).to_nz().expect("Correct by construction");
+        upcast_uint(<Secp256k1 as Curve>::ORDER)
+            .to_nz()
+            .expect("Correct by construction");
 }
 
 /// Production strength parameters.
+///
+/// The values follow Table 2 of the CGGMP21 paper for a 256-bit curve:
+///
+/// | Parameter            | Value            |
+/// |----------------------|------------------|
+/// | `SECURITY_PARAMETER` | 80               |
+/// | `L_BOUND`            | 256              |
+/// | `LP_BOUND`           | 1280 (5 `L_BOUND`) |
+/// | `EPS_BOUND`          | 512 (2 `L_BOUND`)  |
+/// | Paillier primes      | 1024 bits        |
+/// | Paillier modulus     | 2048 bits        |
+///
+/// The relationships between the bounds are checked at compile time.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ProductionParams;
 
 impl SchemeParams for ProductionParams {
+    type Curve = Secp256k1;
     const SECURITY_PARAMETER: usize = 80; // The value is given in Table 2 in the paper
     const L_BOUND: usize = 256;
     const LP_BOUND: usize = Self::L_BOUND * 5;
     const EPS_BOUND: usize = Self::L_BOUND * 2;
     type Paillier = PaillierProduction;
     const CURVE_ORDER: NonZero<<Self::Paillier as PaillierParams>::Uint> =
-        upcast_uint(ORDER).to_nz().expect("Correct by construction");
+        upcast_uint(<Secp256k1 as Curve>::ORDER)
+            .to_nz()
+            .expect("Correct by construction");
+    const CURVE_ORDER_WIDE: NonZero<<Self::Paillier as PaillierParams>::WideUint> =
+        upcast_uint(<Secp256k1 as Curve>::ORDER)
+            .to_nz()
+            .expect("Correct by construction");
+}
+
+/// Same as [`TestParams`], but on the P-256 curve.
+///
+/// Scheme parameters **for testing purposes only**.
+#[cfg(feature = "p256")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestParamsP256;
+
+#[cfg(feature = "p256")]
+impl SchemeParams for TestParamsP256 {
+    type Curve = Secp256r1;
+    const SECURITY_PARAMETER: usize = TestParams::SECURITY_PARAMETER;
+    const L_BOUND: usize = TestParams::L_BOUND;
+    const LP_BOUND: usize = TestParams::LP_BOUND;
+    const EPS_BOUND: usize = TestParams::EPS_BOUND;
+    type Paillier = PaillierTest;
+    const CURVE_ORDER: NonZero<<Self::Paillier as PaillierParams>::Uint> =
+        upcast_uint(<Secp256r1 as Curve>::ORDER)
+            .to_nz()
+            .expect("Correct by construction");
+    const CURVE_ORDER_WIDE: NonZero<<Self::Paillier as PaillierParams>::WideUint> =
+        upcast_uint(<Secp256r1 as Curve>::ORDER)
+            .to_nz()
+            .expect("Correct by construction");
+}
+
+/// Same as [`ProductionParams`], but on the P-256 curve.
+///
+/// The curve order is 256 bits long, so the values from Table 2 of the paper apply as well.
+#[cfg(feature = "p256")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProductionParamsP256;
+
+#[cfg(feature = "p256")]
+impl SchemeParams for ProductionParamsP256 {
+    type Curve = Secp256r1;
+    const SECURITY_PARAMETER: usize = ProductionParams::SECURITY_PARAMETER;
+    const L_BOUND: usize = ProductionParams::L_BOUND;
+    const LP_BOUND: usize = ProductionParams::LP_BOUND;
+    const EPS_BOUND: usize = ProductionParams::EPS_BOUND;
+    type Paillier = PaillierProduction;
+    const CURVE_ORDER: NonZero<<Self::Paillier as PaillierParams>::Uint> =
+        upcast_uint(<Secp256r1 as Curve>::ORDER)
+            .to_nz()
+            .expect("Correct by construction");
     const CURVE_ORDER_WIDE: NonZero<<Self::Paillier as PaillierParams>::WideUint> =
-        upcast_uint(ORDER).to_nz().expect("Correct by construction");
+        upcast_uint(<Secp256r1 as Curve>::ORDER)
+            .to_nz()
+            .expect("Correct by construction");
+}
+
+/// Compile-time versions of the checks in [`validate_scheme_params`],
+/// for the parameter sets defined in the library.
+mod static_checks {
+    use super::{Curve, PaillierParams, ProductionParams, SchemeParams, TestParams};
+
+    const fn bounds_hold<P: SchemeParams>(max_parties_bits: usize) -> bool {
+        let order_bits = <P::Curve as Curve>::ORDER.bits_vartime() as usize;
+        let uint_bits =
+            <<P::Paillier as PaillierParams>::Uint as crypto_bigint::Bounded>::BITS as usize;
+        let modulus_bits = <P::Paillier as PaillierParams>::MODULUS_BITS;
+
+        let max_mask_bits = if P::L_BOUND > P::LP_BOUND {
+            P::L_BOUND
+        } else {
+            P::LP_BOUND
+        };
+        let max_product_bits = if 2 * P::L_BOUND > P::LP_BOUND + 2 {
+            2 * P::L_BOUND
+        } else {
+            P::LP_BOUND + 2
+        };
+
+        P::L_BOUND >= order_bits
+            && P::EPS_BOUND >= order_bits + P::SECURITY_PARAMETER
+            && max_mask_bits + P::EPS_BOUND + 1 < uint_bits - 1
+            && max_product_bits + order_bits + max_parties_bits < modulus_bits - 2
+    }
+
+    // The tests are not run with more than 32 parties (see the comment in `PaillierTest`).
+    const _: () = assert!(bounds_hold::<TestParams>(5));
+    const _: () = assert!(bounds_hold::<ProductionParams>(10));
+
+    #[cfg(feature = "p256")]
+    const _: () = assert!(bounds_hold::<super::TestParamsP256>(5));
+    #[cfg(feature = "p256")]
+    const _: () = assert!(bounds_hold::<super::ProductionParamsP256>(10));
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+    use alloc::vec::Vec;
+
+    use k256::ecdsa::{Signature, SigningKey};
+    use rand_core::OsRng;
+
+    use super::{
+        validate_scheme_params, PaillierProduction, PaillierTest, ProductionParams, SchemeParams,
+        TestParams,
+    };
+    use crate::cggmp21::sigma::EncProof;
+    use crate::curve::{Scalar, Secp256k1};
+    use crate::make_key_gen_session;
+    use crate::paillier::{
+        CiphertextMod, PaillierParams, RPParamsMod, RandomizerMod, SecretKeyPaillier,
+    };
+    use crate::sessions::{run_protocol, SessionId};
+    use crate::uint::NonZero;
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    struct TightSlackParams;
+
+    // Same as `TestParams`, but with the slack too small for the range checks to pass reliably.
+    impl SchemeParams for TightSlackParams {
+        const SECURITY_PARAMETER: usize = TestParams::SECURITY_PARAMETER;
+        const L_BOUND: usize = TestParams::L_BOUND;
+        const LP_BOUND: usize = TestParams::LP_BOUND;
+        const EPS_BOUND: usize = 258;
+        type Curve = Secp256k1;
+        type Paillier = PaillierTest;
+        const CURVE_ORDER: NonZero<<Self::Paillier as PaillierParams>::Uint> =
+            TestParams::CURVE_ORDER;
+        const CURVE_ORDER_WIDE: NonZero<<Self::Paillier as PaillierParams>::WideUint> =
+            TestParams::CURVE_ORDER_WIDE;
+    }
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    struct WideMaskParams;
+
+    // Same as `TestParams`, but with the masks too wide for the Paillier modulus.
+    impl SchemeParams for WideMaskParams {
+        const SECURITY_PARAMETER: usize = TestParams::SECURITY_PARAMETER;
+        const L_BOUND: usize = TestParams::L_BOUND;
+        const LP_BOUND: usize = 768;
+        const EPS_BOUND: usize = TestParams::EPS_BOUND;
+        type Curve = Secp256k1;
+        type Paillier = PaillierTest;
+        const CURVE_ORDER: NonZero<<Self::Paillier as PaillierParams>::Uint> =
+            TestParams::CURVE_ORDER;
+        const CURVE_ORDER_WIDE: NonZero<<Self::Paillier as PaillierParams>::WideUint> =
+            TestParams::CURVE_ORDER_WIDE;
+    }
+
+    #[test]
+    fn default_params_are_valid() {
+        for num_parties in [2, 3, 32] {
+            validate_scheme_params::<TestParams>(num_parties).unwrap();
+            validate_scheme_params::<ProductionParams>(num_parties).unwrap();
+            #[cfg(feature = "p256")]
+            validate_scheme_params::<super::TestParamsP256>(num_parties).unwrap();
+            #[cfg(feature = "p256")]
+            validate_scheme_params::<super::ProductionParamsP256>(num_parties).unwrap();
+        }
+    }
+
+    #[test]
+    #[ignore = "generates production-sized Paillier keys, which takes a long time"]
+    fn production_key_gen() {
+        let signers = (0..2)
+            .map(|_| SigningKey::random(&mut OsRng))
+            .collect::<Vec<_>>();
+        let verifiers = signers
+            .iter()
+            .map(|signer| *signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+        let session_id = SessionId::from_seed(b"production key gen");
+
+        let sessions = signers
+            .iter()
+            .map(|signer| {
+                make_key_gen_session::<ProductionParams, Signature, _, _>(
+                    &mut OsRng,
+                    session_id,
+                    signer.clone(),
+                    &verifiers,
+                )
+                .unwrap()
+            })
+            .collect();
+        let results = run_protocol(&mut OsRng, sessions).unwrap();
+
+        // The primes have their most significant bit set,
+        // so the modulus may be one bit shorter than their combined size.
+        let modulus_bits = PaillierProduction::MODULUS_BITS as u32;
+        for (_key_share, aux_info) in results {
+            for public_aux in aux_info.public_aux.values() {
+                let bits = public_aux.paillier_pk.modulus().bits_vartime();
+                assert!(bits == modulus_bits || bits == modulus_bits - 1);
+            }
+        }
+    }
+
+    #[test]
+    fn tight_bounds_are_rejected() {
+        assert!(validate_scheme_params::<TightSlackParams>(3).is_err());
+        assert!(validate_scheme_params::<WideMaskParams>(3).is_err());
+
+        assert!(validate_scheme_params::<TestParams>(3).is_ok());
+        assert!(validate_scheme_params::<ProductionParams>(3).is_ok());
+    }
+
+    /// Runs the range proof an honest party sends in Presigning Round 1 `runs` times,
+    /// and returns the number of runs in which it was rejected.
+    ///
+    /// The presigning itself cannot be started with invalid parameters
+    /// (see `validate_scheme_params`), so the proof is run directly.
+    fn count_benign_aborts<P: SchemeParams<Curve = Secp256k1>>(runs: usize) -> usize {
+        let sk = SecretKeyPaillier::<P::Paillier>::random(&mut OsRng).to_precomputed();
+        let pk = sk.public_key();
+        let aux_sk = SecretKeyPaillier::<P::Paillier>::random(&mut OsRng).to_precomputed();
+        let setup = RPParamsMod::random(&mut OsRng, &aux_sk);
+        let aux: &[u8] = b"abcde";
+
+        (0..runs)
+            .filter(|_| {
+                // Same as the ephemeral scalar `k` in Round 1.
+                let k = P::signed_from_scalar(&Scalar::random(&mut OsRng));
+                let rho = RandomizerMod::random(&mut OsRng, pk);
+                let cap_k = CiphertextMod::new_with_randomizer_signed(pk, &k, &rho.retrieve());
+                let proof = EncProof::<P>::new(&mut OsRng, &k, &rho, pk, &cap_k, &setup, &aux);
+                !proof.verify(pk, &cap_k, &setup, &aux)
+            })
+            .count()
+    }
+
+    #[test]
+    #[ignore = "creates and verifies a thousand range proofs, which takes a long time"]
+    fn tight_slack_increases_abort_rate() {
+        // With `EPS_BOUND` only 2 bits above the curve order size,
+        // an honest proof is rejected in about 1 in 32 runs,
+        // so not seeing any in 512 runs has the probability of about `e^(-16)`.
+        let runs = 512;
+        let tight_aborts = count_benign_aborts::<TightSlackParams>(runs);
+        assert!(tight_aborts > 0);
+
+        // With the default parameters the probability is about `2^(-64)` per run.
+        assert_eq!(count_benign_aborts::<TestParams>(runs), 0);
+    }
 }
@@ -0,0 +1,145 @@
+use alloc::collections::VecDeque;
+
+use super::{PresigningData, SchemeParams};
+use crate::sessions::Clock;
+
+/// A collection of presignatures waiting to be used for signing,
+/// each of which expires after a fixed time since it was added.
+///
+/// Presignatures that are kept around for a long time give an attacker who compromises
+/// the storage more chances to obtain them, so they are discarded after `max_age`
+/// (in the units of the [`Clock`] passed to the methods, that is seconds).
+/// The presignatures are only moved in and out of the pool, never copied,
+/// so each of them can still be used at most once.
+#[derive(Debug)]
+pub struct PresignaturePool<P: SchemeParams, I> {
+    max_age: u64,
+    // Ordered by the time of addition, oldest first.
+    entries: VecDeque<(u64, PresigningData<P, I>)>,
+}
+
+impl<P: SchemeParams, I> PresignaturePool<P, I> {
+    /// Creates an empty pool, in which the presignatures expire `max_age` seconds after addition.
+    pub fn new(max_age: u64) -> Self {
+        Self {
+            max_age,
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// Adds a presignature to the pool, recording the current time given by `clock`.
+    pub fn insert(&mut self, clock: &dyn Clock, presigning_data: PresigningData<P, I>) {
+        let now = clock.now();
+        // Keep the entries ordered even if the clock has gone backwards.
+        let created = self
+            .entries
+            .back()
+            .map_or(now, |(last_created, _)| now.max(*last_created));
+        self.entries.push_back((created, presigning_data));
+    }
+
+    /// Removes the expired presignatures, returning the number of the removed ones.
+    pub fn prune(&mut self, clock: &dyn Clock) -> usize {
+        let now = clock.now();
+        let fresh_from = self
+            .entries
+            .iter()
+            .position(|(created, _)| now.saturating_sub(*created) <= self.max_age)
+            .unwrap_or(self.entries.len());
+        self.entries.drain(..fresh_from).count()
+    }
+
+    /// Removes the expired presignatures and returns the oldest of the remaining ones,
+    /// if there are any.
+    pub fn take(&mut self, clock: &dyn Clock) -> Option<PresigningData<P, I>> {
+        self.prune(clock);
+        self.entries
+            .pop_front()
+            .map(|(_created, presigning_data)| presigning_data)
+    }
+
+    /// Returns the number of presignatures in the pool, including the expired ones
+    /// that have not been pruned yet.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there are no presignatures in the pool.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+    use core::cell::Cell;
+
+    use k256::ecdsa::{SigningKey, VerifyingKey};
+    use rand_core::OsRng;
+
+    use super::PresignaturePool;
+    use crate::cggmp21::{AuxInfo, KeyShare, PresigningData, TestParams};
+    use crate::sessions::Clock;
+
+    struct MockClock(Cell<u64>);
+
+    impl MockClock {
+        fn advance(&self, seconds: u64) {
+            self.0.set(self.0.get() + seconds);
+        }
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> u64 {
+            self.0.get()
+        }
+    }
+
+    #[test]
+    fn expired_presignatures_are_pruned() {
+        let ids = BTreeSet::from([
+            *SigningKey::random(&mut OsRng).verifying_key(),
+            *SigningKey::random(&mut OsRng).verifying_key(),
+        ]);
+        let key_shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids);
+        let make_data = || {
+            PresigningData::new_centralized(&mut OsRng, &key_shares, &aux_infos)
+                .into_values()
+                .next()
+                .unwrap()
+        };
+
+        let clock = MockClock(Cell::new(1000));
+        let mut pool = PresignaturePool::new(10);
+
+        pool.insert(&clock, make_data());
+        clock.advance(5);
+        pool.insert(&clock, make_data());
+        clock.advance(5);
+        let third = make_data();
+        let third_nonce = third.nonce;
+        pool.insert(&clock, third);
+
+        // Nothing has expired yet.
+        assert_eq!(pool.prune(&clock), 0);
+        assert_eq!(pool.len(), 3);
+
+        // The first one is older than `max_age`.
+        clock.advance(1);
+        assert_eq!(pool.prune(&clock), 1);
+        assert_eq!(pool.len(), 2);
+
+        // Taking a presignature skips the expired ones.
+        clock.advance(5);
+        assert_eq!(pool.take(&clock).unwrap().nonce, third_nonce);
+        assert!(pool.is_empty());
+
+        pool.insert(&clock, make_data());
+        clock.advance(11);
+        assert!(pool.take(&clock).is_none());
+        assert!(pool.is_empty());
+    }
+}
@@ -13,5 +13,8 @@ pub use interactive_signing::{
 pub use key_gen::{KeyGenError, KeyGenProof, KeyGenResult};
 pub use key_init::{KeyInitError, KeyInitResult};
 pub use key_refresh::KeyRefreshResult;
-pub use presigning::{PresigningError, PresigningProof, PresigningResult};
+pub use presigning::{
+    PresigningError, PresigningProof, PresigningResult, PresigningRetryReason,
+    VerificationFailReason,
+};
 pub use signing::{SigningProof, SigningResult};
@@ -4,6 +4,7 @@
 use alloc::boxed::Box;
 use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::string::String;
+use core::convert::Infallible;
 use core::fmt::Debug;
 use core::marker::PhantomData;
 
@@ -13,7 +14,7 @@ use serde::{Deserialize, Serialize};
 
 use super::super::{
     sigma::{FacProof, ModProof, PrmProof, SchCommitment, SchProof, SchSecret},
-    AuxInfo, PublicAuxInfo, SchemeParams, SecretAuxInfo,
+    AuxInfo, PaillierPrimes, PublicAuxInfo, SchemeParams, SecretAuxInfo,
 };
 use crate::curve::{Point, Scalar};
 use crate::paillier::{
@@ -36,6 +37,7 @@ impl<P: SchemeParams, I: Debug + Ord> ProtocolResult for AuxGenResult<P, I> {
     type Success = AuxInfo<P, I>;
     type ProvableError = AuxGenError;
     type CorrectnessProof = ();
+    type RetryReason = Infallible;
 }
 
 /// Possible errors for AuxGen protocol.
@@ -56,8 +58,8 @@ enum AuxGenErrorEnum {
 #[serde(bound(serialize = "PrmProof<P>: Serialize"))]
 #[serde(bound(deserialize = "PrmProof<P>: for<'x> Deserialize<'x>"))]
 pub struct PublicData1<P: SchemeParams> {
-    cap_y: Point,
-    cap_b: SchCommitment,
+    cap_y: Point<P::Curve>,
+    cap_b: SchCommitment<P::Curve>,
     paillier_pk: PublicKeyPaillier<P::Paillier>, // $N_i$
     rp_params: RPParams<P::Paillier>,            // $s_i$ and $t_i$
     hat_psi: PrmProof<P>,
@@ -74,8 +76,8 @@ pub struct PublicData1Precomp<P: SchemeParams> {
 
 struct Context<P: SchemeParams, I> {
     paillier_sk: SecretKeyPaillierPrecomputed<P::Paillier>,
-    y: Scalar,
-    tau_y: SchSecret,
+    y: Scalar<P::Curve>,
+    tau_y: SchSecret<P::Curve>,
     data_precomp: PublicData1Precomp<P>,
     my_id: I,
     other_ids: BTreeSet<I>,
@@ -97,13 +99,14 @@ pub struct Round1<P: SchemeParams, I> {
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FirstRound<I> for Round1<P, I> {
-    type Inputs = ();
+    /// Optionally, externally generated primes for the Paillier key of this party.
+    type Inputs = Option<PaillierPrimes<P>>;
     fn new(
         rng: &mut impl CryptoRngCore,
         shared_randomness: &[u8],
         other_ids: BTreeSet<I>,
         my_id: I,
-        _inputs: Self::Inputs,
+        inputs: Self::Inputs,
     ) -> Result<Self, InitError> {
         let mut all_ids = other_ids.clone();
         all_ids.insert(my_id.clone());
@@ -115,12 +118,16 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FirstRound<I> for Roun
             .finalize();
 
         // $p_i$, $q_i$
-        let paillier_sk = SecretKeyPaillier::<P::Paillier>::random(rng).to_precomputed();
+        let paillier_sk = match inputs {
+            Some(primes) => primes.0,
+            None => SecretKeyPaillier::<P::Paillier>::random(rng),
+        }
+        .to_precomputed();
         // $N_i$
         let paillier_pk = paillier_sk.public_key();
 
         // El-Gamal key
-        let y = Scalar::random(rng);
+        let y = Scalar::<P::Curve>::random(rng);
         let cap_y = y.mul_by_generator();
 
         // The secret and the commitment for the Schnorr PoK of the El-Gamal key
@@ -328,6 +335,11 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round2<P,
             },
         })
     }
+
+    fn verification_cost(&self) -> usize {
+        // The `prm` proof.
+        1
+    }
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToNextRound<I>
@@ -358,7 +370,7 @@ pub struct Round3<P: SchemeParams, I> {
     rho: BitVec,
     others_data: BTreeMap<I, PublicData1Precomp<P>>,
     psi_mod: ModProof<P>,
-    pi: SchProof,
+    pi: SchProof<P::Curve>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -373,7 +385,7 @@ pub struct Round3<P: SchemeParams, I> {
 pub struct PublicData2<P: SchemeParams> {
     psi_mod: ModProof<P>, // $\psi_i$, a P^{mod} for the Paillier modulus
     phi: FacProof<P>,
-    pi: SchProof,
+    pi: SchProof<P::Curve>,
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round3<P, I> {
@@ -498,6 +510,11 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round3<P,
 
         Ok(())
     }
+
+    fn verification_cost(&self) -> usize {
+        // The `mod`, `fac` and `sch` proofs.
+        3
+    }
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I> for Round3<P, I> {
@@ -548,11 +565,15 @@ mod tests {
     use secrecy::ExposeSecret;
 
     use super::Round1;
-    use crate::cggmp21::TestParams;
+    use crate::cggmp21::{params::PaillierTest, PaillierPrimes, TestParams};
+    use crate::paillier::PaillierParams;
     use crate::rounds::{
         test_utils::{step_next_round, step_result, step_round, Id, Without},
         FirstRound,
     };
+    use crate::uint::{HasWide, RandomPrimeWithRng};
+
+    type HalfUint = <PaillierTest as PaillierParams>::HalfUint;
 
     #[test]
     fn execute_aux_gen() {
@@ -569,7 +590,7 @@ mod tests {
                     &shared_randomness,
                     ids.clone().without(id),
                     *id,
-                    (),
+                    None,
                 )
                 .unwrap();
                 (*id, round)
@@ -596,4 +617,64 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn execute_aux_gen_with_external_primes() {
+        let mut shared_randomness = [0u8; 32];
+        OsRng.fill_bytes(&mut shared_randomness);
+
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+
+        // Safe primes generated outside of the protocol
+        let make_prime = || {
+            HalfUint::generate_safe_prime_with_rng(
+                &mut OsRng,
+                PaillierTest::PRIME_BITS as u32,
+                HalfUint::BITS,
+            )
+        };
+        let p = make_prime();
+        let q = make_prime();
+        let expected_modulus = p.mul_wide(&q);
+
+        // Invalid primes are rejected
+        assert!(PaillierPrimes::<TestParams>::new(&mut OsRng, p, p).is_err());
+        // (if `p` is a safe prime, `p + 2` cannot be one)
+        let not_safe_prime = p.wrapping_add(&HalfUint::from_u64(2));
+        assert!(PaillierPrimes::<TestParams>::new(&mut OsRng, p, not_safe_prime).is_err());
+
+        let r1 = ids
+            .iter()
+            .map(|id| {
+                let primes = if id == &Id(1) {
+                    Some(PaillierPrimes::new(&mut OsRng, p, q).unwrap())
+                } else {
+                    None
+                };
+                let round = Round1::<TestParams, Id>::new(
+                    &mut OsRng,
+                    &shared_randomness,
+                    ids.clone().without(id),
+                    *id,
+                    primes,
+                )
+                .unwrap();
+                (*id, round)
+            })
+            .collect();
+
+        let r1a = step_round(&mut OsRng, r1).unwrap();
+        let r2 = step_next_round(&mut OsRng, r1a).unwrap();
+        let r2a = step_round(&mut OsRng, r2).unwrap();
+        let r3 = step_next_round(&mut OsRng, r2a).unwrap();
+        let r3a = step_round(&mut OsRng, r3).unwrap();
+        let aux_infos = step_result(&mut OsRng, r3a).unwrap();
+
+        for aux_info in aux_infos.values() {
+            assert_eq!(
+                aux_info.public_aux[&Id(1)].paillier_pk.modulus(),
+                &expected_modulus
+            );
+        }
+    }
 }
@@ -1,4 +1,3 @@
-use alloc::boxed::Box;
 use alloc::collections::{BTreeMap, BTreeSet};
 use core::fmt::Debug;
 use core::marker::PhantomData;
@@ -8,7 +7,7 @@ use serde::Serialize;
 
 use super::super::params::SchemeParams;
 use super::super::{AuxInfo, KeyShare};
-use super::presigning::{self, PresigningResult};
+use super::presigning::{self, PresigningResult, PresigningRetryReason};
 use super::signing::{self, SigningResult};
 use crate::curve::{RecoverableSignature, Scalar};
 use crate::rounds::{
@@ -16,15 +15,20 @@ use crate::rounds::{
     FinalizeError, FirstRound, InitError, ProtocolResult, ProvableErrorWrapper, Round,
     RoundWrapper, ToNextRound, ToResult, WrappedRound,
 };
+use crate::tools::hashing::{derive_phase_randomness, HashOutput};
+
+const PRESIGNING_PHASE: &[u8] = b"InteractiveSigning/Presigning";
+const SIGNING_PHASE: &[u8] = b"InteractiveSigning/Signing";
 
 /// Possible results of the merged Presigning and Signing protocols.
 #[derive(Debug)]
 pub struct InteractiveSigningResult<P: SchemeParams, I: Debug>(PhantomData<P>, PhantomData<I>);
 
 impl<P: SchemeParams, I: Debug> ProtocolResult for InteractiveSigningResult<P, I> {
-    type Success = RecoverableSignature;
+    type Success = RecoverableSignature<P::Curve>;
     type ProvableError = InteractiveSigningError<P, I>;
     type CorrectnessProof = InteractiveSigningProof<P, I>;
+    type RetryReason = PresigningRetryReason;
 }
 
 /// Possible verifiable errors of the merged Presigning and Signing protocols.
@@ -63,6 +67,12 @@ impl<P: SchemeParams, I: Debug> CorrectnessProofWrapper<PresigningResult<P, I>>
     ) -> Self::CorrectnessProof {
         InteractiveSigningProof::Presigning(proof)
     }
+
+    fn wrap_retry_reason(
+        reason: <PresigningResult<P, I> as ProtocolResult>::RetryReason,
+    ) -> Self::RetryReason {
+        reason
+    }
 }
 
 impl<P: SchemeParams, I: Debug> ProvableErrorWrapper<SigningResult<P, I>>
@@ -83,20 +93,26 @@ impl<P: SchemeParams, I: Debug> CorrectnessProofWrapper<SigningResult<P, I>>
     ) -> Self::CorrectnessProof {
         InteractiveSigningProof::Signing(proof)
     }
+
+    fn wrap_retry_reason(
+        reason: <SigningResult<P, I> as ProtocolResult>::RetryReason,
+    ) -> Self::RetryReason {
+        match reason {}
+    }
 }
 
 struct Context<P: SchemeParams, I: Ord> {
-    shared_randomness: Box<[u8]>,
+    signing_randomness: HashOutput,
     key_share: KeyShare<P, I>,
     aux_info: AuxInfo<P, I>,
-    message: Scalar,
+    message: Scalar<P::Curve>,
 }
 
 #[derive(Clone)]
 pub(crate) struct Inputs<P: SchemeParams, I: Ord> {
     pub(crate) key_share: KeyShare<P, I>,
     pub(crate) aux_info: AuxInfo<P, I>,
-    pub(crate) message: Scalar,
+    pub(crate) message: Scalar<P::Curve>,
 }
 
 pub(crate) struct Round1<P: SchemeParams, I: Ord> {
@@ -113,15 +129,21 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FirstRound<I> for Roun
         my_id: I,
         inputs: Self::Inputs,
     ) -> Result<Self, InitError> {
+        let presigning_randomness = derive_phase_randomness(PRESIGNING_PHASE, shared_randomness);
         let round = presigning::Round1::new(
             rng,
-            shared_randomness,
+            presigning_randomness.as_ref(),
             other_ids,
             my_id,
-            (inputs.key_share.clone(), inputs.aux_info.clone()),
+            presigning::Inputs {
+                key_share: inputs.key_share.clone(),
+                aux_info: inputs.aux_info.clone(),
+                nonce_only: false,
+                rng_seed: None,
+            },
         )?;
         let context = Context {
-            shared_randomness: shared_randomness.into(),
+            signing_randomness: derive_phase_randomness(SIGNING_PHASE, shared_randomness),
             key_share: inputs.key_share,
             aux_info: inputs.aux_info,
             message: inputs.message,
@@ -245,7 +267,7 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToNextRound
         };
         let signing_round = signing::Round1::new(
             rng,
-            &self.context.shared_randomness,
+            self.context.signing_randomness.as_ref(),
             other_ids,
             my_id,
             signing_context,
@@ -290,29 +312,29 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
 
 #[cfg(test)]
 mod tests {
-    use alloc::collections::BTreeSet;
+    use alloc::collections::{BTreeMap, BTreeSet};
 
-    use k256::ecdsa::{signature::hazmat::PrehashVerifier, VerifyingKey};
+    use k256::ecdsa::{signature::hazmat::PrehashVerifier, SigningKey, VerifyingKey};
     use rand_core::{OsRng, RngCore};
 
     use super::{Inputs, Round1};
     use crate::cggmp21::{AuxInfo, KeyShare, TestParams};
-    use crate::curve::Scalar;
+    use crate::curve::{RecoverableSignature, Secp256k1};
     use crate::rounds::{
         test_utils::{step_next_round, step_result, step_round, Id, Without},
         FirstRound,
     };
 
-    #[test]
-    fn execute_interactive_signing() {
+    type Scalar = crate::curve::Scalar<Secp256k1>;
+
+    fn run_signing(
+        key_shares: &BTreeMap<Id, KeyShare<TestParams, Id>>,
+        message: &Scalar,
+    ) -> BTreeMap<Id, RecoverableSignature> {
         let mut shared_randomness = [0u8; 32];
         OsRng.fill_bytes(&mut shared_randomness);
 
-        let message = Scalar::random(&mut OsRng);
-
-        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
-
-        let key_shares = KeyShare::new_centralized(&mut OsRng, &ids, None);
+        let ids = key_shares.keys().cloned().collect::<BTreeSet<_>>();
         let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids);
 
         let r1 = ids
@@ -324,7 +346,7 @@ mod tests {
                     ids.clone().without(id),
                     *id,
                     Inputs {
-                        message,
+                        message: *message,
                         key_share: key_shares[id].clone(),
                         aux_info: aux_infos[id].clone(),
                     },
@@ -341,7 +363,17 @@ mod tests {
         let r3a = step_round(&mut OsRng, r3).unwrap();
         let r4 = step_next_round(&mut OsRng, r3a).unwrap();
         let r4a = step_round(&mut OsRng, r4).unwrap();
-        let signatures = step_result(&mut OsRng, r4a).unwrap();
+        step_result(&mut OsRng, r4a).unwrap()
+    }
+
+    #[test]
+    fn execute_interactive_signing() {
+        let message = Scalar::random(&mut OsRng);
+
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+        let key_shares = KeyShare::new_centralized(&mut OsRng, &ids, None);
+
+        let signatures = run_signing(&key_shares, &message);
 
         for signature in signatures.values() {
             let (sig, rec_id) = signature.to_backend();
@@ -357,4 +389,27 @@ mod tests {
             assert_eq!(recovered_key, vkey);
         }
     }
+
+    #[test]
+    fn sign_with_imported_key() {
+        let message = Scalar::random(&mut OsRng);
+
+        // An existing key is split between the parties.
+        let sk = SigningKey::random(&mut OsRng);
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+        let key_shares = KeyShare::new_centralized(&mut OsRng, &ids, Some(&sk));
+        for key_share in key_shares.values() {
+            assert_eq!(&key_share.verifying_key(), sk.verifying_key());
+        }
+
+        let signatures = run_signing(&key_shares, &message);
+
+        // The signatures are valid for the original key.
+        for signature in signatures.values() {
+            let (sig, _rec_id) = signature.to_backend();
+            sk.verifying_key()
+                .verify_prehash(&message.to_bytes(), &sig)
+                .unwrap();
+        }
+    }
 }
@@ -2,13 +2,14 @@
 //! Since both take three rounds and are independent, we can execute them in parallel.
 
 use alloc::collections::{BTreeMap, BTreeSet};
+use core::convert::Infallible;
 use core::fmt::Debug;
 use core::marker::PhantomData;
 
 use rand_core::CryptoRngCore;
 use serde::Serialize;
 
-use super::super::{AuxInfo, KeyShare, SchemeParams};
+use super::super::{AuxInfo, KeyShare, PaillierPrimes, SchemeParams};
 use super::key_init::{self, KeyInitResult};
 use super::key_refresh::{self, KeyRefreshResult};
 use crate::rounds::{
@@ -16,6 +17,28 @@ use crate::rounds::{
     FinalizableToResult, FinalizeError, FirstRound, InitError, ProtocolResult, Round, ToNextRound,
     ToResult,
 };
+use crate::tools::hashing::derive_phase_randomness;
+#[cfg(any(test, feature = "state-digest"))]
+use crate::tools::hashing::{Chain, FofHasher, HashOutput};
+
+const KEY_INIT_PHASE: &[u8] = b"KeyGen/KeyInit";
+const KEY_REFRESH_PHASE: &[u8] = b"KeyGen/KeyRefresh";
+
+/// Combines the state digests of the two phases running in parallel.
+#[cfg(any(test, feature = "state-digest"))]
+fn combined_state_digest(
+    round_num: u8,
+    key_init_digest: Option<HashOutput>,
+    key_refresh_digest: Option<HashOutput>,
+) -> Option<HashOutput> {
+    Some(
+        FofHasher::new_with_dst(b"KeyGen state")
+            .chain(&round_num)
+            .chain(&key_init_digest?)
+            .chain(&key_refresh_digest?)
+            .finalize(),
+    )
+}
 
 /// Possible results of the merged KeyGen and KeyRefresh protocols.
 #[derive(Debug)]
@@ -25,6 +48,7 @@ impl<P: SchemeParams, I: Debug + Ord> ProtocolResult for KeyGenResult<P, I> {
     type Success = (KeyShare<P, I>, AuxInfo<P, I>);
     type ProvableError = KeyGenError<P, I>;
     type CorrectnessProof = KeyGenProof<P, I>;
+    type RetryReason = Infallible;
 }
 
 /// Possible verifiable errors of the merged KeyGen and KeyRefresh protocols.
@@ -53,6 +77,12 @@ impl<P: SchemeParams, I: Debug + Ord> CorrectnessProofWrapper<KeyInitResult<P, I
     ) -> Self::CorrectnessProof {
         KeyGenProof::KeyInit(proof)
     }
+
+    fn wrap_retry_reason(
+        reason: <KeyInitResult<P, I> as ProtocolResult>::RetryReason,
+    ) -> Self::RetryReason {
+        match reason {}
+    }
 }
 
 impl<P: SchemeParams, I: Debug + Ord> CorrectnessProofWrapper<KeyRefreshResult<P, I>>
@@ -63,6 +93,12 @@ impl<P: SchemeParams, I: Debug + Ord> CorrectnessProofWrapper<KeyRefreshResult<P
     ) -> Self::CorrectnessProof {
         KeyGenProof::KeyRefresh(proof)
     }
+
+    fn wrap_retry_reason(
+        reason: <KeyRefreshResult<P, I> as ProtocolResult>::RetryReason,
+    ) -> Self::RetryReason {
+        match reason {}
+    }
 }
 
 pub(crate) struct Round1<P: SchemeParams, I> {
@@ -71,18 +107,33 @@ pub(crate) struct Round1<P: SchemeParams, I> {
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FirstRound<I> for Round1<P, I> {
-    type Inputs = ();
+    /// Optionally, externally generated primes for the Paillier key of this party.
+    type Inputs = Option<PaillierPrimes<P>>;
     fn new(
         rng: &mut impl CryptoRngCore,
         shared_randomness: &[u8],
         other_ids: BTreeSet<I>,
         my_id: I,
-        _inputs: Self::Inputs,
+        inputs: Self::Inputs,
     ) -> Result<Self, InitError> {
-        let key_init_round =
-            key_init::Round1::new(rng, shared_randomness, other_ids.clone(), my_id.clone(), ())?;
-        let key_refresh_round =
-            key_refresh::Round1::new(rng, shared_randomness, other_ids, my_id, ())?;
+        // Both phases run in parallel, so they must not share the transcript seed.
+        let key_init_randomness = derive_phase_randomness(KEY_INIT_PHASE, shared_randomness);
+        let key_refresh_randomness = derive_phase_randomness(KEY_REFRESH_PHASE, shared_randomness);
+
+        let key_init_round = key_init::Round1::new(
+            rng,
+            key_init_randomness.as_ref(),
+            other_ids.clone(),
+            my_id.clone(),
+            (),
+        )?;
+        let key_refresh_round = key_refresh::Round1::new(
+            rng,
+            key_refresh_randomness.as_ref(),
+            other_ids,
+            my_id,
+            inputs,
+        )?;
         Ok(Self {
             key_init_round,
             key_refresh_round,
@@ -104,6 +155,15 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round1<P,
         self.key_init_round.my_id()
     }
 
+    #[cfg(any(test, feature = "state-digest"))]
+    fn state_digest(&self) -> Option<HashOutput> {
+        combined_state_digest(
+            Self::ROUND_NUM,
+            self.key_init_round.state_digest(),
+            self.key_refresh_round.state_digest(),
+        )
+    }
+
     const REQUIRES_ECHO: bool = <key_init::Round1<P, I> as Round<I>>::REQUIRES_ECHO
         || <key_refresh::Round1<P, I> as Round<I>>::REQUIRES_ECHO;
     type BroadcastMessage = (
@@ -148,6 +208,10 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round1<P,
             .map_err(KeyGenError::KeyRefresh)?;
         Ok((key_init_payload, key_refresh_payload))
     }
+
+    fn verification_cost(&self) -> usize {
+        self.key_init_round.verification_cost() + self.key_refresh_round.verification_cost()
+    }
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToNextRound<I>
@@ -201,6 +265,15 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round2<P,
         self.key_init_round.my_id()
     }
 
+    #[cfg(any(test, feature = "state-digest"))]
+    fn state_digest(&self) -> Option<HashOutput> {
+        combined_state_digest(
+            Self::ROUND_NUM,
+            self.key_init_round.state_digest(),
+            self.key_refresh_round.state_digest(),
+        )
+    }
+
     const REQUIRES_ECHO: bool = <key_init::Round1<P, I> as Round<I>>::REQUIRES_ECHO
         || <key_refresh::Round1<P, I> as Round<I>>::REQUIRES_ECHO;
     type BroadcastMessage = (
@@ -245,6 +318,10 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round2<P,
             .map_err(KeyGenError::KeyRefresh)?;
         Ok((key_init_payload, key_refresh_payload))
     }
+
+    fn verification_cost(&self) -> usize {
+        self.key_init_round.verification_cost() + self.key_refresh_round.verification_cost()
+    }
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToNextRound<I>
@@ -298,6 +375,15 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round3<P,
         self.key_init_round.my_id()
     }
 
+    #[cfg(any(test, feature = "state-digest"))]
+    fn state_digest(&self) -> Option<HashOutput> {
+        combined_state_digest(
+            Self::ROUND_NUM,
+            self.key_init_round.state_digest(),
+            self.key_refresh_round.state_digest(),
+        )
+    }
+
     const REQUIRES_ECHO: bool = <key_init::Round3<P, I> as Round<I>>::REQUIRES_ECHO
         || <key_refresh::Round3<P, I> as Round<I>>::REQUIRES_ECHO;
     type BroadcastMessage = <key_init::Round3<P, I> as Round<I>>::BroadcastMessage;
@@ -341,6 +427,10 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round3<P,
             .map_err(KeyGenError::KeyRefresh)?;
         Ok((key_init_payload, key_refresh_payload))
     }
+
+    fn verification_cost(&self) -> usize {
+        self.key_init_round.verification_cost() + self.key_refresh_round.verification_cost()
+    }
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I> for Round3<P, I> {
@@ -368,3 +458,60 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
         Ok((key_share.update(key_share_change), aux_info))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+
+    use rand_core::{OsRng, RngCore};
+
+    use super::{key_init, key_refresh, Round1, KEY_INIT_PHASE, KEY_REFRESH_PHASE};
+    use crate::cggmp21::TestParams;
+    use crate::rounds::{test_utils::Id, FirstRound};
+    use crate::tools::hashing::derive_phase_randomness;
+
+    #[test]
+    fn phases_use_distinct_randomness() {
+        let mut shared_randomness = [0u8; 32];
+        OsRng.fill_bytes(&mut shared_randomness);
+
+        let other_ids = BTreeSet::from([Id(1), Id(2)]);
+        let round = Round1::<TestParams, Id>::new(
+            &mut OsRng,
+            &shared_randomness,
+            other_ids.clone(),
+            Id(0),
+            None,
+        )
+        .unwrap();
+
+        // The phases would end up with the same session identifier
+        // if they were seeded with the shared randomness directly.
+        let key_init_sid = round.key_init_round.sid_hash();
+        let key_refresh_sid = round.key_refresh_round.sid_hash();
+        assert_ne!(key_init_sid, key_refresh_sid);
+
+        // Each phase is seeded with its own derived randomness.
+        let key_init_randomness = derive_phase_randomness(KEY_INIT_PHASE, &shared_randomness);
+        let key_init_round = key_init::Round1::<TestParams, Id>::new(
+            &mut OsRng,
+            key_init_randomness.as_ref(),
+            other_ids.clone(),
+            Id(0),
+            (),
+        )
+        .unwrap();
+        assert_eq!(key_init_sid, key_init_round.sid_hash());
+
+        let key_refresh_randomness = derive_phase_randomness(KEY_REFRESH_PHASE, &shared_randomness);
+        let key_refresh_round = key_refresh::Round1::<TestParams, Id>::new(
+            &mut OsRng,
+            key_refresh_randomness.as_ref(),
+            other_ids,
+            Id(0),
+            None,
+        )
+        .unwrap();
+        assert_eq!(key_refresh_sid, key_refresh_round.sid_hash());
+    }
+}
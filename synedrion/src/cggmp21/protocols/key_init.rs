@@ -4,6 +4,7 @@
 
 use alloc::boxed::Box;
 use alloc::collections::{BTreeMap, BTreeSet};
+use core::convert::Infallible;
 use core::fmt::Debug;
 use core::marker::PhantomData;
 
@@ -31,6 +32,7 @@ impl<P: SchemeParams, I: Debug + Ord> ProtocolResult for KeyInitResult<P, I> {
     type Success = KeyShare<P, I>;
     type ProvableError = KeyInitError;
     type CorrectnessProof = ();
+    type RetryReason = Infallible;
 }
 
 /// Possible verifiable errors of the KeyGen protocol.
@@ -38,14 +40,16 @@ impl<P: SchemeParams, I: Debug + Ord> ProtocolResult for KeyInitResult<P, I> {
 pub enum KeyInitError {
     /// A hash mismatch in Round 2.
     R2HashMismatch,
+    /// The revealed `rid` has a wrong length in Round 2.
+    R2InvalidRidLength,
     /// Failed to verify `П^{sch}` in Round 3.
     R3InvalidSchProof,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct PublicData<P: SchemeParams> {
-    cap_x: Point,
-    cap_a: SchCommitment,
+    cap_x: Point<P::Curve>,
+    cap_a: SchCommitment<P::Curve>,
     rid: BitVec,
     u: BitVec,
     phantom: PhantomData<P>,
@@ -56,7 +60,12 @@ impl<P: SchemeParams> PublicData<P> {
         FofHasher::new_with_dst(b"KeyInit")
             .chain(sid_hash)
             .chain(&id)
-            .chain(self)
+            .chain(&self.cap_x)
+            .chain(&self.cap_a)
+            // `rid` and `u` have variable length, so they are length-prefixed
+            // to prevent collisions between different splits of the same bytes.
+            .chain_bytes(&self.rid)
+            .chain_bytes(&self.u)
             .finalize()
     }
 }
@@ -64,16 +73,36 @@ impl<P: SchemeParams> PublicData<P> {
 struct Context<P: SchemeParams, I> {
     other_ids: BTreeSet<I>,
     my_id: I,
-    x: Scalar,
-    tau: SchSecret,
+    x: Scalar<P::Curve>,
+    tau: SchSecret<P::Curve>,
     public_data: PublicData<P>,
     sid_hash: HashOutput,
 }
 
+#[cfg(any(test, feature = "state-digest"))]
+impl<P: SchemeParams, I: Serialize> Context<P, I> {
+    /// Starts the state digest with the public values known from the start of the protocol.
+    fn state_hasher(&self, round_num: u8) -> FofHasher {
+        FofHasher::new_with_dst(b"KeyInit state")
+            .chain(&round_num)
+            .chain(&self.sid_hash)
+            .chain(&self.my_id)
+            .chain(&self.public_data)
+    }
+}
+
 pub struct Round1<P: SchemeParams, I> {
     context: Context<P, I>,
 }
 
+#[cfg(test)]
+impl<P: SchemeParams, I> Round1<P, I> {
+    /// The session identifier hash this round was seeded with.
+    pub(crate) fn sid_hash(&self) -> &HashOutput {
+        &self.context.sid_hash
+    }
+}
+
 impl<P: SchemeParams, I: Clone + Ord + Serialize + Debug> FirstRound<I> for Round1<P, I> {
     type Inputs = ();
 
@@ -94,7 +123,7 @@ impl<P: SchemeParams, I: Clone + Ord + Serialize + Debug> FirstRound<I> for Roun
             .finalize();
 
         // The secret share
-        let x = Scalar::random(rng);
+        let x = Scalar::<P::Curve>::random(rng);
         // The public share
         let cap_x = x.mul_by_generator();
 
@@ -147,6 +176,11 @@ impl<P: SchemeParams, I: Clone + Ord + Serialize + Debug> Round<I> for Round1<P,
         &self.context.my_id
     }
 
+    #[cfg(any(test, feature = "state-digest"))]
+    fn state_digest(&self) -> Option<HashOutput> {
+        Some(self.context.state_hasher(Self::ROUND_NUM).finalize())
+    }
+
     const REQUIRES_ECHO: bool = true;
     type BroadcastMessage = Round1Message;
     type DirectMessage = ();
@@ -228,6 +262,16 @@ impl<P: SchemeParams, I: Serialize + Ord + Clone + Debug> Round<I> for Round2<P,
         &self.context.my_id
     }
 
+    #[cfg(any(test, feature = "state-digest"))]
+    fn state_digest(&self) -> Option<HashOutput> {
+        Some(
+            self.context
+                .state_hasher(Self::ROUND_NUM)
+                .chain(&self.others_cap_v)
+                .finalize(),
+        )
+    }
+
     type BroadcastMessage = Round2Message<P>;
     type DirectMessage = ();
     type Payload = Round2Payload<P>;
@@ -257,6 +301,13 @@ impl<P: SchemeParams, I: Serialize + Ord + Clone + Debug> Round<I> for Round2<P,
             return Err(KeyInitError::R2HashMismatch);
         }
 
+        // The hash check above makes sure that `rid` is the one the sender committed to
+        // before seeing anyone else's, so it cannot be chosen to bias the combined value.
+        // But it still has to be compatible with ours to be combined.
+        if broadcast_msg.data.rid.byte_len() != self.context.public_data.rid.byte_len() {
+            return Err(KeyInitError::R2InvalidRidLength);
+        }
+
         Ok(Round2Payload {
             data: broadcast_msg.data,
         })
@@ -295,8 +346,8 @@ pub struct Round3<P: SchemeParams, I> {
 }
 
 #[derive(Clone, Serialize, Deserialize)]
-pub struct Round3Message {
-    psi: SchProof,
+pub struct Round3Message<P: SchemeParams> {
+    psi: SchProof<P::Curve>,
 }
 
 impl<P: SchemeParams, I: Serialize + Ord + Clone + Debug> Round<I> for Round3<P, I> {
@@ -313,7 +364,18 @@ impl<P: SchemeParams, I: Serialize + Ord + Clone + Debug> Round<I> for Round3<P,
         &self.context.my_id
     }
 
-    type BroadcastMessage = Round3Message;
+    #[cfg(any(test, feature = "state-digest"))]
+    fn state_digest(&self) -> Option<HashOutput> {
+        Some(
+            self.context
+                .state_hasher(Self::ROUND_NUM)
+                .chain(&self.others_data)
+                .chain_bytes(&self.rid)
+                .finalize(),
+        )
+    }
+
+    type BroadcastMessage = Round3Message<P>;
     type DirectMessage = ();
     type Payload = ();
     type Artifact = ();
@@ -350,6 +412,11 @@ impl<P: SchemeParams, I: Serialize + Ord + Clone + Debug> Round<I> for Round3<P,
         }
         Ok(())
     }
+
+    fn verification_cost(&self) -> usize {
+        // The `sch` proof.
+        1
+    }
 }
 
 impl<P: SchemeParams, I: Serialize + Clone + Ord + Debug> FinalizableToResult<I> for Round3<P, I> {
@@ -370,7 +437,6 @@ impl<P: SchemeParams, I: Serialize + Clone + Ord + Debug> FinalizableToResult<I>
             owner: my_id,
             secret_share: SecretBox::new(Box::new(self.context.x)),
             public_shares,
-            phantom: PhantomData,
         })
     }
 }
@@ -378,16 +444,80 @@ impl<P: SchemeParams, I: Serialize + Clone + Ord + Debug> FinalizableToResult<I>
 #[cfg(test)]
 mod tests {
     use alloc::collections::{BTreeMap, BTreeSet};
+    use core::marker::PhantomData;
 
-    use rand_core::{OsRng, RngCore};
+    use alloc::vec::Vec;
+
+    use rand_chacha::ChaCha8Rng;
+    use rand_core::{OsRng, RngCore, SeedableRng};
     use secrecy::ExposeSecret;
 
-    use super::Round1;
-    use crate::cggmp21::TestParams;
+    use super::{KeyInitError, PublicData, Round1};
+    use crate::cggmp21::sigma::{SchCommitment, SchSecret};
+    use crate::cggmp21::{SchemeParams, TestParams};
+    use crate::curve::Secp256k1;
     use crate::rounds::{
-        test_utils::{step_next_round, step_result, step_round, Id, Without},
-        FirstRound,
+        test_utils::{state_digests, step_next_round, step_result, step_round, Id, Without},
+        FirstRound, Round,
     };
+    use crate::tools::bitvec::BitVec;
+    use crate::tools::hashing::{FofHasher, HashOutput};
+
+    type Scalar = crate::curve::Scalar<Secp256k1>;
+
+    /// Runs KeyGen with the given seed, returning the state digests of all the nodes at each round.
+    fn keygen_state_digests(seed: u64) -> Vec<BTreeMap<Id, Option<HashOutput>>> {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let shared_randomness = [0u8; 32];
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+
+        let r1 = ids
+            .iter()
+            .map(|id| {
+                let round = Round1::<TestParams, Id>::new(
+                    &mut rng,
+                    &shared_randomness,
+                    ids.clone().without(id),
+                    *id,
+                    (),
+                )
+                .unwrap();
+                (*id, round)
+            })
+            .collect();
+
+        let mut digests = Vec::new();
+        digests.push(state_digests(&r1));
+        let r1a = step_round(&mut rng, r1).unwrap();
+        let r2 = step_next_round(&mut rng, r1a).unwrap();
+        digests.push(state_digests(&r2));
+        let r2a = step_round(&mut rng, r2).unwrap();
+        let r3 = step_next_round(&mut rng, r2a).unwrap();
+        digests.push(state_digests(&r3));
+        let r3a = step_round(&mut rng, r3).unwrap();
+        step_result(&mut rng, r3a).unwrap();
+
+        digests
+    }
+
+    #[test]
+    fn state_digests_are_reproducible() {
+        let digests = keygen_state_digests(123);
+
+        // All the rounds support digests, and they are different for each node and round.
+        let all_digests = digests
+            .iter()
+            .flat_map(|round_digests| round_digests.values())
+            .map(|digest| digest.unwrap())
+            .collect::<BTreeSet<_>>();
+        assert_eq!(all_digests.len(), 9);
+
+        // The same randomness leads to the same states...
+        assert_eq!(keygen_state_digests(123), digests);
+
+        // ...and different randomness to different ones.
+        assert_ne!(keygen_state_digests(456), digests);
+    }
 
     #[test]
     fn execute_keygen() {
@@ -437,4 +567,74 @@ mod tests {
 
         assert!(public_set == &public_from_secret);
     }
+
+    #[test]
+    fn rid_is_bound_to_commitment() {
+        let mut shared_randomness = [0u8; 32];
+        OsRng.fill_bytes(&mut shared_randomness);
+
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+
+        let mut r1 = ids
+            .iter()
+            .map(|id| {
+                let round = Round1::<TestParams, Id>::new(
+                    &mut OsRng,
+                    &shared_randomness,
+                    ids.clone().without(id),
+                    *id,
+                    (),
+                )
+                .unwrap();
+                (*id, round)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        // `Id(2)` commits to a `rid` of a wrong length
+        r1.get_mut(&Id(2)).unwrap().context.public_data.rid = BitVec::random(&mut OsRng, 8);
+
+        let r1a = step_round(&mut OsRng, r1).unwrap();
+        let r2 = step_next_round(&mut OsRng, r1a).unwrap();
+
+        // An honest message is accepted
+        let message = r2[&Id(1)].make_broadcast_message(&mut OsRng).unwrap();
+        assert!(r2[&Id(0)]
+            .verify_message(&mut OsRng, &Id(1), message.clone(), ())
+            .is_ok());
+
+        // Revealing a `rid` different from the committed one is detected
+        let mut forged_message = message;
+        forged_message.data.rid = BitVec::random(&mut OsRng, TestParams::SECURITY_PARAMETER);
+        let result = r2[&Id(0)].verify_message(&mut OsRng, &Id(1), forged_message, ());
+        assert!(matches!(result, Err(KeyInitError::R2HashMismatch)));
+
+        // A committed `rid` of a wrong length is rejected instead of breaking the combination
+        let message = r2[&Id(2)].make_broadcast_message(&mut OsRng).unwrap();
+        let result = r2[&Id(0)].verify_message(&mut OsRng, &Id(2), message, ());
+        assert!(matches!(result, Err(KeyInitError::R2InvalidRidLength)));
+    }
+
+    #[test]
+    fn public_data_hash_is_unambiguous() {
+        let sid_hash = FofHasher::new_with_dst(b"SID").finalize();
+        let x = Scalar::random(&mut OsRng);
+        let tau = SchSecret::random(&mut OsRng);
+
+        let make_data = |rid: &[u8], u: &[u8]| PublicData::<TestParams> {
+            cap_x: x.mul_by_generator(),
+            cap_a: SchCommitment::new(&tau),
+            rid: BitVec::from_bytes(rid),
+            u: BitVec::from_bytes(u),
+            phantom: PhantomData,
+        };
+
+        // The same bytes split differently between `rid` and `u`
+        // would produce the same hash without length prefixes.
+        let data1 = make_data(&[1, 2, 3], &[4]);
+        let data2 = make_data(&[1, 2], &[3, 4]);
+        assert_ne!(data1.hash(&sid_hash, Id(0)), data2.hash(&sid_hash, Id(0)));
+
+        let data3 = make_data(&[1, 2, 3], &[4]);
+        assert_eq!(data1.hash(&sid_hash, Id(0)), data3.hash(&sid_hash, Id(0)));
+    }
 }
@@ -6,6 +6,7 @@ use alloc::boxed::Box;
 use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::string::String;
 use alloc::vec::Vec;
+use core::convert::Infallible;
 use core::fmt::Debug;
 use core::marker::PhantomData;
 
@@ -15,9 +16,9 @@ use serde::{Deserialize, Serialize};
 
 use super::super::{
     sigma::{FacProof, ModProof, PrmProof, SchCommitment, SchProof, SchSecret},
-    AuxInfo, KeyShareChange, PublicAuxInfo, SchemeParams, SecretAuxInfo,
+    AuxInfo, KeyShareChange, PaillierPrimes, PublicAuxInfo, SchemeParams, SecretAuxInfo,
 };
-use crate::curve::{Point, Scalar};
+use crate::curve::{Curve, Point, Scalar};
 use crate::paillier::{
     Ciphertext, CiphertextMod, PublicKeyPaillier, PublicKeyPaillierPrecomputed, RPParams,
     RPParamsMod, RPSecret, Randomizer, SecretKeyPaillier, SecretKeyPaillierPrecomputed,
@@ -38,6 +39,7 @@ impl<P: SchemeParams, I: Debug + Ord> ProtocolResult for KeyRefreshResult<P, I>
     type Success = (KeyShareChange<P, I>, AuxInfo<P, I>);
     type ProvableError = KeyRefreshError<P>;
     type CorrectnessProof = ();
+    type RetryReason = Infallible;
 }
 
 #[derive(Debug)]
@@ -55,7 +57,7 @@ enum KeyRefreshErrorEnum<P: SchemeParams> {
     #[allow(dead_code)]
     Round3MismatchedSecret {
         cap_c: Ciphertext<P::Paillier>,
-        x: Scalar,
+        x: Scalar<P::Curve>,
         mu: Randomizer<P::Paillier>,
     },
 }
@@ -67,10 +69,10 @@ enum KeyRefreshErrorEnum<P: SchemeParams> {
         PrmProof<P>: for<'x> Deserialize<'x>,
     "))]
 pub struct PublicData1<P: SchemeParams> {
-    cap_x_to_send: Vec<Point>, // $X_i^j$ where $i$ is this party's index
-    cap_a_to_send: Vec<SchCommitment>, // $A_i^j$ where $i$ is this party's index
-    cap_y: Point,
-    cap_b: SchCommitment,
+    cap_x_to_send: Vec<Point<P::Curve>>, // $X_i^j$ where $i$ is this party's index
+    cap_a_to_send: Vec<SchCommitment<P::Curve>>, // $A_i^j$ where $i$ is this party's index
+    cap_y: Point<P::Curve>,
+    cap_b: SchCommitment<P::Curve>,
     paillier_pk: PublicKeyPaillier<P::Paillier>, // $N_i$
     rp_params: RPParams<P::Paillier>,            // $s_i$ and $t_i$
     hat_psi: PrmProof<P>,
@@ -87,10 +89,10 @@ pub struct PublicData1Precomp<P: SchemeParams> {
 
 struct Context<P: SchemeParams, I> {
     paillier_sk: SecretKeyPaillierPrecomputed<P::Paillier>,
-    y: Scalar,
-    x_to_send: BTreeMap<I, Scalar>, // $x_i^j$ where $i$ is this party's index
-    tau_y: SchSecret,
-    tau_x: BTreeMap<I, SchSecret>,
+    y: Scalar<P::Curve>,
+    x_to_send: BTreeMap<I, Scalar<P::Curve>>, // $x_i^j$ where $i$ is this party's index
+    tau_y: SchSecret<P::Curve>,
+    tau_x: BTreeMap<I, SchSecret<P::Curve>>,
     data_precomp: PublicData1Precomp<P>,
     my_id: I,
     other_ids: BTreeSet<I>,
@@ -98,6 +100,18 @@ struct Context<P: SchemeParams, I> {
     ids_ordering: BTreeMap<I, usize>,
 }
 
+#[cfg(any(test, feature = "state-digest"))]
+impl<P: SchemeParams, I: Serialize> Context<P, I> {
+    /// Starts the state digest with the public values known from the start of the protocol.
+    fn state_hasher(&self, round_num: u8) -> FofHasher {
+        FofHasher::new_with_dst(b"KeyRefresh state")
+            .chain(&round_num)
+            .chain(&self.sid_hash)
+            .chain(&self.my_id)
+            .chain(&self.data_precomp.data)
+    }
+}
+
 impl<P: SchemeParams> PublicData1<P> {
     fn hash<I: Serialize>(&self, sid_hash: &HashOutput, id: &I) -> HashOutput {
         FofHasher::new_with_dst(b"Auxiliary")
@@ -112,14 +126,23 @@ pub struct Round1<P: SchemeParams, I> {
     context: Context<P, I>,
 }
 
+#[cfg(test)]
+impl<P: SchemeParams, I> Round1<P, I> {
+    /// The session identifier hash this round was seeded with.
+    pub(crate) fn sid_hash(&self) -> &HashOutput {
+        &self.context.sid_hash
+    }
+}
+
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FirstRound<I> for Round1<P, I> {
-    type Inputs = ();
+    /// Optionally, externally generated primes for the Paillier key of this party.
+    type Inputs = Option<PaillierPrimes<P>>;
     fn new(
         rng: &mut impl CryptoRngCore,
         shared_randomness: &[u8],
         other_ids: BTreeSet<I>,
         my_id: I,
-        _inputs: Self::Inputs,
+        inputs: Self::Inputs,
     ) -> Result<Self, InitError> {
         let mut all_ids = other_ids.clone();
         all_ids.insert(my_id.clone());
@@ -138,12 +161,16 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FirstRound<I> for Roun
             .finalize();
 
         // $p_i$, $q_i$
-        let paillier_sk = SecretKeyPaillier::<P::Paillier>::random(rng).to_precomputed();
+        let paillier_sk = match inputs {
+            Some(primes) => primes.0,
+            None => SecretKeyPaillier::<P::Paillier>::random(rng),
+        }
+        .to_precomputed();
         // $N_i$
         let paillier_pk = paillier_sk.public_key();
 
         // El-Gamal key
-        let y = Scalar::random(rng);
+        let y = Scalar::<P::Curve>::random(rng);
         let cap_y = y.mul_by_generator();
 
         // The secret and the commitment for the Schnorr PoK of the El-Gamal key
@@ -154,7 +181,7 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FirstRound<I> for Roun
         let x_to_send = all_ids
             .iter()
             .cloned()
-            .zip(Scalar::ZERO.split(rng, all_ids.len()))
+            .zip(Scalar::<P::Curve>::zero_sum(rng, all_ids.len()))
             .collect::<BTreeMap<_, _>>();
 
         // Public counterparts of secret share updates ($X_i^j$ where $i$ is this party's index).
@@ -237,6 +264,11 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round1<P,
         &self.context.my_id
     }
 
+    #[cfg(any(test, feature = "state-digest"))]
+    fn state_digest(&self) -> Option<HashOutput> {
+        Some(self.context.state_hasher(Self::ROUND_NUM).finalize())
+    }
+
     const REQUIRES_ECHO: bool = true;
     type BroadcastMessage = Round1Message;
     type DirectMessage = ();
@@ -322,6 +354,16 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round2<P,
         &self.context.my_id
     }
 
+    #[cfg(any(test, feature = "state-digest"))]
+    fn state_digest(&self) -> Option<HashOutput> {
+        Some(
+            self.context
+                .state_hasher(Self::ROUND_NUM)
+                .chain(&self.others_cap_v)
+                .finalize(),
+        )
+    }
+
     type BroadcastMessage = Round2Message<P>;
     type DirectMessage = ();
     type Payload = Round2Payload<P>;
@@ -361,7 +403,13 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round2<P,
             )));
         }
 
-        if broadcast_msg.data.cap_x_to_send.iter().sum::<Point>() != Point::IDENTITY {
+        if broadcast_msg
+            .data
+            .cap_x_to_send
+            .iter()
+            .sum::<Point<P::Curve>>()
+            != Point::<P::Curve>::IDENTITY
+        {
             return Err(KeyRefreshError(KeyRefreshErrorEnum::Round2(
                 "Sum of X points is not identity".into(),
             )));
@@ -384,6 +432,11 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round2<P,
             },
         })
     }
+
+    fn verification_cost(&self) -> usize {
+        // The `prm` proof.
+        1
+    }
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToNextRound<I>
@@ -414,7 +467,7 @@ pub struct Round3<P: SchemeParams, I> {
     rho: BitVec,
     others_data: BTreeMap<I, PublicData1Precomp<P>>,
     psi_mod: ModProof<P>,
-    pi: SchProof,
+    pi: SchProof<P::Curve>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -431,9 +484,9 @@ pub struct Round3<P: SchemeParams, I> {
 pub struct PublicData2<P: SchemeParams> {
     psi_mod: ModProof<P>, // $\psi_i$, a P^{mod} for the Paillier modulus
     phi: FacProof<P>,
-    pi: SchProof,
+    pi: SchProof<P::Curve>,
     paillier_enc_x: Ciphertext<P::Paillier>, // `C_j,i`
-    psi_sch: SchProof,                       // $psi_i^j$, a P^{sch} for the secret share change
+    psi_sch: SchProof<P::Curve>,             // $psi_i^j$, a P^{sch} for the secret share change
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round3<P, I> {
@@ -471,8 +524,8 @@ pub struct Round3Message<P: SchemeParams> {
     data2: PublicData2<P>,
 }
 
-pub struct Round3Payload {
-    x: Scalar, // $x_j^i$, a secret share change received from the party $j$
+pub struct Round3Payload<C: Curve> {
+    x: Scalar<C>, // $x_j^i$, a secret share change received from the party $j$
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round3<P, I> {
@@ -489,9 +542,27 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round3<P,
         &self.context.my_id
     }
 
+    #[cfg(any(test, feature = "state-digest"))]
+    fn state_digest(&self) -> Option<HashOutput> {
+        let others_data = self
+            .others_data
+            .iter()
+            .map(|(id, data)| (id, &data.data))
+            .collect::<BTreeMap<_, _>>();
+        Some(
+            self.context
+                .state_hasher(Self::ROUND_NUM)
+                .chain(&others_data)
+                .chain(&self.rho)
+                .chain(&self.psi_mod)
+                .chain(&self.pi)
+                .finalize(),
+        )
+    }
+
     type BroadcastMessage = ();
     type DirectMessage = Round3Message<P>;
-    type Payload = Round3Payload;
+    type Payload = Round3Payload<P::Curve>;
     type Artifact = ();
 
     no_broadcast_messages!();
@@ -550,7 +621,13 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round3<P,
         let enc_x = direct_msg
             .data2
             .paillier_enc_x
-            .to_mod(self.context.paillier_sk.public_key());
+            .clone()
+            .validated(self.context.paillier_sk.public_key())
+            .ok_or_else(|| {
+                KeyRefreshError(KeyRefreshErrorEnum::Round3(
+                    "The encrypted share is out of range".into(),
+                ))
+            })?;
 
         let x = P::scalar_from_uint(&enc_x.decrypt(&self.context.paillier_sk));
 
@@ -611,6 +688,11 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round3<P,
 
         Ok(Round3Payload { x })
     }
+
+    fn verification_cost(&self) -> usize {
+        // The decryption of the share, and the `mod`, `fac` and two `sch` proofs.
+        5
+    }
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I> for Round3<P, I> {
@@ -626,7 +708,8 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
             .collect::<BTreeMap<_, _>>();
 
         // The combined secret share change
-        let x_star = others_x.values().sum::<Scalar>() + self.context.x_to_send[self.my_id()];
+        let x_star =
+            others_x.values().sum::<Scalar<P::Curve>>() + self.context.x_to_send[self.my_id()];
 
         let my_id = self.context.my_id.clone();
         let mut all_ids = self.context.other_ids;
@@ -673,7 +756,6 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
             owner: my_id.clone(),
             secret_share_change: SecretBox::new(Box::new(x_star)),
             public_share_changes: cap_x_star,
-            phantom: PhantomData,
         };
 
         let aux_info = AuxInfo {
@@ -696,12 +778,14 @@ mod tests {
 
     use super::Round1;
     use crate::cggmp21::TestParams;
-    use crate::curve::Scalar;
+    use crate::curve::Secp256k1;
     use crate::rounds::{
         test_utils::{step_next_round, step_result, step_round, Id, Without},
         FirstRound,
     };
 
+    type Scalar = crate::curve::Scalar<Secp256k1>;
+
     #[test]
     fn execute_key_refresh() {
         let mut shared_randomness = [0u8; 32];
@@ -717,7 +801,7 @@ mod tests {
                     &shared_randomness,
                     ids.clone().without(id),
                     *id,
-                    (),
+                    None,
                 )
                 .unwrap();
                 (*id, round)
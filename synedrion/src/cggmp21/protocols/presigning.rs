@@ -2,22 +2,30 @@
 
 use alloc::boxed::Box;
 use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
 use alloc::string::String;
+use alloc::vec;
 use alloc::vec::Vec;
 use core::fmt::Debug;
 use core::marker::PhantomData;
 
-use rand_core::CryptoRngCore;
+use displaydoc::Display;
+use rand_chacha::ChaCha8Rng;
+use rand_core::{CryptoRng, CryptoRngCore, RngCore, SeedableRng};
 use secrecy::{ExposeSecret, SecretBox};
 use serde::{Deserialize, Serialize};
 
 use super::super::{
-    entities::{AuxInfoPrecomputed, PresigningValues},
-    sigma::{AffGProof, DecProof, EncProof, LogStarProof, MulProof},
+    entities::{AuxInfoPrecomputed, PresigningProduct, PresigningValues},
+    params::validate_scheme_params,
+    sigma::{AffGBatchItem, AffGProof, DecProof, EncProof, LogStarProof, MulProof},
     AuxInfo, KeyShare, PresigningData, SchemeParams,
 };
 use crate::curve::{Point, Scalar};
-use crate::paillier::{Ciphertext, CiphertextMod, PaillierParams, Randomizer, RandomizerMod};
+use crate::paillier::{
+    Ciphertext, CiphertextMod, PaillierParams, Randomizer, RandomizerMod,
+    SecretKeyPaillierPrecomputed,
+};
 use crate::rounds::{
     no_broadcast_messages, FinalizableToNextRound, FinalizableToResult, FinalizeError, FirstRound,
     InitError, ProtocolResult, Round, ToNextRound, ToResult,
@@ -33,6 +41,18 @@ impl<P: SchemeParams, I: Debug> ProtocolResult for PresigningResult<P, I> {
     type Success = PresigningData<P, I>;
     type ProvableError = PresigningError;
     type CorrectnessProof = PresigningProof<P, I>;
+    type RetryReason = PresigningRetryReason;
+}
+
+/// The reasons to restart the Presigning protocol when no party is at fault.
+///
+/// Honest parties produce these with a negligible probability.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum PresigningRetryReason {
+    /// The combined `delta` is zero, so the nonce cannot be derived from it
+    ZeroDelta,
+    /// The x-coordinate of the nonce is zero, so it cannot be used to create a signature
+    ZeroNonce,
 }
 
 /// Possible verifiable errors of the Presigning protocol.
@@ -44,6 +64,40 @@ pub enum PresigningError {
     Round2(String),
     /// An error in Round 3.
     Round3(String),
+    /// A message failed to verify.
+    VerificationFail {
+        /// The round of the message.
+        round: u8,
+        /// The check that failed.
+        reason: VerificationFailReason,
+    },
+}
+
+/// A check of a message or a correctness proof of the Presigning protocol that failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum VerificationFailReason {
+    /// Failed to verify EncProof
+    Enc,
+    /// Failed to verify AffGProof (psi)
+    AffGPsi,
+    /// Failed to verify AffGProof (hat_psi)
+    AffGPsiHat,
+    /// The proof `hat_psi_prime` for `Gamma` in a Round 2 direct message.
+    #[displaydoc("Failed to verify LogStarProof")]
+    LogStar,
+    /// The proof `psi_pprime` for `Delta` in a Round 3 direct message.
+    #[displaydoc("Failed to verify Log-Star proof")]
+    LogStarDelta,
+    /// A decrypted value is out of range
+    OutOfRange,
+    /// The proof for `H` in a [`PresigningProof`].
+    #[displaydoc("Failed to verify MulProof")]
+    Mul,
+    /// The proof for `delta` in a [`PresigningProof`].
+    #[displaydoc("Failed to verify DecProof")]
+    Dec,
+    /// The correctness proof is missing or does not match the messages of the prover
+    InconsistentProof,
 }
 
 struct Context<P: SchemeParams, I: Ord> {
@@ -52,10 +106,77 @@ struct Context<P: SchemeParams, I: Ord> {
     other_ids: BTreeSet<I>,
     key_share: KeyShare<P, I>,
     aux_info: AuxInfoPrecomputed<P, I>,
-    k: Scalar,
-    gamma: Scalar,
+    nonce_only: bool,
+    k: Scalar<P::Curve>,
+    gamma: Scalar<P::Curve>,
     rho: RandomizerMod<P::Paillier>,
     nu: RandomizerMod<P::Paillier>,
+    // The seed for the randomness of the messages and the correctness proofs (see `Inputs`).
+    rng_seed: Option<[u8; 32]>,
+}
+
+/// The RNG used for a message or the correctness proofs (see [`Context::message_rng`]).
+enum MessageRng<'a, R> {
+    Given(&'a mut R),
+    Seeded(Box<ChaCha8Rng>),
+}
+
+impl<R: RngCore> RngCore for MessageRng<'_, R> {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            Self::Given(rng) => rng.next_u32(),
+            Self::Seeded(rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            Self::Given(rng) => rng.next_u64(),
+            Self::Seeded(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            Self::Given(rng) => rng.fill_bytes(dest),
+            Self::Seeded(rng) => rng.fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        match self {
+            Self::Given(rng) => rng.try_fill_bytes(dest),
+            Self::Seeded(rng) => rng.try_fill_bytes(dest),
+        }
+    }
+}
+
+impl<R: CryptoRng> CryptoRng for MessageRng<'_, R> {}
+
+impl<P: SchemeParams, I: Ord + Serialize> Context<P, I> {
+    /// Returns the RNG to use for a message to `destination` in the given round
+    /// (or for the correctness proofs, if `destination` is `None`).
+    ///
+    /// If the session was created with a seed, the RNG is derived from it,
+    /// otherwise the given `rng` is used.
+    fn message_rng<'a, R: CryptoRngCore>(
+        &self,
+        rng: &'a mut R,
+        round_num: u8,
+        destination: Option<&I>,
+    ) -> MessageRng<'a, R> {
+        match &self.rng_seed {
+            Some(rng_seed) => {
+                let seed = FofHasher::new_with_dst(b"Presigning RNG")
+                    .chain(rng_seed)
+                    .chain(&round_num)
+                    .chain(&destination)
+                    .finalize();
+                MessageRng::Seeded(Box::new(ChaCha8Rng::from_seed(seed.0)))
+            }
+            None => MessageRng::Given(rng),
+        }
+    }
 }
 
 pub struct Round1<P: SchemeParams, I: Ord> {
@@ -64,8 +185,23 @@ pub struct Round1<P: SchemeParams, I: Ord> {
     cap_g: CiphertextMod<P::Paillier>,
 }
 
+#[derive(Clone)]
+pub struct Inputs<P: SchemeParams, I: Ord> {
+    pub key_share: KeyShare<P, I>,
+    pub aux_info: AuxInfo<P, I>,
+    /// If `true`, only the nonce is generated, skipping the calculation of the share of `k * x`.
+    /// The resulting [`PresigningData`] cannot be used for signing,
+    /// but provides the nonce point and the ephemeral scalar shares.
+    pub nonce_only: bool,
+    /// If set, the randomness of the messages and the correctness proofs in all the rounds
+    /// is derived from this seed instead of being taken from the RNGs passed to the rounds.
+    ///
+    /// **Insecure, for tests only**: the secrets are known to anyone who knows the seed.
+    pub rng_seed: Option<[u8; 32]>,
+}
+
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FirstRound<I> for Round1<P, I> {
-    type Inputs = (KeyShare<P, I>, AuxInfo<P, I>);
+    type Inputs = Inputs<P, I>;
     fn new(
         rng: &mut impl CryptoRngCore,
         shared_randomness: &[u8],
@@ -73,7 +209,52 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FirstRound<I> for Roun
         my_id: I,
         inputs: Self::Inputs,
     ) -> Result<Self, InitError> {
-        let (key_share, aux_info) = inputs;
+        let Inputs {
+            key_share,
+            aux_info,
+            nonce_only,
+            rng_seed,
+        } = inputs;
+
+        // The protocol runs over the parties in `other_ids` and `my_id` only,
+        // and all the aggregations are performed over this set.
+        // So the key share must be an additive sharing between exactly these parties
+        // (e.g. obtained via `ThresholdKeyShare::to_key_share()` for the chosen quorum),
+        // otherwise the contributions of the absent parties would be silently lost.
+        let mut participants = other_ids.clone();
+        participants.insert(my_id.clone());
+
+        if key_share.all_parties() != participants {
+            return Err(InitError(
+                "The key share must be shared between exactly the participating parties".into(),
+            ));
+        }
+
+        // A share of another party would fail only deep in the protocol, in one of the proofs.
+        if key_share.owner() != &my_id {
+            return Err(InitError(format!(
+                "The key share belongs to {:?}, not to this party ({my_id:?})",
+                key_share.owner()
+            )));
+        }
+        if aux_info.owner() != &my_id {
+            return Err(InitError(format!(
+                "The auxiliary data belongs to {:?}, not to this party ({my_id:?})",
+                aux_info.owner()
+            )));
+        }
+
+        validate_scheme_params::<P>(participants.len())
+            .map_err(|err| InitError(format!("Invalid scheme parameters: {err}")))?;
+
+        // The public auxiliary data is hashed into the session ID below,
+        // so all the parties must use the data for exactly the participating parties
+        // (e.g. restricted via `AuxInfo::to_subset()` for the chosen quorum).
+        if !aux_info.public_aux.keys().eq(participants.iter()) {
+            return Err(InitError(
+                "The auxiliary data must be for exactly the participating parties".into(),
+            ));
+        }
 
         // This includes the info of $ssid$ in the paper
         // (scheme parameters + public data from all shares - hashed in `share_set_id`),
@@ -87,12 +268,10 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FirstRound<I> for Roun
 
         let aux_info = aux_info.to_precomputed();
 
-        // TODO (#68): check that KeyShare is consistent with num_parties/party_idx
-
         // The share of an ephemeral scalar
-        let k = Scalar::random(rng);
+        let k = Scalar::<P::Curve>::random(rng);
         // The share of the mask used to generate the inverse of the ephemeral scalar
-        let gamma = Scalar::random(rng);
+        let gamma = Scalar::<P::Curve>::random(rng);
 
         let pk = aux_info.secret_aux.paillier_sk.public_key();
 
@@ -111,10 +290,12 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FirstRound<I> for Roun
                 other_ids,
                 key_share,
                 aux_info,
+                nonce_only,
                 k,
                 gamma,
                 rho,
                 nu,
+                rng_seed,
             },
             cap_k,
             cap_g,
@@ -177,6 +358,9 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round1<P,
         rng: &mut impl CryptoRngCore,
         destination: &I,
     ) -> (Self::DirectMessage, Self::Artifact) {
+        let rng = &mut self
+            .context
+            .message_rng(rng, Self::ROUND_NUM, Some(destination));
         let aux = (&self.context.ssid_hash, &destination);
         let psi0 = EncProof::new(
             rng,
@@ -210,7 +394,10 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round1<P,
             &public_aux.rp_params,
             &aux,
         ) {
-            return Err(PresigningError::Round1("Failed to verify EncProof".into()));
+            return Err(PresigningError::VerificationFail {
+                round: 1,
+                reason: VerificationFailReason::Enc,
+            });
         }
 
         Ok(Round1Payload {
@@ -218,6 +405,11 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round1<P,
             cap_g: broadcast_msg.cap_g,
         })
     }
+
+    fn verification_cost(&self) -> usize {
+        // The `enc` proof.
+        1
+    }
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToNextRound<I>
@@ -283,35 +475,62 @@ pub struct Round2<P: SchemeParams, I: Ord> {
     LogStarProof<P>: for<'x> Deserialize<'x>,
 "))]
 pub struct Round2Message<P: SchemeParams> {
-    cap_gamma: Point,
+    cap_gamma: Point<P::Curve>,
     cap_d: Ciphertext<P::Paillier>,
-    hat_cap_d: Ciphertext<P::Paillier>,
     cap_f: Ciphertext<P::Paillier>,
-    hat_cap_f: Ciphertext<P::Paillier>,
     psi: AffGProof<P>,
-    hat_psi: AffGProof<P>,
     hat_psi_prime: LogStarProof<P>,
+    // `None` in the nonce-only mode.
+    product: Option<Round2ProductMessage<P>>,
+}
+
+/// The part of the Round 2 message used to calculate the share of `k * x`.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "
+    Ciphertext<P::Paillier>: Serialize,
+    AffGProof<P>: Serialize,
+"))]
+#[serde(bound(deserialize = "
+    Ciphertext<P::Paillier>: for<'x> Deserialize<'x>,
+    AffGProof<P>: for<'x> Deserialize<'x>,
+"))]
+pub struct Round2ProductMessage<P: SchemeParams> {
+    hat_cap_d: Ciphertext<P::Paillier>,
+    hat_cap_f: Ciphertext<P::Paillier>,
+    hat_psi: AffGProof<P>,
 }
 
 #[derive(Debug, Clone)]
 pub struct Round2Artifact<P: SchemeParams> {
     beta: SecretBox<Signed<<P::Paillier as PaillierParams>::Uint>>,
-    hat_beta: SecretBox<Signed<<P::Paillier as PaillierParams>::Uint>>,
     r: Randomizer<P::Paillier>,
     s: Randomizer<P::Paillier>,
-    hat_r: Randomizer<P::Paillier>,
-    hat_s: Randomizer<P::Paillier>,
     cap_d: CiphertextMod<P::Paillier>,
     cap_f: CiphertextMod<P::Paillier>,
+    // `None` in the nonce-only mode.
+    product: Option<Round2ProductArtifact<P>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Round2ProductArtifact<P: SchemeParams> {
+    hat_beta: SecretBox<Signed<<P::Paillier as PaillierParams>::Uint>>,
+    hat_r: Randomizer<P::Paillier>,
+    hat_s: Randomizer<P::Paillier>,
     hat_cap_d: CiphertextMod<P::Paillier>,
     hat_cap_f: CiphertextMod<P::Paillier>,
 }
 
 pub struct Round2Payload<P: SchemeParams> {
-    cap_gamma: Point,
+    cap_gamma: Point<P::Curve>,
     alpha: Signed<<P::Paillier as PaillierParams>::Uint>,
-    hat_alpha: Signed<<P::Paillier as PaillierParams>::Uint>,
     cap_d: CiphertextMod<P::Paillier>,
+    cap_f: CiphertextMod<P::Paillier>,
+    // `None` in the nonce-only mode.
+    product: Option<Round2ProductPayload<P>>,
+}
+
+pub struct Round2ProductPayload<P: SchemeParams> {
+    hat_alpha: Signed<<P::Paillier as PaillierParams>::Uint>,
     hat_cap_d: CiphertextMod<P::Paillier>,
 }
 
@@ -341,6 +560,9 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round2<P,
         rng: &mut impl CryptoRngCore,
         destination: &I,
     ) -> (Self::DirectMessage, Self::Artifact) {
+        let rng = &mut self
+            .context
+            .message_rng(rng, Self::ROUND_NUM, Some(destination));
         let aux = (&self.context.ssid_hash, &self.my_id());
 
         let cap_gamma = self.context.gamma.mul_by_generator();
@@ -349,11 +571,8 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round2<P,
         let target_pk = &self.context.aux_info.public_aux[destination].paillier_pk;
 
         let beta = SecretBox::new(Box::new(Signed::random_bounded_bits(rng, P::LP_BOUND)));
-        let hat_beta = SecretBox::new(Box::new(Signed::random_bounded_bits(rng, P::LP_BOUND)));
         let r = RandomizerMod::random(rng, pk);
         let s = RandomizerMod::random(rng, target_pk);
-        let hat_r = RandomizerMod::random(rng, pk);
-        let hat_s = RandomizerMod::random(rng, target_pk);
 
         let cap_f =
             CiphertextMod::new_with_randomizer_signed(pk, beta.expose_secret(), &r.retrieve());
@@ -364,19 +583,6 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round2<P,
                 &s.retrieve(),
             );
 
-        let hat_cap_f = CiphertextMod::new_with_randomizer_signed(
-            pk,
-            hat_beta.expose_secret(),
-            &hat_r.retrieve(),
-        );
-        let hat_cap_d = &self.all_cap_k[destination]
-            * P::signed_from_scalar(self.context.key_share.secret_share.expose_secret())
-            + CiphertextMod::new_with_randomizer_signed(
-                target_pk,
-                &-hat_beta.expose_secret(),
-                &hat_s.retrieve(),
-            );
-
         let public_aux = &self.context.aux_info.public_aux[destination];
         let rp = &public_aux.rp_params;
 
@@ -396,68 +602,138 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round2<P,
             &aux,
         );
 
-        let hat_psi = AffGProof::new(
-            rng,
-            &P::signed_from_scalar(self.context.key_share.secret_share.expose_secret()),
-            &hat_beta,
-            hat_s.clone(),
-            hat_r.clone(),
-            target_pk,
-            pk,
-            &self.all_cap_k[destination],
-            &hat_cap_d,
-            &hat_cap_f,
-            &self.context.key_share.public_shares[self.my_id()],
-            rp,
-            &aux,
-        );
-
         let hat_psi_prime = LogStarProof::new(
             rng,
             &P::signed_from_scalar(&self.context.gamma),
             &self.context.nu,
             pk,
             &self.all_cap_g[self.my_id()],
-            &Point::GENERATOR,
+            &Point::<P::Curve>::GENERATOR,
             &cap_gamma,
             rp,
             &aux,
         );
 
+        // In the nonce-only mode the share of `k * x` is not needed,
+        // so we skip the corresponding MtA exchange.
+        let (product_msg, product_artifact) = if self.context.nonce_only {
+            (None, None)
+        } else {
+            let hat_beta = SecretBox::new(Box::new(Signed::random_bounded_bits(rng, P::LP_BOUND)));
+            let hat_r = RandomizerMod::random(rng, pk);
+            let hat_s = RandomizerMod::random(rng, target_pk);
+
+            let hat_cap_f = CiphertextMod::new_with_randomizer_signed(
+                pk,
+                hat_beta.expose_secret(),
+                &hat_r.retrieve(),
+            );
+            let hat_cap_d = &self.all_cap_k[destination]
+                * P::signed_from_scalar(self.context.key_share.secret_share.expose_secret())
+                + CiphertextMod::new_with_randomizer_signed(
+                    target_pk,
+                    &-hat_beta.expose_secret(),
+                    &hat_s.retrieve(),
+                );
+
+            let hat_psi = AffGProof::new(
+                rng,
+                &P::signed_from_scalar(self.context.key_share.secret_share.expose_secret()),
+                &hat_beta,
+                hat_s.clone(),
+                hat_r.clone(),
+                target_pk,
+                pk,
+                &self.all_cap_k[destination],
+                &hat_cap_d,
+                &hat_cap_f,
+                &self.context.key_share.public_shares[self.my_id()],
+                rp,
+                &aux,
+            );
+
+            let msg = Round2ProductMessage {
+                hat_cap_d: hat_cap_d.retrieve(),
+                hat_cap_f: hat_cap_f.retrieve(),
+                hat_psi,
+            };
+            let artifact = Round2ProductArtifact {
+                hat_beta,
+                hat_r: hat_r.retrieve(),
+                hat_s: hat_s.retrieve(),
+                hat_cap_d,
+                hat_cap_f,
+            };
+            (Some(msg), Some(artifact))
+        };
+
         let msg = Round2Message {
             cap_gamma,
             cap_d: cap_d.retrieve(),
             cap_f: cap_f.retrieve(),
-            hat_cap_d: hat_cap_d.retrieve(),
-            hat_cap_f: hat_cap_f.retrieve(),
             psi,
-            hat_psi,
             hat_psi_prime,
+            product: product_msg,
         };
 
         let artifact = Round2Artifact {
             beta,
-            hat_beta,
             r: r.retrieve(),
             s: s.retrieve(),
-            hat_r: hat_r.retrieve(),
-            hat_s: hat_s.retrieve(),
             cap_d,
             cap_f,
-            hat_cap_d,
-            hat_cap_f,
+            product: product_artifact,
         };
 
         (msg, artifact)
     }
 
+    fn validate_structure(
+        &self,
+        from: &I,
+        _broadcast_msg: &Self::BroadcastMessage,
+        direct_msg: &Self::DirectMessage,
+    ) -> Result<(), &'static str> {
+        if direct_msg.cap_gamma == Point::<P::Curve>::IDENTITY {
+            return Err("`Gamma` is the identity point");
+        }
+
+        if direct_msg.product.is_some() == self.context.nonce_only {
+            return Err("The message does not match the presigning mode");
+        }
+
+        let pk = self.context.aux_info.secret_aux.paillier_sk.public_key();
+        let from_pk = &self.context.aux_info.public_aux[from].paillier_pk;
+
+        if !direct_msg.cap_d.is_in_range(pk) {
+            return Err("`D` is out of range");
+        }
+        if !direct_msg.cap_f.is_in_range(from_pk) {
+            return Err("`F` is out of range");
+        }
+
+        if let Some(product) = &direct_msg.product {
+            if !product.hat_cap_d.is_in_range(pk) {
+                return Err("`hat{D}` is out of range");
+            }
+            if !product.hat_cap_f.is_in_range(from_pk) {
+                return Err("`hat{F}` is out of range");
+            }
+        }
+
+        Ok(())
+    }
+
     fn verify_message(
         &self,
-        _rng: &mut impl CryptoRngCore,
+        rng: &mut impl CryptoRngCore,
         from: &I,
         _broadcast_msg: Self::BroadcastMessage,
         direct_msg: Self::DirectMessage,
     ) -> Result<Self::Payload, <Self::Result as ProtocolResult>::ProvableError> {
+        // Note that `Round2Message` has a fixed structure for the given presigning mode,
+        // so the amount of homomorphic operations performed here
+        // does not depend on the contents of the message.
         let aux = (&self.context.ssid_hash, &from);
         let pk = &self.context.aux_info.secret_aux.paillier_sk.public_key();
         let from_pk = &self.context.aux_info.public_aux[from].paillier_pk;
@@ -467,73 +743,130 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round2<P,
         let public_aux = &self.context.aux_info.public_aux[self.my_id()];
         let rp = &public_aux.rp_params;
 
-        let cap_d = direct_msg.cap_d.to_mod(pk);
-        let hat_cap_d = direct_msg.hat_cap_d.to_mod(pk);
-
-        if !direct_msg.psi.verify(
-            pk,
-            from_pk,
-            &self.all_cap_k[self.my_id()],
-            &cap_d,
-            &direct_msg.cap_f.to_mod(from_pk),
-            &direct_msg.cap_gamma,
-            rp,
-            &aux,
-        ) {
+        // The range of `D` and `hat{D}` was checked in `validate_structure()`,
+        // but they are decrypted below, so they are converted with the checked method.
+        let cap_d = direct_msg
+            .cap_d
+            .validated(pk)
+            .ok_or_else(|| PresigningError::Round2("`D` is out of range".into()))?;
+
+        // `psi` and `hat_psi` prove that the sender applied an affine operation
+        // to *our* ciphertext `K`, while `hat_psi_prime` is about the *sender's* ciphertext `G`.
+        // Mixing these up would make us verify the proofs against the wrong ciphertexts,
+        // so we look them up once here and make sure they belong to different parties.
+        if from == self.my_id() {
             return Err(PresigningError::Round2(
-                "Failed to verify AffGProof (psi)".into(),
+                "The message sender cannot be the same as the receiver".into(),
             ));
         }
+        let my_cap_k = &self.all_cap_k[self.my_id()];
+        let from_cap_g = &self.all_cap_g[from];
+        let cap_f = direct_msg.cap_f.to_mod(from_pk);
+
+        let product = match (direct_msg.product, self.context.nonce_only) {
+            (None, true) => None,
+            (Some(product), false) => {
+                let hat_cap_d = product
+                    .hat_cap_d
+                    .validated(pk)
+                    .ok_or_else(|| PresigningError::Round2("`hat{D}` is out of range".into()))?;
+                let hat_cap_f = product.hat_cap_f.to_mod(from_pk);
+                Some((product.hat_psi, hat_cap_d, hat_cap_f))
+            }
+            _ => {
+                return Err(PresigningError::Round2(
+                    "The message does not match the presigning mode".into(),
+                ))
+            }
+        };
 
-        if !direct_msg.hat_psi.verify(
-            pk,
-            from_pk,
-            &self.all_cap_k[self.my_id()],
-            &hat_cap_d,
-            &direct_msg.hat_cap_f.to_mod(from_pk),
-            &cap_x,
-            rp,
-            &aux,
-        ) {
-            return Err(PresigningError::Round2(
-                "Failed to verify AffGProof (hat_psi)".into(),
-            ));
+        // `psi` and `hat_psi` share our `K` and setup parameters, so they are verified together.
+        // If the batch fails, `psi` is checked on its own to find out which of them is invalid.
+        let mut items = vec![AffGBatchItem {
+            proof: &direct_msg.psi,
+            pk1: from_pk,
+            cap_d: &cap_d,
+            cap_y: &cap_f,
+            cap_x: &direct_msg.cap_gamma,
+            aux: &aux,
+        }];
+        if let Some((hat_psi, hat_cap_d, hat_cap_f)) = &product {
+            items.push(AffGBatchItem {
+                proof: hat_psi,
+                pk1: from_pk,
+                cap_d: hat_cap_d,
+                cap_y: hat_cap_f,
+                cap_x: &cap_x,
+                aux: &aux,
+            });
+        }
+        if !AffGProof::verify_batch(rng, pk, my_cap_k, rp, &items) {
+            let reason = if product.is_none()
+                || !direct_msg.psi.verify(
+                    pk,
+                    from_pk,
+                    my_cap_k,
+                    &cap_d,
+                    &cap_f,
+                    &direct_msg.cap_gamma,
+                    rp,
+                    &aux,
+                ) {
+                VerificationFailReason::AffGPsi
+            } else {
+                VerificationFailReason::AffGPsiHat
+            };
+            return Err(PresigningError::VerificationFail { round: 2, reason });
         }
 
         if !direct_msg.hat_psi_prime.verify(
             from_pk,
-            &self.all_cap_g[from],
-            &Point::GENERATOR,
+            from_cap_g,
+            &Point::<P::Curve>::GENERATOR,
             &direct_msg.cap_gamma,
             rp,
             &aux,
         ) {
-            return Err(PresigningError::Round2(
-                "Failed to verify LogStarProof".into(),
-            ));
+            return Err(PresigningError::VerificationFail {
+                round: 2,
+                reason: VerificationFailReason::LogStar,
+            });
         }
 
-        let alpha = cap_d.decrypt_signed(&self.context.aux_info.secret_aux.paillier_sk);
-        let hat_alpha = hat_cap_d.decrypt_signed(&self.context.aux_info.secret_aux.paillier_sk);
-
-        // `alpha == x * y + z` where `0 <= x, y < q`, and `-2^l' <= z <= 2^l'`,
-        // where `q` is the curve order.
-        // We will need this bound later, so we're asserting it.
-        let alpha = alpha
-            .assert_bit_bound_usize(core::cmp::max(2 * P::L_BOUND, P::LP_BOUND) + 1)
-            .unwrap();
-        let hat_alpha = hat_alpha
-            .assert_bit_bound_usize(core::cmp::max(2 * P::L_BOUND, P::LP_BOUND) + 1)
-            .unwrap();
+        let alpha = decrypt_bounded::<P>(&cap_d, &self.context.aux_info.secret_aux.paillier_sk)?;
+
+        // `hat_alpha == x * k + hat_beta`, where `x` is the sender's secret share,
+        // so the same bound as for `alpha` applies.
+        let product = product
+            .map(|(_hat_psi, hat_cap_d, _hat_cap_f)| {
+                decrypt_bounded::<P>(&hat_cap_d, &self.context.aux_info.secret_aux.paillier_sk).map(
+                    |hat_alpha| Round2ProductPayload {
+                        hat_alpha,
+                        hat_cap_d,
+                    },
+                )
+            })
+            .transpose()?;
 
         Ok(Round2Payload {
             cap_gamma: direct_msg.cap_gamma,
             alpha,
-            hat_alpha,
             cap_d,
-            hat_cap_d,
+            cap_f,
+            product,
         })
     }
+
+    fn verification_cost(&self) -> usize {
+        if self.context.nonce_only {
+            // The `aff-g` proof for `D`, the `log*` proof, and the decryption of `D`.
+            3
+        } else {
+            // The batch of two `aff-g` proofs and the separate check of the first one if it fails,
+            // the `log*` proof, and the decryptions of `D` and `hat{D}`.
+            6
+        }
+    }
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToNextRound<I>
@@ -549,7 +882,7 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToNextRound
         let cap_gamma = payloads
             .values()
             .map(|payload| payload.cap_gamma)
-            .sum::<Point>()
+            .sum::<Point<P::Curve>>()
             + self.context.gamma.mul_by_generator();
 
         let cap_delta = cap_gamma * self.context.k;
@@ -561,20 +894,41 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToNextRound
             + alpha_sum
             + beta_sum;
 
-        let hat_alpha_sum: Signed<_> = payloads.values().map(|payload| payload.hat_alpha).sum();
-        let hat_beta_sum: Signed<_> = artifacts
-            .values()
-            .map(|artifact| artifact.hat_beta.expose_secret())
-            .sum();
-        let chi = P::signed_from_scalar(self.context.key_share.secret_share.expose_secret())
-            * P::signed_from_scalar(&self.context.k)
-            + hat_alpha_sum
-            + hat_beta_sum;
+        // The message structure was checked against the mode in `verify_message()`,
+        // so in the regular mode all the payloads and artifacts have the product part.
+        let chi = if self.context.nonce_only {
+            None
+        } else {
+            let hat_alpha_sum: Signed<_> = payloads
+                .values()
+                .filter_map(|payload| payload.product.as_ref())
+                .map(|product| product.hat_alpha)
+                .sum();
+            let hat_beta_sum: Signed<_> = artifacts
+                .values()
+                .filter_map(|artifact| artifact.product.as_ref())
+                .map(|product| product.hat_beta.expose_secret())
+                .sum();
+            Some(
+                P::signed_from_scalar(self.context.key_share.secret_share.expose_secret())
+                    * P::signed_from_scalar(&self.context.k)
+                    + hat_alpha_sum
+                    + hat_beta_sum,
+            )
+        };
 
-        let (cap_ds, hat_cap_ds) = payloads
-            .into_iter()
-            .map(|(id, payload)| ((id.clone(), payload.cap_d), (id, payload.hat_cap_d)))
-            .unzip();
+        let mut cap_ds = BTreeMap::new();
+        let mut cap_fs = BTreeMap::new();
+        let mut hat_cap_ds = BTreeMap::new();
+        let mut cap_gammas = BTreeMap::new();
+        for (id, payload) in payloads {
+            if let Some(product) = payload.product {
+                hat_cap_ds.insert(id.clone(), product.hat_cap_d);
+            }
+            cap_ds.insert(id.clone(), payload.cap_d);
+            cap_fs.insert(id.clone(), payload.cap_f);
+            cap_gammas.insert(id, payload.cap_gamma);
+        }
 
         Ok(Round3 {
             context: self.context,
@@ -584,7 +938,9 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToNextRound
             cap_gamma,
             all_cap_k: self.all_cap_k,
             all_cap_g: self.all_cap_g,
+            cap_gammas,
             cap_ds,
+            cap_fs,
             hat_cap_ds,
             round2_artifacts: artifacts,
         })
@@ -594,12 +950,17 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToNextRound
 pub struct Round3<P: SchemeParams, I: Ord> {
     context: Context<P, I>,
     delta: Signed<<P::Paillier as PaillierParams>::Uint>,
-    chi: Signed<<P::Paillier as PaillierParams>::Uint>,
-    cap_delta: Point,
-    cap_gamma: Point,
+    // `None` in the nonce-only mode.
+    chi: Option<Signed<<P::Paillier as PaillierParams>::Uint>>,
+    cap_delta: Point<P::Curve>,
+    cap_gamma: Point<P::Curve>,
     all_cap_k: BTreeMap<I, CiphertextMod<P::Paillier>>,
     all_cap_g: BTreeMap<I, CiphertextMod<P::Paillier>>,
+    // The values received from the other parties in Round 2,
+    // kept to verify their correctness proofs if the protocol fails.
+    cap_gammas: BTreeMap<I, Point<P::Curve>>,
     cap_ds: BTreeMap<I, CiphertextMod<P::Paillier>>,
+    cap_fs: BTreeMap<I, CiphertextMod<P::Paillier>>,
     hat_cap_ds: BTreeMap<I, CiphertextMod<P::Paillier>>,
     round2_artifacts: BTreeMap<I, Round2Artifact<P>>,
 }
@@ -608,14 +969,25 @@ pub struct Round3<P: SchemeParams, I: Ord> {
 #[serde(bound(serialize = "LogStarProof<P>: Serialize"))]
 #[serde(bound(deserialize = "LogStarProof<P>: for<'x> Deserialize<'x>"))]
 pub struct Round3Message<P: SchemeParams> {
-    delta: Scalar,
-    cap_delta: Point,
+    /// The hash of the sender's `Gamma`, so that the parties can check that they agree on it.
+    cap_gamma_hash: HashOutput,
+    delta: Scalar<P::Curve>,
+    cap_delta: Point<P::Curve>,
     psi_pprime: LogStarProof<P>,
 }
 
-pub struct Round3Payload {
-    delta: Scalar,
-    cap_delta: Point,
+pub struct Round3Payload<P: SchemeParams> {
+    delta: Scalar<P::Curve>,
+    cap_delta: Point<P::Curve>,
+}
+
+impl<P: SchemeParams, I: Ord> Round3<P, I> {
+    fn cap_gamma_hash(&self) -> HashOutput {
+        FofHasher::new_with_dst(b"Gamma")
+            .chain(&self.context.ssid_hash)
+            .chain(&self.cap_gamma)
+            .finalize()
+    }
 }
 
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round3<P, I> {
@@ -634,7 +1006,7 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round3<P,
 
     type BroadcastMessage = ();
     type DirectMessage = Round3Message<P>;
-    type Payload = Round3Payload;
+    type Payload = Round3Payload<P>;
     type Artifact = ();
 
     no_broadcast_messages!();
@@ -644,6 +1016,9 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round3<P,
         rng: &mut impl CryptoRngCore,
         destination: &I,
     ) -> (Self::DirectMessage, Self::Artifact) {
+        let rng = &mut self
+            .context
+            .message_rng(rng, Self::ROUND_NUM, Some(destination));
         let aux = (&self.context.ssid_hash, &self.my_id());
         let pk = &self.context.aux_info.secret_aux.paillier_sk.public_key();
 
@@ -662,6 +1037,7 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round3<P,
             &aux,
         );
         let message = Round3Message {
+            cap_gamma_hash: self.cap_gamma_hash(),
             delta: P::scalar_from_signed(&self.delta),
             cap_delta: self.cap_delta,
             psi_pprime,
@@ -670,6 +1046,22 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round3<P,
         (message, ())
     }
 
+    fn check_consistency(
+        &self,
+        _from: &I,
+        _broadcast_msg: &Self::BroadcastMessage,
+        direct_msg: &Self::DirectMessage,
+    ) -> Result<(), &'static str> {
+        // Everyone must have assembled the same `Gamma` in Round 2,
+        // otherwise the nonce will be unusable even if the `delta` check passes.
+        // The sender is not necessarily at fault, since a malicious party
+        // could have sent different `Gamma`s to different parties in Round 2.
+        if direct_msg.cap_gamma_hash != self.cap_gamma_hash() {
+            return Err("The sender's `Gamma` differs from ours");
+        }
+        Ok(())
+    }
+
     fn verify_message(
         &self,
         _rng: &mut impl CryptoRngCore,
@@ -691,26 +1083,249 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round3<P,
             rp,
             &aux,
         ) {
-            return Err(PresigningError::Round3(
-                "Failed to verify Log-Star proof".into(),
-            ));
+            return Err(PresigningError::VerificationFail {
+                round: 3,
+                reason: VerificationFailReason::LogStarDelta,
+            });
         }
         Ok(Round3Payload {
             delta: direct_msg.delta,
             cap_delta: direct_msg.cap_delta,
         })
     }
+
+    fn verification_cost(&self) -> usize {
+        // The `log*` proof.
+        1
+    }
 }
 
+/// The ciphertexts `D` and `F` exchanged in Round 2.
+type CiphertextPair<P> = (
+    Ciphertext<<P as SchemeParams>::Paillier>,
+    Ciphertext<<P as SchemeParams>::Paillier>,
+);
+
 /// A proof of a node's correct behavior for the Presigning protocol.
-#[allow(dead_code)] // TODO (#43): this can be removed when error verification is added
-#[derive(Debug, Clone)]
+///
+/// Produced when the assembled `delta` does not match `Delta` in Round 3.
+/// Besides the proofs themselves, it contains the public values received by the node,
+/// so that the node can check the proofs of the other parties against them
+/// (see [`Self::find_faulty_parties`]).
+/// The proof is serializable, so that it could be passed to other parties.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "I: Serialize,
+    AffGProof<P>: Serialize,
+    MulProof<P>: Serialize,
+    DecProof<P>: Serialize,
+    Ciphertext<P::Paillier>: Serialize"))]
+#[serde(bound(deserialize = "I: Ord + for<'x> Deserialize<'x>,
+    AffGProof<P>: for<'x> Deserialize<'x>,
+    MulProof<P>: for<'x> Deserialize<'x>,
+    DecProof<P>: for<'x> Deserialize<'x>,
+    Ciphertext<P::Paillier>: for<'x> Deserialize<'x>"))]
 pub struct PresigningProof<P: SchemeParams, I> {
+    owner: I,
+    ssid_hash: HashOutput,
+    /// The `delta` sent by the owner in Round 3.
+    delta: Scalar<P::Curve>,
+    /// `K` and `G` of all the parties, sent in Round 1.
+    cap_ks: BTreeMap<I, Ciphertext<P::Paillier>>,
+    cap_gs: BTreeMap<I, Ciphertext<P::Paillier>>,
+    /// `Gamma` received from each of the other parties in Round 2.
+    cap_gammas: BTreeMap<I, Point<P::Curve>>,
+    /// `delta` received from each of the other parties in Round 3.
+    deltas: BTreeMap<I, Scalar<P::Curve>>,
+    /// `D` and `F` sent to each of the other parties in Round 2.
+    sent: BTreeMap<I, CiphertextPair<P>>,
+    /// `D` and `F` received from each of the other parties in Round 2.
+    received: BTreeMap<I, CiphertextPair<P>>,
     aff_g_proofs: Vec<(I, I, AffGProof<P>)>,
+    /// The ciphertext `H` the multiplication proof refers to.
+    cap_h: Ciphertext<P::Paillier>,
     mul_proof: MulProof<P>,
     dec_proofs: Vec<(I, DecProof<P>)>,
 }
 
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> PresigningProof<P, I> {
+    /// Returns the parties whose proofs do not hold against the values
+    /// received by the owner of this proof, along with the check that failed.
+    ///
+    /// `proofs` are the proofs collected from all the parties of the session
+    /// (the owner's own proof is not checked), and `aux_info` is the owner's auxiliary data.
+    /// A party whose proof is missing is considered faulty as well.
+    ///
+    /// Each party checks the parts of the proofs addressed to it,
+    /// so the faulty parties are identified by every honest party independently.
+    pub fn find_faulty_parties(
+        &self,
+        proofs: &BTreeMap<I, Self>,
+        aux_info: &AuxInfo<P, I>,
+    ) -> BTreeMap<I, VerificationFailReason> {
+        let aux_info = aux_info.to_precomputed();
+        self.cap_ks
+            .keys()
+            .filter(|id| *id != &self.owner)
+            .filter_map(|id| {
+                let result = match proofs.get(id) {
+                    Some(proof) => self.verify_other(id, proof, &aux_info),
+                    None => Err(VerificationFailReason::InconsistentProof),
+                };
+                result.err().map(|reason| (id.clone(), reason))
+            })
+            .collect()
+    }
+
+    fn verify_other(
+        &self,
+        prover: &I,
+        proof: &Self,
+        aux_info: &AuxInfoPrecomputed<P, I>,
+    ) -> Result<(), VerificationFailReason> {
+        use VerificationFailReason::InconsistentProof;
+
+        let my_id = &self.owner;
+
+        if &proof.owner != prover || proof.ssid_hash != self.ssid_hash {
+            return Err(InconsistentProof);
+        }
+
+        // The proofs must refer to the `delta` the prover sent us.
+        if self.deltas.get(prover) != Some(&proof.delta) {
+            return Err(InconsistentProof);
+        }
+
+        let (prover_aux, my_aux) = match (
+            aux_info.public_aux.get(prover),
+            aux_info.public_aux.get(my_id),
+        ) {
+            (Some(prover_aux), Some(my_aux)) => (prover_aux, my_aux),
+            _ => return Err(InconsistentProof),
+        };
+        let pk = &prover_aux.paillier_pk;
+        let my_pk = &my_aux.paillier_pk;
+        let rp = &my_aux.rp_params;
+        let aux = (&self.ssid_hash, prover);
+
+        let others = self
+            .cap_ks
+            .keys()
+            .filter(|id| *id != prover)
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        if proof.sent.keys().cloned().collect::<BTreeSet<_>>() != others
+            || proof.received.keys().cloned().collect::<BTreeSet<_>>() != others
+        {
+            return Err(InconsistentProof);
+        }
+
+        // An honest prover sends one `P^{aff-g}` proof for each ordered pair of the other parties,
+        // and one `P^{dec}` proof for each of them. Anything else is rejected before verification,
+        // so that a crafted proof cannot make us do more than `O(n)` homomorphic operations.
+        if proof.aff_g_proofs.len() != others.len() * others.len().saturating_sub(1)
+            || proof.dec_proofs.len() != others.len()
+        {
+            return Err(InconsistentProof);
+        }
+
+        // The values exchanged between the prover and us must match our records.
+        let (sent_cap_d, sent_cap_f) = &proof.sent[my_id];
+        let (received_cap_d, _received_cap_f) = &proof.received[my_id];
+        let (my_received_cap_d, my_received_cap_f) = match self.received.get(prover) {
+            Some(received) => received,
+            None => return Err(InconsistentProof),
+        };
+        let (my_sent_cap_d, _my_sent_cap_f) = match self.sent.get(prover) {
+            Some(sent) => sent,
+            None => return Err(InconsistentProof),
+        };
+        if sent_cap_d.to_mod(my_pk) != my_received_cap_d.to_mod(my_pk)
+            || sent_cap_f.to_mod(pk) != my_received_cap_f.to_mod(pk)
+            || received_cap_d.to_mod(pk) != my_sent_cap_d.to_mod(pk)
+        {
+            return Err(InconsistentProof);
+        }
+
+        let (cap_k, cap_g, cap_gamma) = match (
+            self.cap_ks.get(prover),
+            self.cap_gs.get(prover),
+            self.cap_gammas.get(prover),
+        ) {
+            (Some(cap_k), Some(cap_g), Some(cap_gamma)) => {
+                (cap_k.to_mod(pk), cap_g.to_mod(pk), cap_gamma)
+            }
+            _ => return Err(InconsistentProof),
+        };
+
+        // The ciphertexts sent to the other parties are well-formed.
+        for id_j in others.iter().filter(|id| *id != my_id) {
+            let target_pk = match aux_info.public_aux.get(id_j) {
+                Some(public_aux) => &public_aux.paillier_pk,
+                None => return Err(InconsistentProof),
+            };
+            let p_aff_g = match proof
+                .aff_g_proofs
+                .iter()
+                .find(|(id, id_l, _proof)| id == id_j && id_l == my_id)
+            {
+                Some((_id, _id_l, p_aff_g)) => p_aff_g,
+                None => return Err(InconsistentProof),
+            };
+            let (cap_d, cap_f) = &proof.sent[id_j];
+            let (cap_d, cap_f) = match (
+                cap_d.clone().validated(target_pk),
+                cap_f.clone().validated(pk),
+            ) {
+                (Some(cap_d), Some(cap_f)) => (cap_d, cap_f),
+                _ => return Err(InconsistentProof),
+            };
+            if !p_aff_g.verify(
+                target_pk,
+                pk,
+                &self.cap_ks[id_j].to_mod(target_pk),
+                &cap_d,
+                &cap_f,
+                cap_gamma,
+                rp,
+                &aux,
+            ) {
+                return Err(VerificationFailReason::AffGPsi);
+            }
+        }
+
+        // `H` encrypts `k * gamma`.
+        let cap_h = match proof.cap_h.clone().validated(pk) {
+            Some(cap_h) => cap_h,
+            None => return Err(InconsistentProof),
+        };
+        if !proof.mul_proof.verify(pk, &cap_k, &cap_g, &cap_h, &aux) {
+            return Err(VerificationFailReason::Mul);
+        }
+
+        // `delta` is the decryption of `H` with the MtA shares added.
+        let mut ciphertext = cap_h;
+        for id_j in others.iter() {
+            let (cap_d, _cap_f) = &proof.received[id_j];
+            let (_cap_d, cap_f) = &proof.sent[id_j];
+            let (cap_d, cap_f) = match (cap_d.clone().validated(pk), cap_f.clone().validated(pk)) {
+                (Some(cap_d), Some(cap_f)) => (cap_d, cap_f),
+                _ => return Err(InconsistentProof),
+            };
+            ciphertext = ciphertext + &cap_d + &cap_f;
+        }
+
+        let p_dec = match proof.dec_proofs.iter().find(|(id, _proof)| id == my_id) {
+            Some((_id, p_dec)) => p_dec,
+            None => return Err(InconsistentProof),
+        };
+        if !p_dec.verify(pk, &proof.delta, &ciphertext, rp, &aux) {
+            return Err(VerificationFailReason::Dec);
+        }
+
+        Ok(())
+    }
+}
+
 impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I> for Round3<P, I> {
     fn finalize_to_result(
         self,
@@ -724,41 +1339,67 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
             .unzip();
 
         let scalar_delta = P::scalar_from_signed(&self.delta);
-        let assembled_delta: Scalar = scalar_delta + deltas.values().sum::<Scalar>();
-        let assembled_cap_delta: Point = self.cap_delta + cap_deltas.values().sum::<Point>();
+        let assembled_delta: Scalar<P::Curve> =
+            scalar_delta + deltas.values().sum::<Scalar<P::Curve>>();
+        let assembled_cap_delta: Point<P::Curve> =
+            self.cap_delta + cap_deltas.values().sum::<Point<P::Curve>>();
 
         if assembled_delta.mul_by_generator() == assembled_cap_delta {
-            let nonce = (self.cap_gamma * assembled_delta.invert().unwrap()).x_coordinate();
+            // A zero `delta` cannot be produced by honest parties (barring a negligible chance),
+            // and there is no nonce to be had from it.
+            let delta_inv = Option::<Scalar<P::Curve>>::from(assembled_delta.invert())
+                .ok_or(FinalizeError::Retry(PresigningRetryReason::ZeroDelta))?;
+            let nonce_point = self.cap_gamma * delta_inv;
+            let nonce = nonce_point.x_coordinate();
+            // A zero `r` would make the signature independent of the key share,
+            // and is rejected by ECDSA verifiers. Cannot be caused by a malicious party
+            // without breaking the protocol, so it's not a provable fault.
+            if nonce == Scalar::<P::Curve>::ZERO {
+                return Err(FinalizeError::Retry(PresigningRetryReason::ZeroNonce));
+            }
             let my_id = self.my_id().clone();
 
-            let values = self
-                .round2_artifacts
-                .into_iter()
-                .map(|(id, artifact)| {
-                    let values = PresigningValues {
-                        hat_beta: artifact.hat_beta,
-                        hat_r: artifact.hat_r,
-                        hat_s: artifact.hat_s,
-                        cap_k: self.all_cap_k[&id].clone(),
-                        hat_cap_d_received: self.hat_cap_ds[&id].clone(),
-                        hat_cap_d: artifact.hat_cap_d,
-                        hat_cap_f: artifact.hat_cap_f,
-                    };
-                    (id, values)
-                })
-                .collect();
+            let product = self.chi.map(|chi| {
+                let values = self
+                    .round2_artifacts
+                    .into_iter()
+                    .filter_map(|(id, artifact)| artifact.product.map(|product| (id, product)))
+                    .map(|(id, product)| {
+                        let values = PresigningValues {
+                            hat_beta: product.hat_beta,
+                            hat_r: product.hat_r,
+                            hat_s: product.hat_s,
+                            cap_k: self.all_cap_k[&id].retrieve(),
+                            hat_cap_d_received: self.hat_cap_ds[&id].retrieve(),
+                            hat_cap_d: product.hat_cap_d.retrieve(),
+                            hat_cap_f: product.hat_cap_f.retrieve(),
+                        };
+                        (id, values)
+                    })
+                    .collect();
+
+                PresigningProduct {
+                    product_share: SecretBox::new(Box::new(P::scalar_from_signed(&chi))),
+                    product_share_nonreduced: chi,
+                    values,
+                }
+            });
 
             return Ok(PresigningData {
                 nonce,
+                nonce_point,
+                key_id: self.context.key_share.key_id(),
                 ephemeral_scalar_share: SecretBox::new(Box::new(self.context.k)),
-                product_share: SecretBox::new(Box::new(P::scalar_from_signed(&self.chi))),
-                product_share_nonreduced: self.chi,
-                cap_k: self.all_cap_k[&my_id].clone(),
-                values,
+                product,
+                cap_k: self.all_cap_k[&my_id].retrieve(),
             });
         }
 
-        // Construct the correctness proofs
+        // Construct the correctness proofs.
+        let rng = &mut self.context.message_rng(rng, Self::ROUND_NUM, None);
+        // All the loops below go over the set of other parties fixed at the start of the protocol,
+        // and not over anything received from them, so the amount of work here is bounded
+        // by `O(n^2)` proofs and `O(n)` homomorphic operations regardless of what was sent to us.
 
         let sk = &self.context.aux_info.secret_aux.paillier_sk;
         let pk = sk.public_key();
@@ -872,53 +1513,154 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
             dec_proofs.push((id_j.clone(), p_dec));
         }
 
+        let retrieve_all = |ciphertexts: BTreeMap<I, CiphertextMod<P::Paillier>>| {
+            ciphertexts
+                .into_iter()
+                .map(|(id, ciphertext)| (id, ciphertext.retrieve()))
+                .collect()
+        };
+
+        let sent = self
+            .round2_artifacts
+            .iter()
+            .map(|(id, artifact)| {
+                (
+                    id.clone(),
+                    (artifact.cap_d.retrieve(), artifact.cap_f.retrieve()),
+                )
+            })
+            .collect();
+        let received = self
+            .cap_ds
+            .iter()
+            .map(|(id, cap_d)| (id.clone(), (cap_d.retrieve(), self.cap_fs[id].retrieve())))
+            .collect();
+
         Err(FinalizeError::Proof(PresigningProof {
+            owner: self.context.my_id.clone(),
+            ssid_hash: self.context.ssid_hash,
+            delta: scalar_delta,
+            cap_ks: retrieve_all(self.all_cap_k),
+            cap_gs: retrieve_all(self.all_cap_g),
+            cap_gammas: self.cap_gammas,
+            deltas,
+            sent,
+            received,
             aff_g_proofs,
+            cap_h: cap_h.retrieve(),
             dec_proofs,
             mul_proof: p_mul,
         }))
     }
 }
 
+/// Decrypts a value received in Round 2 and checks its bound.
+///
+/// The values are `x * y + z` where `0 <= x, y < q`, and `-2^l' <= z <= 2^l'`,
+/// where `q` is the curve order. We will need this bound later, so we're asserting it.
+/// The proofs guarantee it for an honest sender, so a value out of range is the sender's fault.
+fn decrypt_bounded<P: SchemeParams>(
+    ciphertext: &CiphertextMod<P::Paillier>,
+    sk: &SecretKeyPaillierPrecomputed<P::Paillier>,
+) -> Result<Signed<<P::Paillier as PaillierParams>::Uint>, PresigningError> {
+    ciphertext
+        .decrypt_signed_ct(sk)
+        .assert_bit_bound_usize(core::cmp::max(2 * P::L_BOUND, P::LP_BOUND) + 1)
+        .ok_or(PresigningError::VerificationFail {
+            round: 2,
+            reason: VerificationFailReason::OutOfRange,
+        })
+}
+
 #[cfg(test)]
 mod tests {
-    use alloc::collections::BTreeSet;
+    use alloc::collections::{BTreeMap, BTreeSet};
 
-    use rand_core::{OsRng, RngCore};
+    use k256::ecdsa::signature::hazmat::PrehashVerifier;
+    use rand_chacha::ChaCha8Rng;
+    use rand_core::{CryptoRngCore, OsRng, RngCore, SeedableRng};
     use secrecy::ExposeSecret;
 
-    use super::Round1;
-    use crate::cggmp21::{AuxInfo, KeyShare, TestParams};
-    use crate::curve::Scalar;
+    use super::super::signing::{Inputs as SigningInputs, Round1 as SigningRound1};
+    #[cfg(feature = "parallel")]
+    use super::Round2Message;
+    use super::{
+        decrypt_bounded, Inputs, PresigningError, PresigningProof, PresigningRetryReason, Round1,
+        VerificationFailReason,
+    };
+    use crate::cggmp21::{AuxInfo, KeyShare, PresigningData, SchemeParams, TestParams};
+    use crate::curve::{Curve, Secp256k1};
+    use crate::paillier::{op_count, CiphertextMod, SecretKeyPaillier};
     use crate::rounds::{
         test_utils::{step_next_round, step_result, step_round, Id, Without},
-        FirstRound,
+        FinalizableToResult, FinalizeError, FirstRound, Round,
     };
+    use crate::uint::Signed;
+    use crate::www02::ThresholdKeyShare;
 
-    #[test]
-    fn execute_presigning() {
-        let mut shared_randomness = [0u8; 32];
-        OsRng.fill_bytes(&mut shared_randomness);
+    type Point = crate::curve::Point<Secp256k1>;
+    type Scalar = crate::curve::Scalar<Secp256k1>;
+    const ORDER: k256::elliptic_curve::bigint::U256 = <Secp256k1 as Curve>::ORDER;
 
-        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+    type KeyShares = BTreeMap<Id, KeyShare<TestParams, Id>>;
+    type AuxInfos = BTreeMap<Id, AuxInfo<TestParams, Id>>;
 
-        let key_shares = KeyShare::new_centralized(&mut OsRng, &ids, None);
-        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids);
+    /// Creates the first rounds of presigning between the owners of the given inputs.
+    ///
+    /// If `seeded` is `true`, each party also gets a seed for the randomness of the later rounds,
+    /// drawn from `rng`.
+    fn round1s_with_inputs(
+        rng: &mut impl CryptoRngCore,
+        key_shares: &KeyShares,
+        aux_infos: &AuxInfos,
+        nonce_only: bool,
+        seeded: bool,
+    ) -> BTreeMap<Id, Round1<TestParams, Id>> {
+        let mut shared_randomness = [0u8; 32];
+        rng.fill_bytes(&mut shared_randomness);
 
-        let r1 = ids
-            .iter()
+        let ids = key_shares.keys().cloned().collect::<BTreeSet<_>>();
+        ids.iter()
             .map(|id| {
+                let rng_seed = seeded.then(|| {
+                    let mut seed = [0u8; 32];
+                    rng.fill_bytes(&mut seed);
+                    seed
+                });
                 let round = Round1::<TestParams, Id>::new(
-                    &mut OsRng,
+                    rng,
                     &shared_randomness,
                     ids.clone().without(id),
                     *id,
-                    (key_shares[id].clone(), aux_infos[id].clone()),
+                    Inputs {
+                        key_share: key_shares[id].clone(),
+                        aux_info: aux_infos[id].clone(),
+                        nonce_only,
+                        rng_seed,
+                    },
                 )
                 .unwrap();
                 (*id, round)
             })
-            .collect();
+            .collect()
+    }
+
+    /// Creates a centralized key and auxiliary data for `ids`,
+    /// and the first rounds of presigning between them.
+    fn make_round1s(
+        ids: &BTreeSet<Id>,
+        nonce_only: bool,
+    ) -> (BTreeMap<Id, Round1<TestParams, Id>>, KeyShares, AuxInfos) {
+        let key_shares = KeyShare::new_centralized(&mut OsRng, ids, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, ids);
+        let r1 = round1s_with_inputs(&mut OsRng, &key_shares, &aux_infos, nonce_only, false);
+        (r1, key_shares, aux_infos)
+    }
+
+    #[test]
+    fn execute_presigning() {
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+        let (r1, key_shares, _aux_infos) = make_round1s(&ids, false);
 
         let r1a = step_round(&mut OsRng, r1).unwrap();
         let r2 = step_next_round(&mut OsRng, r1a).unwrap();
@@ -944,7 +1686,7 @@ mod tests {
             .sum();
         let k_times_x: Scalar = presigning_datas
             .values()
-            .map(|data| data.product_share.expose_secret())
+            .map(|data| data.product.as_ref().unwrap().product_share.expose_secret())
             .sum();
         let x: Scalar = key_shares
             .values()
@@ -955,5 +1697,684 @@ mod tests {
             k.invert().unwrap().mul_by_generator().x_coordinate(),
             presigning_datas[&Id(0)].nonce
         );
+
+        // Check the experimental accessors
+        let k_backend = presigning_datas
+            .values()
+            .map(|data| data.experimental_ephemeral_scalar_share())
+            .fold(k256::Scalar::ZERO, |acc, share| acc + share);
+        assert_eq!(k_backend, k.to_backend());
+        for data in presigning_datas.values() {
+            assert_eq!(
+                data.experimental_nonce_point(),
+                k.invert().unwrap().mul_by_generator().to_backend()
+            );
+        }
+    }
+
+    #[test]
+    fn execute_nonce_only_presigning() {
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+        let (r1, key_shares, aux_infos) = make_round1s(&ids, true);
+
+        let r1a = step_round(&mut OsRng, r1).unwrap();
+        let r2 = step_next_round(&mut OsRng, r1a).unwrap();
+        let r2a = step_round(&mut OsRng, r2).unwrap();
+        let r3 = step_next_round(&mut OsRng, r2a).unwrap();
+        let r3a = step_round(&mut OsRng, r3).unwrap();
+        let presigning_datas = step_result(&mut OsRng, r3a).unwrap();
+
+        // Check that the nonce point is consistent with the ephemeral scalar shares.
+        let k: Scalar = presigning_datas
+            .values()
+            .map(|data| data.ephemeral_scalar_share.expose_secret())
+            .sum();
+        for data in presigning_datas.values() {
+            assert!(data.product.is_none());
+            assert_eq!(data.nonce_point, k.invert().unwrap().mul_by_generator());
+            assert_eq!(data.nonce, data.nonce_point.x_coordinate());
+        }
+
+        // The result cannot be used for signing.
+        let mut shared_randomness = [0u8; 32];
+        OsRng.fill_bytes(&mut shared_randomness);
+        let result = SigningRound1::<TestParams, Id>::new(
+            &mut OsRng,
+            &shared_randomness,
+            ids.clone().without(&Id(0)),
+            Id(0),
+            SigningInputs {
+                presigning: presigning_datas[&Id(0)].clone(),
+                message: Scalar::random(&mut OsRng),
+                key_share: key_shares[&Id(0)].clone(),
+                aux_info: aux_infos[&Id(0)].clone(),
+            },
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn seeded_round1_is_reproducible() {
+        let mut shared_randomness = [0u8; 32];
+        OsRng.fill_bytes(&mut shared_randomness);
+
+        let ids = BTreeSet::from([Id(0), Id(1)]);
+
+        let key_shares = KeyShare::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids);
+
+        // All the secrets are sampled from the RNG passed to the constructor.
+        let make_broadcast = |seed: [u8; 32]| {
+            let round = Round1::<TestParams, Id>::new(
+                &mut ChaCha8Rng::from_seed(seed),
+                &shared_randomness,
+                ids.clone().without(&Id(0)),
+                Id(0),
+                Inputs {
+                    key_share: key_shares[&Id(0)].clone(),
+                    aux_info: aux_infos[&Id(0)].clone(),
+                    nonce_only: false,
+                    rng_seed: None,
+                },
+            )
+            .unwrap();
+            let message = round.make_broadcast_message(&mut OsRng).unwrap();
+            bincode::serde::encode_to_vec(&message, bincode::config::standard()).unwrap()
+        };
+
+        assert_eq!(make_broadcast([1; 32]), make_broadcast([1; 32]));
+        assert_ne!(make_broadcast([1; 32]), make_broadcast([2; 32]));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn round2_parallel_messages_match_serial() {
+        use alloc::vec::Vec;
+
+        use crate::tools::parallel::map_with_rng;
+
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2), Id(3)]);
+        let (r1, _key_shares, _aux_infos) = make_round1s(&ids, false);
+
+        let r1a = step_round(&mut OsRng, r1).unwrap();
+        let r2 = step_next_round(&mut OsRng, r1a).unwrap();
+        let round = &r2[&Id(0)];
+        let destinations = round.other_ids().iter().cloned().collect::<Vec<_>>();
+
+        let encode = |message: &Round2Message<TestParams>| {
+            bincode::serde::encode_to_vec(message, bincode::config::standard()).unwrap()
+        };
+
+        let parallel = map_with_rng(
+            &mut ChaCha8Rng::from_seed([1; 32]),
+            &destinations,
+            |rng, destination| encode(&round.make_direct_message(rng, destination).0),
+        );
+
+        // The same seeds drawn in the order of the destinations.
+        let mut rng = ChaCha8Rng::from_seed([1; 32]);
+        let serial = destinations
+            .iter()
+            .map(|destination| {
+                let mut seed = [0u8; 32];
+                rng.fill_bytes(&mut seed);
+                let (message, _artifact) =
+                    round.make_direct_message(&mut ChaCha8Rng::from_seed(seed), destination);
+                encode(&message)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(parallel.len(), 3);
+        assert_eq!(parallel, serial);
+
+        // The messages created in parallel are valid.
+        for (destination, message) in destinations.iter().zip(parallel.iter()) {
+            let message: Round2Message<TestParams> =
+                bincode::serde::decode_borrowed_from_slice(message, bincode::config::standard())
+                    .unwrap();
+            assert!(r2[destination]
+                .verify_message(&mut OsRng, &Id(0), (), message)
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn mismatched_inputs_owner_is_rejected() {
+        let mut shared_randomness = [0u8; 32];
+        OsRng.fill_bytes(&mut shared_randomness);
+
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+
+        let key_shares = KeyShare::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids);
+
+        let make_round = |key_share_owner: Id, aux_info_owner: Id| {
+            Round1::<TestParams, Id>::new(
+                &mut OsRng,
+                &shared_randomness,
+                ids.clone().without(&Id(0)),
+                Id(0),
+                Inputs {
+                    key_share: key_shares[&key_share_owner].clone(),
+                    aux_info: aux_infos[&aux_info_owner].clone(),
+                    nonce_only: false,
+                    rng_seed: None,
+                },
+            )
+        };
+
+        assert!(make_round(Id(0), Id(0)).is_ok());
+
+        let err = make_round(Id(1), Id(0)).err().unwrap();
+        assert!(err.0.contains("key share belongs to"));
+
+        let err = make_round(Id(0), Id(1)).err().unwrap();
+        assert!(err.0.contains("auxiliary data belongs to"));
+    }
+
+    #[test]
+    fn seeded_presigning_is_reproducible() {
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+
+        let key_shares = KeyShare::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids);
+
+        // Only the RNG passed to `Round1::new` is seeded,
+        // the rest of the rounds get a random one.
+        let run = |seed: u8, seeded: bool| {
+            let mut rng = ChaCha8Rng::from_seed([seed; 32]);
+            let r1 = round1s_with_inputs(&mut rng, &key_shares, &aux_infos, false, seeded);
+
+            let r1a = step_round(&mut OsRng, r1).unwrap();
+            let r2 = step_next_round(&mut OsRng, r1a).unwrap();
+            let r2a = step_round(&mut OsRng, r2).unwrap();
+            let r3 = step_next_round(&mut OsRng, r2a).unwrap();
+            let r3a = step_round(&mut OsRng, r3).unwrap();
+            step_result(&mut OsRng, r3a).unwrap()
+        };
+
+        let summary = |datas: &BTreeMap<Id, PresigningData<TestParams, Id>>| {
+            datas
+                .values()
+                .map(|data| {
+                    let product = data.product.as_ref().unwrap();
+                    (
+                        data.nonce,
+                        *data.ephemeral_scalar_share.expose_secret(),
+                        *product.product_share.expose_secret(),
+                        bincode::serde::encode_to_vec(&data.cap_k, bincode::config::standard())
+                            .unwrap(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(summary(&run(1, true)), summary(&run(1, true)));
+        assert_ne!(summary(&run(1, true)), summary(&run(10, true)));
+
+        // Without the seeds the later rounds use the RNGs they are given.
+        assert_ne!(summary(&run(1, false)), summary(&run(1, false)));
+    }
+
+    #[test]
+    fn round2_proofs_are_bound_to_recipient() {
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+        let (r1, _key_shares, _aux_infos) = make_round1s(&ids, false);
+
+        let r1a = step_round(&mut OsRng, r1).unwrap();
+        let r2 = step_next_round(&mut OsRng, r1a).unwrap();
+
+        // The proofs in a message for `Id(1)` are built against `Id(1)`'s ciphertexts.
+        let (direct_msg, _artifact) = r2[&Id(0)].make_direct_message(&mut OsRng, &Id(1));
+
+        assert!(r2[&Id(1)]
+            .verify_message(&mut OsRng, &Id(0), (), direct_msg.clone())
+            .is_ok());
+        // `D` is encrypted with `Id(1)`'s key, so depending on the moduli it can already
+        // fail the range check for `Id(2)`'s key; otherwise the proof itself is rejected.
+        let result = r2[&Id(2)].verify_message(&mut OsRng, &Id(0), (), direct_msg.clone());
+        assert!(matches!(
+            result,
+            Err(PresigningError::VerificationFail {
+                round: 2,
+                reason: VerificationFailReason::AffGPsi
+            }) | Err(PresigningError::Round2(_))
+        ));
+        assert_eq!(
+            VerificationFailReason::AffGPsi.to_string(),
+            "Failed to verify AffGProof (psi)"
+        );
+        // The sender's own ciphertexts are not accepted either
+        assert!(r2[&Id(0)]
+            .verify_message(&mut OsRng, &Id(0), (), direct_msg)
+            .is_err());
+    }
+
+    #[test]
+    fn round2_invalid_hat_psi_is_attributed() {
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+        let (r1, _key_shares, _aux_infos) = make_round1s(&ids, false);
+
+        let r1a = step_round(&mut OsRng, r1).unwrap();
+        let r2 = step_next_round(&mut OsRng, r1a).unwrap();
+
+        // `Id(1)` is honest, while `Id(0)` sends `psi` in place of `hat_psi`.
+        // The batch of the two proofs fails, and the valid `psi` must not be blamed for it.
+        let (honest_msg, _artifact) = r2[&Id(1)].make_direct_message(&mut OsRng, &Id(2));
+        let (mut faulty_msg, _artifact) = r2[&Id(0)].make_direct_message(&mut OsRng, &Id(2));
+        faulty_msg.product.as_mut().unwrap().hat_psi = faulty_msg.psi.clone();
+
+        assert!(r2[&Id(2)]
+            .verify_message(&mut OsRng, &Id(1), (), honest_msg)
+            .is_ok());
+        let result = r2[&Id(2)].verify_message(&mut OsRng, &Id(0), (), faulty_msg);
+        assert!(matches!(
+            result,
+            Err(PresigningError::VerificationFail {
+                round: 2,
+                reason: VerificationFailReason::AffGPsiHat
+            })
+        ));
+    }
+
+    #[test]
+    fn round2_rejects_out_of_range_hat_d() {
+        let ids = BTreeSet::from([Id(0), Id(1)]);
+        let (r1, _key_shares, _aux_infos) = make_round1s(&ids, false);
+
+        let r1a = step_round(&mut OsRng, r1).unwrap();
+        let r2 = step_next_round(&mut OsRng, r1a).unwrap();
+
+        let (mut direct_msg, _artifact) = r2[&Id(0)].make_direct_message(&mut OsRng, &Id(1));
+
+        // Replace `hat{D}` with an encryption of a value outside of the expected bound.
+        let receiver_pk = r2[&Id(1)]
+            .context
+            .aux_info
+            .secret_aux
+            .paillier_sk
+            .public_key();
+        let bound = core::cmp::max(2 * TestParams::L_BOUND, TestParams::LP_BOUND) + 1;
+        let value = Signed::random_bounded_bits(&mut OsRng, bound + 64);
+        direct_msg.product.as_mut().unwrap().hat_cap_d =
+            CiphertextMod::new_signed(&mut OsRng, receiver_pk, &value).retrieve();
+
+        // The message is rejected with an error instead of a panic.
+        assert!(r2[&Id(1)]
+            .verify_message(&mut OsRng, &Id(0), (), direct_msg)
+            .is_err());
+    }
+
+    #[test]
+    fn out_of_range_plaintext_is_rejected() {
+        type Paillier = <TestParams as SchemeParams>::Paillier;
+
+        let sk = SecretKeyPaillier::<Paillier>::random(&mut OsRng).to_precomputed();
+        let pk = sk.public_key();
+        let bound = core::cmp::max(2 * TestParams::L_BOUND, TestParams::LP_BOUND) + 1;
+
+        let value = Signed::random_bounded_bits(&mut OsRng, bound - 1);
+        let ciphertext = CiphertextMod::new_signed(&mut OsRng, pk, &value);
+        assert!(decrypt_bounded::<TestParams>(&ciphertext, &sk).is_ok());
+
+        // A value out of range results in an error instead of a panic.
+        let value = Signed::random_bounded_bits(&mut OsRng, bound + 64);
+        let ciphertext = CiphertextMod::new_signed(&mut OsRng, pk, &value);
+        assert!(matches!(
+            decrypt_bounded::<TestParams>(&ciphertext, &sk),
+            Err(PresigningError::VerificationFail {
+                round: 2,
+                reason: VerificationFailReason::OutOfRange
+            })
+        ));
+    }
+
+    #[test]
+    fn wrong_delta_produces_proof() {
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+        let (r1, _key_shares, _aux_infos) = make_round1s(&ids, false);
+
+        let r1a = step_round(&mut OsRng, r1).unwrap();
+        let r2 = step_next_round(&mut OsRng, r1a).unwrap();
+        let r2a = step_round(&mut OsRng, r2).unwrap();
+        let mut r3 = step_next_round(&mut OsRng, r2a).unwrap();
+
+        // One of the nodes sends a wrong `delta` to everyone.
+        r3.get_mut(&Id(2)).unwrap().delta =
+            Signed::random_bounded_bits(&mut OsRng, TestParams::L_BOUND);
+
+        let r3a = step_round(&mut OsRng, r3).unwrap();
+        let proof = match step_result(&mut OsRng, r3a) {
+            Err(FinalizeError::Proof(proof)) => proof,
+            _ => panic!("expected a correctness proof"),
+        };
+
+        // The proof can be passed on to be checked by other parties.
+        let config = bincode::config::standard();
+        let serialized = bincode::serde::encode_to_vec(&proof, config).unwrap();
+        let deserialized: PresigningProof<TestParams, Id> =
+            bincode::serde::decode_borrowed_from_slice(&serialized, config).unwrap();
+        assert_eq!(deserialized.aff_g_proofs.len(), proof.aff_g_proofs.len());
+        assert_eq!(deserialized.dec_proofs.len(), proof.dec_proofs.len());
+    }
+
+    #[test]
+    fn faulty_party_is_identified() {
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+        let (r1, _key_shares, aux_infos) = make_round1s(&ids, false);
+
+        let r1a = step_round(&mut OsRng, r1).unwrap();
+        let r2 = step_next_round(&mut OsRng, r1a).unwrap();
+        let r2a = step_round(&mut OsRng, r2).unwrap();
+        let r3 = step_next_round(&mut OsRng, r2a).unwrap();
+        let mut r3a = step_round(&mut OsRng, r3).unwrap();
+
+        // `Id(2)` sends a wrong `delta` to `Id(0)` and `Id(1)`,
+        // and claims to have received a wrong one from `Id(0)`,
+        // so every node fails to finalize and produces a proof.
+        for id in [Id(0), Id(1)] {
+            let payload = r3a.get_mut(&id).unwrap().payloads.get_mut(&Id(2)).unwrap();
+            payload.delta = payload.delta + Scalar::ONE;
+        }
+        let payload = r3a
+            .get_mut(&Id(2))
+            .unwrap()
+            .payloads
+            .get_mut(&Id(0))
+            .unwrap();
+        payload.delta = payload.delta - Scalar::ONE;
+
+        let proofs = r3a
+            .into_iter()
+            .map(|(id, assembled)| {
+                let result = assembled.round.finalize_to_result(
+                    &mut OsRng,
+                    assembled.payloads,
+                    assembled.artifacts,
+                );
+                match result {
+                    Err(FinalizeError::Proof(proof)) => (id, proof),
+                    _ => panic!("expected a correctness proof"),
+                }
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        // The honest nodes agree on the culprit.
+        for id in [Id(0), Id(1)] {
+            assert_eq!(
+                proofs[&id].find_faulty_parties(&proofs, &aux_infos[&id]),
+                BTreeMap::from([(Id(2), VerificationFailReason::InconsistentProof)])
+            );
+        }
+
+        // A proof that does not match the prover's messages is rejected,
+        // and so is a missing one.
+        // (`H` is replaced with another ciphertext under the prover's key,
+        // so that it still passes the range check and fails the multiplication proof.)
+        let mut tampered = proofs.clone();
+        let cap_k = proofs[&Id(1)].cap_ks[&Id(1)].clone();
+        tampered.get_mut(&Id(1)).unwrap().cap_h = cap_k;
+        assert_eq!(
+            proofs[&Id(0)].find_faulty_parties(&tampered, &aux_infos[&Id(0)]),
+            BTreeMap::from([
+                (Id(1), VerificationFailReason::Mul),
+                (Id(2), VerificationFailReason::InconsistentProof)
+            ])
+        );
+
+        let mut missing = proofs.clone();
+        missing.remove(&Id(1));
+        assert_eq!(
+            proofs[&Id(0)].find_faulty_parties(&missing, &aux_infos[&Id(0)]),
+            BTreeMap::from([
+                (Id(1), VerificationFailReason::InconsistentProof),
+                (Id(2), VerificationFailReason::InconsistentProof)
+            ])
+        );
+
+        // A proof padded with extra entries is rejected before any of them is verified.
+        let mut padded = proofs.clone();
+        let padded_proof = padded.get_mut(&Id(1)).unwrap();
+        let extra = padded_proof.aff_g_proofs[0].clone();
+        padded_proof.aff_g_proofs.push(extra);
+        op_count::take();
+        assert_eq!(
+            proofs[&Id(0)].find_faulty_parties(&padded, &aux_infos[&Id(0)]),
+            BTreeMap::from([
+                (Id(1), VerificationFailReason::InconsistentProof),
+                (Id(2), VerificationFailReason::InconsistentProof)
+            ])
+        );
+        assert_eq!(op_count::take(), 0);
+    }
+
+    #[test]
+    fn round2_message_processing_does_bounded_work() {
+        // The number of homomorphic operations performed when verifying a Round 2 message
+        // does not depend on the number of parties.
+        let ops_per_message = |num_parties: u32| {
+            let ids = (0..num_parties).map(Id).collect::<BTreeSet<_>>();
+            let (r1, _key_shares, _aux_infos) = make_round1s(&ids, false);
+
+            let r1a = step_round(&mut OsRng, r1).unwrap();
+            let r2 = step_next_round(&mut OsRng, r1a).unwrap();
+
+            let (direct_msg, _artifact) = r2[&Id(0)].make_direct_message(&mut OsRng, &Id(1));
+            op_count::take();
+            assert!(r2[&Id(1)]
+                .verify_message(&mut OsRng, &Id(0), (), direct_msg)
+                .is_ok());
+            op_count::take()
+        };
+
+        let ops = ops_per_message(2);
+        assert!(ops > 0);
+        assert_eq!(ops_per_message(4), ops);
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn round2_message_compression() {
+        use crate::tools::compression::{decode, encode};
+
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+        let (r1, _key_shares, _aux_infos) = make_round1s(&ids, false);
+
+        let r1a = step_round(&mut OsRng, r1).unwrap();
+        let r2 = step_next_round(&mut OsRng, r1a).unwrap();
+
+        let (message, _artifact) = r2[&Id(0)].make_direct_message(&mut OsRng, &Id(1));
+        let serialized =
+            bincode::serde::encode_to_vec(&message, bincode::config::standard()).unwrap();
+        let uncompressed = encode(&serialized, false);
+        let compressed = encode(&serialized, true);
+        assert_eq!(decode(&uncompressed).unwrap(), serialized);
+        assert_eq!(decode(&compressed).unwrap(), serialized);
+
+        // The message consists mostly of Paillier ciphertexts and the values masked by them,
+        // which are indistinguishable from random, so no particular reduction is expected;
+        // but the compressed encoding is only used if it is shorter.
+        assert!(compressed.len() <= uncompressed.len());
+    }
+
+    #[test]
+    fn zero_nonce_requires_retry() {
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+        let (r1, _key_shares, _aux_infos) = make_round1s(&ids, false);
+
+        let r1a = step_round(&mut OsRng, r1).unwrap();
+        let r2 = step_next_round(&mut OsRng, r1a).unwrap();
+        let r2a = step_round(&mut OsRng, r2).unwrap();
+        let r3 = step_next_round(&mut OsRng, r2a).unwrap();
+
+        let assembled_delta: Scalar = r3
+            .values()
+            .map(|round| TestParams::scalar_from_signed(&round.delta))
+            .sum();
+
+        // The point with the x-coordinate equal to the curve order,
+        // which is reduced to zero when converted to a scalar.
+        let mut bytes = [0u8; 33];
+        bytes[0] = 0x02;
+        bytes[1..].copy_from_slice(&ORDER.to_be_bytes());
+        let crafted_nonce_point = Point::try_from_compressed_bytes(&bytes).unwrap();
+        assert_eq!(crafted_nonce_point.x_coordinate(), Scalar::ZERO);
+
+        // The nonce cannot be brought to this point honestly without knowing its discrete log,
+        // so we substitute `Gamma` after the messages of the last round have been checked.
+        let mut r3a = step_round(&mut OsRng, r3).unwrap();
+        for assembled in r3a.values_mut() {
+            assembled.round.cap_gamma = crafted_nonce_point * assembled_delta;
+        }
+
+        assert!(matches!(
+            step_result(&mut OsRng, r3a),
+            Err(FinalizeError::Retry(PresigningRetryReason::ZeroNonce))
+        ));
+    }
+
+    #[test]
+    fn round3_detects_divergent_gamma() {
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+        let (r1, _key_shares, _aux_infos) = make_round1s(&ids, false);
+
+        let r1a = step_round(&mut OsRng, r1).unwrap();
+        let r2 = step_next_round(&mut OsRng, r1a).unwrap();
+        let r2a = step_round(&mut OsRng, r2).unwrap();
+        let mut r3 = step_next_round(&mut OsRng, r2a).unwrap();
+
+        // `Id(2)` ends up with a different `Gamma`.
+        let r3_2 = r3.get_mut(&Id(2)).unwrap();
+        r3_2.cap_gamma = r3_2.cap_gamma + Point::GENERATOR;
+
+        let (direct_msg, _artifact) = r3[&Id(2)].make_direct_message(&mut OsRng, &Id(0));
+        assert!(r3[&Id(0)]
+            .check_consistency(&Id(2), &(), &direct_msg)
+            .is_err());
+
+        // The honest parties still agree with each other.
+        let (direct_msg, _artifact) = r3[&Id(1)].make_direct_message(&mut OsRng, &Id(0));
+        assert!(r3[&Id(0)]
+            .check_consistency(&Id(1), &(), &direct_msg)
+            .is_ok());
+    }
+
+    #[test]
+    fn round2_rejects_identity_gamma() {
+        let ids = BTreeSet::from([Id(0), Id(1)]);
+        let (r1, _key_shares, _aux_infos) = make_round1s(&ids, false);
+
+        let r1a = step_round(&mut OsRng, r1).unwrap();
+        let r2 = step_next_round(&mut OsRng, r1a).unwrap();
+
+        let (mut direct_msg, _artifact) = r2[&Id(0)].make_direct_message(&mut OsRng, &Id(1));
+        assert!(r2[&Id(1)]
+            .validate_structure(&Id(0), &(), &direct_msg)
+            .is_ok());
+
+        direct_msg.cap_gamma = Point::IDENTITY;
+        assert!(r2[&Id(1)]
+            .validate_structure(&Id(0), &(), &direct_msg)
+            .is_err());
+    }
+
+    #[test]
+    fn execute_presigning_with_quorum() {
+        let mut shared_randomness = [0u8; 32];
+        OsRng.fill_bytes(&mut shared_randomness);
+
+        let all_ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+        // `Id(1)` is offline, but the remaining parties still constitute a 2-of-3 quorum.
+        let ids = BTreeSet::from([Id(0), Id(2)]);
+
+        let threshold_key_shares =
+            ThresholdKeyShare::<TestParams, Id>::new_centralized(&mut OsRng, &all_ids, 2, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &all_ids);
+
+        let key_shares = ids
+            .iter()
+            .map(|id| (*id, threshold_key_shares[id].to_key_share(&ids)))
+            .collect::<BTreeMap<_, _>>();
+
+        // The aux data of all the parties cannot be used with a reduced set of parties either.
+        assert!(Round1::<TestParams, Id>::new(
+            &mut OsRng,
+            &shared_randomness,
+            ids.clone().without(&Id(0)),
+            Id(0),
+            Inputs {
+                key_share: key_shares[&Id(0)].clone(),
+                aux_info: aux_infos[&Id(0)].clone(),
+                nonce_only: false,
+                rng_seed: None,
+            },
+        )
+        .is_err());
+        let aux_infos = ids
+            .iter()
+            .map(|id| (*id, aux_infos[id].to_subset(&ids).unwrap()))
+            .collect::<BTreeMap<_, _>>();
+
+        // A full key share cannot be used with a reduced set of parties.
+        let full_key_share = KeyShare::new_centralized(&mut OsRng, &all_ids, None);
+        assert!(Round1::<TestParams, Id>::new(
+            &mut OsRng,
+            &shared_randomness,
+            ids.clone().without(&Id(0)),
+            Id(0),
+            Inputs {
+                key_share: full_key_share[&Id(0)].clone(),
+                aux_info: aux_infos[&Id(0)].clone(),
+                nonce_only: false,
+                rng_seed: None,
+            },
+        )
+        .is_err());
+
+        let r1 = round1s_with_inputs(&mut OsRng, &key_shares, &aux_infos, false, false);
+
+        let r1a = step_round(&mut OsRng, r1).unwrap();
+        let r2 = step_next_round(&mut OsRng, r1a).unwrap();
+        let r2a = step_round(&mut OsRng, r2).unwrap();
+        let r3 = step_next_round(&mut OsRng, r2a).unwrap();
+        let r3a = step_round(&mut OsRng, r3).unwrap();
+        let presigning_datas = step_result(&mut OsRng, r3a).unwrap();
+
+        assert_eq!(
+            presigning_datas[&Id(0)].nonce,
+            presigning_datas[&Id(2)].nonce
+        );
+
+        // Check that the presignature can be used to sign with the shared key.
+        let message = Scalar::random(&mut OsRng);
+
+        let r1 = ids
+            .iter()
+            .map(|id| {
+                let round = SigningRound1::<TestParams, Id>::new(
+                    &mut OsRng,
+                    &shared_randomness,
+                    ids.clone().without(id),
+                    *id,
+                    SigningInputs {
+                        presigning: presigning_datas[id].clone(),
+                        message,
+                        key_share: key_shares[id].clone(),
+                        aux_info: aux_infos[id].clone(),
+                    },
+                )
+                .unwrap();
+                (*id, round)
+            })
+            .collect();
+
+        let r1a = step_round(&mut OsRng, r1).unwrap();
+        let signatures = step_result(&mut OsRng, r1a).unwrap();
+
+        let vkey = threshold_key_shares[&Id(0)].verifying_key();
+        for signature in signatures.values() {
+            let (sig, _rec_id) = signature.to_backend();
+            vkey.verify_prehash(&message.to_bytes(), &sig).unwrap();
+        }
     }
 }
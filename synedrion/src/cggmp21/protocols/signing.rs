@@ -2,6 +2,7 @@
 
 use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::vec::Vec;
+use core::convert::Infallible;
 use core::fmt::Debug;
 use core::marker::PhantomData;
 
@@ -10,11 +11,11 @@ use secrecy::ExposeSecret;
 use serde::{Deserialize, Serialize};
 
 use super::super::{
-    entities::AuxInfoPrecomputed,
+    entities::{AuxInfoPrecomputed, PresigningProduct},
     sigma::{AffGProof, DecProof, MulStarProof},
     AuxInfo, KeyShare, PresigningData, SchemeParams,
 };
-use crate::curve::{RecoverableSignature, Scalar};
+use crate::curve::{Point, RecoverableSignature, Scalar};
 use crate::paillier::RandomizerMod;
 use crate::rounds::{
     no_direct_messages, FinalizableToResult, FinalizeError, FirstRound, InitError, ProtocolResult,
@@ -27,9 +28,10 @@ use crate::tools::hashing::{Chain, FofHasher, HashOutput};
 pub struct SigningResult<P: SchemeParams, I: Debug>(PhantomData<P>, PhantomData<I>);
 
 impl<P: SchemeParams, I: Debug> ProtocolResult for SigningResult<P, I> {
-    type Success = RecoverableSignature;
+    type Success = RecoverableSignature<P::Curve>;
     type ProvableError = ();
     type CorrectnessProof = SigningProof<P, I>;
+    type RetryReason = Infallible;
 }
 
 /// A proof of a node's correct behavior for the Signing protocol.
@@ -41,19 +43,32 @@ pub struct SigningProof<P: SchemeParams, I> {
     dec_proofs: Vec<(I, DecProof<P>)>,
 }
 
-pub struct Round1<P: SchemeParams, I: Ord> {
+/// The part of the Round 1 state that does not contain any secrets.
+pub struct PublicState<P: SchemeParams, I: Ord> {
     ssid_hash: HashOutput,
-    r: Scalar,
-    sigma: Scalar,
-    inputs: Inputs<P, I>,
-    aux_info: AuxInfoPrecomputed<P, I>,
+    message: Scalar<P::Curve>,
+    r: Scalar<P::Curve>,
+    sigma: Scalar<P::Curve>,
+    verifying_key: Point<P::Curve>,
     other_ids: BTreeSet<I>,
     my_id: I,
 }
 
-#[derive(Clone)]
+/// The part of the Round 1 state containing the secrets,
+/// only needed to prove the correctness of the signature share.
+pub struct SecretState<P: SchemeParams, I: Ord> {
+    inputs: Inputs<P, I>,
+    product: PresigningProduct<P, I>,
+    aux_info: AuxInfoPrecomputed<P, I>,
+}
+
+pub struct Round1<P: SchemeParams, I: Ord> {
+    public: PublicState<P, I>,
+    secret: SecretState<P, I>,
+}
+
 pub struct Inputs<P: SchemeParams, I: Ord> {
-    pub message: Scalar,
+    pub message: Scalar<P::Curve>,
     pub presigning: PresigningData<P, I>,
     pub key_share: KeyShare<P, I>,
     pub aux_info: AuxInfo<P, I>,
@@ -68,6 +83,29 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FirstRound<I> for Roun
         my_id: I,
         inputs: Self::Inputs,
     ) -> Result<Self, InitError> {
+        // The public shares of all the participants are used when constructing
+        // the correctness proofs, so they must be present in the key share.
+        let mut participants = other_ids.clone();
+        participants.insert(my_id.clone());
+        if inputs.key_share.all_parties() != participants {
+            return Err(InitError(
+                "The key share must be shared between exactly the participating parties".into(),
+            ));
+        }
+
+        // Using the presigning data created for another key share
+        // would result in an invalid signature share.
+        if inputs.presigning.key_id != inputs.key_share.key_id() {
+            return Err(InitError(
+                "The presigning data was created with a different key share".into(),
+            ));
+        }
+
+        // Presigning data created in the nonce-only mode does not have the share of `k * x`.
+        let product = inputs.presigning.product.clone().ok_or_else(|| {
+            InitError("The presigning data was created in the nonce-only mode".into())
+        })?;
+
         // This includes the info of $ssid$ in the paper
         // (scheme parameters + public data from all shares - hashed in `share_set_id`),
         // with the session randomness added.
@@ -80,95 +118,80 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FirstRound<I> for Roun
 
         let r = inputs.presigning.nonce;
         let sigma = inputs.presigning.ephemeral_scalar_share.expose_secret() * &inputs.message
-            + r * inputs.presigning.product_share.expose_secret();
-        Ok(Self {
+            + r * product.product_share.expose_secret();
+
+        let public = PublicState {
             ssid_hash,
+            message: inputs.message,
             r,
             sigma,
-            aux_info: inputs.aux_info.clone().to_precomputed(),
-            inputs,
+            verifying_key: inputs.key_share.verifying_key_as_point(),
             other_ids,
             my_id,
-        })
+        };
+
+        let secret = SecretState {
+            product,
+            aux_info: inputs.aux_info.clone().to_precomputed(),
+            inputs,
+        };
+
+        Ok(Self { public, secret })
     }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
-pub struct Round1Message {
-    sigma: Scalar,
+pub struct Round1Message<P: SchemeParams> {
+    sigma: Scalar<P::Curve>,
 }
 
-pub struct Round1Payload {
-    sigma: Scalar,
+pub struct Round1Payload<P: SchemeParams> {
+    sigma: Scalar<P::Curve>,
 }
 
-impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round1<P, I> {
-    type Type = ToResult;
-    type Result = SigningResult<P, I>;
-    const ROUND_NUM: u8 = 1;
-    const NEXT_ROUND_NUM: Option<u8> = None;
-
-    fn other_ids(&self) -> &BTreeSet<I> {
-        &self.other_ids
+impl<P: SchemeParams, I: Ord + Serialize> PublicState<P, I> {
+    fn make_broadcast_message(&self) -> Round1Message<P> {
+        Round1Message { sigma: self.sigma }
     }
 
-    fn my_id(&self) -> &I {
-        &self.my_id
-    }
-
-    type BroadcastMessage = Round1Message;
-    type DirectMessage = ();
-    type Payload = Round1Payload;
-    type Artifact = ();
-
-    fn make_broadcast_message(
-        &self,
-        _rng: &mut impl CryptoRngCore,
-    ) -> Option<Self::BroadcastMessage> {
-        Some(Round1Message { sigma: self.sigma })
-    }
-
-    no_direct_messages!(I);
-
-    fn verify_message(
-        &self,
-        _rng: &mut impl CryptoRngCore,
-        _from: &I,
-        broadcast_msg: Self::BroadcastMessage,
-        _direct_msg: Self::DirectMessage,
-    ) -> Result<Self::Payload, <Self::Result as ProtocolResult>::ProvableError> {
-        Ok(Round1Payload {
+    fn verify_message(&self, broadcast_msg: Round1Message<P>) -> Round1Payload<P> {
+        Round1Payload {
             sigma: broadcast_msg.sigma,
-        })
+        }
     }
-}
 
-impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I> for Round1<P, I> {
-    fn finalize_to_result(
-        self,
-        rng: &mut impl CryptoRngCore,
-        payloads: BTreeMap<I, <Self as Round<I>>::Payload>,
-        _artifacts: BTreeMap<I, <Self as Round<I>>::Artifact>,
-    ) -> Result<<Self::Result as ProtocolResult>::Success, FinalizeError<Self::Result>> {
+    /// Assembles the signature from the signature shares received from the other parties.
+    ///
+    /// Returns `None` if the resulting signature is invalid,
+    /// in which case the signer has to create a correctness proof.
+    fn assemble_signature(
+        &self,
+        payloads: &BTreeMap<I, Round1Payload<P>>,
+    ) -> Option<RecoverableSignature<P::Curve>> {
         let assembled_sigma = payloads
             .values()
             .map(|payload| payload.sigma)
-            .sum::<Scalar>()
+            .sum::<Scalar<P::Curve>>()
             + self.sigma;
 
-        let signature = RecoverableSignature::from_scalars(
+        RecoverableSignature::from_scalars(
             &self.r,
             &assembled_sigma,
-            &self.inputs.key_share.verifying_key_as_point(),
-            &self.inputs.message,
-        );
-
-        if let Some(signature) = signature {
-            return Ok(signature);
-        }
+            &self.verifying_key,
+            &self.message,
+        )
+    }
+}
 
-        let my_id = self.my_id().clone();
-        let aux = (&self.ssid_hash, &my_id);
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> SecretState<P, I> {
+    /// Creates the proofs that the signature share in `public` was calculated correctly.
+    fn correctness_proof(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        public: &PublicState<P, I>,
+    ) -> SigningProof<P, I> {
+        let my_id = &public.my_id;
+        let aux = (&public.ssid_hash, my_id);
 
         let sk = &self.aux_info.secret_aux.paillier_sk;
         let pk = sk.public_key();
@@ -177,12 +200,15 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
 
         let mut aff_g_proofs = Vec::new();
 
-        for id_j in self.other_ids() {
-            for id_l in self.other_ids().iter().filter(|id| id != &id_j) {
+        for id_j in public.other_ids.iter() {
+            for id_l in public.other_ids.iter().filter(|id| id != &id_j) {
                 let target_pk = &self.aux_info.public_aux[id_j].paillier_pk;
                 let rp = &self.aux_info.public_aux[id_l].rp_params;
 
-                let values = self.inputs.presigning.values.get(id_j).unwrap();
+                let values = self.product.values.get(id_j).unwrap();
+                let cap_k = values.cap_k.to_mod(target_pk);
+                let hat_cap_d = values.hat_cap_d.to_mod(target_pk);
+                let hat_cap_f = values.hat_cap_f.to_mod(pk);
 
                 let p_aff_g = AffGProof::<P>::new(
                     rng,
@@ -192,10 +218,10 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
                     values.hat_r.to_mod(pk),
                     target_pk,
                     pk,
-                    &values.cap_k,
-                    &values.hat_cap_d,
-                    &values.hat_cap_f,
-                    &self.inputs.key_share.public_shares[&my_id],
+                    &cap_k,
+                    &hat_cap_d,
+                    &hat_cap_f,
+                    &self.inputs.key_share.public_shares[my_id],
                     rp,
                     &aux,
                 );
@@ -203,10 +229,10 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
                 assert!(p_aff_g.verify(
                     target_pk,
                     pk,
-                    &values.cap_k,
-                    &values.hat_cap_d,
-                    &values.hat_cap_f,
-                    &self.inputs.key_share.public_shares[&my_id],
+                    &cap_k,
+                    &hat_cap_d,
+                    &hat_cap_f,
+                    &self.inputs.key_share.public_shares[my_id],
                     rp,
                     &aux,
                 ));
@@ -218,23 +244,23 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
         // mul* proofs
 
         let x = &self.inputs.key_share.secret_share;
-        let cap_x = self.inputs.key_share.public_shares[&my_id];
+        let cap_x = self.inputs.key_share.public_shares[my_id];
 
-        let rho = RandomizerMod::random(rng, pk);
-        let hat_cap_h = (&self.inputs.presigning.cap_k * P::bounded_from_scalar(x.expose_secret()))
-            .mul_randomizer(&rho.retrieve());
+        let cap_k = self.inputs.presigning.cap_k.to_mod(pk);
 
-        let aux = (&self.ssid_hash, &my_id);
+        let rho = RandomizerMod::random(rng, pk);
+        let hat_cap_h =
+            (&cap_k * P::bounded_from_scalar(x.expose_secret())).mul_randomizer(&rho.retrieve());
 
         let mut mul_star_proofs = Vec::new();
 
-        for id_l in self.other_ids() {
+        for id_l in public.other_ids.iter() {
             let p_mul = MulStarProof::<P>::new(
                 rng,
                 &P::signed_from_scalar(x.expose_secret()),
                 &rho,
                 pk,
-                &self.inputs.presigning.cap_k,
+                &cap_k,
                 &hat_cap_h,
                 &cap_x,
                 &self.aux_info.public_aux[id_l].rp_params,
@@ -243,7 +269,7 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
 
             assert!(p_mul.verify(
                 pk,
-                &self.inputs.presigning.cap_k,
+                &cap_k,
                 &hat_cap_h,
                 &cap_x,
                 &self.aux_info.public_aux[id_l].rp_params,
@@ -256,15 +282,16 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
         // dec proofs
 
         let mut ciphertext = hat_cap_h.clone();
-        for id_j in self.other_ids() {
-            let values = &self.inputs.presigning.values.get(id_j).unwrap();
-            ciphertext = ciphertext + &values.hat_cap_d_received + &values.hat_cap_f;
+        for id_j in public.other_ids.iter() {
+            let values = &self.product.values.get(id_j).unwrap();
+            ciphertext =
+                ciphertext + &values.hat_cap_d_received.to_mod(pk) + &values.hat_cap_f.to_mod(pk);
         }
 
         let r = self.inputs.presigning.nonce;
 
         let ciphertext = ciphertext * P::bounded_from_scalar(&r)
-            + &self.inputs.presigning.cap_k * P::bounded_from_scalar(&self.inputs.message);
+            + &cap_k * P::bounded_from_scalar(&self.inputs.message);
 
         let rho = ciphertext.derive_randomizer(sk);
         // This is the same as `s_part` but if all the calculations were performed
@@ -275,23 +302,23 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
                 .ephemeral_scalar_share
                 .expose_secret(),
         ) * P::signed_from_scalar(&self.inputs.message)
-            + self.inputs.presigning.product_share_nonreduced * P::signed_from_scalar(&r);
+            + self.product.product_share_nonreduced * P::signed_from_scalar(&r);
 
         let mut dec_proofs = Vec::new();
-        for id_l in self.other_ids() {
+        for id_l in public.other_ids.iter() {
             let p_dec = DecProof::<P>::new(
                 rng,
                 &s_part_nonreduced,
                 &rho,
                 pk,
-                &self.sigma,
+                &public.sigma,
                 &ciphertext,
                 &self.aux_info.public_aux[id_l].rp_params,
                 &aux,
             );
             assert!(p_dec.verify(
                 pk,
-                &self.sigma,
+                &public.sigma,
                 &ciphertext,
                 &self.aux_info.public_aux[id_l].rp_params,
                 &aux,
@@ -299,12 +326,65 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
             dec_proofs.push((id_l.clone(), p_dec));
         }
 
-        let proof = SigningProof {
+        SigningProof {
             aff_g_proofs,
             mul_star_proofs,
             dec_proofs,
-        };
+        }
+    }
+}
+
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> Round<I> for Round1<P, I> {
+    type Type = ToResult;
+    type Result = SigningResult<P, I>;
+    const ROUND_NUM: u8 = 1;
+    const NEXT_ROUND_NUM: Option<u8> = None;
+
+    fn other_ids(&self) -> &BTreeSet<I> {
+        &self.public.other_ids
+    }
+
+    fn my_id(&self) -> &I {
+        &self.public.my_id
+    }
+
+    type BroadcastMessage = Round1Message<P>;
+    type DirectMessage = ();
+    type Payload = Round1Payload<P>;
+    type Artifact = ();
+
+    fn make_broadcast_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+    ) -> Option<Self::BroadcastMessage> {
+        Some(self.public.make_broadcast_message())
+    }
+
+    no_direct_messages!(I);
+
+    fn verify_message(
+        &self,
+        _rng: &mut impl CryptoRngCore,
+        _from: &I,
+        broadcast_msg: Self::BroadcastMessage,
+        _direct_msg: Self::DirectMessage,
+    ) -> Result<Self::Payload, <Self::Result as ProtocolResult>::ProvableError> {
+        Ok(self.public.verify_message(broadcast_msg))
+    }
+}
+
+impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I> for Round1<P, I> {
+    fn finalize_to_result(
+        self,
+        rng: &mut impl CryptoRngCore,
+        payloads: BTreeMap<I, <Self as Round<I>>::Payload>,
+        _artifacts: BTreeMap<I, <Self as Round<I>>::Artifact>,
+    ) -> Result<<Self::Result as ProtocolResult>::Success, FinalizeError<Self::Result>> {
+        if let Some(signature) = self.public.assemble_signature(&payloads) {
+            return Ok(signature);
+        }
 
+        let proof = self.secret.correctness_proof(rng, &self.public);
         Err(FinalizeError::Proof(proof))
     }
 }
@@ -313,17 +393,22 @@ impl<P: SchemeParams, I: Debug + Clone + Ord + Serialize> FinalizableToResult<I>
 mod tests {
     use alloc::collections::BTreeSet;
 
+    use alloc::boxed::Box;
+
     use k256::ecdsa::{signature::hazmat::PrehashVerifier, VerifyingKey};
     use rand_core::{OsRng, RngCore};
+    use secrecy::SecretBox;
 
     use super::{Inputs, Round1};
     use crate::cggmp21::{AuxInfo, KeyShare, PresigningData, TestParams};
-    use crate::curve::Scalar;
+    use crate::curve::Secp256k1;
     use crate::rounds::{
         test_utils::{step_result, step_round, Id, Without},
-        FirstRound,
+        FinalizeError, FirstRound, InitError,
     };
 
+    type Scalar = crate::curve::Scalar<Secp256k1>;
+
     #[test]
     fn execute_signing() {
         let mut shared_randomness = [0u8; 32];
@@ -375,4 +460,120 @@ mod tests {
             assert_eq!(recovered_key, vkey);
         }
     }
+
+    #[test]
+    fn invalid_signature_share_is_detected() {
+        let mut shared_randomness = [0u8; 32];
+        OsRng.fill_bytes(&mut shared_randomness);
+
+        let ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+
+        let key_shares = KeyShare::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids);
+
+        let mut presigning_datas =
+            PresigningData::new_centralized(&mut OsRng, &key_shares, &aux_infos);
+
+        // Corrupt the product share of one of the parties,
+        // so that its signature share is invalid.
+        presigning_datas
+            .get_mut(&Id(2))
+            .unwrap()
+            .product
+            .as_mut()
+            .unwrap()
+            .product_share = SecretBox::new(Box::new(Scalar::random(&mut OsRng)));
+
+        let message = Scalar::random(&mut OsRng);
+
+        let r1 = ids
+            .iter()
+            .map(|id| {
+                let round = Round1::<TestParams, Id>::new(
+                    &mut OsRng,
+                    &shared_randomness,
+                    ids.clone().without(id),
+                    *id,
+                    Inputs {
+                        presigning: presigning_datas[id].clone(),
+                        message,
+                        key_share: key_shares[id].clone(),
+                        aux_info: aux_infos[id].clone(),
+                    },
+                )
+                .unwrap();
+                (*id, round)
+            })
+            .collect();
+
+        let r1a = step_round(&mut OsRng, r1).unwrap();
+
+        // An honest party must not return the invalid signature,
+        // and produce a correctness proof instead.
+        let result = step_result(&mut OsRng, r1a);
+        assert!(matches!(result, Err(FinalizeError::Proof(_))));
+    }
+
+    #[test]
+    fn presigning_for_another_key_is_rejected() {
+        let mut shared_randomness = [0u8; 32];
+        OsRng.fill_bytes(&mut shared_randomness);
+
+        let ids = BTreeSet::from([Id(0), Id(1)]);
+
+        let key_shares = KeyShare::new_centralized(&mut OsRng, &ids, None);
+        let other_key_shares = KeyShare::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids);
+
+        let presigning_datas =
+            PresigningData::new_centralized(&mut OsRng, &other_key_shares, &aux_infos);
+
+        let result = Round1::<TestParams, Id>::new(
+            &mut OsRng,
+            &shared_randomness,
+            ids.clone().without(&Id(0)),
+            Id(0),
+            Inputs {
+                presigning: presigning_datas[&Id(0)].clone(),
+                message: Scalar::random(&mut OsRng),
+                key_share: key_shares[&Id(0)].clone(),
+                aux_info: aux_infos[&Id(0)].clone(),
+            },
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn inconsistent_key_share_is_rejected() {
+        let mut shared_randomness = [0u8; 32];
+        OsRng.fill_bytes(&mut shared_randomness);
+
+        let ids = BTreeSet::from([Id(0), Id(1)]);
+
+        let key_shares = KeyShare::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids);
+
+        let presigning_datas = PresigningData::new_centralized(&mut OsRng, &key_shares, &aux_infos);
+
+        // A key share that does not have the public share of one of the participants
+        let mut key_share = key_shares[&Id(0)].clone();
+        key_share.public_shares.remove(&Id(1));
+
+        let result = Round1::<TestParams, Id>::new(
+            &mut OsRng,
+            &shared_randomness,
+            ids.clone().without(&Id(0)),
+            Id(0),
+            Inputs {
+                presigning: presigning_datas[&Id(0)].clone(),
+                message: Scalar::random(&mut OsRng),
+                key_share,
+                aux_info: aux_infos[&Id(0)].clone(),
+            },
+        );
+        assert!(matches!(
+            result,
+            Err(InitError(message)) if message.contains("participating parties")
+        ));
+    }
 }
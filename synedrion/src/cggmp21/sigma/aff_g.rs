@@ -1,14 +1,17 @@
 //! Paillier Affine Operation with Group Commitment in Range ($\Pi^{aff-g}$, Section 6.2, Fig. 15)
 
+use alloc::vec::Vec;
+
+use crypto_bigint::BitOps;
 use rand_core::CryptoRngCore;
 use secrecy::{ExposeSecret, SecretBox};
 use serde::{Deserialize, Serialize};
 
 use super::super::SchemeParams;
-use crate::curve::Point;
+use crate::curve::{Point, Scalar};
 use crate::paillier::{
     Ciphertext, CiphertextMod, PaillierParams, PublicKeyPaillierPrecomputed, RPCommitment,
-    RPParamsMod, Randomizer, RandomizerMod,
+    RPCommitmentMod, RPParamsMod, Randomizer, RandomizerMod,
 };
 use crate::tools::hashing::{Chain, Hashable, XofHasher};
 use crate::uint::Signed;
@@ -41,7 +44,7 @@ Public inputs:
 pub(crate) struct AffGProof<P: SchemeParams> {
     e: Signed<<P::Paillier as PaillierParams>::Uint>,
     cap_a: Ciphertext<P::Paillier>,
-    cap_b_x: Point,
+    cap_b_x: Point<P::Curve>,
     cap_b_y: Ciphertext<P::Paillier>,
     cap_e: RPCommitment<P::Paillier>,
     cap_s: RPCommitment<P::Paillier>,
@@ -68,7 +71,7 @@ impl<P: SchemeParams> AffGProof<P> {
         cap_c: &CiphertextMod<P::Paillier>,
         cap_d: &CiphertextMod<P::Paillier>,
         cap_y: &CiphertextMod<P::Paillier>,
-        cap_x: &Point,
+        cap_x: &Point<P::Curve>,
         setup: &RPParamsMod<P::Paillier>,
         aux: &impl Hashable,
     ) -> Self {
@@ -174,7 +177,135 @@ impl<P: SchemeParams> AffGProof<P> {
         cap_c: &CiphertextMod<P::Paillier>,
         cap_d: &CiphertextMod<P::Paillier>,
         cap_y: &CiphertextMod<P::Paillier>,
-        cap_x: &Point,
+        cap_x: &Point<P::Curve>,
+        setup: &RPParamsMod<P::Paillier>,
+        aux: &impl Hashable,
+    ) -> bool {
+        self.verify_unbatched_parts(pk0, pk1, cap_c, cap_d, cap_y, cap_x, setup, aux)
+            && self.verify_group_equation(cap_x)
+            && self.verify_commitment_equations(setup)
+    }
+
+    /// Verifies several proofs sharing the same $N_0$, $C$ and setup parameters
+    /// (e.g. `psi` and `hat_psi` in a Presigning Round 2 message).
+    ///
+    /// The group equation and the ring-Pedersen equations are checked
+    /// for a random linear combination of the proofs, amortizing the exponentiations.
+    /// Returns `true` if and only if (with overwhelming probability)
+    /// each of the proofs would pass [`verify`](Self::verify).
+    pub fn verify_batch(
+        rng: &mut impl CryptoRngCore,
+        pk0: &PublicKeyPaillierPrecomputed<P::Paillier>,
+        cap_c: &CiphertextMod<P::Paillier>,
+        setup: &RPParamsMod<P::Paillier>,
+        items: &[AffGBatchItem<'_, P, impl Hashable>],
+    ) -> bool {
+        if !items.iter().all(|item| {
+            item.proof.verify_unbatched_parts(
+                pk0, item.pk1, cap_c, item.cap_d, item.cap_y, item.cap_x, setup, item.aux,
+            )
+        }) {
+            return false;
+        }
+
+        // g^{\sum_i c_i z_{1,i}} = \sum_i c_i (B_{x,i} + X_i e_i)
+        let mut lhs = Scalar::<P::Curve>::ZERO;
+        let mut rhs = Point::<P::Curve>::IDENTITY;
+        for item in items {
+            let proof = item.proof;
+            let c = Scalar::<P::Curve>::random(rng);
+            lhs = lhs + c * P::scalar_from_signed(&proof.z1);
+            rhs = rhs + (proof.cap_b_x + item.cap_x * &P::scalar_from_signed(&proof.e)) * c;
+        }
+        if lhs.mul_by_generator() != rhs {
+            return false;
+        }
+
+        // The sums of the exponents must not overflow `WideUint`
+        // (each addition of `Signed` values increases the bound by one bit).
+        // This is the case for the honest proofs, but the sender controls `z_3` and `z_4`,
+        // so the proofs where they are too large are verified on their own.
+        let extra_bits = P::SECURITY_PARAMETER + items.len() + 1;
+        let mut batched = Vec::with_capacity(items.len());
+        for item in items {
+            let proof = item.proof;
+            match (
+                bounded_for_batching::<P>(&proof.z3, extra_bits),
+                bounded_for_batching::<P>(&proof.z4, extra_bits),
+            ) {
+                (Some(z3), Some(z4)) => batched.push((proof, z3, z4)),
+                _ => {
+                    if !proof.verify_commitment_equations(setup) {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        // s^{\sum_i c_i z_{1,i}} t^{\sum_i c_i z_{3,i}} = \prod_i (E_i S_i^{e_i})^{c_i} \mod \hat{N}
+        // s^{\sum_i c_i z_{2,i}} t^{\sum_i c_i z_{4,i}} = \prod_i (F_i T_i^{e_i})^{c_i} \mod \hat{N}
+        let aux_pk = setup.public_key();
+        let mut sum_z1 = Signed::default();
+        let mut sum_z2 = Signed::default();
+        let mut sum_z3 = Signed::default();
+        let mut sum_z4 = Signed::default();
+        let mut prod_es: Option<RPCommitmentMod<P::Paillier>> = None;
+        let mut prod_ft: Option<RPCommitmentMod<P::Paillier>> = None;
+        for (proof, z3, z4) in batched {
+            let c = Signed::random_bounded_bits(rng, P::SECURITY_PARAMETER);
+            let c_wide = c.into_wide();
+
+            // `z_1` and `z_2` passed the range checks, so their bounds can be tightened.
+            let z1 = proof
+                .z1
+                .assert_bit_bound_usize(P::L_BOUND + P::EPS_BOUND + 1)
+                .expect("`z_1` has been range-checked");
+            let z2 = proof
+                .z2
+                .assert_bit_bound_usize(P::LP_BOUND + P::EPS_BOUND + 1)
+                .expect("`z_2` has been range-checked");
+
+            sum_z1 = sum_z1 + z1.mul_wide(&c);
+            sum_z2 = sum_z2 + z2.mul_wide(&c);
+            sum_z3 = sum_z3 + z3 * c_wide;
+            sum_z4 = sum_z4 + z4 * c_wide;
+
+            let cap_es = (&proof.cap_e.to_mod(aux_pk)
+                * &proof.cap_s.to_mod(aux_pk).pow_signed_vartime(&proof.e))
+                .pow_signed_vartime(&c);
+            let cap_ft = (&proof.cap_f.to_mod(aux_pk)
+                * &proof.cap_t.to_mod(aux_pk).pow_signed_vartime(&proof.e))
+                .pow_signed_vartime(&c);
+            prod_es = Some(match prod_es {
+                Some(prod) => &prod * &cap_es,
+                None => cap_es,
+            });
+            prod_ft = Some(match prod_ft {
+                Some(prod) => &prod * &cap_ft,
+                None => cap_ft,
+            });
+        }
+
+        match (prod_es, prod_ft) {
+            (Some(prod_es), Some(prod_ft)) => {
+                setup.commit_wide(&(&sum_z1).into(), &sum_z3) == prod_es
+                    && setup.commit_wide(&(&sum_z2).into(), &sum_z4) == prod_ft
+            }
+            _ => true,
+        }
+    }
+
+    /// Checks the challenge, the ranges, and the equations involving Paillier ciphertexts,
+    /// which differ between the proofs and cannot be batched.
+    #[allow(clippy::too_many_arguments)]
+    fn verify_unbatched_parts(
+        &self,
+        pk0: &PublicKeyPaillierPrecomputed<P::Paillier>,
+        pk1: &PublicKeyPaillierPrecomputed<P::Paillier>,
+        cap_c: &CiphertextMod<P::Paillier>,
+        cap_d: &CiphertextMod<P::Paillier>,
+        cap_y: &CiphertextMod<P::Paillier>,
+        cap_x: &Point<P::Curve>,
         setup: &RPParamsMod<P::Paillier>,
         aux: &impl Hashable,
     ) -> bool {
@@ -209,8 +340,6 @@ impl<P: SchemeParams> AffGProof<P> {
             return false;
         }
 
-        let aux_pk = setup.public_key();
-
         // Range checks
 
         if !self.z1.in_range_bits(P::L_BOUND + P::EPS_BOUND) {
@@ -229,13 +358,6 @@ impl<P: SchemeParams> AffGProof<P> {
             return false;
         }
 
-        // g^{z_1} = B_x X^e
-        if P::scalar_from_signed(&self.z1).mul_by_generator()
-            != self.cap_b_x + cap_x * &P::scalar_from_signed(&e)
-        {
-            return false;
-        }
-
         // NOTE: deviation from the paper to support a different `D`
         // (see the comment in `AffGProof`)
         // Original: `Y^e`. Modified `Y^{-e}`.
@@ -247,10 +369,23 @@ impl<P: SchemeParams> AffGProof<P> {
             return false;
         }
 
+        true
+    }
+
+    fn verify_group_equation(&self, cap_x: &Point<P::Curve>) -> bool {
+        // g^{z_1} = B_x X^e
+        P::scalar_from_signed(&self.z1).mul_by_generator()
+            == self.cap_b_x + cap_x * &P::scalar_from_signed(&self.e)
+    }
+
+    fn verify_commitment_equations(&self, setup: &RPParamsMod<P::Paillier>) -> bool {
+        let aux_pk = setup.public_key();
+
         // s^{z_1} t^{z_3} = E S^e \mod \hat{N}
         let cap_e_mod = self.cap_e.to_mod(aux_pk);
         let cap_s_mod = self.cap_s.to_mod(aux_pk);
-        if setup.commit(&self.z1.into(), &self.z3) != &cap_e_mod * &cap_s_mod.pow_signed_vartime(&e)
+        if setup.commit(&self.z1.into(), &self.z3)
+            != &cap_e_mod * &cap_s_mod.pow_signed_vartime(&self.e)
         {
             return false;
         }
@@ -258,7 +393,8 @@ impl<P: SchemeParams> AffGProof<P> {
         // s^{z_2} t^{z_4} = F T^e \mod \hat{N}
         let cap_f_mod = self.cap_f.to_mod(aux_pk);
         let cap_t_mod = self.cap_t.to_mod(aux_pk);
-        if setup.commit(&self.z2.into(), &self.z4) != &cap_f_mod * &cap_t_mod.pow_signed_vartime(&e)
+        if setup.commit(&self.z2.into(), &self.z4)
+            != &cap_f_mod * &cap_t_mod.pow_signed_vartime(&self.e)
         {
             return false;
         }
@@ -267,15 +403,46 @@ impl<P: SchemeParams> AffGProof<P> {
     }
 }
 
+/// The public inputs of a single proof verified with [`AffGProof::verify_batch`].
+pub(crate) struct AffGBatchItem<'a, P: SchemeParams, A> {
+    pub proof: &'a AffGProof<P>,
+    pub pk1: &'a PublicKeyPaillierPrecomputed<P::Paillier>,
+    pub cap_d: &'a CiphertextMod<P::Paillier>,
+    pub cap_y: &'a CiphertextMod<P::Paillier>,
+    pub cap_x: &'a Point<P::Curve>,
+    pub aux: &'a A,
+}
+
+/// Returns the value with its bound set to its actual bit size,
+/// or `None` if it is too large to be multiplied by a value of `extra_bits` bits
+/// and summed without overflowing.
+fn bounded_for_batching<P: SchemeParams>(
+    value: &Signed<<P::Paillier as PaillierParams>::WideUint>,
+    extra_bits: usize,
+) -> Option<Signed<<P::Paillier as PaillierParams>::WideUint>> {
+    let wide_bits =
+        <<P::Paillier as PaillierParams>::WideUint as crypto_bigint::Bounded>::BITS as usize;
+    let bits = value.abs().bits_vartime() as usize;
+    if bits + extra_bits >= wide_bits - 1 {
+        return None;
+    }
+    value.assert_bit_bound_usize(bits)
+}
+
 #[cfg(test)]
 mod tests {
     use rand_core::OsRng;
     use secrecy::{ExposeSecret, SecretBox};
 
-    use super::AffGProof;
+    use super::{AffGBatchItem, AffGProof};
     use crate::cggmp21::{SchemeParams, TestParams};
-    use crate::paillier::{CiphertextMod, RPParamsMod, RandomizerMod, SecretKeyPaillier};
-    use crate::uint::Signed;
+    use crate::curve::Secp256k1;
+    use crate::paillier::{
+        CiphertextMod, PublicKeyPaillierPrecomputed, RPParamsMod, RandomizerMod, SecretKeyPaillier,
+    };
+    use crate::uint::{Signed, U1024, U2048};
+
+    type Point = crate::curve::Point<Secp256k1>;
 
     #[test]
     fn prove_and_verify() {
@@ -315,4 +482,111 @@ mod tests {
         );
         assert!(proof.verify(pk0, pk1, &cap_c, &cap_d, &cap_y, &cap_x, &setup, &aux));
     }
+
+    #[test]
+    fn batch_verify() {
+        type Params = TestParams;
+        type Paillier = <Params as SchemeParams>::Paillier;
+
+        let sk0 = SecretKeyPaillier::<Paillier>::random(&mut OsRng).to_precomputed();
+        let pk0 = sk0.public_key();
+
+        let aux_sk = SecretKeyPaillier::<Paillier>::random(&mut OsRng).to_precomputed();
+        let setup = RPParamsMod::random(&mut OsRng, &aux_sk);
+
+        let aux: &[u8] = b"abcde";
+
+        let secret = Signed::random(&mut OsRng);
+        let cap_c = CiphertextMod::new_signed(&mut OsRng, pk0, &secret);
+
+        let sk1s = (0..3)
+            .map(|_| SecretKeyPaillier::<Paillier>::random(&mut OsRng).to_precomputed())
+            .collect::<Vec<_>>();
+
+        let mut instances = sk1s
+            .iter()
+            .map(|sk1| {
+                let pk1 = sk1.public_key();
+
+                let x = Signed::random_bounded_bits(&mut OsRng, Params::L_BOUND);
+                let y = SecretBox::new(Box::new(Signed::random_bounded_bits(
+                    &mut OsRng,
+                    Params::LP_BOUND,
+                )));
+
+                let rho = RandomizerMod::random(&mut OsRng, pk0);
+                let rho_y = RandomizerMod::random(&mut OsRng, pk1);
+
+                let cap_d = &cap_c * x
+                    + CiphertextMod::new_with_randomizer_signed(
+                        pk0,
+                        &-y.expose_secret(),
+                        &rho.retrieve(),
+                    );
+                let cap_y = CiphertextMod::new_with_randomizer_signed(
+                    pk1,
+                    y.expose_secret(),
+                    &rho_y.retrieve(),
+                );
+                let cap_x = Params::scalar_from_signed(&x).mul_by_generator();
+
+                let proof = AffGProof::<Params>::new(
+                    &mut OsRng, &x, &y, rho, rho_y, pk0, pk1, &cap_c, &cap_d, &cap_y, &cap_x,
+                    &setup, &aux,
+                );
+                (proof, pk1, cap_d, cap_y, cap_x)
+            })
+            .collect::<Vec<_>>();
+
+        type Instance<'a> = (
+            AffGProof<Params>,
+            &'a PublicKeyPaillierPrecomputed<Paillier>,
+            CiphertextMod<Paillier>,
+            CiphertextMod<Paillier>,
+            Point,
+        );
+
+        let verify_all = |instances: &[Instance<'_>]| {
+            let individually = instances.iter().all(|(proof, pk1, cap_d, cap_y, cap_x)| {
+                proof.verify(pk0, pk1, &cap_c, cap_d, cap_y, cap_x, &setup, &aux)
+            });
+            let items = instances
+                .iter()
+                .map(|(proof, pk1, cap_d, cap_y, cap_x)| AffGBatchItem {
+                    proof,
+                    pk1,
+                    cap_d,
+                    cap_y,
+                    cap_x,
+                    aux: &aux,
+                })
+                .collect::<Vec<_>>();
+            let batched = AffGProof::verify_batch(&mut OsRng, pk0, &cap_c, &setup, &items);
+            (individually, batched)
+        };
+
+        assert_eq!(verify_all(&instances), (true, true));
+
+        // A single invalid proof among the valid ones fails the whole batch,
+        // whichever of the equations it breaks.
+        let corruptions: [fn(&mut AffGProof<Params>); 4] = [
+            // `z_1` enters the group equation and the first ring-Pedersen equation
+            |proof| proof.z1 = proof.z1 + Signed::new_positive(U1024::ONE, 1).unwrap(),
+            // `z_2` enters the second ring-Pedersen equation
+            |proof| proof.z2 = proof.z2 + Signed::new_positive(U1024::ONE, 1).unwrap(),
+            // `B_x` only enters the batched group equation
+            |proof| proof.cap_b_x = proof.cap_b_x + Point::GENERATOR,
+            // `z_3` only enters the batched ring-Pedersen equation
+            |proof| proof.z3 = proof.z3 + Signed::new_positive(U2048::ONE, 1).unwrap(),
+        ];
+        for corrupt in corruptions {
+            let mut instances = instances.clone();
+            corrupt(&mut instances[1].0);
+            assert_eq!(verify_all(&instances), (false, false));
+        }
+
+        // The order of the proofs in the batch does not matter.
+        instances.reverse();
+        assert_eq!(verify_all(&instances), (true, true));
+    }
 }
@@ -34,7 +34,7 @@ pub(crate) struct DecProof<P: SchemeParams> {
     cap_s: RPCommitment<P::Paillier>,
     cap_t: RPCommitment<P::Paillier>,
     cap_a: Ciphertext<P::Paillier>,
-    gamma: Scalar,
+    gamma: Scalar<P::Curve>,
     z1: Signed<<P::Paillier as PaillierParams>::WideUint>,
     z2: Signed<<P::Paillier as PaillierParams>::WideUint>,
     omega: Randomizer<P::Paillier>,
@@ -47,7 +47,7 @@ impl<P: SchemeParams> DecProof<P> {
         y: &Signed<<P::Paillier as PaillierParams>::Uint>,
         rho: &RandomizerMod<P::Paillier>,
         pk0: &PublicKeyPaillierPrecomputed<P::Paillier>,
-        x: &Scalar,
+        x: &Scalar<P::Curve>,
         cap_c: &CiphertextMod<P::Paillier>,
         setup: &RPParamsMod<P::Paillier>,
         aux: &impl Hashable,
@@ -107,7 +107,7 @@ impl<P: SchemeParams> DecProof<P> {
     pub fn verify(
         &self,
         pk0: &PublicKeyPaillierPrecomputed<P::Paillier>,
-        x: &Scalar,
+        x: &Scalar<P::Curve>,
         cap_c: &CiphertextMod<P::Paillier>,
         setup: &RPParamsMod<P::Paillier>,
         aux: &impl Hashable,
@@ -166,11 +166,14 @@ mod tests {
 
     use super::DecProof;
     use crate::cggmp21::{SchemeParams, TestParams};
+    use crate::curve::Secp256k1;
     use crate::paillier::{
         CiphertextMod, PaillierParams, RPParamsMod, RandomizerMod, SecretKeyPaillier,
     };
     use crate::uint::Signed;
 
+    type Scalar = crate::curve::Scalar<Secp256k1>;
+
     #[test]
     fn prove_and_verify() {
         type Params = TestParams;
@@ -194,4 +197,34 @@ mod tests {
         let proof = DecProof::<Params>::new(&mut OsRng, &y, &rho, pk, &x, &cap_c, &setup, &aux);
         assert!(proof.verify(pk, &x, &cap_c, &setup, &aux));
     }
+
+    #[test]
+    fn wrong_scalar_is_rejected() {
+        type Params = TestParams;
+        type Paillier = <Params as SchemeParams>::Paillier;
+
+        let sk = SecretKeyPaillier::<Paillier>::random(&mut OsRng).to_precomputed();
+        let pk = sk.public_key();
+
+        let aux_sk = SecretKeyPaillier::<Paillier>::random(&mut OsRng).to_precomputed();
+        let setup = RPParamsMod::random(&mut OsRng, &aux_sk);
+
+        let aux: &[u8] = b"abcde";
+
+        let y = Signed::random_bounded_bits(&mut OsRng, Paillier::PRIME_BITS * 2 - 2);
+        let x = Params::scalar_from_signed(&y);
+        let wrong_x = x + Scalar::ONE;
+
+        let rho = RandomizerMod::random(&mut OsRng, pk);
+        let cap_c = CiphertextMod::new_with_randomizer_signed(pk, &y, &rho.retrieve());
+
+        // An honest proof does not verify against a different scalar
+        let proof = DecProof::<Params>::new(&mut OsRng, &y, &rho, pk, &x, &cap_c, &setup, &aux);
+        assert!(!proof.verify(pk, &wrong_x, &cap_c, &setup, &aux));
+
+        // A proof claiming a scalar that is not the plaintext modulo `q` does not verify either
+        let proof =
+            DecProof::<Params>::new(&mut OsRng, &y, &rho, pk, &wrong_x, &cap_c, &setup, &aux);
+        assert!(!proof.verify(pk, &wrong_x, &cap_c, &setup, &aux));
+    }
 }
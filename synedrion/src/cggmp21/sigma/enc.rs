@@ -47,6 +47,20 @@ impl<P: SchemeParams> EncProof<P> {
         aux: &impl Hashable,
     ) -> Self {
         k.assert_bound(P::L_BOUND);
+        Self::new_unchecked(rng, k, rho, pk0, cap_k, setup, aux)
+    }
+
+    /// Creates the proof without checking that `k` is in range,
+    /// which allows tests to play a dishonest prover.
+    fn new_unchecked(
+        rng: &mut impl CryptoRngCore,
+        k: &Signed<<P::Paillier as PaillierParams>::Uint>,
+        rho: &RandomizerMod<P::Paillier>,
+        pk0: &PublicKeyPaillierPrecomputed<P::Paillier>,
+        cap_k: &CiphertextMod<P::Paillier>,
+        setup: &RPParamsMod<P::Paillier>,
+        aux: &impl Hashable,
+    ) -> Self {
         assert_eq!(cap_k.public_key(), pk0);
 
         let hat_cap_n = &setup.public_key().modulus_bounded(); // $\hat{N}$
@@ -182,4 +196,36 @@ mod tests {
         );
         assert!(proof.verify(pk, &ciphertext, &setup, &aux));
     }
+
+    #[test]
+    fn out_of_range_plaintext_is_rejected() {
+        type Params = TestParams;
+        type Paillier = <Params as SchemeParams>::Paillier;
+
+        let sk = SecretKeyPaillier::<Paillier>::random(&mut OsRng).to_precomputed();
+        let pk = sk.public_key();
+
+        let aux_sk = SecretKeyPaillier::<Paillier>::random(&mut OsRng).to_precomputed();
+        let setup = RPParamsMod::random(&mut OsRng, &aux_sk);
+
+        let aux: &[u8] = b"abcde";
+
+        // The plaintext is well over the bound, so the response `z1` will be out of range.
+        let secret =
+            Signed::random_bounded_bits(&mut OsRng, Params::L_BOUND + Params::EPS_BOUND + 64);
+        let randomizer = RandomizerMod::random(&mut OsRng, pk);
+        let ciphertext =
+            CiphertextMod::new_with_randomizer_signed(pk, &secret, &randomizer.retrieve());
+
+        let proof = EncProof::<Params>::new_unchecked(
+            &mut OsRng,
+            &secret,
+            &randomizer,
+            pk,
+            &ciphertext,
+            &setup,
+            &aux,
+        );
+        assert!(!proof.verify(pk, &ciphertext, &setup, &aux));
+    }
 }
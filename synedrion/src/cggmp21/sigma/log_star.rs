@@ -33,7 +33,7 @@ pub(crate) struct LogStarProof<P: SchemeParams> {
     e: Signed<<P::Paillier as PaillierParams>::Uint>,
     cap_s: RPCommitment<P::Paillier>,
     cap_a: Ciphertext<P::Paillier>,
-    cap_y: Point,
+    cap_y: Point<P::Curve>,
     cap_d: RPCommitment<P::Paillier>,
     z1: Signed<<P::Paillier as PaillierParams>::Uint>,
     z2: Randomizer<P::Paillier>,
@@ -48,8 +48,8 @@ impl<P: SchemeParams> LogStarProof<P> {
         rho: &RandomizerMod<P::Paillier>,
         pk0: &PublicKeyPaillierPrecomputed<P::Paillier>,
         cap_c: &CiphertextMod<P::Paillier>,
-        g: &Point,
-        cap_x: &Point,
+        g: &Point<P::Curve>,
+        cap_x: &Point<P::Curve>,
         setup: &RPParamsMod<P::Paillier>,
         aux: &impl Hashable,
     ) -> Self {
@@ -108,8 +108,8 @@ impl<P: SchemeParams> LogStarProof<P> {
         &self,
         pk0: &PublicKeyPaillierPrecomputed<P::Paillier>,
         cap_c: &CiphertextMod<P::Paillier>,
-        g: &Point,
-        cap_x: &Point,
+        g: &Point<P::Curve>,
+        cap_x: &Point<P::Curve>,
         setup: &RPParamsMod<P::Paillier>,
         aux: &impl Hashable,
     ) -> bool {
@@ -171,10 +171,13 @@ mod tests {
 
     use super::LogStarProof;
     use crate::cggmp21::{SchemeParams, TestParams};
-    use crate::curve::{Point, Scalar};
+    use crate::curve::Secp256k1;
     use crate::paillier::{CiphertextMod, RPParamsMod, RandomizerMod, SecretKeyPaillier};
     use crate::uint::Signed;
 
+    type Point = crate::curve::Point<Secp256k1>;
+    type Scalar = crate::curve::Scalar<Secp256k1>;
+
     #[test]
     fn prove_and_verify() {
         type Params = TestParams;
@@ -198,4 +201,54 @@ mod tests {
             LogStarProof::<Params>::new(&mut OsRng, &x, &rho, pk, &cap_c, &g, &cap_x, &setup, &aux);
         assert!(proof.verify(pk, &cap_c, &g, &cap_x, &setup, &aux));
     }
+
+    #[test]
+    fn mismatched_statement_is_rejected() {
+        type Params = TestParams;
+        type Paillier = <Params as SchemeParams>::Paillier;
+
+        let sk = SecretKeyPaillier::<Paillier>::random(&mut OsRng).to_precomputed();
+        let pk = sk.public_key();
+
+        let aux_sk = SecretKeyPaillier::<Paillier>::random(&mut OsRng).to_precomputed();
+        let setup = RPParamsMod::random(&mut OsRng, &aux_sk);
+
+        let aux: &[u8] = b"abcde";
+
+        let g = Point::GENERATOR * Scalar::random(&mut OsRng);
+        let x = Signed::random_bounded_bits(&mut OsRng, Params::L_BOUND);
+        let rho = RandomizerMod::random(&mut OsRng, pk);
+        let cap_c = CiphertextMod::new_with_randomizer_signed(pk, &x, &rho.retrieve());
+        let cap_x = g * Params::scalar_from_signed(&x);
+
+        let proof =
+            LogStarProof::<Params>::new(&mut OsRng, &x, &rho, pk, &cap_c, &g, &cap_x, &setup, &aux);
+
+        // The point is not the exponent of the encrypted value
+        let other_x = g * Scalar::random(&mut OsRng);
+        assert!(!proof.verify(pk, &cap_c, &g, &other_x, &setup, &aux));
+
+        // The exponent is taken relative to a different generator
+        let other_g = Point::GENERATOR * Scalar::random(&mut OsRng);
+        assert!(!proof.verify(pk, &cap_c, &other_g, &cap_x, &setup, &aux));
+
+        // The ciphertext encrypts a different value
+        let other_c = CiphertextMod::new_with_randomizer_signed(
+            pk,
+            &Signed::random_bounded_bits(&mut OsRng, Params::L_BOUND),
+            &rho.retrieve(),
+        );
+        assert!(!proof.verify(pk, &other_c, &g, &cap_x, &setup, &aux));
+
+        // The proof is bound to the auxiliary data
+        let other_aux: &[u8] = b"fghij";
+        assert!(!proof.verify(pk, &cap_c, &g, &cap_x, &setup, &other_aux));
+
+        // A prover lying about the exponent cannot produce a valid proof
+        let wrong_x = cap_x + Point::GENERATOR;
+        let proof = LogStarProof::<Params>::new(
+            &mut OsRng, &x, &rho, pk, &cap_c, &g, &wrong_x, &setup, &aux,
+        );
+        assert!(!proof.verify(pk, &cap_c, &g, &wrong_x, &setup, &aux));
+    }
 }
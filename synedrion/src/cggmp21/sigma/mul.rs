@@ -183,4 +183,40 @@ mod tests {
         );
         assert!(proof.verify(pk, &cap_x, &cap_y, &cap_c, &aux));
     }
+
+    #[test]
+    fn wrong_product_is_rejected() {
+        type Params = TestParams;
+        type Paillier = <Params as SchemeParams>::Paillier;
+
+        let sk = SecretKeyPaillier::<Paillier>::random(&mut OsRng).to_precomputed();
+        let pk = sk.public_key();
+
+        let aux: &[u8] = b"abcde";
+
+        let x = Signed::random_bounded_bits(&mut OsRng, Params::L_BOUND);
+        let y = Signed::random_bounded_bits(&mut OsRng, Params::L_BOUND);
+        let other_x = Signed::random_bounded_bits(&mut OsRng, Params::L_BOUND);
+        let rho_x = RandomizerMod::random(&mut OsRng, pk);
+        let rho = RandomizerMod::random(&mut OsRng, pk);
+
+        let cap_x = CiphertextMod::new_with_randomizer_signed(pk, &x, &rho_x.retrieve());
+        let cap_y = CiphertextMod::new_signed(&mut OsRng, pk, &y);
+
+        // `C` encrypts the product of the plaintext of `Y` with something other than `x`
+        let cap_c = (&cap_y * other_x).mul_randomizer(&rho.retrieve());
+        let proof = MulProof::<Params>::new(
+            &mut OsRng, &x, &rho_x, &rho, pk, &cap_x, &cap_y, &cap_c, &aux,
+        );
+        assert!(!proof.verify(pk, &cap_x, &cap_y, &cap_c, &aux));
+
+        // An honest proof does not verify against a substituted product
+        let cap_c = (&cap_y * x).mul_randomizer(&rho.retrieve());
+        let proof = MulProof::<Params>::new(
+            &mut OsRng, &x, &rho_x, &rho, pk, &cap_x, &cap_y, &cap_c, &aux,
+        );
+        let other_c = (&cap_y * other_x).mul_randomizer(&rho.retrieve());
+        assert!(!proof.verify(pk, &cap_x, &cap_y, &other_c, &aux));
+        assert!(proof.verify(pk, &cap_x, &cap_y, &cap_c, &aux));
+    }
 }
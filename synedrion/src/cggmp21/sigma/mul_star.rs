@@ -32,7 +32,7 @@ Public inputs:
 pub(crate) struct MulStarProof<P: SchemeParams> {
     e: Signed<<P::Paillier as PaillierParams>::Uint>,
     cap_a: Ciphertext<P::Paillier>,
-    cap_b_x: Point,
+    cap_b_x: Point<P::Curve>,
     cap_e: RPCommitment<P::Paillier>,
     cap_s: RPCommitment<P::Paillier>,
     z1: Signed<<P::Paillier as PaillierParams>::Uint>,
@@ -49,7 +49,7 @@ impl<P: SchemeParams> MulStarProof<P> {
         pk0: &PublicKeyPaillierPrecomputed<P::Paillier>,
         cap_c: &CiphertextMod<P::Paillier>,
         cap_d: &CiphertextMod<P::Paillier>,
-        cap_x: &Point,
+        cap_x: &Point<P::Curve>,
         setup: &RPParamsMod<P::Paillier>,
         aux: &impl Hashable,
     ) -> Self {
@@ -117,7 +117,7 @@ impl<P: SchemeParams> MulStarProof<P> {
         pk0: &PublicKeyPaillierPrecomputed<P::Paillier>,
         cap_c: &CiphertextMod<P::Paillier>,
         cap_d: &CiphertextMod<P::Paillier>,
-        cap_x: &Point,
+        cap_x: &Point<P::Curve>,
         setup: &RPParamsMod<P::Paillier>,
         aux: &impl Hashable,
     ) -> bool {
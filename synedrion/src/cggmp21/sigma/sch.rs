@@ -6,19 +6,19 @@
 use rand_core::CryptoRngCore;
 use serde::{Deserialize, Serialize};
 
-use crate::curve::{Point, Scalar};
+use crate::curve::{Curve, Point, Scalar};
 use crate::tools::hashing::{Chain, FofHasher, Hashable};
 
 const HASH_TAG: &[u8] = b"P_sch";
 
 /// Secret data the proof is based on (~ signing key)
 #[derive(Clone)]
-pub(crate) struct SchSecret(
+pub(crate) struct SchSecret<C: Curve>(
     /// `\alpha`
-    Scalar,
+    Scalar<C>,
 );
 
-impl SchSecret {
+impl<C: Curve> SchSecret<C> {
     pub fn random(rng: &mut impl CryptoRngCore) -> Self {
         Self(Scalar::random(rng))
     }
@@ -26,19 +26,23 @@ impl SchSecret {
 
 /// Public data for the proof (~ verifying key)
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct SchCommitment(Point);
+#[serde(bound(serialize = "Point<C>: Serialize"))]
+#[serde(bound(deserialize = "Point<C>: for <'x> Deserialize<'x>"))]
+pub(crate) struct SchCommitment<C: Curve>(Point<C>);
 
-impl SchCommitment {
-    pub fn new(secret: &SchSecret) -> Self {
+impl<C: Curve> SchCommitment<C> {
+    pub fn new(secret: &SchSecret<C>) -> Self {
         Self(secret.0.mul_by_generator())
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-struct SchChallenge(Scalar);
+#[serde(bound(serialize = "Scalar<C>: Serialize"))]
+#[serde(bound(deserialize = "Scalar<C>: for <'x> Deserialize<'x>"))]
+struct SchChallenge<C: Curve>(Scalar<C>);
 
-impl SchChallenge {
-    fn new(public: &Point, commitment: &SchCommitment, aux: &impl Hashable) -> Self {
+impl<C: Curve> SchChallenge<C> {
+    fn new(public: &Point<C>, commitment: &SchCommitment<C>, aux: &impl Hashable) -> Self {
         Self(
             FofHasher::new_with_dst(HASH_TAG)
                 .chain(aux)
@@ -59,17 +63,19 @@ Public inputs:
 - Point $X = g * x$, where $g$ is the curve generator.
 */
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct SchProof {
-    challenge: SchChallenge,
-    proof: Scalar,
+#[serde(bound(serialize = "Scalar<C>: Serialize"))]
+#[serde(bound(deserialize = "Scalar<C>: for <'x> Deserialize<'x>"))]
+pub(crate) struct SchProof<C: Curve> {
+    challenge: SchChallenge<C>,
+    proof: Scalar<C>,
 }
 
-impl SchProof {
+impl<C: Curve> SchProof<C> {
     pub fn new(
-        proof_secret: &SchSecret,
-        x: &Scalar,
-        commitment: &SchCommitment,
-        cap_x: &Point,
+        proof_secret: &SchSecret<C>,
+        x: &Scalar<C>,
+        commitment: &SchCommitment<C>,
+        cap_x: &Point<C>,
         aux: &impl Hashable,
     ) -> Self {
         let challenge = SchChallenge::new(cap_x, commitment, aux);
@@ -77,7 +83,12 @@ impl SchProof {
         Self { challenge, proof }
     }
 
-    pub fn verify(&self, commitment: &SchCommitment, cap_x: &Point, aux: &impl Hashable) -> bool {
+    pub fn verify(
+        &self,
+        commitment: &SchCommitment<C>,
+        cap_x: &Point<C>,
+        aux: &impl Hashable,
+    ) -> bool {
         let challenge = SchChallenge::new(cap_x, commitment, aux);
         challenge == self.challenge
             && self.proof.mul_by_generator() == commitment.0 + cap_x * &challenge.0
@@ -89,11 +100,11 @@ mod tests {
     use rand_core::OsRng;
 
     use super::{SchCommitment, SchProof, SchSecret};
-    use crate::curve::Scalar;
+    use crate::curve::{Scalar, Secp256k1};
 
     #[test]
     fn prove_and_verify() {
-        let secret = Scalar::random(&mut OsRng);
+        let secret = Scalar::<Secp256k1>::random(&mut OsRng);
         let public = secret.mul_by_generator();
         let aux: &[u8] = b"abcde";
 
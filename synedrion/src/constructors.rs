@@ -1,7 +1,14 @@
 use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::String;
 use core::fmt::Debug;
 
+use displaydoc::Display;
+#[cfg(feature = "testvectors")]
+use rand_chacha::ChaCha8Rng;
 use rand_core::CryptoRngCore;
+#[cfg(feature = "testvectors")]
+use rand_core::{RngCore, SeedableRng};
 use serde::{Deserialize, Serialize};
 use signature::{
     hazmat::{PrehashVerifier, RandomizedPrehashSigner},
@@ -9,12 +16,13 @@ use signature::{
 };
 
 use crate::cggmp21::{
-    aux_gen, interactive_signing, key_gen, key_init, key_refresh, AuxGenResult, AuxInfo,
-    InteractiveSigningResult, KeyGenResult, KeyInitResult, KeyRefreshResult, KeyShare,
-    SchemeParams,
+    aux_gen, interactive_signing, key_gen, key_init, key_refresh, presigning, signing,
+    AuxGenResult, AuxInfo, InteractiveSigningResult, KeyGenResult, KeyInitResult, KeyRefreshResult,
+    KeyShare, PaillierPrimes, PresigningData, PresigningResult, SchemeParams, SigningResult,
 };
-use crate::curve::Scalar;
-use crate::sessions::{LocalError, Session, SessionId};
+use crate::curve::{Curve, Point, Scalar};
+use crate::sessions::{LocalError, PartyId, Session, SessionId};
+use crate::tools::hashing::{Chain, FofHasher};
 use crate::www02::{key_resharing, KeyResharingInputs, KeyResharingResult};
 
 /// Prehashed message to sign.
@@ -31,15 +39,7 @@ where
     Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
     P: SchemeParams + 'static,
     Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
-    Verifier: PrehashVerifier<Sig>
-        + Debug
-        + Clone
-        + Ord
-        + Serialize
-        + for<'de> Deserialize<'de>
-        + Send
-        + Sync
-        + 'static,
+    Verifier: PartyId + PrehashVerifier<Sig> + Send + Sync + 'static,
 {
     Session::new::<key_init::Round1<P, Verifier>>(rng, session_id, signer, verifiers, ())
 }
@@ -55,20 +55,38 @@ where
     Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
     P: SchemeParams + 'static,
     Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
-    Verifier: PrehashVerifier<Sig>
-        + Debug
-        + Clone
-        + Ord
-        + Serialize
-        + for<'de> Deserialize<'de>
-        + Send
-        + Sync
-        + 'static,
+    Verifier: PartyId + PrehashVerifier<Sig> + Send + Sync + 'static,
 {
-    Session::new::<key_gen::Round1<P, Verifier>>(rng, session_id, signer, verifiers, ())
+    Session::new::<key_gen::Round1<P, Verifier>>(rng, session_id, signer, verifiers, None)
 }
 
-/// Creates the initial state for the joined KeyGen and KeyRefresh+Auxiliary protocols.
+/// Creates the initial state for the joined KeyGen and KeyRefresh+Auxiliary protocols,
+/// using the given primes for the Paillier key of this party instead of generating them.
+///
+/// See [`PaillierPrimes`] for the security implications.
+pub fn make_key_gen_session_with_primes<P, Sig, Signer, Verifier>(
+    rng: &mut impl CryptoRngCore,
+    session_id: SessionId,
+    signer: Signer,
+    verifiers: &BTreeSet<Verifier>,
+    primes: PaillierPrimes<P>,
+) -> Result<Session<KeyGenResult<P, Verifier>, Sig, Signer, Verifier>, LocalError>
+where
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+    P: SchemeParams + 'static,
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+    Verifier: PartyId + PrehashVerifier<Sig> + Send + Sync + 'static,
+{
+    Session::new::<key_gen::Round1<P, Verifier>>(rng, session_id, signer, verifiers, Some(primes))
+}
+
+/// Creates the initial state for the AuxGen protocol.
+///
+/// The key shares do not depend on the auxiliary data, so this can be used on its own
+/// to replace the Paillier keys and the ring-Pedersen parameters of a set of parties
+/// (e.g. if they are suspected to be weak) while keeping their key shares as they are.
+/// Note that the presigning data refers to the Paillier keys it was created with,
+/// so it must be used together with the auxiliary data it was created from.
 pub fn make_aux_gen_session<P, Sig, Signer, Verifier>(
     rng: &mut impl CryptoRngCore,
     session_id: SessionId,
@@ -79,17 +97,29 @@ where
     Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
     P: SchemeParams + 'static,
     Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
-    Verifier: PrehashVerifier<Sig>
-        + Debug
-        + Clone
-        + Ord
-        + Serialize
-        + for<'de> Deserialize<'de>
-        + Send
-        + Sync
-        + 'static,
+    Verifier: PartyId + PrehashVerifier<Sig> + Send + Sync + 'static,
 {
-    Session::new::<aux_gen::Round1<P, Verifier>>(rng, session_id, signer, verifiers, ())
+    Session::new::<aux_gen::Round1<P, Verifier>>(rng, session_id, signer, verifiers, None)
+}
+
+/// Creates the initial state for the AuxGen protocol,
+/// using the given primes for the Paillier key of this party instead of generating them.
+///
+/// See [`PaillierPrimes`] for the security implications.
+pub fn make_aux_gen_session_with_primes<P, Sig, Signer, Verifier>(
+    rng: &mut impl CryptoRngCore,
+    session_id: SessionId,
+    signer: Signer,
+    verifiers: &BTreeSet<Verifier>,
+    primes: PaillierPrimes<P>,
+) -> Result<Session<AuxGenResult<P, Verifier>, Sig, Signer, Verifier>, LocalError>
+where
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+    P: SchemeParams + 'static,
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+    Verifier: PartyId + PrehashVerifier<Sig> + Send + Sync + 'static,
+{
+    Session::new::<aux_gen::Round1<P, Verifier>>(rng, session_id, signer, verifiers, Some(primes))
 }
 
 /// Creates the initial state for the KeyRefresh+Auxiliary protocol.
@@ -103,20 +133,52 @@ where
     Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
     P: SchemeParams + 'static,
     Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
-    Verifier: PrehashVerifier<Sig>
-        + Debug
-        + Clone
-        + Ord
-        + Serialize
-        + for<'de> Deserialize<'de>
-        + Send
-        + Sync
-        + 'static,
+    Verifier: PartyId + PrehashVerifier<Sig> + Send + Sync + 'static,
+{
+    Session::new::<key_refresh::Round1<P, Verifier>>(rng, session_id, signer, verifiers, None)
+}
+
+/// Checks that the parties of a (pre)signing session are exactly the ones holding the key shares.
+///
+/// `verifiers` identify the parties for the purpose of authenticating protocol messages,
+/// and have nothing to do with the key being shared; but the key share is additive,
+/// so every one of its holders must take part, and no one else can.
+fn check_signing_parties<P, Verifier>(
+    verifiers: &BTreeSet<Verifier>,
+    key_share: &KeyShare<P, Verifier>,
+) -> Result<(), LocalError>
+where
+    P: SchemeParams,
+    Verifier: Debug + Clone + Ord,
 {
-    Session::new::<key_refresh::Round1<P, Verifier>>(rng, session_id, signer, verifiers, ())
+    let key_share_parties = key_share.all_parties();
+    if verifiers.len() != key_share_parties.len() {
+        return Err(LocalError(format!(
+            "The number of verifiers ({}) does not match the number of parties in the key share ({}); \
+            to sign with a subset of the parties, derive the key shares for that subset first \
+            (see `ThresholdKeyShare::to_key_share`)",
+            verifiers.len(),
+            key_share_parties.len()
+        )));
+    }
+    if verifiers != &key_share_parties {
+        return Err(LocalError(
+            "The given verifiers are not the same as the parties in the key share".into(),
+        ));
+    }
+    Ok(())
 }
 
 /// Creates the initial state for the joined Presigning and Signing protocols.
+///
+/// Two independent kinds of keys are involved:
+/// - `signer` and `verifiers` are the keys used to sign and verify the protocol messages
+///   (that is, the identities of the parties), of any signature scheme;
+/// - `key_share` is this party's share of the ECDSA key that the resulting signature
+///   will be verified with.
+///
+/// The key share refers to the parties by their verifiers, so `verifiers`
+/// must be exactly the set of parties of `key_share`; an error is returned otherwise.
 pub fn make_interactive_signing_session<P, Sig, Signer, Verifier>(
     rng: &mut impl CryptoRngCore,
     session_id: SessionId,
@@ -130,22 +192,9 @@ where
     Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
     P: SchemeParams + 'static,
     Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
-    Verifier: PrehashVerifier<Sig>
-        + Debug
-        + Clone
-        + Ord
-        + Serialize
-        + for<'de> Deserialize<'de>
-        + Send
-        + Sync
-        + 'static,
+    Verifier: PartyId + PrehashVerifier<Sig> + Send + Sync + 'static,
 {
-    // TODO (#68): check that key share and aux data owner corresponds to the signer
-    if !verifiers.is_subset(&key_share.all_parties()) {
-        return Err(LocalError(
-            "The given verifiers are not a subset of the ones in the key share".into(),
-        ));
-    }
+    check_signing_parties(verifiers, key_share)?;
 
     let scalar_message = Scalar::from_reduced_bytes(prehashed_message);
 
@@ -160,6 +209,358 @@ where
     )
 }
 
+/// An inconsistency found by [`presigning_preflight`].
+#[derive(Debug, Clone, Display)]
+pub enum PreflightError<Verifier: Debug> {
+    /// The key share is not consistent with itself: {0}.
+    KeyShare(String),
+    /// The auxiliary data of this party is not consistent with itself or with the key share: {0}.
+    AuxInfo(String),
+    /// The public auxiliary data of party {party:?} is malformed: {error}.
+    Party {
+        /// The party whose data is malformed.
+        party: Verifier,
+        /// The description of the problem.
+        error: String,
+    },
+}
+
+/// Checks that the key share and the auxiliary data can be used for presigning,
+/// without running any rounds.
+///
+/// This is much cheaper than the protocol itself, and allows failing early
+/// on data that was corrupted or mixed up in storage.
+/// It does not replace the checks done during the protocol:
+/// the parties still have to have the same key share set, which can be confirmed
+/// by comparing [`KeyShare::verifying_key`] out of band.
+pub fn presigning_preflight<P, Verifier>(
+    key_share: &KeyShare<P, Verifier>,
+    aux_info: &AuxInfo<P, Verifier>,
+) -> Result<(), PreflightError<Verifier>>
+where
+    P: SchemeParams,
+    Verifier: Debug + Clone + Ord,
+{
+    key_share
+        .check_consistency()
+        .map_err(PreflightError::KeyShare)?;
+
+    if aux_info.owner() != key_share.owner() {
+        return Err(PreflightError::AuxInfo(
+            "The owner of the aux data is not the owner of the key share".into(),
+        ));
+    }
+    aux_info.check_own_keys().map_err(PreflightError::AuxInfo)?;
+    if !aux_info
+        .public_aux
+        .keys()
+        .eq(key_share.all_parties().iter())
+    {
+        return Err(PreflightError::AuxInfo(
+            "The parties of the aux data are not the parties of the key share".into(),
+        ));
+    }
+
+    for party in key_share.all_parties() {
+        if let Err(error) = aux_info.check_public_aux(&party) {
+            return Err(PreflightError::Party { party, error });
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates the initial state for the Presigning protocol.
+///
+/// The resulting [`PresigningData`] does not depend on the message,
+/// so it can be created in advance, stored, and later used in [`make_signing_session`].
+///
+/// All the secrets of the protocol are sampled from `rng`;
+/// see `make_presigning_session_from_seed` (behind the `testvectors` feature)
+/// for reproducible runs.
+pub fn make_presigning_session<P, Sig, Signer, Verifier>(
+    rng: &mut impl CryptoRngCore,
+    session_id: SessionId,
+    signer: Signer,
+    verifiers: &BTreeSet<Verifier>,
+    key_share: &KeyShare<P, Verifier>,
+    aux_info: &AuxInfo<P, Verifier>,
+) -> Result<Session<PresigningResult<P, Verifier>, Sig, Signer, Verifier>, LocalError>
+where
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+    P: SchemeParams + 'static,
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+    Verifier: PartyId + PrehashVerifier<Sig> + Send + Sync + 'static,
+{
+    check_signing_parties(verifiers, key_share)?;
+
+    let inputs = presigning::Inputs {
+        key_share: key_share.clone(),
+        aux_info: aux_info.clone(),
+        nonce_only: false,
+        rng_seed: None,
+    };
+
+    Session::new::<presigning::Round1<P, Verifier>>(rng, session_id, signer, verifiers, inputs)
+}
+
+/// Creates the initial state for the Presigning protocol that only generates the nonce.
+///
+/// This skips the calculation of the share of `k * x` (roughly halving the work in Round 2),
+/// so the resulting [`PresigningData`] cannot be used in [`make_signing_session`].
+/// Only the nonce point and the ephemeral scalar share are available
+/// (see [`PresigningData::experimental_nonce_point`]
+/// and [`PresigningData::experimental_ephemeral_scalar_share`]).
+pub fn make_nonce_presigning_session<P, Sig, Signer, Verifier>(
+    rng: &mut impl CryptoRngCore,
+    session_id: SessionId,
+    signer: Signer,
+    verifiers: &BTreeSet<Verifier>,
+    key_share: &KeyShare<P, Verifier>,
+    aux_info: &AuxInfo<P, Verifier>,
+) -> Result<Session<PresigningResult<P, Verifier>, Sig, Signer, Verifier>, LocalError>
+where
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+    P: SchemeParams + 'static,
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+    Verifier: PartyId + PrehashVerifier<Sig> + Send + Sync + 'static,
+{
+    check_signing_parties(verifiers, key_share)?;
+
+    let inputs = presigning::Inputs {
+        key_share: key_share.clone(),
+        aux_info: aux_info.clone(),
+        nonce_only: true,
+        rng_seed: None,
+    };
+
+    Session::new::<presigning::Round1<P, Verifier>>(rng, session_id, signer, verifiers, inputs)
+}
+
+/// Creates the initial state for the Presigning protocol
+/// with all the secrets of this party derived from `seed`.
+///
+/// This includes the randomness used in the later rounds; the RNGs passed
+/// to the methods of the session are only used to sign the messages.
+/// Running the protocol with the same seeds and inputs produces identical message payloads
+/// and [`PresigningData`], which is useful for regression testing and test vectors.
+///
+/// **Insecure, for tests only**: the secrets are known to anyone who knows the seed.
+#[cfg(feature = "testvectors")]
+pub fn make_presigning_session_from_seed<P, Sig, Signer, Verifier>(
+    seed: [u8; 32],
+    session_id: SessionId,
+    signer: Signer,
+    verifiers: &BTreeSet<Verifier>,
+    key_share: &KeyShare<P, Verifier>,
+    aux_info: &AuxInfo<P, Verifier>,
+) -> Result<Session<PresigningResult<P, Verifier>, Sig, Signer, Verifier>, LocalError>
+where
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+    P: SchemeParams + 'static,
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+    Verifier: PartyId + PrehashVerifier<Sig> + Send + Sync + 'static,
+{
+    check_signing_parties(verifiers, key_share)?;
+
+    let mut rng = ChaCha8Rng::from_seed(seed);
+    let mut rng_seed = [0u8; 32];
+    rng.fill_bytes(&mut rng_seed);
+
+    let inputs = presigning::Inputs {
+        key_share: key_share.clone(),
+        aux_info: aux_info.clone(),
+        nonce_only: false,
+        rng_seed: Some(rng_seed),
+    };
+
+    Session::new::<presigning::Round1<P, Verifier>>(&mut rng, session_id, signer, verifiers, inputs)
+}
+
+/// Creates the initial state for the Signing protocol using a previously created presignature.
+///
+/// The presigning data is consumed, since using it for more than one message
+/// would reveal the secret key.
+/// The session must be created for the same set of parties as the presigning session.
+#[allow(clippy::too_many_arguments)]
+pub fn make_signing_session<P, Sig, Signer, Verifier>(
+    rng: &mut impl CryptoRngCore,
+    session_id: SessionId,
+    signer: Signer,
+    verifiers: &BTreeSet<Verifier>,
+    key_share: &KeyShare<P, Verifier>,
+    aux_info: &AuxInfo<P, Verifier>,
+    presigning: PresigningData<P, Verifier>,
+    prehashed_message: &PrehashedMessage,
+) -> Result<Session<SigningResult<P, Verifier>, Sig, Signer, Verifier>, LocalError>
+where
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+    P: SchemeParams + 'static,
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+    Verifier: PartyId + PrehashVerifier<Sig> + Send + Sync + 'static,
+{
+    check_signing_parties(verifiers, key_share)?;
+
+    let inputs = signing::Inputs {
+        message: Scalar::from_reduced_bytes(prehashed_message),
+        presigning,
+        key_share: key_share.clone(),
+        aux_info: aux_info.clone(),
+    };
+
+    Session::new::<signing::Round1<P, Verifier>>(rng, session_id, signer, verifiers, inputs)
+}
+
+/// An error returned by [`SigningSessionBuilder::build`].
+#[derive(Debug, Clone, Display)]
+pub enum InitError {
+    /// The key share does not correspond to the expected verifying key
+    VerifyingKeyMismatch,
+    /// {0}
+    Local(LocalError),
+}
+
+impl From<LocalError> for InitError {
+    fn from(err: LocalError) -> Self {
+        Self::Local(err)
+    }
+}
+
+enum SessionIdSource {
+    None,
+    Given(SessionId),
+    Deterministic,
+}
+
+/// A builder for the interactive signing session,
+/// for the cases when the defaults of [`make_interactive_signing_session`] are not enough.
+pub struct SigningSessionBuilder<'a, P: SchemeParams, Signer, Verifier: Ord> {
+    signer: Signer,
+    key_share: &'a KeyShare<P, Verifier>,
+    aux_info: &'a AuxInfo<P, Verifier>,
+    prehashed_message: &'a PrehashedMessage,
+    verifiers: Option<BTreeSet<Verifier>>,
+    session_id: SessionIdSource,
+    expected_verifying_key: Option<<P::Curve as Curve>::VerifyingKey>,
+}
+
+impl<'a, P, Signer, Verifier> SigningSessionBuilder<'a, P, Signer, Verifier>
+where
+    P: SchemeParams + 'static,
+    Verifier: PartyId + Send + Sync + 'static,
+{
+    /// Creates a new builder for signing `prehashed_message` with the given key share.
+    ///
+    /// By default all the parties in the key share take part in the signing.
+    /// The session ID must be set either explicitly via [`Self::session_id`],
+    /// or by calling [`Self::deterministic_session_id`].
+    pub fn new(
+        signer: Signer,
+        key_share: &'a KeyShare<P, Verifier>,
+        aux_info: &'a AuxInfo<P, Verifier>,
+        prehashed_message: &'a PrehashedMessage,
+    ) -> Self {
+        Self {
+            signer,
+            key_share,
+            aux_info,
+            prehashed_message,
+            verifiers: None,
+            session_id: SessionIdSource::None,
+            expected_verifying_key: None,
+        }
+    }
+
+    /// Sets the parties taking part in the signing.
+    ///
+    /// They must be the same as the parties of the key share
+    /// (see [`make_interactive_signing_session`]), so this is only a safety check.
+    pub fn with_verifiers(self, verifiers: &BTreeSet<Verifier>) -> Self {
+        Self {
+            verifiers: Some(verifiers.clone()),
+            ..self
+        }
+    }
+
+    /// Sets the session ID.
+    pub fn session_id(self, session_id: SessionId) -> Self {
+        Self {
+            session_id: SessionIdSource::Given(session_id),
+            ..self
+        }
+    }
+
+    /// Derives the session ID from the verifying key, the set of parties, and the message.
+    ///
+    /// This way all the parties arrive at the same session ID without additional communication.
+    /// Note that it will be the same for every signing of the same message by the same parties,
+    /// so this should only be used if the message is never signed twice.
+    pub fn deterministic_session_id(self) -> Self {
+        Self {
+            session_id: SessionIdSource::Deterministic,
+            ..self
+        }
+    }
+
+    /// Makes [`Self::build`] fail if the key share does not correspond to the given verifying key
+    /// (e.g. if a wrong key share was loaded from the storage).
+    pub fn expected_verifying_key(self, verifying_key: &<P::Curve as Curve>::VerifyingKey) -> Self {
+        Self {
+            expected_verifying_key: Some(*verifying_key),
+            ..self
+        }
+    }
+
+    /// Creates the initial state for the Interactive Signing protocol.
+    pub fn build<Sig>(
+        self,
+        rng: &mut impl CryptoRngCore,
+    ) -> Result<Session<InteractiveSigningResult<P, Verifier>, Sig, Signer, Verifier>, InitError>
+    where
+        Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+        Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+        Verifier: PrehashVerifier<Sig>,
+    {
+        if let Some(verifying_key) = self.expected_verifying_key {
+            if self.key_share.verifying_key_as_point()
+                != Point::<P::Curve>::from_verifying_key(&verifying_key)
+            {
+                return Err(InitError::VerifyingKeyMismatch);
+            }
+        }
+
+        let verifiers = match self.verifiers {
+            Some(verifiers) => verifiers,
+            None => self.key_share.all_parties(),
+        };
+
+        let session_id = match self.session_id {
+            SessionIdSource::None => {
+                return Err(LocalError("The session ID was not set".into()).into());
+            }
+            SessionIdSource::Given(session_id) => session_id,
+            SessionIdSource::Deterministic => {
+                let hash = FofHasher::new_with_dst(b"SigningSessionId")
+                    .chain(&self.key_share.verifying_key_as_point())
+                    .chain(&verifiers)
+                    .chain(self.prehashed_message)
+                    .finalize();
+                SessionId::from_seed(hash.as_ref())
+            }
+        };
+
+        Ok(make_interactive_signing_session(
+            rng,
+            session_id,
+            self.signer,
+            &verifiers,
+            self.key_share,
+            self.aux_info,
+            self.prehashed_message,
+        )?)
+    }
+}
+
 /// Creates the initial state for the Key Resharing protocol.
 pub fn make_key_resharing_session<P, Sig, Signer, Verifier>(
     rng: &mut impl CryptoRngCore,
@@ -172,15 +573,7 @@ where
     Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
     P: SchemeParams + 'static,
     Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
-    Verifier: PrehashVerifier<Sig>
-        + Debug
-        + Clone
-        + Ord
-        + Serialize
-        + for<'de> Deserialize<'de>
-        + Send
-        + Sync
-        + 'static,
+    Verifier: PartyId + PrehashVerifier<Sig> + Send + Sync + 'static,
 {
     let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
 
@@ -1,12 +1,19 @@
 //! This module is an adapter to the ECC backend.
 //! `elliptic_curves` has a somewhat unstable API,
 //! and we isolate all the related logic here.
-// TODO (#27): make the library generic over the curve
 
 mod arithmetic;
+mod curves;
 mod ecdsa;
+mod encoding;
 
-pub(crate) use arithmetic::ORDER;
-
-pub use self::ecdsa::RecoverableSignature;
-pub(crate) use arithmetic::{Curve, Point, Scalar};
+pub use self::ecdsa::{batch_verify_signatures, RecoverableSignature};
+pub(crate) use arithmetic::{Point, Scalar};
+#[cfg(feature = "p256")]
+pub use curves::Secp256r1;
+pub use curves::{Curve, Secp256k1};
+pub use encoding::{
+    point_from_sec1_bytes, point_from_verifying_key, point_to_uncompressed_bytes,
+    point_to_verifying_key, scalar_from_reduced_bytes, scalar_from_wide_bytes,
+    scalar_to_reduced_bytes, EncodingError,
+};
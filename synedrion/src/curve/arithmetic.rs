@@ -1,65 +1,60 @@
 use alloc::format;
 use alloc::string::String;
 use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
 use core::default::Default;
 use core::ops::{Add, Mul, Neg, Sub};
 
 use digest::Digest;
-use k256::elliptic_curve::group::ff::PrimeField;
 use k256::elliptic_curve::{
-    array::{typenum::marker_traits::Unsigned, Array},
-    bigint::U256, // Note that this type is different from typenum::U256
-    ops::Reduce,
-    point::AffineCoordinates,
-    sec1::{EncodedPoint, FromEncodedPoint, ModulusSize, ToEncodedPoint},
-    subtle::{Choice, ConditionallySelectable, CtOption},
-    Curve as _,
+    consts::U32,
+    subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption},
     Field,
-    FieldBytesSize,
-    NonZeroScalar,
-};
-use k256::{
-    ecdsa::{SigningKey, VerifyingKey},
-    Secp256k1,
 };
 use rand_core::CryptoRngCore;
 use secrecy::{CloneableSecret, SerializableSecret};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use zeroize::DefaultIsZeroes;
 
-use crate::tools::hashing::{Chain, HashableType};
+use super::curves::{Curve, Secp256k1};
 use crate::tools::serde_bytes;
 
-pub(crate) type Curve = Secp256k1;
-pub(crate) type BackendScalar = k256::Scalar;
-pub(crate) type BackendPoint = k256::ProjectivePoint;
-pub(crate) type CompressedPointSize =
-    <FieldBytesSize<Secp256k1> as ModulusSize>::CompressedPointSize;
-
-pub(crate) const ORDER: U256 = Secp256k1::ORDER;
-
-impl HashableType for Curve {
-    fn chain_type<C: Chain>(digest: C) -> C {
-        digest.chain(&ORDER).chain(&Point::GENERATOR)
-    }
-}
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, PartialOrd, Ord)]
-pub struct Scalar(BackendScalar);
+/// A curve scalar.
+///
+/// Note that `==` is constant-time (it is delegated to the backend scalar),
+/// but the ordering is not, so it must not be used on secret values.
+/// [`ConstantTimeEq`] can be used where a [`Choice`] is needed instead of a `bool`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Scalar<C: Curve>(C::BackendScalar);
 
-impl Scalar {
-    pub const ZERO: Self = Self(BackendScalar::ZERO);
-    pub const ONE: Self = Self(BackendScalar::ONE);
+impl<C: Curve> Scalar<C> {
+    pub const ZERO: Self = Self(<C::BackendScalar as Field>::ZERO);
+    pub const ONE: Self = Self(<C::BackendScalar as Field>::ONE);
 
     pub fn random(rng: &mut impl CryptoRngCore) -> Self {
-        Self(BackendScalar::random(rng))
+        Self(C::BackendScalar::random(rng))
     }
 
     pub fn random_nonzero(rng: &mut impl CryptoRngCore) -> Self {
-        Self(*NonZeroScalar::<Secp256k1>::random(rng).as_ref())
+        loop {
+            let scalar = C::BackendScalar::random(&mut *rng);
+            if !bool::from(scalar.is_zero()) {
+                return Self(scalar);
+            }
+        }
     }
 
-    pub fn mul_by_generator(&self) -> Point {
+    /// Returns `self * G`, where `G` is the curve generator.
+    ///
+    /// Uses the backend's precomputed multiples of the generator,
+    /// which is several times faster than a generic point multiplication.
+    pub fn mul_by_generator(&self) -> Point<C> {
+        Point(C::mul_by_generator(&self.0))
+    }
+
+    /// Same as [`Self::mul_by_generator`], but via a generic point multiplication.
+    #[cfg(any(test, feature = "bench-internals"))]
+    pub(crate) fn mul_by_generator_naive(&self) -> Point<C> {
         Point::GENERATOR * self
     }
 
@@ -67,310 +62,570 @@ impl Scalar {
         self.0.invert().map(Self)
     }
 
-    pub fn from_digest(d: impl Digest<OutputSize = FieldBytesSize<Secp256k1>>) -> Self {
+    pub fn from_digest(d: impl Digest<OutputSize = U32>) -> Self {
         // There's currently no way to make the required digest output size
         // depend on the target scalar size, so we are hardcoding it to 256 bit
-        // (that is, equal to the scalar size).
-        Self(<BackendScalar as Reduce<U256>>::reduce_bytes(&d.finalize()))
+        // (that is, equal to the scalar size of all the supported curves).
+        Self::from_reduced_bytes(&d.finalize().into())
     }
 
     /// Convert a 32-byte hash digest into a scalar as per SEC1:
     /// <https://www.secg.org/sec1-v2.pdf< Section 4.1.3 steps 5-6 page 45
     ///
-    /// SEC1 specifies to subtract the secp256k1 modulus when the byte array
-    /// is larger than the modulus.
+    /// SEC1 specifies to subtract the curve order when the byte array
+    /// is larger than the order.
     pub fn from_reduced_bytes(bytes: &[u8; 32]) -> Self {
-        let arr = Array::<u8, FieldBytesSize<Secp256k1>>::from(*bytes);
-        Self(<BackendScalar as Reduce<U256>>::reduce_bytes(&arr))
+        Self(C::reduce_bytes(bytes))
+    }
+
+    /// Same as [`Self::from_reduced_bytes`], but takes a slice,
+    /// returning an error if it is not 32 bytes long.
+    pub fn try_from_reduced_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let bytes: &[u8; 32] = bytes
+            .try_into()
+            .map_err(|_| format!("Expected 32 bytes, got {}", bytes.len()))?;
+        Ok(Self::from_reduced_bytes(bytes))
+    }
+
+    /// Returns the big-endian representation of the scalar.
+    ///
+    /// Since the value is already reduced,
+    /// [`Self::from_reduced_bytes`] maps the result back to the same scalar.
+    pub fn to_reduced_bytes(self) -> [u8; 32] {
+        self.to_bytes()
+    }
+
+    /// Converts a 64-byte big-endian integer into a scalar by reducing it modulo the curve order
+    /// (e.g. for deriving nonces from a wide hash, as in RFC 6979 or `hash_to_field`).
+    ///
+    /// Unlike [`Self::from_reduced_bytes`], the bias of the result is negligible
+    /// for a uniformly distributed input.
+    pub fn from_wide_bytes(bytes: &[u8; 64]) -> Self {
+        // The value is `hi * 2^256 + lo`. Since the curve order is greater than `2^255`,
+        // reducing each half takes at most one subtraction, so both are exact.
+        let (hi, lo) = bytes.split_at(32);
+        let hi = Self::try_from_reduced_bytes(hi).expect("the slice is 32 bytes long");
+        let lo = Self::try_from_reduced_bytes(lo).expect("the slice is 32 bytes long");
+        let mut two_128 = [0u8; 32];
+        two_128[15] = 1;
+        let two_128 = Self::from_reduced_bytes(&two_128);
+        hi * two_128 * two_128 + lo
     }
 
-    pub fn to_bytes(self) -> k256::FieldBytes {
-        self.0.to_bytes()
+    pub fn to_bytes(self) -> [u8; 32] {
+        C::scalar_to_bytes(&self.0)
     }
 
     pub fn repr_len() -> usize {
-        <FieldBytesSize<Secp256k1> as Unsigned>::to_usize()
+        32
+    }
+
+    pub(crate) fn from_backend(scalar: C::BackendScalar) -> Self {
+        Self(scalar)
     }
 
-    pub(crate) fn to_backend(self) -> BackendScalar {
+    pub(crate) fn to_backend(self) -> C::BackendScalar {
         self.0
     }
 
-    pub fn to_signing_key(self) -> Option<SigningKey> {
-        let scalar: Option<NonZeroScalar<Secp256k1>> = NonZeroScalar::new(self.0).into();
-        Some(SigningKey::from(scalar?))
+    pub fn to_signing_key(self) -> Option<C::SigningKey> {
+        C::signing_key_from_scalar(&self.0)
     }
 
-    pub fn from_signing_key(sk: &SigningKey) -> Self {
-        Self(*sk.as_nonzero_scalar().as_ref())
+    pub fn from_signing_key(sk: &C::SigningKey) -> Self {
+        Self(C::scalar_from_signing_key(sk))
     }
 
     pub(crate) fn try_from_bytes(bytes: &[u8]) -> Result<Self, String> {
-        let arr = Array::<u8, FieldBytesSize<Secp256k1>>::try_from_iter(bytes.iter().cloned())
-            .map_err(|e| format!("Invalid length of a curve scalar: {:?}", e))?;
+        let bytes: &[u8; 32] = bytes
+            .try_into()
+            .map_err(|_| format!("Invalid length of a curve scalar: {}", bytes.len()))?;
 
-        BackendScalar::from_repr_vartime(arr)
+        C::scalar_from_bytes(bytes)
             .map(Self)
             .ok_or_else(|| "Invalid curve scalar representation".into())
     }
 
-    pub(crate) fn split(&self, rng: &mut impl CryptoRngCore, num: usize) -> Vec<Scalar> {
+    /// Returns `num` random scalars summing to zero (e.g. to be used as masks).
+    pub(crate) fn zero_sum(rng: &mut impl CryptoRngCore, num: usize) -> Vec<Self> {
         // TODO (#5): do all the parts have to be non-zero?
-        if num == 1 {
-            return vec![*self];
+        if num <= 1 {
+            return vec![Self::ZERO; num];
         }
 
         let mut parts = (0..(num - 1))
-            .map(|_| Scalar::random(rng))
+            .map(|_| Self::random(rng))
             .collect::<Vec<_>>();
-        let partial_sum: Scalar = parts.iter().sum();
-        parts.push(self - &partial_sum);
+        let partial_sum: Self = parts.iter().sum();
+        parts.push(-partial_sum);
         parts
     }
+
+    /// Splits `value` into `num` additive shares: random scalars summing to `value`.
+    ///
+    /// This is a trusted dealer primitive: whoever calls it knows `value`
+    /// and all of the shares, so it must only be used where that is acceptable
+    /// (tests, importing an existing key, etc).
+    /// Any `num - 1` of the shares are uniformly random and independent of `value`.
+    ///
+    /// Panics if `num` is zero.
+    pub(crate) fn split_additive(
+        rng: &mut impl CryptoRngCore,
+        value: &Self,
+        num: usize,
+    ) -> Vec<Self> {
+        assert!(num > 0, "Cannot split a scalar into zero shares");
+        let mut shares = Self::zero_sum(rng, num);
+        shares[0] = &shares[0] + value;
+        shares
+    }
 }
 
-impl<'a> TryFrom<&'a [u8]> for Scalar {
+impl<C: Curve> Default for Scalar<C> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl<C: Curve> PartialOrd for Scalar<C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<C: Curve> Ord for Scalar<C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Big-endian representations of reduced values compare as the values themselves
+        self.to_bytes().cmp(&other.to_bytes())
+    }
+}
+
+impl<'a, C: Curve> TryFrom<&'a [u8]> for Scalar<C> {
     type Error = String;
     fn try_from(val: &'a [u8]) -> Result<Self, Self::Error> {
         Self::try_from_bytes(val)
     }
 }
 
-impl From<&NonZeroScalar<Secp256k1>> for Scalar {
-    fn from(val: &NonZeroScalar<Secp256k1>) -> Self {
+impl From<&k256::NonZeroScalar> for Scalar<Secp256k1> {
+    fn from(val: &k256::NonZeroScalar) -> Self {
         Self(*val.as_ref())
     }
 }
 
-impl ConditionallySelectable for Scalar {
+impl<C: Curve> ConditionallySelectable for Scalar<C> {
     fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
-        Self(BackendScalar::conditional_select(&a.0, &b.0, choice))
+        Self(C::BackendScalar::conditional_select(&a.0, &b.0, choice))
+    }
+}
+
+impl<C: Curve> ConstantTimeEq for Scalar<C> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
     }
 }
 
-impl Serialize for Scalar {
+impl<C: Curve> Serialize for Scalar<C> {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         serde_bytes::as_hex::serialize(&self.to_bytes(), serializer)
     }
 }
 
-impl<'de> Deserialize<'de> for Scalar {
+impl<'de, C: Curve> Deserialize<'de> for Scalar<C> {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
         serde_bytes::as_hex::deserialize(deserializer)
     }
 }
 
-impl DefaultIsZeroes for Scalar {}
+impl<C: Curve> DefaultIsZeroes for Scalar<C> {}
 
-impl CloneableSecret for Scalar {}
+impl<C: Curve> CloneableSecret for Scalar<C> {}
 
-impl SerializableSecret for Scalar {}
+impl<C: Curve> SerializableSecret for Scalar<C> {}
 
+/// A curve point.
+///
+/// Note that `==` is constant-time (it is delegated to the backend point).
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub struct Point(BackendPoint);
+pub struct Point<C: Curve>(C::BackendPoint);
+
+impl<C: Curve> ConstantTimeEq for Point<C> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl<C: Curve> Point<C> {
+    pub const GENERATOR: Self = Self(C::GENERATOR);
 
-impl Point {
-    pub const GENERATOR: Self = Self(BackendPoint::GENERATOR);
+    pub const IDENTITY: Self = Self(C::IDENTITY);
 
-    pub const IDENTITY: Self = Self(BackendPoint::IDENTITY);
+    pub fn x_coordinate(&self) -> Scalar<C> {
+        Scalar(C::x_coordinate(&self.0))
+    }
 
-    pub fn x_coordinate(&self) -> Scalar {
-        let bytes = self.0.to_affine().x();
-        Scalar(<BackendScalar as Reduce<U256>>::reduce_bytes(&bytes))
+    /// Converts a verifying key (e.g. loaded from storage) to a curve point.
+    pub fn from_verifying_key(key: &C::VerifyingKey) -> Self {
+        Self(C::point_from_verifying_key(key))
     }
 
-    pub fn from_verifying_key(key: &VerifyingKey) -> Self {
-        Self(key.as_affine().into())
+    /// Converts the point to a verifying key.
+    ///
+    /// Returns `None` if the point is the identity, which is not a valid verifying key.
+    /// Otherwise, this is the inverse of [`Self::from_verifying_key`].
+    pub fn to_verifying_key(self) -> Option<C::VerifyingKey> {
+        C::verifying_key_from_point(&self.0)
     }
 
-    pub fn to_verifying_key(self) -> Option<VerifyingKey> {
-        VerifyingKey::from_affine(self.0.to_affine()).ok()
+    /// Deserializes a point from a SEC1 encoding, either compressed (`0x02`/`0x03`-prefixed)
+    /// or uncompressed (`0x04`-prefixed).
+    ///
+    /// The identity point (encoded as a single `0x00` byte) is rejected,
+    /// as well as any other form of the encoding.
+    pub fn try_from_sec1_bytes(bytes: &[u8]) -> Result<Self, String> {
+        match (bytes.first(), bytes.len()) {
+            (Some(0x02) | Some(0x03), 33) | (Some(0x04), 65) => {}
+            (Some(0x00), 1) => return Err("The identity point is not accepted".into()),
+            (Some(tag), len) => {
+                return Err(format!(
+                    "Unsupported SEC1 encoding: tag {tag:#04x}, length {len}"
+                ))
+            }
+            (None, _) => return Err("Empty SEC1 encoding".into()),
+        }
+
+        C::point_from_sec1_bytes(bytes).map(Self)
+    }
+
+    /// Returns the uncompressed SEC1 encoding of the point (`0x04`-prefixed).
+    ///
+    /// Returns `None` if the point is the identity, which does not have one.
+    pub fn to_uncompressed_bytes(self) -> Option<[u8; 65]> {
+        C::point_to_uncompressed_bytes(&self.0)
     }
 
     pub(crate) fn try_from_compressed_bytes(bytes: &[u8]) -> Result<Self, String> {
-        let ep = EncodedPoint::<Secp256k1>::from_bytes(bytes).map_err(|err| format!("{err}"))?;
+        C::point_from_sec1_bytes(bytes).map(Self)
+    }
 
-        // Unwrap CtOption into Option
-        let cp_opt: Option<BackendPoint> = BackendPoint::from_encoded_point(&ep).into();
-        cp_opt
-            .map(Self)
-            .ok_or_else(|| "Invalid curve point representation".into())
+    pub(crate) fn to_compressed_array(self) -> [u8; 33] {
+        C::point_to_compressed_bytes(&self.0)
+            .expect("An AffinePoint is composed of elements of the correct size and their slice repr fits in the 33-byte array.")
     }
 
-    pub(crate) fn to_compressed_array(self) -> Array<u8, CompressedPointSize> {
-        self.0
-            .to_affine()
-            .to_encoded_point(true)
-            .as_bytes()
-            .try_into()
-            .expect("An AffinePoint is composed of elements of the correct size and their slice repr fits in the `CompressedPointSize`-sized array.")
+    pub(crate) fn from_backend(point: C::BackendPoint) -> Self {
+        Self(point)
     }
 
-    pub(crate) fn to_backend(self) -> BackendPoint {
+    pub(crate) fn to_backend(self) -> C::BackendPoint {
         self.0
     }
 }
 
-impl<'a> TryFrom<&'a [u8]> for Point {
+impl<'a, C: Curve> TryFrom<&'a [u8]> for Point<C> {
     type Error = String;
     fn try_from(val: &'a [u8]) -> Result<Self, Self::Error> {
         Self::try_from_compressed_bytes(val)
     }
 }
 
-impl Serialize for Point {
+impl<C: Curve> Serialize for Point<C> {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         serde_bytes::as_hex::serialize(&self.to_compressed_array(), serializer)
     }
 }
 
-impl<'de> Deserialize<'de> for Point {
+impl<'de, C: Curve> Deserialize<'de> for Point<C> {
     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
         serde_bytes::as_hex::deserialize(deserializer)
     }
 }
 
-impl From<u32> for Scalar {
+impl<C: Curve> From<u32> for Scalar<C> {
     fn from(val: u32) -> Self {
-        Self(BackendScalar::from(val))
+        Self(C::BackendScalar::from(u64::from(val)))
     }
 }
 
-impl From<usize> for Scalar {
+impl<C: Curve> From<usize> for Scalar<C> {
     fn from(val: usize) -> Self {
-        Self(BackendScalar::from(u64::try_from(val).unwrap()))
+        Self(C::BackendScalar::from(u64::try_from(val).unwrap()))
     }
 }
 
-impl Neg for Scalar {
+impl<C: Curve> Neg for Scalar<C> {
     type Output = Self;
     fn neg(self) -> Self::Output {
         Self(-self.0)
     }
 }
 
-impl Add<Scalar> for Scalar {
-    type Output = Scalar;
+impl<C: Curve> Add<Scalar<C>> for Scalar<C> {
+    type Output = Scalar<C>;
 
-    fn add(self, other: Scalar) -> Scalar {
+    fn add(self, other: Scalar<C>) -> Scalar<C> {
         Scalar(self.0.add(&other.0))
     }
 }
 
-impl Add<&Scalar> for &Scalar {
-    type Output = Scalar;
+impl<C: Curve> Add<&Scalar<C>> for &Scalar<C> {
+    type Output = Scalar<C>;
 
-    fn add(self, other: &Scalar) -> Scalar {
+    fn add(self, other: &Scalar<C>) -> Scalar<C> {
         Scalar(self.0.add(&other.0))
     }
 }
 
-impl Add<Point> for Point {
-    type Output = Point;
+impl<C: Curve> Add<Point<C>> for Point<C> {
+    type Output = Point<C>;
 
-    fn add(self, other: Point) -> Point {
+    fn add(self, other: Point<C>) -> Point<C> {
         Point(self.0.add(&(other.0)))
     }
 }
 
-impl Add<&Point> for &Point {
-    type Output = Point;
+impl<C: Curve> Add<&Point<C>> for &Point<C> {
+    type Output = Point<C>;
 
-    fn add(self, other: &Point) -> Point {
+    fn add(self, other: &Point<C>) -> Point<C> {
         Point(self.0.add(&(other.0)))
     }
 }
 
-impl Sub<Scalar> for Scalar {
-    type Output = Scalar;
+impl<C: Curve> Sub<Scalar<C>> for Scalar<C> {
+    type Output = Scalar<C>;
 
-    fn sub(self, other: Scalar) -> Scalar {
+    fn sub(self, other: Scalar<C>) -> Scalar<C> {
         Scalar(self.0.sub(&(other.0)))
     }
 }
 
-impl Sub<&Scalar> for &Scalar {
-    type Output = Scalar;
+impl<C: Curve> Sub<&Scalar<C>> for &Scalar<C> {
+    type Output = Scalar<C>;
 
-    fn sub(self, other: &Scalar) -> Scalar {
+    fn sub(self, other: &Scalar<C>) -> Scalar<C> {
         Scalar(self.0.sub(&(other.0)))
     }
 }
 
-impl Mul<Scalar> for Point {
-    type Output = Point;
+impl<C: Curve> Mul<Scalar<C>> for Point<C> {
+    type Output = Point<C>;
 
-    fn mul(self, other: Scalar) -> Point {
+    fn mul(self, other: Scalar<C>) -> Point<C> {
         Point(self.0.mul(&(other.0)))
     }
 }
 
-impl Mul<&Scalar> for Point {
-    type Output = Point;
+impl<C: Curve> Mul<&Scalar<C>> for Point<C> {
+    type Output = Point<C>;
 
-    fn mul(self, other: &Scalar) -> Point {
+    fn mul(self, other: &Scalar<C>) -> Point<C> {
         Point(self.0.mul(&(other.0)))
     }
 }
 
-impl Mul<&Scalar> for &Point {
-    type Output = Point;
+impl<C: Curve> Mul<&Scalar<C>> for &Point<C> {
+    type Output = Point<C>;
 
-    fn mul(self, other: &Scalar) -> Point {
+    fn mul(self, other: &Scalar<C>) -> Point<C> {
         Point(self.0.mul(&(other.0)))
     }
 }
 
-impl Mul<Scalar> for Scalar {
-    type Output = Scalar;
+impl<C: Curve> Mul<Scalar<C>> for Scalar<C> {
+    type Output = Scalar<C>;
 
-    fn mul(self, other: Scalar) -> Scalar {
+    fn mul(self, other: Scalar<C>) -> Scalar<C> {
         Scalar(self.0.mul(&(other.0)))
     }
 }
 
-impl Mul<&Scalar> for Scalar {
-    type Output = Scalar;
+impl<C: Curve> Mul<&Scalar<C>> for Scalar<C> {
+    type Output = Scalar<C>;
 
-    fn mul(self, other: &Scalar) -> Scalar {
+    fn mul(self, other: &Scalar<C>) -> Scalar<C> {
         Scalar(self.0.mul(&(other.0)))
     }
 }
 
-impl Mul<&Scalar> for &Scalar {
-    type Output = Scalar;
+impl<C: Curve> Mul<&Scalar<C>> for &Scalar<C> {
+    type Output = Scalar<C>;
 
-    fn mul(self, other: &Scalar) -> Scalar {
+    fn mul(self, other: &Scalar<C>) -> Scalar<C> {
         Scalar(self.0.mul(&(other.0)))
     }
 }
 
-impl core::iter::Sum for Scalar {
+impl<C: Curve> core::iter::Sum for Scalar<C> {
     fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
         iter.reduce(Add::add).unwrap_or(Self::ZERO)
     }
 }
 
-impl<'a> core::iter::Sum<&'a Self> for Scalar {
+impl<'a, C: Curve> core::iter::Sum<&'a Self> for Scalar<C> {
     fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
         iter.cloned().sum()
     }
 }
 
-impl core::iter::Product for Scalar {
+impl<C: Curve> core::iter::Product for Scalar<C> {
     fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
         iter.reduce(Mul::mul).unwrap_or(Self::ONE)
     }
 }
 
-impl core::iter::Sum for Point {
+impl<C: Curve> core::iter::Sum for Point<C> {
     fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
         iter.reduce(Add::add).unwrap_or(Self::IDENTITY)
     }
 }
 
-impl<'a> core::iter::Sum<&'a Self> for Point {
+impl<'a, C: Curve> core::iter::Sum<&'a Self> for Point<C> {
     fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
         iter.cloned().sum()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use k256::elliptic_curve::{bigint::U256, subtle::ConstantTimeEq};
+    use rand_core::OsRng;
+    use sha2::{Digest, Sha256, Sha512};
+
+    use crate::curve::{Curve, Secp256k1};
+
+    type Scalar = super::Scalar<Secp256k1>;
+    type Point = super::Point<Secp256k1>;
+    const ORDER: U256 = <Secp256k1 as Curve>::ORDER;
+
+    #[test]
+    fn ct_eq_agrees_with_eq() {
+        let x = Scalar::random(&mut OsRng);
+        let y = Scalar::random(&mut OsRng);
+        assert!(bool::from(x.ct_eq(&x)));
+        assert!(!bool::from(x.ct_eq(&y)));
+        assert_eq!(bool::from(x.ct_eq(&y)), x == y);
+
+        let p = x.mul_by_generator();
+        let q = y.mul_by_generator();
+        assert!(bool::from(p.ct_eq(&p)));
+        assert!(!bool::from(p.ct_eq(&q)));
+        assert!(bool::from(
+            Point::IDENTITY.ct_eq(&(p + (-x).mul_by_generator()))
+        ));
+    }
+
+    #[test]
+    fn mul_by_generator() {
+        for _ in 0..16 {
+            let x = Scalar::random(&mut OsRng);
+            assert_eq!(x.mul_by_generator(), x.mul_by_generator_naive());
+        }
+        assert_eq!(Scalar::ZERO.mul_by_generator(), Point::IDENTITY);
+        assert_eq!(Scalar::ONE.mul_by_generator(), Point::GENERATOR);
+        assert_eq!(
+            (-Scalar::ONE).mul_by_generator() + Point::GENERATOR,
+            Point::IDENTITY
+        );
+    }
+
+    #[test]
+    fn split_additive() {
+        let value = Scalar::random(&mut OsRng);
+
+        let shares = Scalar::split_additive(&mut OsRng, &value, 4);
+        assert_eq!(shares.len(), 4);
+        assert_eq!(shares.iter().sum::<Scalar>(), value);
+
+        // The shares are randomized on each call
+        let other_shares = Scalar::split_additive(&mut OsRng, &value, 4);
+        assert_eq!(other_shares.iter().sum::<Scalar>(), value);
+        assert!(shares.iter().zip(other_shares.iter()).all(|(x, y)| x != y));
+        assert!(!shares.contains(&value));
+
+        assert_eq!(Scalar::split_additive(&mut OsRng, &value, 1), [value]);
+
+        let masks = Scalar::zero_sum(&mut OsRng, 3);
+        assert_eq!(masks.iter().sum::<Scalar>(), Scalar::ZERO);
+    }
+
+    #[test]
+    fn verifying_key_round_trip() {
+        let signing_key = k256::ecdsa::SigningKey::random(&mut OsRng);
+        let verifying_key = *signing_key.verifying_key();
+
+        let point = Point::from_verifying_key(&verifying_key);
+        assert_eq!(point.to_verifying_key(), Some(verifying_key));
+
+        assert!(Point::IDENTITY.to_verifying_key().is_none());
+    }
+
+    #[test]
+    fn sec1_round_trip() {
+        let point = Scalar::random(&mut OsRng).mul_by_generator();
+
+        let uncompressed = point.to_uncompressed_bytes().unwrap();
+        assert_eq!(uncompressed[0], 0x04);
+        assert_eq!(Point::try_from_sec1_bytes(&uncompressed).unwrap(), point);
+
+        let compressed = point.to_compressed_array();
+        assert_eq!(Point::try_from_sec1_bytes(&compressed).unwrap(), point);
+
+        // The identity has no uncompressed encoding, and its SEC1 encoding is rejected
+        assert!(Point::IDENTITY.to_uncompressed_bytes().is_none());
+        assert!(Point::try_from_sec1_bytes(&[0x00]).is_err());
+
+        // Malformed prefixes are rejected
+        for prefix in [0x00, 0x01, 0x02, 0x05, 0x06, 0x07] {
+            let mut bytes = uncompressed;
+            bytes[0] = prefix;
+            assert!(Point::try_from_sec1_bytes(&bytes).is_err());
+        }
+        let mut bytes = compressed;
+        bytes[0] = 0x04;
+        assert!(Point::try_from_sec1_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn reduced_bytes_round_trip() {
+        let hash: [u8; 32] = Sha256::digest(b"message").into();
+        let scalar = Scalar::from_reduced_bytes(&hash);
+        assert_eq!(Scalar::try_from_reduced_bytes(&hash).unwrap(), scalar);
+        assert_eq!(
+            Scalar::from_reduced_bytes(&scalar.to_reduced_bytes()),
+            scalar
+        );
+        assert!(Scalar::try_from_reduced_bytes(&hash[..31]).is_err());
+
+        let serialized =
+            bincode::serde::encode_to_vec(scalar, bincode::config::standard()).unwrap();
+        let deserialized: Scalar =
+            bincode::serde::decode_borrowed_from_slice(&serialized, bincode::config::standard())
+                .unwrap();
+        assert_eq!(deserialized, scalar);
+    }
+
+    #[test]
+    fn over_order_bytes_are_reduced() {
+        // `q + 1` reduces to 1
+        let bytes = ORDER.wrapping_add(&U256::ONE).to_be_bytes();
+        let scalar = Scalar::from_reduced_bytes(&bytes);
+        assert_eq!(scalar, Scalar::ONE);
+        assert_eq!(scalar.to_reduced_bytes(), U256::ONE.to_be_bytes());
+
+        // `q * 2^256 + 1` reduces to 1
+        let mut wide = [0u8; 64];
+        wide[..32].copy_from_slice(&ORDER.to_be_bytes());
+        wide[63] = 1;
+        assert_eq!(Scalar::from_wide_bytes(&wide), Scalar::ONE);
+
+        // `hi * 2^256 + lo` for a wide hash
+        let hash: [u8; 64] = Sha512::digest(b"message").into();
+        let hi = Scalar::from_reduced_bytes(hash[..32].try_into().unwrap());
+        let lo = Scalar::from_reduced_bytes(hash[32..].try_into().unwrap());
+        let mut two_128 = [0u8; 32];
+        two_128[15] = 1;
+        let two_128 = Scalar::from_reduced_bytes(&two_128);
+        assert_eq!(Scalar::from_wide_bytes(&hash), hi * two_128 * two_128 + lo);
+    }
+}
@@ -0,0 +1,266 @@
+//! The curves supported by the library, and the adapters to their backend crates.
+
+use alloc::format;
+use alloc::string::String;
+use core::fmt::Debug;
+
+use k256::ecdsa::{signature::hazmat::PrehashVerifier, RecoveryId};
+use k256::elliptic_curve::{bigint::U256, subtle::ConstantTimeEq, Field, Group};
+use serde::{Deserialize, Serialize};
+
+/// An elliptic curve the protocols can be run on, along with its ECDSA signature scheme.
+///
+/// Abstracts the backend crate implementing the curve arithmetic.
+/// Only the curves with 256-bit scalars (and the order greater than `2^255`) are supported,
+/// since the Paillier parameters and the bounds in [`SchemeParams`](`crate::SchemeParams`)
+/// are chosen for them, and the messages are hashed to scalars with SHA-256.
+pub trait Curve:
+    'static
+    + Debug
+    + Clone
+    + Copy
+    + PartialEq
+    + Eq
+    + PartialOrd
+    + Ord
+    + Send
+    + Sync
+    + Serialize
+    + for<'de> Deserialize<'de>
+{
+    /// The scalar type of the backend crate.
+    type BackendScalar: Field + From<u64>;
+    /// The point type of the backend crate.
+    type BackendPoint: Group<Scalar = Self::BackendScalar> + ConstantTimeEq;
+    /// The ECDSA signing key type of the backend crate.
+    type SigningKey;
+    /// The ECDSA verifying key type of the backend crate.
+    type VerifyingKey: Debug
+        + Clone
+        + Copy
+        + PartialEq
+        + Eq
+        + Send
+        + Sync
+        + PrehashVerifier<Self::Signature>;
+    /// The ECDSA signature type of the backend crate.
+    type Signature: Debug + Clone + Copy + PartialEq + Eq;
+
+    /// The order of the curve.
+    const ORDER: U256;
+    /// The generator of the curve group.
+    const GENERATOR: Self::BackendPoint;
+    /// The identity of the curve group (the point at infinity).
+    const IDENTITY: Self::BackendPoint;
+
+    /// Returns `scalar * G`, where `G` is the curve generator.
+    fn mul_by_generator(scalar: &Self::BackendScalar) -> Self::BackendPoint;
+
+    /// Converts a 256-bit big-endian integer into a scalar, reducing it modulo the curve order.
+    fn reduce_bytes(bytes: &[u8; 32]) -> Self::BackendScalar;
+
+    /// Returns the big-endian representation of the scalar.
+    fn scalar_to_bytes(scalar: &Self::BackendScalar) -> [u8; 32];
+
+    /// Restores the scalar from its big-endian representation.
+    ///
+    /// Returns `None` if the value is not reduced modulo the curve order.
+    fn scalar_from_bytes(bytes: &[u8; 32]) -> Option<Self::BackendScalar>;
+
+    /// Returns the x-coordinate of the point reduced modulo the curve order.
+    fn x_coordinate(point: &Self::BackendPoint) -> Self::BackendScalar;
+
+    /// Deserializes a point from any valid SEC1 encoding (including the identity).
+    fn point_from_sec1_bytes(bytes: &[u8]) -> Result<Self::BackendPoint, String>;
+
+    /// Returns the compressed SEC1 encoding of the point,
+    /// or `None` if the point is the identity.
+    fn point_to_compressed_bytes(point: &Self::BackendPoint) -> Option<[u8; 33]>;
+
+    /// Returns the uncompressed SEC1 encoding of the point,
+    /// or `None` if the point is the identity.
+    fn point_to_uncompressed_bytes(point: &Self::BackendPoint) -> Option<[u8; 65]>;
+
+    /// Creates a signing key from a scalar, returning `None` if it is zero.
+    fn signing_key_from_scalar(scalar: &Self::BackendScalar) -> Option<Self::SigningKey>;
+
+    /// Returns the secret scalar of the signing key.
+    fn scalar_from_signing_key(key: &Self::SigningKey) -> Self::BackendScalar;
+
+    /// Creates a verifying key from a point, returning `None` if it is the identity.
+    fn verifying_key_from_point(point: &Self::BackendPoint) -> Option<Self::VerifyingKey>;
+
+    /// Returns the point of the verifying key.
+    fn point_from_verifying_key(key: &Self::VerifyingKey) -> Self::BackendPoint;
+
+    /// Creates a signature from its components, returning `None` if any of them is zero.
+    fn signature_from_scalars(
+        r: &Self::BackendScalar,
+        s: &Self::BackendScalar,
+    ) -> Option<Self::Signature>;
+
+    /// Returns the `r` and `s` components of the signature.
+    fn signature_to_scalars(
+        signature: &Self::Signature,
+    ) -> (Self::BackendScalar, Self::BackendScalar);
+
+    /// Returns the signature with `s` normalized to the lower half of the curve order.
+    fn normalize_s(signature: &Self::Signature) -> Self::Signature;
+
+    /// Returns the big-endian `r` and `s` of the signature.
+    fn signature_to_bytes(signature: &Self::Signature) -> [u8; 64];
+
+    /// Restores the signature from the big-endian `r` and `s`.
+    fn signature_from_bytes(bytes: &[u8]) -> Option<Self::Signature>;
+
+    /// Finds the recovery ID of the signature of the prehashed message
+    /// which restores the given verifying key.
+    fn recovery_id(
+        key: &Self::VerifyingKey,
+        prehash: &[u8],
+        signature: &Self::Signature,
+    ) -> Option<RecoveryId>;
+}
+
+/// The secp256k1 curve (backed by the `k256` crate).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Secp256k1;
+
+/// The secp256r1 (NIST P-256) curve (backed by the `p256` crate).
+#[cfg(feature = "p256")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Secp256r1;
+
+/// Implements [`Curve`] for `$curve` with the types of the backend crate `$backend`
+/// (which has the same layout for all the RustCrypto curves).
+macro_rules! impl_curve {
+    ($curve:ident, $backend:ident, $backend_curve:ident) => {
+        impl Curve for $curve {
+            type BackendScalar = $backend::Scalar;
+            type BackendPoint = $backend::ProjectivePoint;
+            type SigningKey = $backend::ecdsa::SigningKey;
+            type VerifyingKey = $backend::ecdsa::VerifyingKey;
+            type Signature = $backend::ecdsa::Signature;
+
+            const ORDER: U256 =
+                <$backend::$backend_curve as $backend::elliptic_curve::Curve>::ORDER;
+            const GENERATOR: Self::BackendPoint = $backend::ProjectivePoint::GENERATOR;
+            const IDENTITY: Self::BackendPoint = $backend::ProjectivePoint::IDENTITY;
+
+            fn mul_by_generator(scalar: &Self::BackendScalar) -> Self::BackendPoint {
+                use $backend::elliptic_curve::ops::MulByGenerator;
+                <$backend::ProjectivePoint as MulByGenerator>::mul_by_generator(scalar)
+            }
+
+            fn reduce_bytes(bytes: &[u8; 32]) -> Self::BackendScalar {
+                use $backend::elliptic_curve::ops::Reduce;
+                <$backend::Scalar as Reduce<U256>>::reduce_bytes(&$backend::FieldBytes::from(
+                    *bytes,
+                ))
+            }
+
+            fn scalar_to_bytes(scalar: &Self::BackendScalar) -> [u8; 32] {
+                use $backend::elliptic_curve::PrimeField;
+                scalar.to_repr().into()
+            }
+
+            fn scalar_from_bytes(bytes: &[u8; 32]) -> Option<Self::BackendScalar> {
+                use $backend::elliptic_curve::PrimeField;
+                $backend::Scalar::from_repr_vartime($backend::FieldBytes::from(*bytes))
+            }
+
+            fn x_coordinate(point: &Self::BackendPoint) -> Self::BackendScalar {
+                use $backend::elliptic_curve::point::AffineCoordinates;
+                let bytes: [u8; 32] = point.to_affine().x().into();
+                Self::reduce_bytes(&bytes)
+            }
+
+            fn point_from_sec1_bytes(bytes: &[u8]) -> Result<Self::BackendPoint, String> {
+                use $backend::elliptic_curve::sec1::FromEncodedPoint;
+                let ep =
+                    $backend::EncodedPoint::from_bytes(bytes).map_err(|err| format!("{err}"))?;
+                // Unwrap CtOption into Option
+                let cp_opt: Option<Self::BackendPoint> =
+                    $backend::ProjectivePoint::from_encoded_point(&ep).into();
+                cp_opt.ok_or_else(|| "Invalid curve point representation".into())
+            }
+
+            fn point_to_compressed_bytes(point: &Self::BackendPoint) -> Option<[u8; 33]> {
+                use $backend::elliptic_curve::sec1::ToEncodedPoint;
+                point
+                    .to_affine()
+                    .to_encoded_point(true)
+                    .as_bytes()
+                    .try_into()
+                    .ok()
+            }
+
+            fn point_to_uncompressed_bytes(point: &Self::BackendPoint) -> Option<[u8; 65]> {
+                use $backend::elliptic_curve::sec1::ToEncodedPoint;
+                point
+                    .to_affine()
+                    .to_encoded_point(false)
+                    .as_bytes()
+                    .try_into()
+                    .ok()
+            }
+
+            fn signing_key_from_scalar(scalar: &Self::BackendScalar) -> Option<Self::SigningKey> {
+                let scalar: Option<$backend::NonZeroScalar> =
+                    $backend::NonZeroScalar::new(*scalar).into();
+                Some(Self::SigningKey::from(scalar?))
+            }
+
+            fn scalar_from_signing_key(key: &Self::SigningKey) -> Self::BackendScalar {
+                *key.as_nonzero_scalar().as_ref()
+            }
+
+            fn verifying_key_from_point(point: &Self::BackendPoint) -> Option<Self::VerifyingKey> {
+                Self::VerifyingKey::from_affine(point.to_affine()).ok()
+            }
+
+            fn point_from_verifying_key(key: &Self::VerifyingKey) -> Self::BackendPoint {
+                (*key.as_affine()).into()
+            }
+
+            fn signature_from_scalars(
+                r: &Self::BackendScalar,
+                s: &Self::BackendScalar,
+            ) -> Option<Self::Signature> {
+                Self::Signature::from_scalars(*r, *s).ok()
+            }
+
+            fn signature_to_scalars(
+                signature: &Self::Signature,
+            ) -> (Self::BackendScalar, Self::BackendScalar) {
+                let (r, s) = signature.split_scalars();
+                (*r, *s)
+            }
+
+            fn normalize_s(signature: &Self::Signature) -> Self::Signature {
+                signature.normalize_s()
+            }
+
+            fn signature_to_bytes(signature: &Self::Signature) -> [u8; 64] {
+                signature.to_bytes().into()
+            }
+
+            fn signature_from_bytes(bytes: &[u8]) -> Option<Self::Signature> {
+                Self::Signature::from_slice(bytes).ok()
+            }
+
+            fn recovery_id(
+                key: &Self::VerifyingKey,
+                prehash: &[u8],
+                signature: &Self::Signature,
+            ) -> Option<RecoveryId> {
+                RecoveryId::trial_recovery_from_prehash(key, prehash, signature).ok()
+            }
+        }
+    };
+}
+
+impl_curve!(Secp256k1, k256, Secp256k1);
+
+#[cfg(feature = "p256")]
+impl_curve!(Secp256r1, p256, NistP256);
@@ -1,35 +1,47 @@
-use k256::ecdsa::{RecoveryId, Signature as BackendSignature, VerifyingKey};
+use alloc::string::String;
+
+use k256::ecdsa::RecoveryId;
+use rand_core::CryptoRngCore;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use super::arithmetic::{Point, Scalar};
+use super::curves::{Curve, Secp256k1};
+use crate::constructors::PrehashedMessage;
+use crate::tools::serde_bytes;
+
+/// The length of the serialized signature: `r` and `s` (32 bytes each), and the recovery ID.
+const SIGNATURE_BYTES: usize = 65;
+
+/// The value added to the recovery ID in [`RecoverableSignature::to_bytes_legacy`].
+const LEGACY_RECOVERY_ID_OFFSET: u8 = 27;
 
 /// A wrapper for a signature and public key recovery info.
+///
+/// Serialized as 65 bytes: the big-endian `r` and `s` followed by the recovery ID byte.
 #[derive(Debug, Clone, Copy)]
-pub struct RecoverableSignature {
-    signature: BackendSignature,
+pub struct RecoverableSignature<C: Curve = Secp256k1> {
+    signature: C::Signature,
     recovery_id: RecoveryId,
 }
 
-impl RecoverableSignature {
+impl<C: Curve> RecoverableSignature<C> {
     pub(crate) fn from_scalars(
-        r: &Scalar,
-        s: &Scalar,
-        vkey: &Point,
-        message: &Scalar,
+        r: &Scalar<C>,
+        s: &Scalar<C>,
+        vkey: &Point<C>,
+        message: &Scalar<C>,
     ) -> Option<Self> {
-        let signature = BackendSignature::from_scalars(r.to_backend(), s.to_backend()).ok()?;
+        let signature = C::signature_from_scalars(&r.to_backend(), &s.to_backend())?;
 
         // Normalize the `s` component.
         // `BackendSignature`'s constructor does not require `s` to be normalized,
         // but consequent usage of it may fail otherwise.
-        let signature = signature.normalize_s();
+        let signature = C::normalize_s(&signature);
 
+        // Negating `s` corresponds to negating the nonce point, which flips the parity of its `y`,
+        // so the recovery ID must be found for the normalized signature.
         let message_bytes = message.to_bytes();
-        let recovery_id = RecoveryId::trial_recovery_from_prehash(
-            &VerifyingKey::from_affine(vkey.to_backend().to_affine()).ok()?,
-            &message_bytes,
-            &signature,
-        )
-        .ok()?;
+        let recovery_id = C::recovery_id(&vkey.to_verifying_key()?, &message_bytes, &signature)?;
 
         Some(Self {
             signature,
@@ -38,7 +50,331 @@ impl RecoverableSignature {
     }
 
     /// Unwraps into the signature and recovery info objects from the backend crate.
-    pub fn to_backend(self) -> (BackendSignature, RecoveryId) {
+    pub fn to_backend(self) -> (C::Signature, RecoveryId) {
         (self.signature, self.recovery_id)
     }
+
+    /// Returns the big-endian `r` and `s` components of the signature, and the recovery ID byte.
+    ///
+    /// Useful for building custom signature encodings.
+    pub fn components(&self) -> ([u8; 32], [u8; 32], u8) {
+        let (r, s) = C::signature_to_scalars(&self.signature);
+        (
+            Scalar::<C>::from_backend(r).to_bytes(),
+            Scalar::<C>::from_backend(s).to_bytes(),
+            self.recovery_id.to_byte(),
+        )
+    }
+
+    /// Returns the point `R` whose x-coordinate is the `r` component of the signature.
+    fn nonce_point(&self) -> Option<Point<C>> {
+        // The x-coordinate of `R` was reduced modulo the curve order;
+        // this is extremely unlikely, so we do not handle it here.
+        if self.recovery_id.is_x_reduced() {
+            return None;
+        }
+        let (r, _s) = C::signature_to_scalars(&self.signature);
+        let mut bytes = [0u8; 33];
+        bytes[0] = if self.recovery_id.is_y_odd() { 3 } else { 2 };
+        bytes[1..].copy_from_slice(&Scalar::<C>::from_backend(r).to_bytes());
+        Point::try_from_compressed_bytes(&bytes).ok()
+    }
+
+    /// Returns the compact `r || s || v` representation:
+    /// the big-endian `r` and `s` (with `s` normalized to the lower half of the curve order),
+    /// followed by the recovery ID byte `v` (0 or 1, or, in the unlikely case of `R.x`
+    /// exceeding the curve order, 2 or 3).
+    ///
+    /// This is also the serialized form of the signature.
+    pub fn to_bytes(&self) -> [u8; SIGNATURE_BYTES] {
+        let mut bytes = [0u8; SIGNATURE_BYTES];
+        bytes[..SIGNATURE_BYTES - 1].copy_from_slice(&C::signature_to_bytes(&self.signature));
+        bytes[SIGNATURE_BYTES - 1] = self.recovery_id.to_byte();
+        bytes
+    }
+
+    /// Same as [`Self::to_bytes`], but with [`LEGACY_RECOVERY_ID_OFFSET`] added to `v`
+    /// (that is, `v` is 27 or 28), as expected by Ethereum's `ecrecover` and some wallets.
+    pub fn to_bytes_legacy(&self) -> [u8; SIGNATURE_BYTES] {
+        let mut bytes = self.to_bytes();
+        bytes[SIGNATURE_BYTES - 1] += LEGACY_RECOVERY_ID_OFFSET;
+        bytes
+    }
+
+    /// Restores the signature from the output of [`Self::to_bytes`] or [`Self::to_bytes_legacy`].
+    ///
+    /// Signatures with a non-normalized `s` are rejected.
+    pub fn from_bytes(bytes: &[u8; SIGNATURE_BYTES]) -> Result<Self, String> {
+        let mut bytes = *bytes;
+        let v = &mut bytes[SIGNATURE_BYTES - 1];
+        if *v >= LEGACY_RECOVERY_ID_OFFSET {
+            *v -= LEGACY_RECOVERY_ID_OFFSET;
+        }
+        Self::try_from(&bytes[..])
+    }
+}
+
+/// Verifies several signatures created with the same verifying key.
+///
+/// Returns `true` only if all the signatures are valid.
+/// An empty batch is rejected (returns `false`), so that a `true` result
+/// always means that at least one signature was checked.
+/// The verification equations are combined with random coefficients and checked at once
+/// (see the `signature verification` benchmark for the comparison
+/// with verifying the signatures one by one).
+/// The recovery IDs must be correct (which is always the case for the signatures
+/// produced by this library), otherwise the batch is considered invalid.
+///
+/// Note that the result does not identify which signatures are invalid;
+/// if needed, they can be found by verifying the signatures individually.
+pub fn batch_verify_signatures<C: Curve>(
+    rng: &mut impl CryptoRngCore,
+    vkey: &C::VerifyingKey,
+    items: &[(PrehashedMessage, RecoverableSignature<C>)],
+) -> bool {
+    if items.is_empty() {
+        return false;
+    }
+
+    // For each signature `(r, s)` of a message `z` we have `u1 G + u2 Q == R`,
+    // where `u1 = z s^(-1)`, `u2 = r s^(-1)`, `Q` is the verifying key,
+    // and `R` is the nonce point restored from `r` and the recovery ID.
+    // If any of these equations does not hold, the sum of them multiplied by random coefficients
+    // is non-zero with overwhelming probability.
+    let mut generator_coeff = Scalar::<C>::ZERO;
+    let mut vkey_coeff = Scalar::<C>::ZERO;
+    let mut nonce_points_sum = Point::<C>::IDENTITY;
+
+    for (message, signature) in items {
+        let nonce_point = match signature.nonce_point() {
+            Some(point) => point,
+            None => return false,
+        };
+
+        let (r, s) = C::signature_to_scalars(&signature.signature);
+        let r = Scalar::<C>::from_backend(r);
+        let s_inv = Scalar::<C>::from_backend(s)
+            .invert()
+            .expect("`s` is non-zero by construction");
+        let z = Scalar::<C>::from_reduced_bytes(message);
+
+        let coeff = Scalar::<C>::random(rng);
+        generator_coeff = generator_coeff + coeff * z * s_inv;
+        vkey_coeff = vkey_coeff + coeff * r * s_inv;
+        nonce_points_sum = nonce_points_sum + nonce_point * coeff;
+    }
+
+    generator_coeff.mul_by_generator() + Point::<C>::from_verifying_key(vkey) * vkey_coeff
+        == nonce_points_sum
+}
+
+impl<'a, C: Curve> TryFrom<&'a [u8]> for RecoverableSignature<C> {
+    type Error = String;
+    fn try_from(bytes: &'a [u8]) -> Result<Self, Self::Error> {
+        if bytes.len() != SIGNATURE_BYTES {
+            return Err("Invalid length of a recoverable signature".into());
+        }
+
+        let signature = C::signature_from_bytes(&bytes[..SIGNATURE_BYTES - 1])
+            .ok_or_else(|| String::from("Invalid signature"))?;
+        // We only ever produce signatures with a normalized `s`,
+        // so anything else is not a canonical representation.
+        if C::normalize_s(&signature) != signature {
+            return Err("The signature's `s` is not normalized".into());
+        }
+
+        let recovery_id = RecoveryId::from_byte(bytes[SIGNATURE_BYTES - 1])
+            .ok_or_else(|| String::from("Invalid recovery ID"))?;
+
+        Ok(Self {
+            signature,
+            recovery_id,
+        })
+    }
+}
+
+impl<C: Curve> Serialize for RecoverableSignature<C> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde_bytes::as_hex::serialize(&self.to_bytes(), serializer)
+    }
+}
+
+impl<'de, C: Curve> Deserialize<'de> for RecoverableSignature<C> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        serde_bytes::as_hex::deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use alloc::collections::BTreeSet;
+
+    use k256::ecdsa::{RecoveryId, Signature, SigningKey, VerifyingKey};
+    use k256::elliptic_curve::scalar::IsHigh;
+    use rand_core::OsRng;
+
+    use super::batch_verify_signatures;
+    use crate::curve::Secp256k1;
+    use crate::{KeyShare, TestParams};
+
+    type RecoverableSignature = super::RecoverableSignature<Secp256k1>;
+    type Scalar = crate::curve::Scalar<Secp256k1>;
+
+    #[test]
+    fn serialization_round_trip() {
+        let sk = Scalar::random_nonzero(&mut OsRng);
+        let vkey = sk.mul_by_generator();
+        let message = Scalar::random(&mut OsRng);
+
+        let k = Scalar::random_nonzero(&mut OsRng);
+        let r = k.mul_by_generator().x_coordinate();
+        let s = k.invert().unwrap() * (message + r * sk);
+
+        let signature = RecoverableSignature::from_scalars(&r, &s, &vkey, &message).unwrap();
+
+        let serialized =
+            bincode::serde::encode_to_vec(signature, bincode::config::standard()).unwrap();
+        let deserialized: RecoverableSignature =
+            bincode::serde::decode_borrowed_from_slice(&serialized, bincode::config::standard())
+                .unwrap();
+
+        let (sig, rec_id) = deserialized.to_backend();
+        assert_eq!((sig, rec_id), signature.to_backend());
+
+        let recovered_key =
+            VerifyingKey::recover_from_prehash(&message.to_bytes(), &sig, rec_id).unwrap();
+        assert_eq!(recovered_key, vkey.to_verifying_key().unwrap());
+
+        // Only the exact length is accepted
+        assert!(RecoverableSignature::try_from(&[1u8; 64][..]).is_err());
+        assert!(RecoverableSignature::try_from(&[1u8; 66][..]).is_err());
+    }
+
+    #[test]
+    fn high_s_normalization_flips_recovery_id() {
+        let sk = Scalar::random_nonzero(&mut OsRng);
+        let vkey = sk.mul_by_generator();
+        let message = Scalar::random(&mut OsRng);
+
+        let k = Scalar::random_nonzero(&mut OsRng);
+        let nonce_point = k.mul_by_generator();
+        let r = nonce_point.x_coordinate();
+        let s = k.invert().unwrap() * (message + r * sk);
+
+        // `(r, s)` and `(r, -s)` are both valid signatures (for the nonces `k` and `-k`),
+        // so we can always make a signature with the high `s`,
+        // whose nonce point is `R` if `s` was high to begin with, and `-R` otherwise.
+        let nonce_is_y_odd = nonce_point.to_compressed_array()[0] == 3;
+        let (raw_s, raw_nonce_is_y_odd) = if bool::from(s.to_backend().is_high()) {
+            (s, nonce_is_y_odd)
+        } else {
+            (-s, !nonce_is_y_odd)
+        };
+        assert!(bool::from(raw_s.to_backend().is_high()));
+
+        let signature = RecoverableSignature::from_scalars(&r, &raw_s, &vkey, &message).unwrap();
+        let (sig, rec_id) = signature.to_backend();
+
+        // After the normalization, `s` is low, and the signature corresponds
+        // to the negated nonce point of the raw signature, so the parity of `y` is flipped.
+        assert!(!bool::from(sig.s().is_high()));
+        assert_eq!(rec_id.is_y_odd(), !raw_nonce_is_y_odd);
+
+        let recovered_key =
+            VerifyingKey::recover_from_prehash(&message.to_bytes(), &sig, rec_id).unwrap();
+        assert_eq!(recovered_key, vkey.to_verifying_key().unwrap());
+    }
+
+    #[test]
+    fn components_round_trip() {
+        let sk = Scalar::random_nonzero(&mut OsRng);
+        let vkey = sk.mul_by_generator();
+        let message = Scalar::random(&mut OsRng);
+
+        let k = Scalar::random_nonzero(&mut OsRng);
+        let r = k.mul_by_generator().x_coordinate();
+        let s = k.invert().unwrap() * (message + r * sk);
+
+        let signature = RecoverableSignature::from_scalars(&r, &s, &vkey, &message).unwrap();
+        let (r, s, recovery_id) = signature.components();
+
+        let (backend_signature, backend_recovery_id) = signature.to_backend();
+        assert_eq!(Signature::from_scalars(r, s).unwrap(), backend_signature);
+        assert_eq!(recovery_id, backend_recovery_id.to_byte());
+    }
+
+    #[test]
+    fn compact_bytes_recover_key_share_key() {
+        let id = *SigningKey::random(&mut OsRng).verifying_key();
+        let key_shares = KeyShare::<TestParams, VerifyingKey>::new_centralized(
+            &mut OsRng,
+            &BTreeSet::from([id]),
+            None,
+        );
+        let key_share = &key_shares[&id];
+
+        let message = [7u8; 32];
+        let signature = key_share.sign_solo(&mut OsRng, &message).unwrap();
+
+        let bytes = signature.to_bytes();
+        let (sig, rec_id) = signature.to_backend();
+        assert_eq!(sig.normalize_s(), sig);
+        assert!(bytes[64] <= 1);
+
+        let recovered_key = VerifyingKey::recover_from_prehash(
+            &message,
+            &Signature::from_slice(&bytes[..64]).unwrap(),
+            RecoveryId::from_byte(bytes[64]).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(recovered_key, key_share.verifying_key());
+
+        let legacy_bytes = signature.to_bytes_legacy();
+        assert_eq!(legacy_bytes[..64], bytes[..64]);
+        assert_eq!(legacy_bytes[64], bytes[64] + 27);
+
+        for bytes in [bytes, legacy_bytes] {
+            let restored = RecoverableSignature::from_bytes(&bytes).unwrap();
+            assert_eq!(restored.to_backend(), (sig, rec_id));
+        }
+    }
+
+    #[test]
+    fn batch_verification() {
+        let sk = Scalar::random_nonzero(&mut OsRng);
+        let vkey = sk.mul_by_generator();
+
+        let mut items = (0..4)
+            .map(|_| {
+                let message = Scalar::random(&mut OsRng);
+                let k = Scalar::random_nonzero(&mut OsRng);
+                let r = k.mul_by_generator().x_coordinate();
+                let s = k.invert().unwrap() * (message + r * sk);
+                let signature =
+                    RecoverableSignature::from_scalars(&r, &s, &vkey, &message).unwrap();
+                let message_bytes = message.to_bytes();
+                (message_bytes, signature)
+            })
+            .collect::<Vec<_>>();
+
+        let vkey = vkey.to_verifying_key().unwrap();
+        assert!(batch_verify_signatures(&mut OsRng, &vkey, &items));
+
+        // One of the messages does not match its signature.
+        items[2].0[0] ^= 1;
+        assert!(!batch_verify_signatures(&mut OsRng, &vkey, &items));
+        items[2].0[0] ^= 1;
+
+        // A different verifying key.
+        let other_vkey = Scalar::random_nonzero(&mut OsRng)
+            .mul_by_generator()
+            .to_verifying_key()
+            .unwrap();
+        assert!(!batch_verify_signatures(&mut OsRng, &other_vkey, &items));
+
+        // An empty batch does not verify anything.
+        assert!(!batch_verify_signatures(&mut OsRng, &vkey, &items[..0]));
+    }
 }
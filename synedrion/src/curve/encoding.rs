@@ -0,0 +1,123 @@
+//! Conversions between the backend curve types and their byte representations,
+//! for the users interoperating with other systems.
+//!
+//! These are provided for secp256k1 only.
+
+use alloc::string::String;
+
+use displaydoc::Display;
+use k256::ecdsa::VerifyingKey;
+
+use super::arithmetic::{Point, Scalar};
+use super::curves::Secp256k1;
+
+/// An error returned when a curve scalar or point cannot be decoded.
+#[derive(Debug, Clone, Display)]
+#[displaydoc("Encoding error ({0})")]
+pub struct EncodingError(String);
+
+/// Converts a 32-byte big-endian integer (e.g. a hash digest) into a scalar as per SEC1,
+/// reducing it modulo the curve order.
+///
+/// Returns an error if the input is not 32 bytes long.
+pub fn scalar_from_reduced_bytes(bytes: &[u8]) -> Result<k256::Scalar, EncodingError> {
+    Scalar::<Secp256k1>::try_from_reduced_bytes(bytes)
+        .map(Scalar::<Secp256k1>::to_backend)
+        .map_err(EncodingError)
+}
+
+/// Returns the big-endian representation of the scalar.
+///
+/// [`scalar_from_reduced_bytes`] maps the result back to the same scalar.
+pub fn scalar_to_reduced_bytes(scalar: &k256::Scalar) -> [u8; 32] {
+    Scalar::<Secp256k1>::from_backend(*scalar).to_reduced_bytes()
+}
+
+/// Converts a 64-byte big-endian integer into a scalar by reducing it modulo the curve order
+/// (e.g. for deriving nonces from a wide hash, as in RFC 6979 or `hash_to_field`).
+pub fn scalar_from_wide_bytes(bytes: &[u8; 64]) -> k256::Scalar {
+    Scalar::<Secp256k1>::from_wide_bytes(bytes).to_backend()
+}
+
+/// Deserializes a point from a SEC1 encoding, either compressed (`0x02`/`0x03`-prefixed)
+/// or uncompressed (`0x04`-prefixed).
+///
+/// Returns an error for the identity point and any other form of the encoding.
+pub fn point_from_sec1_bytes(bytes: &[u8]) -> Result<k256::ProjectivePoint, EncodingError> {
+    Point::<Secp256k1>::try_from_sec1_bytes(bytes)
+        .map(Point::<Secp256k1>::to_backend)
+        .map_err(EncodingError)
+}
+
+/// Returns the uncompressed SEC1 encoding of the point (`0x04`-prefixed).
+///
+/// Returns `None` if the point is the identity, which does not have one.
+pub fn point_to_uncompressed_bytes(point: &k256::ProjectivePoint) -> Option<[u8; 65]> {
+    Point::<Secp256k1>::from_backend(*point).to_uncompressed_bytes()
+}
+
+/// Converts a verifying key (e.g. loaded from storage) to a curve point.
+pub fn point_from_verifying_key(key: &VerifyingKey) -> k256::ProjectivePoint {
+    Point::<Secp256k1>::from_verifying_key(key).to_backend()
+}
+
+/// Converts a curve point to a verifying key.
+///
+/// Returns `None` if the point is the identity, which is not a valid verifying key.
+/// Otherwise, this is the inverse of [`point_from_verifying_key`].
+pub fn point_to_verifying_key(point: &k256::ProjectivePoint) -> Option<VerifyingKey> {
+    Point::<Secp256k1>::from_backend(*point).to_verifying_key()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand_core::OsRng;
+
+    use super::{
+        point_from_sec1_bytes, point_to_uncompressed_bytes, scalar_from_reduced_bytes,
+        scalar_from_wide_bytes, scalar_to_reduced_bytes,
+    };
+    use crate::curve::{Point, Scalar, Secp256k1};
+
+    #[test]
+    fn scalar_roundtrip() {
+        let scalar = Scalar::<Secp256k1>::random(&mut OsRng).to_backend();
+        let bytes = scalar_to_reduced_bytes(&scalar);
+        assert_eq!(scalar_from_reduced_bytes(&bytes).unwrap(), scalar);
+        assert!(scalar_from_reduced_bytes(&bytes[..31]).is_err());
+
+        let mut wide = [0u8; 64];
+        wide[32..].copy_from_slice(&bytes);
+        assert_eq!(scalar_from_wide_bytes(&wide), scalar);
+    }
+
+    #[test]
+    fn point_roundtrip() {
+        let point = Scalar::<Secp256k1>::random(&mut OsRng)
+            .mul_by_generator()
+            .to_backend();
+        let bytes = point_to_uncompressed_bytes(&point).unwrap();
+        assert_eq!(bytes[0], 0x04);
+        assert_eq!(point_from_sec1_bytes(&bytes).unwrap(), point);
+        assert!(point_from_sec1_bytes(&bytes[..64]).is_err());
+
+        assert!(point_to_uncompressed_bytes(&Point::<Secp256k1>::IDENTITY.to_backend()).is_none());
+        assert!(point_from_sec1_bytes(&[0]).is_err());
+    }
+
+    #[test]
+    fn verifying_key_roundtrip() {
+        // Through the paths available to the users
+        use crate::{point_from_verifying_key, point_to_verifying_key};
+
+        let verifying_key = *k256::ecdsa::SigningKey::random(&mut OsRng).verifying_key();
+        let point = point_from_verifying_key(&verifying_key);
+        assert_eq!(
+            point,
+            k256::ProjectivePoint::from(*verifying_key.as_affine())
+        );
+        assert_eq!(point_to_verifying_key(&point), Some(verifying_key));
+
+        assert!(point_to_verifying_key(&Point::<Secp256k1>::IDENTITY.to_backend()).is_none());
+    }
+}
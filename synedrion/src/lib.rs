@@ -15,6 +15,8 @@
 )]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 // Expose interal entities for benchmarks
 #[cfg(feature = "bench-internals")]
@@ -25,6 +27,8 @@ mod constructors;
 mod curve;
 mod paillier;
 mod rounds;
+#[cfg(feature = "schema")]
+pub mod schema;
 pub mod sessions;
 mod tools;
 mod uint;
@@ -34,21 +38,39 @@ mod www02;
 pub use bip32;
 pub use k256;
 pub use k256::ecdsa;
+#[cfg(feature = "p256")]
+pub use p256;
 pub use signature;
 
 pub use cggmp21::{
     AuxGenError, AuxGenResult, AuxInfo, InteractiveSigningError, InteractiveSigningProof,
     InteractiveSigningResult, KeyGenError, KeyGenProof, KeyGenResult, KeyInitError, KeyInitResult,
-    KeyRefreshResult, KeyShare, KeyShareChange, PresigningError, PresigningProof, PresigningResult,
-    ProductionParams, SchemeParams, SigningProof, SigningResult, TestParams,
+    KeyRefreshResult, KeyShare, KeyShareBytesError, KeyShareChange, PaillierPrimes,
+    PresignaturePool, PresigningData, PresigningError, PresigningProof, PresigningResult,
+    PresigningRetryReason, ProductionParams, SchemeParams, SigningProof, SigningResult, TestParams,
+    VerificationFailReason,
 };
+#[cfg(feature = "p256")]
+pub use cggmp21::{ProductionParamsP256, TestParamsP256};
+#[cfg(feature = "testvectors")]
+pub use constructors::make_presigning_session_from_seed;
 pub use constructors::{
-    make_aux_gen_session, make_interactive_signing_session, make_key_gen_session,
-    make_key_init_session, make_key_refresh_session, make_key_resharing_session, PrehashedMessage,
+    make_aux_gen_session, make_aux_gen_session_with_primes, make_interactive_signing_session,
+    make_key_gen_session, make_key_gen_session_with_primes, make_key_init_session,
+    make_key_refresh_session, make_key_resharing_session, make_nonce_presigning_session,
+    make_presigning_session, make_signing_session, presigning_preflight, InitError, PreflightError,
+    PrehashedMessage, SigningSessionBuilder,
+};
+#[cfg(feature = "p256")]
+pub use curve::Secp256r1;
+pub use curve::{
+    batch_verify_signatures, point_from_sec1_bytes, point_from_verifying_key,
+    point_to_uncompressed_bytes, point_to_verifying_key, scalar_from_reduced_bytes,
+    scalar_from_wide_bytes, scalar_to_reduced_bytes, Curve, EncodingError, RecoverableSignature,
+    Secp256k1,
 };
-pub use curve::RecoverableSignature;
 pub use rounds::ProtocolResult;
-pub use sessions::{FinalizeOutcome, MessageBundle, Session, SessionId};
+pub use sessions::{FinalizeOutcome, MessageBundle, PartyId, ResultMeta, Session, SessionId};
 pub use www02::{
     DeriveChildKey, KeyResharingInputs, KeyResharingResult, NewHolder, OldHolder, ThresholdKeyShare,
 };
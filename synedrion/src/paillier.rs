@@ -3,10 +3,12 @@ mod keys;
 mod params;
 mod ring_pedersen;
 
+#[cfg(test)]
+pub(crate) use encryption::op_count;
 pub(crate) use encryption::{Ciphertext, CiphertextMod, Randomizer, RandomizerMod};
 pub(crate) use keys::{
     PublicKeyPaillier, PublicKeyPaillierPrecomputed, SecretKeyPaillier,
     SecretKeyPaillierPrecomputed,
 };
 pub(crate) use params::PaillierParams;
-pub(crate) use ring_pedersen::{RPCommitment, RPParams, RPParamsMod, RPSecret};
+pub(crate) use ring_pedersen::{RPCommitment, RPCommitmentMod, RPParams, RPParamsMod, RPSecret};
@@ -11,7 +11,7 @@ use super::keys::{PublicKeyPaillierPrecomputed, SecretKeyPaillierPrecomputed};
 use super::params::PaillierParams;
 use crate::uint::{
     pow::pow_signed,
-    subtle::{Choice, ConditionallyNegatable, ConditionallySelectable},
+    subtle::{Choice, ConditionallyNegatable, ConditionallySelectable, ConstantTimeGreater},
     Bounded, HasWide, NonZero, Retrieve, Signed, ToMontgomery,
 };
 
@@ -74,14 +74,14 @@ impl<'a, P: PaillierParams> Mul<&'a RandomizerMod<P>> for &'a RandomizerMod<P> {
     }
 }
 
-impl<'a, P: PaillierParams> Mul<RandomizerMod<P>> for &'a RandomizerMod<P> {
+impl<P: PaillierParams> Mul<RandomizerMod<P>> for &RandomizerMod<P> {
     type Output = RandomizerMod<P>;
     fn mul(self, rhs: RandomizerMod<P>) -> Self::Output {
         self * &rhs
     }
 }
 
-impl<'a, P: PaillierParams> Mul<&'a RandomizerMod<P>> for RandomizerMod<P> {
+impl<P: PaillierParams> Mul<&RandomizerMod<P>> for RandomizerMod<P> {
     type Output = RandomizerMod<P>;
     fn mul(self, rhs: &RandomizerMod<P>) -> Self::Output {
         &self * rhs
@@ -109,23 +109,108 @@ pub(crate) struct Ciphertext<P: PaillierParams> {
 }
 
 impl<P: PaillierParams> Ciphertext<P> {
+    /// Checks that the ciphertext is in the range `[0, N^2)`, where `N` is the modulus of `pk`.
+    pub fn is_in_range(&self, pk: &PublicKeyPaillierPrecomputed<P>) -> bool {
+        self.ciphertext < pk.modulus().square_wide()
+    }
+
+    /// Converts the ciphertext to the form used in homomorphic operations
+    /// if it is in range (see [`Self::is_in_range`]).
+    ///
+    /// Ciphertexts received from other parties must be checked this way
+    /// before they are used, unless their validity is established otherwise
+    /// (e.g. by a ZK proof, or if only the owner of `pk` ever decrypts the results,
+    /// in which case the check can be deferred until decryption).
+    pub fn validated(self, pk: &PublicKeyPaillierPrecomputed<P>) -> Option<CiphertextMod<P>> {
+        if self.is_in_range(pk) {
+            Some(CiphertextMod {
+                validity: Validity::checked(),
+                ..self.to_mod(pk)
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Converts the ciphertext to the form used in homomorphic operations
+    /// without checking that it is in range.
+    ///
+    /// See [`Self::validated`] for the checked version.
     pub fn to_mod(&self, pk: &PublicKeyPaillierPrecomputed<P>) -> CiphertextMod<P> {
         CiphertextMod {
             pk: pk.clone(),
             ciphertext: self
                 .ciphertext
                 .to_montgomery(pk.precomputed_modulus_squared()),
+            validity: Validity::unchecked(),
         }
     }
 }
 
+/// Whether a ciphertext is known to be well-formed,
+/// that is, it was either created by this party, or checked with [`Ciphertext::validated`]
+/// (or derived from such ciphertexts).
+///
+/// Only tracked in debug builds, where decrypting an unchecked ciphertext panics;
+/// in release builds this is a zero-sized type.
+#[derive(Debug, Clone, Copy)]
+struct Validity(#[cfg(debug_assertions)] bool);
+
+impl Validity {
+    fn checked() -> Self {
+        Self(
+            #[cfg(debug_assertions)]
+            true,
+        )
+    }
+
+    fn unchecked() -> Self {
+        Self(
+            #[cfg(debug_assertions)]
+            false,
+        )
+    }
+
+    #[cfg(debug_assertions)]
+    fn and(self, other: Self) -> Self {
+        Self(self.0 && other.0)
+    }
+
+    #[cfg(not(debug_assertions))]
+    fn and(self, _other: Self) -> Self {
+        self
+    }
+
+    fn debug_assert_checked(self) {
+        #[cfg(debug_assertions)]
+        assert!(
+            self.0,
+            "Decrypting a ciphertext that was not checked with `Ciphertext::validated()`"
+        );
+    }
+}
+
 /// Paillier ciphertext.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// Ciphertexts received from other parties must be converted to this form
+/// with [`Ciphertext::validated`] before they are decrypted;
+/// debug builds panic if a ciphertext obtained with [`Ciphertext::to_mod`] is decrypted.
+#[derive(Debug, Clone)]
 pub(crate) struct CiphertextMod<P: PaillierParams> {
     pk: PublicKeyPaillierPrecomputed<P>,
     ciphertext: P::WideUintMod,
+    validity: Validity,
+}
+
+// The validity is not a part of the ciphertext's value.
+impl<P: PaillierParams> PartialEq for CiphertextMod<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.pk == other.pk && self.ciphertext == other.ciphertext
+    }
 }
 
+impl<P: PaillierParams> Eq for CiphertextMod<P> {}
+
 impl<P: PaillierParams> CiphertextMod<P> {
     pub fn public_key(&self) -> &PublicKeyPaillierPrecomputed<P> {
         &self.pk
@@ -161,6 +246,8 @@ impl<P: PaillierParams> CiphertextMod<P> {
 
         let factor1 = prod_mod + P::WideUintMod::one(pk.precomputed_modulus_squared().clone());
 
+        // `rho` is different for every encryption, so there is nothing to precompute
+        // for `rho^N` besides the Montgomery parameters already stored in `pk`.
         let randomizer = randomizer.0.into_wide();
         let pk_mod_bound = pk.modulus_bounded().into_wide();
         let factor2 = randomizer
@@ -172,6 +259,7 @@ impl<P: PaillierParams> CiphertextMod<P> {
         Self {
             pk: pk.clone(),
             ciphertext,
+            validity: Validity::checked(),
         }
     }
 
@@ -225,6 +313,7 @@ impl<P: PaillierParams> CiphertextMod<P> {
     /// Decrypts this ciphertext assuming that the plaintext is in range `[0, N)`.
     pub fn decrypt(&self, sk: &SecretKeyPaillierPrecomputed<P>) -> P::Uint {
         assert_eq!(sk.public_key(), &self.pk);
+        self.validity.debug_assert_checked();
 
         let pk = sk.public_key();
         let totient_wide = sk.totient().expose_secret().into_wide();
@@ -251,6 +340,7 @@ impl<P: PaillierParams> CiphertextMod<P> {
     }
 
     /// Decrypts this ciphertext assuming that the plaintext is in range `[-N/2, N/2)`.
+    #[cfg(test)]
     pub fn decrypt_signed(&self, sk: &SecretKeyPaillierPrecomputed<P>) -> Signed<P::Uint> {
         assert_eq!(sk.public_key(), &self.pk);
 
@@ -270,6 +360,35 @@ impl<P: PaillierParams> CiphertextMod<P> {
         result
     }
 
+    /// Decrypts this ciphertext assuming that the plaintext is in range `[-N/2, N/2)`,
+    /// in constant time with respect to the secret key and the plaintext.
+    ///
+    /// The `L`-function and the CRT recombination are calculated with constant-time operations
+    /// (see [`SecretKeyPaillierPrecomputed::decrypt_crt`]),
+    /// and the sign is determined without branching on the plaintext.
+    ///
+    /// Note that the returned value is not protected beyond that: in particular,
+    /// the bound checks commonly performed on the result (e.g. [`Signed::assert_bit_bound_usize`])
+    /// are variable-time, and leak whether the plaintext is in range.
+    pub fn decrypt_signed_ct(&self, sk: &SecretKeyPaillierPrecomputed<P>) -> Signed<P::Uint> {
+        assert_eq!(sk.public_key(), &self.pk);
+        self.validity.debug_assert_checked();
+
+        let pk = sk.public_key();
+        let positive_result = sk.decrypt_crt(&self.ciphertext.retrieve());
+        let negative_result = pk.modulus().wrapping_sub(&positive_result);
+        let is_negative = positive_result.ct_gt(&pk.modulus().wrapping_shr_vartime(1));
+
+        let mut result = Signed::new_from_unsigned(
+            P::Uint::conditional_select(&positive_result, &negative_result, is_negative),
+            P::MODULUS_BITS as u32 - 1,
+        )
+        .unwrap();
+
+        result.conditional_negate(is_negative);
+        result
+    }
+
     /// Derive the randomizer used to create this ciphertext.
     pub fn derive_randomizer(&self, sk: &SecretKeyPaillierPrecomputed<P>) -> RandomizerMod<P> {
         assert_eq!(sk.public_key(), &self.pk);
@@ -303,16 +422,22 @@ impl<P: PaillierParams> CiphertextMod<P> {
     // So if we want to replicate the Paillier encryption manually and get the same ciphertext
     // (e.g. in the P_enc sigma-protocol), we need to process the sign correctly.
     fn homomorphic_mul(self, rhs: &Signed<P::Uint>) -> Self {
+        #[cfg(test)]
+        op_count::record();
         Self {
             pk: self.pk,
             ciphertext: pow_signed(self.ciphertext, &rhs.into_wide()),
+            validity: self.validity,
         }
     }
 
     fn homomorphic_mul_ref(&self, rhs: &Signed<P::Uint>) -> Self {
+        #[cfg(test)]
+        op_count::record();
         Self {
             pk: self.pk.clone(),
             ciphertext: pow_signed(self.ciphertext, &rhs.into_wide()),
+            validity: self.validity,
         }
     }
 
@@ -320,41 +445,55 @@ impl<P: PaillierParams> CiphertextMod<P> {
         // Unfortunately we cannot implement `Mul` for `Signed<P::Uint>` and `Signed<P::WideUint>`
         // at the same time, since they can be the same type.
         // But this method is only used once, so it's not a problem to spell it out.
+        #[cfg(test)]
+        op_count::record();
         Self {
             pk: self.pk.clone(),
             ciphertext: pow_signed(self.ciphertext, rhs),
+            validity: self.validity,
         }
     }
 
     fn homomorphic_mul_unsigned(self, rhs: &Bounded<P::Uint>) -> Self {
+        #[cfg(test)]
+        op_count::record();
         let rhs_wide = rhs.into_wide();
         Self {
             pk: self.pk,
             ciphertext: self
                 .ciphertext
                 .pow_bounded_exp(rhs_wide.as_ref(), rhs_wide.bound()),
+            validity: self.validity,
         }
     }
 
     fn homomorphic_mul_unsigned_ref(&self, rhs: &Bounded<P::Uint>) -> Self {
+        #[cfg(test)]
+        op_count::record();
         let rhs_wide = rhs.into_wide();
         Self {
             pk: self.pk.clone(),
             ciphertext: self
                 .ciphertext
                 .pow_bounded_exp(rhs_wide.as_ref(), rhs_wide.bound()),
+            validity: self.validity,
         }
     }
 
     fn homomorphic_add(self, rhs: &Self) -> Self {
+        #[cfg(test)]
+        op_count::record();
         assert!(self.pk == rhs.pk);
         Self {
             pk: self.pk,
             ciphertext: self.ciphertext * rhs.ciphertext,
+            validity: self.validity.and(rhs.validity),
         }
     }
 
     pub fn mul_randomizer(self, randomizer: &Randomizer<P>) -> Self {
+        #[cfg(test)]
+        op_count::record();
         let randomizer_mod = randomizer
             .0
             .into_wide()
@@ -365,6 +504,7 @@ impl<P: PaillierParams> CiphertextMod<P> {
         Self {
             pk: self.pk,
             ciphertext,
+            validity: self.validity,
         }
     }
 
@@ -418,17 +558,37 @@ impl<P: PaillierParams> Mul<Bounded<P::Uint>> for &CiphertextMod<P> {
     }
 }
 
+/// Counts the homomorphic operations on ciphertexts performed by the current thread,
+/// so that the tests could check the amount of work caused by a received message.
+#[cfg(test)]
+pub(crate) mod op_count {
+    use std::cell::Cell;
+
+    std::thread_local! {
+        static COUNT: Cell<usize> = const { Cell::new(0) };
+    }
+
+    pub(crate) fn record() {
+        COUNT.with(|count| count.set(count.get() + 1));
+    }
+
+    /// Returns the number of operations performed since the previous call.
+    pub(crate) fn take() -> usize {
+        COUNT.with(|count| count.replace(0))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use rand_core::OsRng;
 
     use super::super::params::PaillierTest;
     use super::super::{PaillierParams, SecretKeyPaillier};
-    use super::{CiphertextMod, RandomizerMod};
+    use super::{op_count, Ciphertext, CiphertextMod, RandomizerMod};
 
     use crate::uint::{
         subtle::{ConditionallyNegatable, ConditionallySelectable},
-        HasWide, NonZero, RandomMod, Signed,
+        HasWide, NonZero, RandomMod, Signed, ToMontgomery,
     };
     use crypto_bigint::{Encoding, Integer, ShrVartime, WrappingSub};
 
@@ -493,6 +653,50 @@ mod tests {
         assert_eq!(plaintext_reduced, plaintext_back);
     }
 
+    #[test]
+    fn constant_time_decryption() {
+        let sk = SecretKeyPaillier::<PaillierTest>::random(&mut OsRng).to_precomputed();
+        let pk = sk.public_key();
+        for _ in 0..16 {
+            let plaintext = Signed::random(&mut OsRng);
+            let ciphertext = CiphertextMod::new_signed(&mut OsRng, pk, &plaintext);
+            assert_eq!(
+                ciphertext.decrypt_signed_ct(&sk),
+                ciphertext.decrypt_signed(&sk)
+            );
+        }
+    }
+
+    #[test]
+    fn encryption_matches_definition() {
+        // The encryption takes a shortcut for `(N + 1)^m`;
+        // check that the result is the same as `(N + 1)^m * rho^N` calculated directly.
+        type Uint = <PaillierTest as PaillierParams>::Uint;
+        type WideUint = <PaillierTest as PaillierParams>::WideUint;
+
+        let sk = SecretKeyPaillier::<PaillierTest>::random(&mut OsRng).to_precomputed();
+        let pk = sk.public_key();
+        let plaintext = Uint::random_mod(&mut OsRng, &pk.modulus_nonzero());
+        let randomizer = RandomizerMod::random(&mut OsRng, pk).retrieve();
+        let ciphertext =
+            CiphertextMod::<PaillierTest>::new_with_randomizer(pk, &plaintext, &randomizer);
+
+        let params = pk.precomputed_modulus_squared();
+        let modulus_bits = PaillierTest::MODULUS_BITS as u32;
+        let modulus = pk.modulus().into_wide();
+
+        let g = modulus
+            .wrapping_add(&<WideUint as Integer>::one())
+            .to_montgomery(params);
+        let g_m = g.pow_bounded_exp(&plaintext.into_wide(), modulus_bits);
+        let r_n = randomizer
+            .0
+            .into_wide()
+            .to_montgomery(params)
+            .pow_bounded_exp(&modulus, modulus_bits);
+        assert_eq!(ciphertext.ciphertext, g_m * r_n);
+    }
+
     #[test]
     fn derive_randomizer() {
         let sk = SecretKeyPaillier::<PaillierTest>::random(&mut OsRng).to_precomputed();
@@ -559,7 +763,9 @@ mod tests {
 
         let ciphertext1 = CiphertextMod::<PaillierTest>::new(&mut OsRng, pk, &plaintext1);
         let ciphertext3 = CiphertextMod::<PaillierTest>::new(&mut OsRng, pk, &plaintext3);
+        op_count::take();
         let result = ciphertext1 * plaintext2 + ciphertext3;
+        assert_eq!(op_count::take(), 2);
 
         let plaintext_back = result.decrypt(&sk);
         assert_eq!(
@@ -568,4 +774,51 @@ mod tests {
             plaintext_back
         );
     }
+
+    #[test]
+    fn ciphertext_range() {
+        let sk = SecretKeyPaillier::<PaillierTest>::random(&mut OsRng).to_precomputed();
+        let pk = sk.public_key();
+
+        let plaintext = <PaillierTest as PaillierParams>::Uint::random_mod(
+            &mut OsRng,
+            &NonZero::new(*pk.modulus()).unwrap(),
+        );
+        let ciphertext = CiphertextMod::<PaillierTest>::new(&mut OsRng, pk, &plaintext).retrieve();
+        assert!(ciphertext.is_in_range(pk));
+
+        let out_of_range = Ciphertext::<PaillierTest> {
+            ciphertext: HasWide::square_wide(pk.modulus()),
+            phantom: core::marker::PhantomData,
+        };
+        assert!(!out_of_range.is_in_range(pk));
+
+        // Only the ciphertexts in range are converted by the checked method.
+        assert_eq!(
+            ciphertext.clone().validated(pk).unwrap(),
+            ciphertext.to_mod(pk)
+        );
+        assert!(out_of_range.validated(pk).is_none());
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "not checked")]
+    fn unchecked_decryption_is_flagged() {
+        let sk = SecretKeyPaillier::<PaillierTest>::random(&mut OsRng).to_precomputed();
+        let pk = sk.public_key();
+        let plaintext = <PaillierTest as PaillierParams>::Uint::from(123u32);
+        let ciphertext = CiphertextMod::<PaillierTest>::new(&mut OsRng, pk, &plaintext).retrieve();
+
+        // The checked conversion, and the ciphertexts derived from it, can be decrypted.
+        let checked = ciphertext.clone().validated(pk).unwrap();
+        assert_eq!(
+            (checked.clone() + &checked).decrypt(&sk),
+            plaintext.add_mod(&plaintext, pk.modulus())
+        );
+
+        // A ciphertext that was not checked cannot, even after homomorphic operations.
+        let unchecked = ciphertext.to_mod(pk);
+        let _ = (checked + &unchecked).decrypt(&sk);
+    }
 }
@@ -1,4 +1,6 @@
 use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
 use core::fmt::Debug;
 
 use rand_core::CryptoRngCore;
@@ -12,7 +14,8 @@ use crate::uint::{
     RandomPrimeWithRng, Retrieve, Signed, ToMontgomery,
 };
 use crypto_bigint::{
-    Bounded as TraitBounded, InvMod, Monty, Odd, ShrVartime, Square, WrappingAdd, WrappingSub,
+    BitOps, Bounded as TraitBounded, InvMod, Monty, Odd, ShrVartime, Square, WrappingAdd,
+    WrappingSub,
 };
 use secrecy::{ExposeSecret, SecretBox};
 
@@ -74,6 +77,35 @@ impl<P: PaillierParams> SecretKeyPaillier<P> {
         }
     }
 
+    /// Creates a secret key from the given primes,
+    /// checking that they are distinct safe primes of the size `P::PRIME_BITS`.
+    pub fn from_primes(
+        rng: &mut impl CryptoRngCore,
+        p: P::HalfUint,
+        q: P::HalfUint,
+    ) -> Result<Self, String> {
+        for prime in [&p, &q] {
+            if prime.bits() as usize != P::PRIME_BITS {
+                return Err(format!(
+                    "Expected a prime of {} bits, got {} bits",
+                    P::PRIME_BITS,
+                    prime.bits()
+                ));
+            }
+            if !prime.is_safe_prime_with_rng(rng) {
+                return Err("The given number is not a safe prime".into());
+            }
+        }
+        if p == q {
+            return Err("The primes must be distinct".into());
+        }
+
+        Ok(Self {
+            p: Box::new(p).into(),
+            q: Box::new(q).into(),
+        })
+    }
+
     pub fn to_precomputed(&self) -> SecretKeyPaillierPrecomputed<P> {
         // Euler's totient function of $p q$ - the number of positive integers up to $p q$
         // that are relatively prime to it.
@@ -101,6 +133,15 @@ impl<P: PaillierParams> SecretKeyPaillier<P> {
                 .expect("`q` is assumed to be a prime greater than 2"),
         );
 
+        let precomputed_mod_p_squared = P::UintMod::new_params_vartime(
+            Odd::new(self.p.expose_secret().mul_wide(self.p.expose_secret()))
+                .expect("`p^2` is odd since `p` is assumed to be a prime greater than 2"),
+        );
+        let precomputed_mod_q_squared = P::UintMod::new_params_vartime(
+            Odd::new(self.q.expose_secret().mul_wide(self.q.expose_secret()))
+                .expect("`q^2` is odd since `q` is assumed to be a prime greater than 2"),
+        );
+
         let public_key = PublicKeyPaillier {
             modulus: self.p.expose_secret().mul_wide(self.q.expose_secret()),
         };
@@ -160,9 +201,12 @@ impl<P: PaillierParams> SecretKeyPaillier<P> {
             inv_totient,
             inv_modulus,
             inv_p_mod_q,
+            inv_q_mod_p,
             nonsquare_sampling_constant,
             precomputed_mod_p: precomputed_mod_p.clone(),
             precomputed_mod_q: precomputed_mod_q.clone(),
+            precomputed_mod_p_squared,
+            precomputed_mod_q_squared,
             public_key,
         }
     }
@@ -177,10 +221,13 @@ pub(crate) struct SecretKeyPaillierPrecomputed<P: PaillierParams> {
     /// $N^{-1} \mod \phi(N)$
     inv_modulus: Bounded<P::Uint>,
     inv_p_mod_q: P::HalfUintMod,
+    inv_q_mod_p: P::HalfUintMod,
     // $u$ such that $u = 1 \mod p$ and $u = -1 \mod q$.
     nonsquare_sampling_constant: P::UintMod,
     precomputed_mod_p: <P::HalfUintMod as Monty>::Params,
     precomputed_mod_q: <P::HalfUintMod as Monty>::Params,
+    precomputed_mod_p_squared: <P::UintMod as Monty>::Params,
+    precomputed_mod_q_squared: <P::UintMod as Monty>::Params,
     public_key: PublicKeyPaillierPrecomputed<P>,
 }
 
@@ -313,6 +360,68 @@ where
             .expect("Will not overflow since 0 <= x < q, and 0 <= a < p.")
     }
 
+    /// Decrypts a Paillier ciphertext using the CRT representation of the secret key.
+    ///
+    /// All the operations involving the secret primes or the ciphertext
+    /// (reductions, exponentiations, Montgomery arithmetic and the CRT recombination)
+    /// are constant-time.
+    pub fn decrypt_crt(&self, ciphertext: &P::WideUint) -> P::Uint {
+        let m_p = self.decrypt_part(
+            ciphertext,
+            self.sk.p.expose_secret(),
+            &self.precomputed_mod_p_squared,
+            &self.precomputed_mod_p,
+            &self.inv_q_mod_p,
+        );
+        let m_q = self.decrypt_part(
+            ciphertext,
+            self.sk.q.expose_secret(),
+            &self.precomputed_mod_q_squared,
+            &self.precomputed_mod_q,
+            &self.inv_p_mod_q,
+        );
+        self.rns_join(&(m_p, m_q))
+    }
+
+    /// Calculates the plaintext modulo one of the primes.
+    fn decrypt_part(
+        &self,
+        ciphertext: &P::WideUint,
+        prime: &P::HalfUint,
+        precomputed_mod_prime_squared: &<P::UintMod as Monty>::Params,
+        precomputed_mod_prime: &<P::HalfUintMod as Monty>::Params,
+        inv_other_prime: &P::HalfUintMod,
+    ) -> P::HalfUintMod {
+        // For the prime `p` and the other prime `q`,
+        // `m mod p = L_p(C^(p-1) mod p^2) * h_p mod p`, where `L_p(x) = (x - 1) / p`,
+        // and `h_p = L_p((N + 1)^(p-1) mod p^2)^(-1) = ((p - 1) q)^(-1) = -q^(-1) mod p`.
+        let one = <P::Uint as Integer>::one();
+        let prime_wide = prime.clone().into_wide();
+        let prime_squared = NonZero::new(prime.mul_wide(prime).into_wide())
+            .expect("a square of a prime is non-zero");
+
+        let mut c = P::Uint::try_from_wide(*ciphertext % prime_squared)
+            .expect("the remainder is smaller than `p^2` and fits into `Uint`");
+        let mut x = c
+            .to_montgomery(precomputed_mod_prime_squared)
+            .pow_bounded_exp(&prime_wide.wrapping_sub(&one), P::PRIME_BITS as u32)
+            .retrieve();
+        let mut l = P::HalfUint::try_from_wide(
+            x.wrapping_sub(&one) / NonZero::new(prime_wide).expect("a prime is non-zero"),
+        )
+        .expect("`L_p(x) < p` and fits into `HalfUint`");
+
+        let l_mod = l.clone().to_montgomery(precomputed_mod_prime);
+        let result = <P::HalfUintMod as Monty>::zero(precomputed_mod_prime.clone())
+            - l_mod * inv_other_prime.clone();
+
+        c.zeroize();
+        x.zeroize();
+        l.zeroize();
+
+        result
+    }
+
     pub fn random_field_elem(&self, rng: &mut impl CryptoRngCore) -> Bounded<P::Uint> {
         Bounded::new(
             P::Uint::random_mod(rng, &self.totient_nonzero()),
@@ -359,6 +468,11 @@ pub(crate) struct PublicKeyPaillier<P: PaillierParams> {
 }
 
 impl<P: PaillierParams> PublicKeyPaillier<P> {
+    #[cfg(test)]
+    pub fn from_modulus(modulus: P::Uint) -> Self {
+        Self { modulus }
+    }
+
     pub fn modulus(&self) -> &P::Uint {
         &self.modulus
     }
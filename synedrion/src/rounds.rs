@@ -1,7 +1,8 @@
 mod generic;
 mod wrappers;
 
-#[cfg(any(test, feature = "bench-internals"))]
+#[cfg(any(test, feature = "bench-internals", feature = "schema"))]
+#[cfg_attr(not(any(test, feature = "bench-internals")), allow(dead_code))]
 pub(crate) mod test_utils;
 
 pub use generic::ProtocolResult;
@@ -6,6 +6,9 @@ use displaydoc::Display;
 use rand_core::CryptoRngCore;
 use serde::{Deserialize, Serialize};
 
+#[cfg(any(test, feature = "state-digest"))]
+use crate::tools::hashing::HashOutput;
+
 /// A round that sends out direct messages.
 pub(crate) trait Round<I: Ord + Clone> {
     type Type: FinalizableType;
@@ -60,6 +63,46 @@ pub(crate) trait Round<I: Ord + Clone> {
         rng: &mut impl CryptoRngCore,
     ) -> Option<Self::BroadcastMessage>;
 
+    /// Returns a digest of the public values held by the round,
+    /// including the ones received from other parties.
+    ///
+    /// Used to compare the execution with another implementation of the protocol
+    /// given the same randomness, and find the first round where they diverge.
+    /// Returns `None` if the round does not support it.
+    #[cfg(any(test, feature = "state-digest"))]
+    fn state_digest(&self) -> Option<HashOutput> {
+        None
+    }
+
+    /// Performs cheap structural checks of a message received from the party `from`
+    /// (e.g. that the points are not the identity, or the ciphertexts are in range)
+    /// before its cryptographic verification.
+    ///
+    /// The default implementation does not check anything.
+    fn validate_structure(
+        &self,
+        _from: &I,
+        _broadcast_msg: &Self::BroadcastMessage,
+        _direct_msg: &Self::DirectMessage,
+    ) -> Result<(), &'static str> {
+        Ok(())
+    }
+
+    /// Checks that a message received from the party `from` agrees with the local state
+    /// of the round, in cases where a disagreement cannot be attributed to the sender
+    /// (e.g. when it could have been caused by another party).
+    ///
+    /// A failure is reported as an unprovable fault.
+    /// The default implementation does not check anything.
+    fn check_consistency(
+        &self,
+        _from: &I,
+        _broadcast_msg: &Self::BroadcastMessage,
+        _direct_msg: &Self::DirectMessage,
+    ) -> Result<(), &'static str> {
+        Ok(())
+    }
+
     /// Processes a direct messsage received from the party `from`.
     // Note that since we assume broadcast and direct messages have the same list of destinations,
     // if `BroadcastMessage` is not `()` there will be a serialized broadcast
@@ -72,6 +115,16 @@ pub(crate) trait Round<I: Ord + Clone> {
         direct_msg: Self::DirectMessage,
     ) -> Result<Self::Payload, <Self::Result as ProtocolResult>::ProvableError>;
 
+    /// The number of expensive operations (ZK proof verifications and Paillier decryptions)
+    /// performed by [`verify_message`](`Self::verify_message`) for a single message
+    /// in the worst case.
+    ///
+    /// Used to charge the [`SessionBudget`](`crate::SessionBudget`) of the session.
+    /// The default implementation assumes the verification only involves cheap operations.
+    fn verification_cost(&self) -> usize {
+        0
+    }
+
     fn finalization_requirement() -> FinalizationRequirement {
         FinalizationRequirement::All
     }
@@ -107,6 +160,9 @@ pub trait ProtocolResult: Debug {
     /// That is, on errors where the culprit cannot be immediately identified,
     /// each node will have to provide the correctness proof for itself.
     type CorrectnessProof: Debug;
+    /// The reason the protocol has to be restarted in a new session
+    /// when it finished without any party being at fault, but its result cannot be used.
+    type RetryReason: Debug;
 }
 
 // This trait is used to fix the possible options for `Round::Type`.
@@ -155,6 +211,9 @@ pub enum FinalizeError<Res: ProtocolResult> {
     /// Returned when there is an error chaining the start of another protocol
     /// on the finalization of the previous one.
     Init(InitError),
+    /// The protocol finished without a fault, but its result cannot be used,
+    /// so it has to be restarted.
+    Retry(Res::RetryReason),
 }
 
 /// An error that can occur when initializing a protocol.
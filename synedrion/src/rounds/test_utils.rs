@@ -6,13 +6,15 @@ use core::fmt::Debug;
 
 use displaydoc::Display;
 use rand_core::CryptoRngCore;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use super::generic::{FinalizableToNextRound, FinalizableToResult, ProtocolResult, Round};
 use super::FinalizeError;
+#[cfg(test)]
+use crate::tools::hashing::HashOutput;
 
 /// A simple identity type for tests.
-#[derive(Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Serialize)]
+#[derive(Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) struct Id(pub(crate) u32);
 
 #[derive(Debug, Display)]
@@ -28,9 +30,9 @@ pub(crate) enum StepError<I: Debug> {
 }
 
 pub(crate) struct AssembledRound<I: Ord + Clone, R: Round<I>> {
-    round: R,
-    payloads: BTreeMap<I, <R as Round<I>>::Payload>,
-    artifacts: BTreeMap<I, <R as Round<I>>::Artifact>,
+    pub(crate) round: R,
+    pub(crate) payloads: BTreeMap<I, <R as Round<I>>::Payload>,
+    pub(crate) artifacts: BTreeMap<I, <R as Round<I>>::Artifact>,
 }
 
 pub(crate) fn step_round<I, R>(
@@ -154,6 +156,17 @@ pub(crate) fn step_result<I: Ord + Clone, R: FinalizableToResult<I>>(
     Ok(results)
 }
 
+/// Collects the digests of the public states of the given rounds (see [`Round::state_digest`]).
+#[cfg(test)]
+pub(crate) fn state_digests<I: Ord + Clone, R: Round<I>>(
+    rounds: &BTreeMap<I, R>,
+) -> BTreeMap<I, Option<HashOutput>> {
+    rounds
+        .iter()
+        .map(|(id, round)| (id.clone(), round.state_digest()))
+        .collect()
+}
+
 pub(crate) trait Without {
     type Item;
     fn without(self, item: &Self::Item) -> Self;
@@ -5,6 +5,8 @@ use rand_core::CryptoRngCore;
 use super::generic::{
     FinalizableType, FinalizationRequirement, FinalizeError, ProtocolResult, Round,
 };
+#[cfg(any(test, feature = "state-digest"))]
+use crate::tools::hashing::HashOutput;
 
 pub(crate) trait ProvableErrorWrapper<Res: ProtocolResult>: ProtocolResult {
     fn wrap_error(error: Res::ProvableError) -> Self::ProvableError;
@@ -12,6 +14,7 @@ pub(crate) trait ProvableErrorWrapper<Res: ProtocolResult>: ProtocolResult {
 
 pub(crate) trait CorrectnessProofWrapper<Res: ProtocolResult>: ProtocolResult {
     fn wrap_proof(proof: Res::CorrectnessProof) -> Self::CorrectnessProof;
+    fn wrap_retry_reason(reason: Res::RetryReason) -> Self::RetryReason;
 }
 
 pub(crate) fn wrap_finalize_error<T: ProtocolResult, Res: CorrectnessProofWrapper<T>>(
@@ -20,6 +23,7 @@ pub(crate) fn wrap_finalize_error<T: ProtocolResult, Res: CorrectnessProofWrappe
     match error {
         FinalizeError::Init(msg) => FinalizeError::Init(msg),
         FinalizeError::Proof(proof) => FinalizeError::Proof(Res::wrap_proof(proof)),
+        FinalizeError::Retry(reason) => FinalizeError::Retry(Res::wrap_retry_reason(reason)),
     }
 }
 
@@ -65,6 +69,11 @@ impl<I: Ord + Clone, T: RoundWrapper<I> + WrappedRound> Round<I> for T {
         self.inner_round().make_broadcast_message(rng)
     }
 
+    #[cfg(any(test, feature = "state-digest"))]
+    fn state_digest(&self) -> Option<HashOutput> {
+        self.inner_round().state_digest()
+    }
+
     fn make_direct_message(
         &self,
         rng: &mut impl CryptoRngCore,
@@ -73,6 +82,26 @@ impl<I: Ord + Clone, T: RoundWrapper<I> + WrappedRound> Round<I> for T {
         self.inner_round().make_direct_message(rng, destination)
     }
 
+    fn validate_structure(
+        &self,
+        from: &I,
+        broadcast_msg: &Self::BroadcastMessage,
+        direct_msg: &Self::DirectMessage,
+    ) -> Result<(), &'static str> {
+        self.inner_round()
+            .validate_structure(from, broadcast_msg, direct_msg)
+    }
+
+    fn check_consistency(
+        &self,
+        from: &I,
+        broadcast_msg: &Self::BroadcastMessage,
+        direct_msg: &Self::DirectMessage,
+    ) -> Result<(), &'static str> {
+        self.inner_round()
+            .check_consistency(from, broadcast_msg, direct_msg)
+    }
+
     fn verify_message(
         &self,
         rng: &mut impl CryptoRngCore,
@@ -84,6 +113,11 @@ impl<I: Ord + Clone, T: RoundWrapper<I> + WrappedRound> Round<I> for T {
             .verify_message(rng, from, broadcast_msg, direct_msg)
             .map_err(Self::Result::wrap_error)
     }
+
+    fn verification_cost(&self) -> usize {
+        self.inner_round().verification_cost()
+    }
+
     fn finalization_requirement() -> FinalizationRequirement {
         T::InnerRound::finalization_requirement()
     }
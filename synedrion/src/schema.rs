@@ -0,0 +1,856 @@
+//! Descriptions of the serialized structure of the protocol messages,
+//! for generating schemas of strongly-typed network layers.
+//!
+//! The structure is recorded by serializing a sample value, so the described variants
+//! of the enums and the presence of the optional fields are the ones of that value.
+//!
+//! The messages of all the protocols can be described with [`visit_messages`].
+
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Display;
+
+use displaydoc::Display;
+use rand_core::CryptoRngCore;
+use serde::ser::{self, Serialize};
+
+use crate::cggmp21::{
+    aux_gen, interactive_signing, key_gen, key_init, key_refresh, presigning, signing, AuxInfo,
+    KeyShare, SchemeParams,
+};
+use crate::curve::Scalar;
+use crate::rounds::{
+    test_utils::{step_next_round, step_result, step_round, Id, Without},
+    FinalizableToNextRound, FinalizableToResult, FirstRound, ProtocolResult, Round,
+};
+use crate::www02::{key_resharing, KeyResharingInputs, NewHolder, OldHolder, ThresholdKeyShare};
+
+/// The structure of a serialized value in terms of the serde data model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Schema {
+    /// A primitive value, with the name of its type (e.g. `"u64"` or `"bool"`).
+    Primitive(&'static str),
+    /// A byte string.
+    Bytes,
+    /// A unit value.
+    Unit,
+    /// An optional value; contains `None` if the value was absent in the sample.
+    Option(Option<Box<Schema>>),
+    /// A sequence, described by its first element; contains `None` if the sequence was empty.
+    Seq(Option<Box<Schema>>),
+    /// A map, described by its first entry; contains `None` if the map was empty.
+    Map(Option<(Box<Schema>, Box<Schema>)>),
+    /// A tuple or a tuple struct.
+    Tuple(Vec<Schema>),
+    /// A unit struct.
+    UnitStruct(&'static str),
+    /// A newtype struct.
+    Newtype(&'static str, Box<Schema>),
+    /// A struct with named fields.
+    Struct(&'static str, Vec<Field>),
+    /// A variant of an enum.
+    Variant {
+        /// The name of the enum.
+        name: &'static str,
+        /// The name of the variant.
+        variant: &'static str,
+        /// The contents of the variant.
+        contents: Box<Schema>,
+    },
+}
+
+/// A named field of a struct.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    /// The name of the field.
+    pub name: &'static str,
+    /// The structure of the field's value.
+    pub schema: Schema,
+}
+
+/// An error returned when a value cannot be described.
+#[derive(Debug, Clone, Display)]
+#[displaydoc("Schema error ({0})")]
+pub struct SchemaError(String);
+
+impl ser::StdError for SchemaError {}
+
+impl ser::Error for SchemaError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+/// Describes the serialized structure of `value`.
+pub fn describe<T: Serialize + ?Sized>(value: &T) -> Result<Schema, SchemaError> {
+    value.serialize(Describer)
+}
+
+/// The protocols whose messages are described by [`visit_messages`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Protocol {
+    /// KeyInit (see [`make_key_init_session`](`crate::make_key_init_session`)).
+    KeyInit,
+    /// KeyRefresh (see [`make_key_refresh_session`](`crate::make_key_refresh_session`)).
+    KeyRefresh,
+    /// AuxGen (see [`make_aux_gen_session`](`crate::make_aux_gen_session`)).
+    AuxGen,
+    /// KeyGen (see [`make_key_gen_session`](`crate::make_key_gen_session`)).
+    KeyGen,
+    /// Presigning (see [`make_presigning_session`](`crate::make_presigning_session`)).
+    Presigning,
+    /// Signing (see [`make_signing_session`](`crate::make_signing_session`)).
+    Signing,
+    /// Interactive signing
+    /// (see [`make_interactive_signing_session`](`crate::make_interactive_signing_session`)).
+    InteractiveSigning,
+    /// Key resharing (see [`make_key_resharing_session`](`crate::make_key_resharing_session`)).
+    KeyResharing,
+}
+
+/// The part of a round message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MessageKind {
+    /// The part that is the same for all the destinations.
+    Broadcast,
+    /// The part that is created separately for each destination.
+    Direct,
+}
+
+/// A receiver of the message descriptions produced by [`visit_messages`].
+pub trait MessageVisitor {
+    /// Called for each part of a message sent in the round `round_num` of `protocol`.
+    fn visit(&mut self, protocol: Protocol, round_num: u8, kind: MessageKind, schema: Schema);
+}
+
+/// Describes the messages sent in every round of every protocol with the parameters `P`,
+/// passing them to `visitor` in the order of the protocols in [`Protocol`] and of the rounds.
+///
+/// The parts of the messages that are not sent in a round (that is, have the type `()`)
+/// are skipped.
+///
+/// The sample messages are created by running the protocols between two parties
+/// (three for the key resharing), which can take some time for the production parameters.
+pub fn visit_messages<P: SchemeParams>(
+    rng: &mut impl CryptoRngCore,
+    visitor: &mut impl MessageVisitor,
+) -> Result<(), SchemaError> {
+    let ids = BTreeSet::from([Id(0), Id(1)]);
+
+    let r1 = first_rounds::<key_init::Round1<P, Id>>(rng, &ids, |_id| ())?;
+    visit_round(rng, visitor, Protocol::KeyInit, &r1)?;
+    let r2 = next_rounds(rng, r1)?;
+    visit_round(rng, visitor, Protocol::KeyInit, &r2)?;
+    let r3 = next_rounds(rng, r2)?;
+    visit_round(rng, visitor, Protocol::KeyInit, &r3)?;
+
+    let r1 = first_rounds::<key_refresh::Round1<P, Id>>(rng, &ids, |_id| None)?;
+    visit_round(rng, visitor, Protocol::KeyRefresh, &r1)?;
+    let r2 = next_rounds(rng, r1)?;
+    visit_round(rng, visitor, Protocol::KeyRefresh, &r2)?;
+    let r3 = next_rounds(rng, r2)?;
+    visit_round(rng, visitor, Protocol::KeyRefresh, &r3)?;
+
+    let r1 = first_rounds::<aux_gen::Round1<P, Id>>(rng, &ids, |_id| None)?;
+    visit_round(rng, visitor, Protocol::AuxGen, &r1)?;
+    let r2 = next_rounds(rng, r1)?;
+    visit_round(rng, visitor, Protocol::AuxGen, &r2)?;
+    let r3 = next_rounds(rng, r2)?;
+    visit_round(rng, visitor, Protocol::AuxGen, &r3)?;
+
+    let r1 = first_rounds::<key_gen::Round1<P, Id>>(rng, &ids, |_id| None)?;
+    visit_round(rng, visitor, Protocol::KeyGen, &r1)?;
+    let r2 = next_rounds(rng, r1)?;
+    visit_round(rng, visitor, Protocol::KeyGen, &r2)?;
+    let r3 = next_rounds(rng, r2)?;
+    visit_round(rng, visitor, Protocol::KeyGen, &r3)?;
+
+    let key_shares = KeyShare::<P, Id>::new_centralized(rng, &ids, None);
+    let aux_infos = AuxInfo::<P, Id>::new_centralized(rng, &ids);
+
+    let r1 = first_rounds::<presigning::Round1<P, Id>>(rng, &ids, |id| presigning::Inputs {
+        key_share: key_shares[id].clone(),
+        aux_info: aux_infos[id].clone(),
+        nonce_only: false,
+        rng_seed: None,
+    })?;
+    visit_round(rng, visitor, Protocol::Presigning, &r1)?;
+    let r2 = next_rounds(rng, r1)?;
+    visit_round(rng, visitor, Protocol::Presigning, &r2)?;
+    let r3 = next_rounds(rng, r2)?;
+    visit_round(rng, visitor, Protocol::Presigning, &r3)?;
+    let mut presigning_datas = results(rng, r3)?;
+
+    let message = Scalar::random(rng);
+    let r1 = first_rounds::<signing::Round1<P, Id>>(rng, &ids, |id| signing::Inputs {
+        message,
+        // Can unwrap here since `results()` returns a result for each party.
+        presigning: presigning_datas.remove(id).unwrap(),
+        key_share: key_shares[id].clone(),
+        aux_info: aux_infos[id].clone(),
+    })?;
+    visit_round(rng, visitor, Protocol::Signing, &r1)?;
+
+    let r1 = first_rounds::<interactive_signing::Round1<P, Id>>(rng, &ids, |id| {
+        interactive_signing::Inputs {
+            message,
+            key_share: key_shares[id].clone(),
+            aux_info: aux_infos[id].clone(),
+        }
+    })?;
+    visit_round(rng, visitor, Protocol::InteractiveSigning, &r1)?;
+    let r2 = next_rounds(rng, r1)?;
+    visit_round(rng, visitor, Protocol::InteractiveSigning, &r2)?;
+    let r3 = next_rounds(rng, r2)?;
+    visit_round(rng, visitor, Protocol::InteractiveSigning, &r3)?;
+    let r4 = next_rounds(rng, r3)?;
+    visit_round(rng, visitor, Protocol::InteractiveSigning, &r4)?;
+
+    // `Id(0)` passes its share on to the new holders `Id(1)` and `Id(2)`,
+    // so that both the old and the new holders send messages.
+    let old_holders = BTreeSet::from([Id(0), Id(1)]);
+    let new_holders = BTreeSet::from([Id(1), Id(2)]);
+    let all_ids = BTreeSet::from([Id(0), Id(1), Id(2)]);
+    let old_key_shares = ThresholdKeyShare::<P, Id>::new_centralized(rng, &old_holders, 2, None);
+    let verifying_key = old_key_shares[&Id(0)].verifying_key();
+    let r1 =
+        first_rounds::<key_resharing::Round1<P, Id>>(rng, &all_ids, |id| KeyResharingInputs {
+            old_holder: old_key_shares.get(id).map(|key_share| OldHolder {
+                key_share: key_share.clone(),
+            }),
+            new_holder: new_holders.contains(id).then(|| NewHolder {
+                verifying_key,
+                old_threshold: 2,
+                old_holders: old_holders.clone(),
+            }),
+            new_holders: new_holders.clone(),
+            new_threshold: 2,
+        })?;
+    visit_round(rng, visitor, Protocol::KeyResharing, &r1)?;
+
+    Ok(())
+}
+
+/// Describes the direct message sent in Round 2 of the Presigning protocol.
+///
+/// A sample message is created by running the first round between two parties,
+/// which can take some time for the production parameters.
+pub fn presigning_round2_direct<P: SchemeParams>(
+    rng: &mut impl CryptoRngCore,
+) -> Result<Schema, SchemaError> {
+    let ids = BTreeSet::from([Id(0), Id(1)]);
+    let key_shares = KeyShare::<P, Id>::new_centralized(rng, &ids, None);
+    let aux_infos = AuxInfo::<P, Id>::new_centralized(rng, &ids);
+
+    let r1 = first_rounds::<presigning::Round1<P, Id>>(rng, &ids, |id| presigning::Inputs {
+        key_share: key_shares[id].clone(),
+        aux_info: aux_infos[id].clone(),
+        nonce_only: false,
+        rng_seed: None,
+    })?;
+    let r2 = next_rounds(rng, r1)?;
+
+    let (message, _artifact) = r2[&Id(0)].make_direct_message(rng, &Id(1));
+    describe(&message)
+}
+
+/// Creates the first rounds of a protocol for the parties `ids`.
+fn first_rounds<R: FirstRound<Id>>(
+    rng: &mut impl CryptoRngCore,
+    ids: &BTreeSet<Id>,
+    mut make_inputs: impl FnMut(&Id) -> R::Inputs,
+) -> Result<BTreeMap<Id, R>, SchemaError> {
+    let mut shared_randomness = [0u8; 32];
+    rng.fill_bytes(&mut shared_randomness);
+
+    ids.iter()
+        .map(|id| {
+            let round = R::new(
+                rng,
+                &shared_randomness,
+                ids.clone().without(id),
+                *id,
+                make_inputs(id),
+            )
+            .map_err(|err| SchemaError(err.to_string()))?;
+            Ok((*id, round))
+        })
+        .collect()
+}
+
+/// Executes the given rounds and creates the next ones.
+fn next_rounds<R>(
+    rng: &mut impl CryptoRngCore,
+    rounds: BTreeMap<Id, R>,
+) -> Result<BTreeMap<Id, R::NextRound>, SchemaError>
+where
+    R: FinalizableToNextRound<Id>,
+    R::BroadcastMessage: Clone,
+{
+    let assembled = step_round(rng, rounds).map_err(|err| SchemaError(err.to_string()))?;
+    step_next_round(rng, assembled).map_err(|err| SchemaError(format!("{err:?}")))
+}
+
+/// Executes the given final rounds and returns the results.
+fn results<R>(
+    rng: &mut impl CryptoRngCore,
+    rounds: BTreeMap<Id, R>,
+) -> Result<BTreeMap<Id, <R::Result as ProtocolResult>::Success>, SchemaError>
+where
+    R: FinalizableToResult<Id>,
+    R::BroadcastMessage: Clone,
+{
+    let assembled = step_round(rng, rounds).map_err(|err| SchemaError(err.to_string()))?;
+    step_result(rng, assembled).map_err(|err| SchemaError(format!("{err:?}")))
+}
+
+/// Describes the messages sent by the first of the given rounds that sends any.
+fn visit_round<R: Round<Id>>(
+    rng: &mut impl CryptoRngCore,
+    visitor: &mut impl MessageVisitor,
+    protocol: Protocol,
+    rounds: &BTreeMap<Id, R>,
+) -> Result<(), SchemaError> {
+    let round = match rounds
+        .values()
+        .find(|round| !round.message_destinations().is_empty())
+    {
+        Some(round) => round,
+        None => return Ok(()),
+    };
+    // Can unwrap here since the destinations list is not empty.
+    let destination = round.message_destinations().first().unwrap();
+
+    if let Some(message) = round.make_broadcast_message(rng) {
+        let schema = describe(&message)?;
+        if schema != Schema::Unit {
+            visitor.visit(protocol, R::ROUND_NUM, MessageKind::Broadcast, schema);
+        }
+    }
+
+    let (message, _artifact) = round.make_direct_message(rng, destination);
+    let schema = describe(&message)?;
+    if schema != Schema::Unit {
+        visitor.visit(protocol, R::ROUND_NUM, MessageKind::Direct, schema);
+    }
+
+    Ok(())
+}
+
+struct Describer;
+
+struct SeqDescriber {
+    first: Option<Schema>,
+}
+
+struct TupleDescriber {
+    elements: Vec<Schema>,
+    variant: Option<(&'static str, &'static str)>,
+}
+
+struct MapDescriber {
+    first: Option<(Schema, Schema)>,
+    key: Option<Schema>,
+}
+
+struct StructDescriber {
+    name: &'static str,
+    fields: Vec<Field>,
+    variant: Option<&'static str>,
+}
+
+fn variant(name: &'static str, variant: &'static str, contents: Schema) -> Schema {
+    Schema::Variant {
+        name,
+        variant,
+        contents: Box::new(contents),
+    }
+}
+
+impl ser::Serializer for Describer {
+    type Ok = Schema;
+    type Error = SchemaError;
+    type SerializeSeq = SeqDescriber;
+    type SerializeTuple = TupleDescriber;
+    type SerializeTupleStruct = TupleDescriber;
+    type SerializeTupleVariant = TupleDescriber;
+    type SerializeMap = MapDescriber;
+    type SerializeStruct = StructDescriber;
+    type SerializeStructVariant = StructDescriber;
+
+    // The messages are sent in binary formats, which may serialize some types differently.
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, _v: bool) -> Result<Schema, SchemaError> {
+        Ok(Schema::Primitive("bool"))
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<Schema, SchemaError> {
+        Ok(Schema::Primitive("i8"))
+    }
+
+    fn serialize_i16(self, _v: i16) -> Result<Schema, SchemaError> {
+        Ok(Schema::Primitive("i16"))
+    }
+
+    fn serialize_i32(self, _v: i32) -> Result<Schema, SchemaError> {
+        Ok(Schema::Primitive("i32"))
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<Schema, SchemaError> {
+        Ok(Schema::Primitive("i64"))
+    }
+
+    fn serialize_i128(self, _v: i128) -> Result<Schema, SchemaError> {
+        Ok(Schema::Primitive("i128"))
+    }
+
+    fn serialize_u8(self, _v: u8) -> Result<Schema, SchemaError> {
+        Ok(Schema::Primitive("u8"))
+    }
+
+    fn serialize_u16(self, _v: u16) -> Result<Schema, SchemaError> {
+        Ok(Schema::Primitive("u16"))
+    }
+
+    fn serialize_u32(self, _v: u32) -> Result<Schema, SchemaError> {
+        Ok(Schema::Primitive("u32"))
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<Schema, SchemaError> {
+        Ok(Schema::Primitive("u64"))
+    }
+
+    fn serialize_u128(self, _v: u128) -> Result<Schema, SchemaError> {
+        Ok(Schema::Primitive("u128"))
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<Schema, SchemaError> {
+        Ok(Schema::Primitive("f32"))
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<Schema, SchemaError> {
+        Ok(Schema::Primitive("f64"))
+    }
+
+    fn serialize_char(self, _v: char) -> Result<Schema, SchemaError> {
+        Ok(Schema::Primitive("char"))
+    }
+
+    fn serialize_str(self, _v: &str) -> Result<Schema, SchemaError> {
+        Ok(Schema::Primitive("str"))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<Schema, SchemaError> {
+        Ok(Schema::Bytes)
+    }
+
+    fn serialize_none(self) -> Result<Schema, SchemaError> {
+        Ok(Schema::Option(None))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Schema, SchemaError> {
+        Ok(Schema::Option(Some(Box::new(describe(value)?))))
+    }
+
+    fn serialize_unit(self) -> Result<Schema, SchemaError> {
+        Ok(Schema::Unit)
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Schema, SchemaError> {
+        Ok(Schema::UnitStruct(name))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant_name: &'static str,
+    ) -> Result<Schema, SchemaError> {
+        Ok(variant(name, variant_name, Schema::Unit))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Schema, SchemaError> {
+        Ok(Schema::Newtype(name, Box::new(describe(value)?)))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant_name: &'static str,
+        value: &T,
+    ) -> Result<Schema, SchemaError> {
+        Ok(variant(name, variant_name, describe(value)?))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<SeqDescriber, SchemaError> {
+        Ok(SeqDescriber { first: None })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<TupleDescriber, SchemaError> {
+        Ok(TupleDescriber {
+            elements: Vec::with_capacity(len),
+            variant: None,
+        })
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<TupleDescriber, SchemaError> {
+        self.serialize_tuple(len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant_name: &'static str,
+        len: usize,
+    ) -> Result<TupleDescriber, SchemaError> {
+        Ok(TupleDescriber {
+            elements: Vec::with_capacity(len),
+            variant: Some((name, variant_name)),
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<MapDescriber, SchemaError> {
+        Ok(MapDescriber {
+            first: None,
+            key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<StructDescriber, SchemaError> {
+        Ok(StructDescriber {
+            name,
+            fields: Vec::with_capacity(len),
+            variant: None,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant_name: &'static str,
+        len: usize,
+    ) -> Result<StructDescriber, SchemaError> {
+        Ok(StructDescriber {
+            name,
+            fields: Vec::with_capacity(len),
+            variant: Some(variant_name),
+        })
+    }
+}
+
+impl ser::SerializeSeq for SeqDescriber {
+    type Ok = Schema;
+    type Error = SchemaError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SchemaError> {
+        if self.first.is_none() {
+            self.first = Some(describe(value)?);
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<Schema, SchemaError> {
+        Ok(Schema::Seq(self.first.map(Box::new)))
+    }
+}
+
+impl TupleDescriber {
+    fn add<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SchemaError> {
+        self.elements.push(describe(value)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Schema {
+        let tuple = Schema::Tuple(self.elements);
+        match self.variant {
+            Some((name, variant_name)) => variant(name, variant_name, tuple),
+            None => tuple,
+        }
+    }
+}
+
+impl ser::SerializeTuple for TupleDescriber {
+    type Ok = Schema;
+    type Error = SchemaError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SchemaError> {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<Schema, SchemaError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleStruct for TupleDescriber {
+    type Ok = Schema;
+    type Error = SchemaError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SchemaError> {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<Schema, SchemaError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleVariant for TupleDescriber {
+    type Ok = Schema;
+    type Error = SchemaError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SchemaError> {
+        self.add(value)
+    }
+
+    fn end(self) -> Result<Schema, SchemaError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeMap for MapDescriber {
+    type Ok = Schema;
+    type Error = SchemaError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), SchemaError> {
+        if self.first.is_none() {
+            self.key = Some(describe(key)?);
+        }
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SchemaError> {
+        if let Some(key) = self.key.take() {
+            self.first = Some((key, describe(value)?));
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<Schema, SchemaError> {
+        Ok(Schema::Map(
+            self.first
+                .map(|(key, value)| (Box::new(key), Box::new(value))),
+        ))
+    }
+}
+
+impl StructDescriber {
+    fn add<T: Serialize + ?Sized>(
+        &mut self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<(), SchemaError> {
+        self.fields.push(Field {
+            name,
+            schema: describe(value)?,
+        });
+        Ok(())
+    }
+
+    fn finish(self) -> Schema {
+        let contents = Schema::Struct(self.name, self.fields);
+        match self.variant {
+            Some(variant_name) => variant(self.name, variant_name, contents),
+            None => contents,
+        }
+    }
+}
+
+impl ser::SerializeStruct for StructDescriber {
+    type Ok = Schema;
+    type Error = SchemaError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SchemaError> {
+        self.add(key, value)
+    }
+
+    fn end(self) -> Result<Schema, SchemaError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeStructVariant for StructDescriber {
+    type Ok = Schema;
+    type Error = SchemaError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SchemaError> {
+        self.add(key, value)
+    }
+
+    fn end(self) -> Result<Schema, SchemaError> {
+        Ok(self.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use rand_core::OsRng;
+
+    use super::{
+        describe, presigning_round2_direct, visit_messages, MessageKind, MessageVisitor, Protocol,
+        Schema,
+    };
+    use crate::TestParams;
+
+    #[derive(Default)]
+    struct Recorder(Vec<(Protocol, u8, MessageKind, Schema)>);
+
+    impl MessageVisitor for Recorder {
+        fn visit(&mut self, protocol: Protocol, round_num: u8, kind: MessageKind, schema: Schema) {
+            self.0.push((protocol, round_num, kind, schema));
+        }
+    }
+
+    fn field_names(schema: &Schema) -> Vec<&'static str> {
+        match schema {
+            Schema::Struct(_name, fields) => fields.iter().map(|field| field.name).collect(),
+            _ => panic!("expected a struct, got {schema:?}"),
+        }
+    }
+
+    #[test]
+    fn describe_primitives() {
+        assert_eq!(describe(&1u64).unwrap(), Schema::Primitive("u64"));
+        assert_eq!(
+            describe(&Some(true)).unwrap(),
+            Schema::Option(Some(Schema::Primitive("bool").into()))
+        );
+        assert_eq!(
+            describe(&(1u8, "a")).unwrap(),
+            Schema::Tuple([Schema::Primitive("u8"), Schema::Primitive("str")].into())
+        );
+        assert_eq!(describe(&Vec::<u32>::new()).unwrap(), Schema::Seq(None));
+    }
+
+    #[test]
+    fn presigning_round2_direct_fields() {
+        let schema = presigning_round2_direct::<TestParams>(&mut OsRng).unwrap();
+
+        let fields = match &schema {
+            Schema::Struct(name, fields) => {
+                assert_eq!(*name, "Round2Message");
+                fields
+            }
+            _ => panic!("expected a struct, got {schema:?}"),
+        };
+        assert_eq!(
+            field_names(&schema),
+            [
+                "cap_gamma",
+                "cap_d",
+                "cap_f",
+                "psi",
+                "hat_psi_prime",
+                "product"
+            ]
+        );
+
+        let product = match &fields[5].schema {
+            Schema::Option(Some(product)) => product,
+            other => panic!("expected a present optional value, got {other:?}"),
+        };
+        assert_eq!(field_names(product), ["hat_cap_d", "hat_cap_f", "hat_psi"]);
+    }
+
+    #[test]
+    fn visit_all_messages() {
+        let mut recorder = Recorder::default();
+        visit_messages::<TestParams>(&mut OsRng, &mut recorder).unwrap();
+
+        let name = |schema: &Schema| match schema {
+            Schema::Struct(name, _fields) => *name,
+            Schema::Tuple(_elements) => "(tuple)",
+            _ => panic!("expected a struct or a tuple, got {schema:?}"),
+        };
+        let messages = recorder
+            .0
+            .iter()
+            .map(|(protocol, round_num, kind, schema)| (*protocol, *round_num, *kind, name(schema)))
+            .collect::<Vec<_>>();
+
+        use MessageKind::{Broadcast, Direct};
+        use Protocol::*;
+        assert_eq!(
+            messages,
+            [
+                (KeyInit, 1, Broadcast, "Round1Message"),
+                (KeyInit, 2, Broadcast, "Round2Message"),
+                (KeyInit, 3, Broadcast, "Round3Message"),
+                (KeyRefresh, 1, Broadcast, "Round1Message"),
+                (KeyRefresh, 2, Broadcast, "Round2Message"),
+                (KeyRefresh, 3, Direct, "Round3Message"),
+                (AuxGen, 1, Broadcast, "Round1Message"),
+                (AuxGen, 2, Broadcast, "Round2Message"),
+                (AuxGen, 3, Direct, "Round3Message"),
+                (KeyGen, 1, Broadcast, "(tuple)"),
+                (KeyGen, 2, Broadcast, "(tuple)"),
+                (KeyGen, 3, Broadcast, "Round3Message"),
+                (KeyGen, 3, Direct, "Round3Message"),
+                (Presigning, 1, Broadcast, "Round1BroadcastMessage"),
+                (Presigning, 1, Direct, "Round1DirectMessage"),
+                (Presigning, 2, Direct, "Round2Message"),
+                (Presigning, 3, Direct, "Round3Message"),
+                (Signing, 1, Broadcast, "Round1Message"),
+                (InteractiveSigning, 1, Broadcast, "Round1BroadcastMessage"),
+                (InteractiveSigning, 1, Direct, "Round1DirectMessage"),
+                (InteractiveSigning, 2, Direct, "Round2Message"),
+                (InteractiveSigning, 3, Direct, "Round3Message"),
+                (InteractiveSigning, 4, Broadcast, "Round1Message"),
+                (KeyResharing, 1, Broadcast, "Round1BroadcastMessage"),
+                (KeyResharing, 1, Direct, "Round1DirectMessage"),
+            ]
+        );
+
+        // The KeyGen messages combine the ones of KeyInit and KeyRefresh.
+        let key_gen_round1 = &recorder.0[9].3;
+        assert_eq!(
+            key_gen_round1,
+            &Schema::Tuple([recorder.0[0].3.clone(), recorder.0[3].3.clone()].into())
+        );
+
+        // The presigning messages are described with their fields.
+        let presigning_round2 = &recorder.0[15].3;
+        assert_eq!(
+            field_names(presigning_round2),
+            [
+                "cap_gamma",
+                "cap_d",
+                "cap_f",
+                "psi",
+                "hat_psi_prime",
+                "product"
+            ]
+        );
+    }
+}
@@ -1,16 +1,42 @@
 //! Mutable wrappers around the protocols for easier handling.
 
+mod audit;
+mod budget;
+mod clock;
 mod echo;
 mod error;
+#[cfg(any(test, feature = "testing"))]
+mod in_process;
 mod message_bundle;
+mod party_id;
+#[cfg(any(test, feature = "reassembly"))]
+mod reassembly;
 mod session;
 mod signed_message;
 mod type_erased;
+mod wire_format;
 
+pub use audit::{
+    audit_quorum, AuditError, AuditErrorEnum, NonParticipation, RoundEvidence, TranscriptEntry,
+};
+pub use budget::{Budgeted, SessionBudget};
+pub use clock::Clock;
+#[cfg(feature = "std")]
+pub use clock::SystemClock;
 pub use echo::EchoError;
 pub use error::{Error, LocalError, ProvableError, RemoteError, RemoteErrorEnum};
+#[cfg(any(test, feature = "testing"))]
+pub use in_process::{run_protocol, InProcessNetwork};
 pub use message_bundle::MessageBundle;
+pub use party_id::PartyId;
+#[cfg(any(test, feature = "reassembly"))]
+pub use reassembly::{
+    fragment_message, read_message, MessageReassembler, ReassemblyError, FRAGMENT_HEADER_LEN,
+    MAX_STREAMED_MESSAGE_LEN,
+};
 pub use session::{
-    Artifact, FinalizeOutcome, PreprocessedMessage, ProcessedMessage, RoundAccumulator, Session,
+    Artifact, FinalizeOutcome, PreprocessedMessage, ProcessedMessage, ResultMeta,
+    RetransmitRequest, RoundAccumulator, Session, SessionDiagnostic,
 };
 pub use signed_message::SessionId;
+pub use wire_format::WireFormat;
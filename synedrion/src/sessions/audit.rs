@@ -0,0 +1,247 @@
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use displaydoc::Display;
+use serde::{Deserialize, Serialize};
+use signature::hazmat::PrehashVerifier;
+
+use super::message_bundle::MessageBundle;
+use super::party_id::PartyId;
+use super::signed_message::{MessageType, SessionId, SignedMessage};
+use super::type_erased::deserialize_message;
+use super::wire_format::WireFormat;
+
+/// A recorded message from a session transcript: the sender, the recipient, and the message.
+pub type TranscriptEntry<Sig, Verifier> = (Verifier, Verifier, MessageBundle<Sig>);
+
+/// The messages of a single round known to a party,
+/// exported with [`Session::export_round_evidence`](`crate::Session::export_round_evidence`).
+///
+/// The messages are signed by their senders, so they can be checked by a third party
+/// (e.g. with [`audit_quorum`]) using only the public keys of the parties.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RoundEvidence<Sig, Verifier> {
+    /// The session the messages belong to.
+    pub session_id: SessionId,
+    /// The round the messages belong to.
+    pub round: u8,
+    /// The messages sent and received by the exporting party.
+    pub messages: Vec<TranscriptEntry<Sig, Verifier>>,
+}
+
+/// A fault found in a session transcript.
+#[derive(Clone, Debug, Display)]
+pub struct AuditError<Verifier> {
+    /// The party the faulty transcript entry is attributed to.
+    pub party: Verifier,
+    /// The error type
+    pub error: AuditErrorEnum,
+}
+
+/// Types of faults found in a session transcript.
+#[derive(Clone, Debug, Display)]
+pub enum AuditErrorEnum {
+    /// The party is not one of the authorized parties.
+    UnauthorizedParty,
+    /// The message belongs to a different session.
+    UnexpectedSessionId,
+    /// The message signature does not match its contents: {0}.
+    InvalidSignature(String),
+    /// The message contents are invalid: {0}.
+    InvalidContents(String),
+}
+
+/// A signed statement by a party that it has not received the messages
+/// of some parties in a round, created with
+/// [`Session::finalize_with_missing`](`crate::Session::finalize_with_missing`).
+///
+/// The statement can be passed on to other parties, who can check it with [`Self::verify`]
+/// using only the public key of the attester.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NonParticipation<Sig, Verifier> {
+    pub(crate) attester: Verifier,
+    pub(crate) message: SignedMessage<Sig>,
+}
+
+impl<Sig, Verifier> NonParticipation<Sig, Verifier>
+where
+    Sig: Clone,
+    Verifier: PartyId + PrehashVerifier<Sig>,
+{
+    /// The party that made the statement.
+    pub fn attester(&self) -> &Verifier {
+        &self.attester
+    }
+
+    /// The session the statement belongs to.
+    pub fn session_id(&self) -> &SessionId {
+        self.message.session_id()
+    }
+
+    /// The round in which the messages were not received.
+    pub fn round(&self) -> u8 {
+        self.message.round()
+    }
+
+    /// Verifies the attester's signature and returns the parties
+    /// whose messages were not received.
+    ///
+    /// `wire_format` must be the format used by the session the statement belongs to.
+    pub fn verify(
+        &self,
+        wire_format: WireFormat,
+    ) -> Result<BTreeSet<Verifier>, AuditError<Verifier>> {
+        let make_error = |error| AuditError {
+            party: self.attester.clone(),
+            error,
+        };
+
+        if self.message.message_type() != MessageType::NonParticipation {
+            return Err(make_error(AuditErrorEnum::InvalidContents(
+                "Unexpected message type".into(),
+            )));
+        }
+
+        let message = self
+            .message
+            .clone()
+            .verify::<Verifier>(&self.attester, None)
+            .map_err(|err| make_error(AuditErrorEnum::InvalidSignature(err)))?;
+
+        deserialize_message(wire_format, message.payload())
+            .map_err(|err| make_error(AuditErrorEnum::InvalidContents(err)))
+    }
+}
+
+/// Determines which parties took part in a session given its transcript.
+///
+/// Every message in the transcript must belong to the session `session_id`,
+/// be exchanged between the parties in `authorized_parties`
+/// (e.g. [`KeyShare::all_parties`](`crate::KeyShare::all_parties`)),
+/// and carry a valid signature of its sender.
+/// Returns the set of parties that sent at least one message.
+pub fn audit_quorum<Sig, Verifier>(
+    session_id: &SessionId,
+    authorized_parties: &BTreeSet<Verifier>,
+    transcript: &[TranscriptEntry<Sig, Verifier>],
+) -> Result<BTreeSet<Verifier>, AuditError<Verifier>>
+where
+    Sig: Clone,
+    Verifier: PrehashVerifier<Sig> + Clone + Ord + Serialize,
+{
+    let mut participants = BTreeSet::new();
+
+    for (from, to, message) in transcript {
+        for party in [from, to] {
+            if !authorized_parties.contains(party) {
+                return Err(AuditError {
+                    party: party.clone(),
+                    error: AuditErrorEnum::UnauthorizedParty,
+                });
+            }
+        }
+
+        if message.session_id() != session_id {
+            return Err(AuditError {
+                party: from.clone(),
+                error: AuditErrorEnum::UnexpectedSessionId,
+            });
+        }
+
+        message.clone().verify(from, to).map_err(|err| AuditError {
+            party: from.clone(),
+            error: AuditErrorEnum::InvalidSignature(err),
+        })?;
+
+        participants.insert(from.clone());
+    }
+
+    Ok(participants)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+    use alloc::vec::Vec;
+
+    use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+    use rand_core::OsRng;
+
+    use super::{audit_quorum, AuditErrorEnum};
+    use crate::{
+        make_interactive_signing_session, AuxInfo, SessionId, TestParams, ThresholdKeyShare,
+    };
+
+    #[test]
+    fn quorum_from_transcript() {
+        let signers = (0..4)
+            .map(|_| SigningKey::random(&mut OsRng))
+            .collect::<Vec<_>>();
+        let all_parties = signers
+            .iter()
+            .map(|signer| *signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+
+        let t_key_shares = ThresholdKeyShare::<TestParams, VerifyingKey>::new_centralized(
+            &mut OsRng,
+            &all_parties,
+            2,
+            None,
+        );
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &all_parties);
+
+        let quorum_signers = [&signers[1], &signers[3]];
+        let quorum = quorum_signers
+            .iter()
+            .map(|signer| *signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+
+        let session_id = SessionId::from_seed(b"signing");
+        let message = b"abcdefghijklmnopqrstuvwxyz123456";
+
+        // Record the first round messages of the signing
+        let mut transcript = Vec::new();
+        for signer in quorum_signers {
+            let id = *signer.verifying_key();
+            let session = make_interactive_signing_session::<_, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signer.clone(),
+                &quorum,
+                &t_key_shares[&id].to_key_share(&quorum),
+                &aux_infos[&id].to_subset(&quorum).unwrap(),
+                message,
+            )
+            .unwrap();
+
+            for destination in session.message_destinations().iter() {
+                let (bundle, _artifact) = session.make_message(&mut OsRng, destination).unwrap();
+                transcript.push((id, *destination, bundle));
+            }
+        }
+
+        let participants = audit_quorum(&session_id, &all_parties, &transcript).unwrap();
+        assert_eq!(participants, quorum);
+
+        // A transcript of another session is rejected
+        let other_session_id = SessionId::from_seed(b"another signing");
+        let err = audit_quorum(&other_session_id, &all_parties, &transcript).unwrap_err();
+        assert!(matches!(err.error, AuditErrorEnum::UnexpectedSessionId));
+
+        // Messages from parties outside of the authorized set are rejected
+        let err = audit_quorum(
+            &session_id,
+            &BTreeSet::from([*signers[1].verifying_key()]),
+            &transcript,
+        )
+        .unwrap_err();
+        assert!(matches!(err.error, AuditErrorEnum::UnauthorizedParty));
+
+        // Messages attributed to a wrong sender are rejected
+        let (_from, to, bundle) = transcript[0].clone();
+        let forged = [(*signers[0].verifying_key(), to, bundle)];
+        let err = audit_quorum(&session_id, &all_parties, &forged).unwrap_err();
+        assert!(matches!(err.error, AuditErrorEnum::InvalidSignature(_)));
+    }
+}
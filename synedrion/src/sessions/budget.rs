@@ -0,0 +1,85 @@
+/// A limit on the amount of work a session performs verifying messages
+/// before yielding control to the caller.
+///
+/// Verifying a message may involve a number of expensive operations (ZK proof verifications
+/// and decryptions), so a node running many sessions concurrently can use a budget per session
+/// to interleave them fairly.
+/// Each message is charged by the number of such operations its verification takes
+/// in the round it belongs to (at least one), so a peer cannot make a session
+/// do more work in a slice by sending messages that are more expensive to verify.
+/// See [`Session::process_message_within`](`crate::Session::process_message_within`).
+#[derive(Debug, Clone, Copy)]
+pub struct SessionBudget {
+    per_slice: usize,
+    spent: usize,
+}
+
+impl SessionBudget {
+    /// Creates a budget allowing `per_slice` expensive operations to be performed
+    /// before [`Self::refill`] has to be called.
+    ///
+    /// A message whose verification costs more than `per_slice` operations
+    /// is still processed if it is the first one in the slice.
+    pub fn new(per_slice: usize) -> Self {
+        Self {
+            per_slice,
+            spent: 0,
+        }
+    }
+
+    /// Makes the full budget available again (e.g. when the session is scheduled next time).
+    pub fn refill(&mut self) {
+        self.spent = 0;
+    }
+
+    /// Returns `true` if no more messages can be verified until the budget is refilled.
+    pub fn is_exhausted(&self) -> bool {
+        self.spent >= self.per_slice
+    }
+
+    pub(crate) fn try_spend(&mut self, cost: usize) -> bool {
+        let cost = cost.max(1);
+        if self.is_exhausted() || (self.spent > 0 && self.spent + cost > self.per_slice) {
+            return false;
+        }
+        self.spent += cost;
+        true
+    }
+}
+
+/// The result of an operation that is postponed if the budget is exhausted.
+#[derive(Debug)]
+pub enum Budgeted<T, Postponed> {
+    /// The operation was performed.
+    Done(T),
+    /// The budget is exhausted; the input is returned to be retried after a refill.
+    WouldBlock(Postponed),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SessionBudget;
+
+    #[test]
+    fn messages_are_charged_by_cost() {
+        let mut budget = SessionBudget::new(6);
+        assert!(budget.try_spend(3));
+        assert!(budget.try_spend(2));
+        // Does not fit in what is left of the slice.
+        assert!(!budget.try_spend(2));
+        // Cheap messages are still charged.
+        assert!(budget.try_spend(0));
+        assert!(budget.is_exhausted());
+        assert!(!budget.try_spend(0));
+    }
+
+    #[test]
+    fn expensive_message_is_not_postponed_forever() {
+        let mut budget = SessionBudget::new(2);
+        assert!(budget.try_spend(5));
+        assert!(budget.is_exhausted());
+        budget.refill();
+        assert!(budget.try_spend(1));
+        assert!(!budget.try_spend(5));
+    }
+}
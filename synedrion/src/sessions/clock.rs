@@ -0,0 +1,34 @@
+/// A source of the current time, used to timestamp the messages and check their freshness,
+/// and to expire the presignatures held in a [`PresignaturePool`](`crate::PresignaturePool`).
+///
+/// See [`Session::with_max_skew`](`crate::Session::with_max_skew`).
+pub trait Clock {
+    /// Returns the current time in seconds since the UNIX epoch.
+    fn now(&self) -> u64;
+}
+
+/// A [`Clock`] based on the system time.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            // The system time is only before the epoch if it is misconfigured.
+            .map_or(0, |duration| duration.as_secs())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::{Clock, SystemClock};
+
+    #[test]
+    fn system_clock_is_after_the_epoch() {
+        // 2024-01-01T00:00:00Z
+        assert!(SystemClock.now() > 1_704_067_200);
+    }
+}
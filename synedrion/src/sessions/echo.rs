@@ -8,11 +8,13 @@ use serde::{Deserialize, Serialize};
 use super::error::LocalError;
 use super::signed_message::{SignedMessage, VerifiedMessage};
 use super::type_erased::{deserialize_message, serialize_message};
+use super::wire_format::WireFormat;
 
 #[derive(Clone)]
 pub(crate) struct EchoRound<I, Sig> {
     destinations: BTreeSet<I>,
     broadcasts: BTreeMap<I, VerifiedMessage<Sig>>,
+    wire_format: WireFormat,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -39,11 +41,12 @@ where
     I: Clone + Ord + PartialEq + Serialize + for<'de> Deserialize<'de>,
     Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
 {
-    pub fn new(broadcasts: BTreeMap<I, VerifiedMessage<Sig>>) -> Self {
+    pub fn new(broadcasts: BTreeMap<I, VerifiedMessage<Sig>>, wire_format: WireFormat) -> Self {
         let destinations = broadcasts.keys().cloned().collect();
         Self {
             broadcasts,
             destinations,
+            wire_format,
         }
     }
 
@@ -64,12 +67,12 @@ where
                 .map(|(idx, msg)| (idx, msg.into_unverified()))
                 .collect(),
         };
-        serialize_message(&message).unwrap()
+        serialize_message(self.wire_format, &message).unwrap()
     }
 
     pub fn verify_broadcast(&self, from: &I, payload: &[u8]) -> Result<(), EchoError> {
         // TODO (#68): check that the direct payload is empty?
-        let message: Message<I, Sig> = deserialize_message(payload)
+        let message: Message<I, Sig> = deserialize_message(self.wire_format, payload)
             .map_err(|err| EchoError::CannotDeserialize(err.to_string()))?;
 
         // TODO (#68): check that there are no repeating indices, and the indices are in range.
@@ -133,6 +136,10 @@ impl<I: Ord + Clone> EchoAccum<I> {
         self.received_messages.contains(from)
     }
 
+    pub fn received(&self) -> &BTreeSet<I> {
+        &self.received_messages
+    }
+
     pub fn add_echo_received(&mut self, from: &I) -> Option<()> {
         if self.received_messages.insert(from.clone()) {
             Some(())
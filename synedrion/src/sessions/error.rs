@@ -1,3 +1,4 @@
+use alloc::collections::BTreeSet;
 use alloc::string::String;
 
 use displaydoc::Display;
@@ -32,6 +33,23 @@ pub enum Error<Res: ProtocolResult, Verifier> {
     /// This error may be eventually provable if there are some external guarantees
     /// provided by the communication channel.
     Remote(RemoteError<Verifier>),
+    /// The protocol finished without any party being at fault, but its result cannot be used
+    /// (e.g. Presigning produced a nonce with the zero x-coordinate).
+    ///
+    /// The protocol has to be started again in a new session.
+    Retry {
+        /// The reason of the restart.
+        reason: Res::RetryReason,
+    },
+    /// The deadline of the round set with
+    /// [`Session::with_round_deadline`](`super::Session::with_round_deadline`)
+    /// has passed before all the messages were received.
+    RoundTimedOut {
+        /// The round that timed out.
+        round: u8,
+        /// The parties whose messages for the round have not been received.
+        missing: BTreeSet<Verifier>,
+    },
 }
 
 /// An error on this party's side.
@@ -54,10 +72,14 @@ pub struct RemoteError<Verifier> {
 pub enum RemoteErrorEnum {
     /// Session ID does not match the one provided to the local session constructor.
     UnexpectedSessionId,
+    /// The sender is not one of the parties of the session.
+    UnknownSender,
     /// A message is intended for an unexpected round (not the current one or the next one).
     OutOfOrderMessage,
     /// A message from this party has already been received.
     DuplicateMessage,
+    /// The message timestamp is missing or outside of the allowed time window.
+    StaleMessage,
     /// The message signature does not match its contents: {0}.
     InvalidSignature(String),
     /// The message has invalid contents, but the fault is unprovable: {0}.
@@ -72,6 +94,8 @@ pub enum ProvableError<Res: ProtocolResult> {
     Protocol(Res::ProvableError),
     /// Failed to deserialize the message.
     CannotDeserialize(String),
+    /// The message was deserialized, but is malformed.
+    InvalidStructure(&'static str),
     /// Echo round failed.
     Echo(EchoError),
 }
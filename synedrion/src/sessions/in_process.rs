@@ -0,0 +1,616 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::vec::Vec;
+
+use rand_core::CryptoRngCore;
+use serde::{Deserialize, Serialize};
+use signature::{
+    hazmat::{PrehashVerifier, RandomizedPrehashSigner},
+    Keypair,
+};
+
+use super::error::{Error, LocalError};
+use super::message_bundle::MessageBundle;
+use super::party_id::PartyId;
+use super::session::{FinalizeOutcome, PreprocessedMessage, RoundAccumulator, Session};
+use crate::rounds::ProtocolResult;
+
+/// A network simulation running several sessions in the same process,
+/// relaying the messages between them.
+///
+/// Intended for tests and examples, where the sessions of all the parties are available locally.
+#[derive(Debug, Clone, Copy)]
+pub struct InProcessNetwork {
+    shuffle: bool,
+}
+
+impl Default for InProcessNetwork {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct Node<Res: ProtocolResult, Sig, Signer, Verifier> {
+    session: Option<Session<Res, Sig, Signer, Verifier>>,
+    accum: Option<RoundAccumulator<Sig, Verifier>>,
+    result: Option<Res::Success>,
+    #[cfg(any(test, feature = "state-digest"))]
+    state_digests: Vec<Option<[u8; 32]>>,
+}
+
+impl<Res, Sig, Signer, Verifier> Node<Res, Sig, Signer, Verifier>
+where
+    Res: ProtocolResult,
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+    Verifier: PartyId + PrehashVerifier<Sig>,
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+{
+    fn new(
+        session: Session<Res, Sig, Signer, Verifier>,
+        accum: RoundAccumulator<Sig, Verifier>,
+    ) -> Self {
+        let mut node = Self {
+            session: None,
+            accum: None,
+            result: None,
+            #[cfg(any(test, feature = "state-digest"))]
+            state_digests: Vec::new(),
+        };
+        node.start(session, accum);
+        node
+    }
+
+    fn start(
+        &mut self,
+        session: Session<Res, Sig, Signer, Verifier>,
+        accum: RoundAccumulator<Sig, Verifier>,
+    ) {
+        #[cfg(any(test, feature = "state-digest"))]
+        self.state_digests.push(session.state_digest());
+        self.session = Some(session);
+        self.accum = Some(accum);
+    }
+}
+
+type Envelope<Sig, Verifier> = (Verifier, Verifier, MessageBundle<Sig>);
+
+impl InProcessNetwork {
+    /// Creates a network delivering the messages in random order,
+    /// to increase the chances of messages from the future rounds arriving early.
+    pub fn new() -> Self {
+        Self { shuffle: true }
+    }
+
+    /// Makes the network deliver the messages in the order they were sent.
+    ///
+    /// Together with a seeded RNG this makes the execution reproducible.
+    pub fn deterministic(self) -> Self {
+        Self { shuffle: false }
+    }
+
+    /// Runs the given sessions until all of them produce a result.
+    ///
+    /// The results are returned in the same order as the sessions.
+    /// Stops at the first error encountered by any of the parties.
+    pub fn run<Res, Sig, Signer, Verifier>(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        sessions: Vec<Session<Res, Sig, Signer, Verifier>>,
+    ) -> Result<Vec<Res::Success>, Error<Res, Verifier>>
+    where
+        Res: ProtocolResult,
+        Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+        Verifier: PartyId + PrehashVerifier<Sig>,
+        Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+    {
+        let nodes = self.run_nodes(rng, sessions)?;
+        // Can unwrap here since `run_nodes()` only returns when all the results are present.
+        Ok(nodes.into_iter().map(|node| node.result.unwrap()).collect())
+    }
+
+    /// Runs the given sessions like [`run`](`Self::run`), additionally returning
+    /// the state digests (see [`Session::state_digest`]) of each session at the start of each round.
+    ///
+    /// The digests are returned in the same order as the sessions,
+    /// each list containing one entry per round.
+    /// Used together with [`deterministic`](`Self::deterministic`) and a seeded RNG
+    /// to produce transcripts that can be compared with another implementation of the protocol.
+    #[cfg(any(test, feature = "state-digest"))]
+    #[allow(clippy::type_complexity)]
+    pub fn run_with_state_digests<Res, Sig, Signer, Verifier>(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        sessions: Vec<Session<Res, Sig, Signer, Verifier>>,
+    ) -> Result<(Vec<Res::Success>, Vec<Vec<Option<[u8; 32]>>>), Error<Res, Verifier>>
+    where
+        Res: ProtocolResult,
+        Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+        Verifier: PartyId + PrehashVerifier<Sig>,
+        Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+    {
+        let nodes = self.run_nodes(rng, sessions)?;
+        // Can unwrap here since `run_nodes()` only returns when all the results are present.
+        Ok(nodes
+            .into_iter()
+            .map(|node| (node.result.unwrap(), node.state_digests))
+            .unzip())
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn run_nodes<Res, Sig, Signer, Verifier>(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        sessions: Vec<Session<Res, Sig, Signer, Verifier>>,
+    ) -> Result<Vec<Node<Res, Sig, Signer, Verifier>>, Error<Res, Verifier>>
+    where
+        Res: ProtocolResult,
+        Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+        Verifier: PartyId + PrehashVerifier<Sig>,
+        Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+    {
+        let ids = sessions
+            .iter()
+            .enumerate()
+            .map(|(idx, session)| (session.verifier(), idx))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut queue = Vec::<Envelope<Sig, Verifier>>::new();
+        let mut nodes = Vec::with_capacity(sessions.len());
+        for session in sessions {
+            let accum = start_round(rng, &session, Vec::new(), &mut queue)?;
+            nodes.push(Node::new(session, accum));
+        }
+
+        loop {
+            for node in nodes.iter_mut() {
+                finalize_if_possible(rng, node, &mut queue)?;
+            }
+
+            if nodes.iter().all(|node| node.result.is_some()) {
+                break;
+            }
+
+            if queue.is_empty() {
+                return Err(Error::Local(LocalError(
+                    "No messages left to deliver, but some sessions have not finished".into(),
+                )));
+            }
+
+            let message_idx = if self.shuffle {
+                rng.next_u32() as usize % queue.len()
+            } else {
+                0
+            };
+            let (from, to, message) = queue.remove(message_idx);
+
+            let node = ids
+                .get(&to)
+                .and_then(|idx| nodes.get_mut(*idx))
+                .ok_or_else(|| Error::Local(LocalError(format!("Unknown destination: {to:?}"))))?;
+            deliver(rng, node, &from, message)?;
+        }
+
+        Ok(nodes)
+    }
+}
+
+/// Runs the given sessions in memory until all of them produce a result,
+/// delivering the messages in the order they were sent.
+///
+/// A shortcut for `InProcessNetwork::new().deterministic().run(rng, sessions)`;
+/// the results are returned in the same order as the sessions,
+/// and the first error encountered by any of the parties is returned as is.
+///
+#[cfg_attr(feature = "testing", doc = "```")]
+#[cfg_attr(not(feature = "testing"), doc = "```ignore")]
+/// use std::collections::BTreeSet;
+///
+/// use rand_core::OsRng;
+/// use synedrion::{
+///     ecdsa::{Signature, SigningKey, VerifyingKey},
+///     make_key_gen_session, sessions::run_protocol, SessionId, TestParams,
+/// };
+///
+/// let signers = (0..3)
+///     .map(|_| SigningKey::random(&mut OsRng))
+///     .collect::<Vec<_>>();
+/// let verifiers = signers
+///     .iter()
+///     .map(|signer| *signer.verifying_key())
+///     .collect::<BTreeSet<_>>();
+///
+/// let session_id = SessionId::from_seed(b"key gen");
+/// let sessions = signers
+///     .iter()
+///     .map(|signer| {
+///         make_key_gen_session::<TestParams, Signature, SigningKey, VerifyingKey>(
+///             &mut OsRng,
+///             session_id,
+///             signer.clone(),
+///             &verifiers,
+///         )
+///         .unwrap()
+///     })
+///     .collect();
+///
+/// let results = run_protocol(&mut OsRng, sessions).unwrap();
+/// let (key_share, _aux_info) = &results[0];
+/// assert!(results
+///     .iter()
+///     .all(|(other, _)| other.verifying_key() == key_share.verifying_key()));
+/// ```
+pub fn run_protocol<Res, Sig, Signer, Verifier>(
+    rng: &mut impl CryptoRngCore,
+    sessions: Vec<Session<Res, Sig, Signer, Verifier>>,
+) -> Result<Vec<Res::Success>, Error<Res, Verifier>>
+where
+    Res: ProtocolResult,
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+    Verifier: PartyId + PrehashVerifier<Sig>,
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+{
+    InProcessNetwork::new().deterministic().run(rng, sessions)
+}
+
+fn start_round<Res, Sig, Signer, Verifier>(
+    rng: &mut impl CryptoRngCore,
+    session: &Session<Res, Sig, Signer, Verifier>,
+    cached_messages: Vec<PreprocessedMessage<Sig, Verifier>>,
+    queue: &mut Vec<Envelope<Sig, Verifier>>,
+) -> Result<RoundAccumulator<Sig, Verifier>, Error<Res, Verifier>>
+where
+    Res: ProtocolResult,
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+    Verifier: PartyId + PrehashVerifier<Sig>,
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+{
+    let mut accum = session.make_accumulator();
+    let my_id = session.verifier();
+
+    for destination in session.message_destinations().iter() {
+        let (message, artifact) = session
+            .make_message(rng, destination)
+            .map_err(Error::Local)?;
+        queue.push((my_id.clone(), destination.clone(), message));
+        accum.add_artifact(artifact).map_err(Error::Local)?;
+    }
+
+    for preprocessed in cached_messages {
+        let processed = session.process_message(rng, preprocessed)?;
+        accum
+            .add_processed_message(processed)
+            .map_err(Error::Local)?
+            .map_err(Error::Remote)?;
+    }
+
+    Ok(accum)
+}
+
+fn finalize_if_possible<Res, Sig, Signer, Verifier>(
+    rng: &mut impl CryptoRngCore,
+    node: &mut Node<Res, Sig, Signer, Verifier>,
+    queue: &mut Vec<Envelope<Sig, Verifier>>,
+) -> Result<(), Error<Res, Verifier>>
+where
+    Res: ProtocolResult,
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+    Verifier: PartyId + PrehashVerifier<Sig>,
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+{
+    let can_finalize = match (&node.session, &node.accum) {
+        (Some(session), Some(accum)) => session.can_finalize(accum).map_err(Error::Local)?,
+        _ => false,
+    };
+    if !can_finalize {
+        return Ok(());
+    }
+
+    // Can unwrap here since we checked above that both are present.
+    let session = node.session.take().unwrap();
+    let accum = node.accum.take().unwrap();
+
+    match session.finalize_round(rng, accum)? {
+        FinalizeOutcome::Success { result, .. } => {
+            node.result = Some(result);
+        }
+        FinalizeOutcome::AnotherRound {
+            session,
+            cached_messages,
+        } => {
+            let accum = start_round(rng, &session, cached_messages, queue)?;
+            node.start(session, accum);
+        }
+    }
+
+    Ok(())
+}
+
+fn deliver<Res, Sig, Signer, Verifier>(
+    rng: &mut impl CryptoRngCore,
+    node: &mut Node<Res, Sig, Signer, Verifier>,
+    from: &Verifier,
+    message: MessageBundle<Sig>,
+) -> Result<(), Error<Res, Verifier>>
+where
+    Res: ProtocolResult,
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
+    Verifier: PartyId + PrehashVerifier<Sig>,
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
+{
+    let (session, accum) = match (&node.session, &mut node.accum) {
+        (Some(session), Some(accum)) => (session, accum),
+        _ => {
+            return Err(Error::Local(LocalError(format!(
+                "Received a message from {from:?} for a session that has already finished"
+            ))))
+        }
+    };
+
+    let preprocessed = session.preprocess_message(accum, from, message)?;
+    if let Some(preprocessed) = preprocessed {
+        let processed = session.process_message(rng, preprocessed)?;
+        accum
+            .add_processed_message(processed)
+            .map_err(Error::Local)?
+            .map_err(Error::Remote)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+    use alloc::vec::Vec;
+
+    use k256::ecdsa::{signature::hazmat::PrehashVerifier, Signature, SigningKey, VerifyingKey};
+    use rand_chacha::ChaCha8Rng;
+    use rand_core::SeedableRng;
+    use secrecy::ExposeSecret;
+
+    use super::InProcessNetwork;
+    use crate::{
+        make_interactive_signing_session, make_key_gen_session, make_key_gen_session_with_primes,
+        make_key_refresh_session, KeyShare, PaillierPrimes, SessionId, TestParams,
+    };
+
+    fn key_gen(network: InProcessNetwork, rng: &mut ChaCha8Rng) {
+        let signers = (0..3).map(|_| SigningKey::random(rng)).collect::<Vec<_>>();
+        let verifiers = signers
+            .iter()
+            .map(|signer| *signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+
+        let session_id = SessionId::from_seed(b"key gen");
+        let sessions = signers
+            .iter()
+            .map(|signer| {
+                make_key_gen_session::<TestParams, Signature, SigningKey, VerifyingKey>(
+                    rng,
+                    session_id,
+                    signer.clone(),
+                    &verifiers,
+                )
+                .unwrap()
+            })
+            .collect();
+        let results = network.run(rng, sessions).unwrap();
+
+        assert_eq!(results.len(), 3);
+        let vkey = results[0].0.verifying_key();
+        for ((key_share, aux_info), signer) in results.iter().zip(signers.iter()) {
+            assert_eq!(key_share.verifying_key(), vkey);
+            assert_eq!(key_share.owner(), signer.verifying_key());
+            assert_eq!(aux_info.owner(), signer.verifying_key());
+        }
+    }
+
+    #[test]
+    fn key_gen_shuffled() {
+        key_gen(InProcessNetwork::new(), &mut ChaCha8Rng::from_entropy());
+    }
+
+    #[test]
+    fn key_gen_deterministic() {
+        key_gen(
+            InProcessNetwork::new().deterministic(),
+            &mut ChaCha8Rng::seed_from_u64(123),
+        );
+    }
+
+    /// Where the state digests of a fixed-seed KeyGen run are pinned:
+    /// one line per session, with the hex-encoded digests of its rounds separated by spaces.
+    const KEY_GEN_STATE_DIGESTS: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/test_vectors/key_gen_state_digests.txt"
+    );
+
+    #[test]
+    fn key_gen_state_digests_are_pinned() {
+        let mut rng = ChaCha8Rng::seed_from_u64(123);
+        let signers = (0..3)
+            .map(|_| SigningKey::random(&mut rng))
+            .collect::<Vec<_>>();
+        let verifiers = signers
+            .iter()
+            .map(|signer| *signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+
+        let session_id = SessionId::from_seed(b"key gen");
+        let sessions = signers
+            .iter()
+            .map(|signer| {
+                make_key_gen_session::<TestParams, Signature, SigningKey, VerifyingKey>(
+                    &mut rng,
+                    session_id,
+                    signer.clone(),
+                    &verifiers,
+                )
+                .unwrap()
+            })
+            .collect();
+        let (_results, digests) = InProcessNetwork::new()
+            .deterministic()
+            .run_with_state_digests(&mut rng, sessions)
+            .unwrap();
+
+        let transcript = digests
+            .iter()
+            .map(|session_digests| {
+                session_digests
+                    .iter()
+                    .map(|digest| {
+                        hex::encode(digest.expect("All the KeyGen rounds support digests"))
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+            + "\n";
+
+        // The digests must only change together with the protocol itself;
+        // in that case the file is regenerated by running the test with `UPDATE_TEST_VECTORS` set.
+        if std::env::var_os("UPDATE_TEST_VECTORS").is_some() {
+            std::fs::write(KEY_GEN_STATE_DIGESTS, &transcript).unwrap();
+        }
+        let expected = std::fs::read_to_string(KEY_GEN_STATE_DIGESTS).unwrap_or_else(|err| {
+            panic!(
+                "Cannot read {KEY_GEN_STATE_DIGESTS} ({err}), \
+                run the test with `UPDATE_TEST_VECTORS=1` to generate it"
+            )
+        });
+        assert_eq!(transcript, expected);
+    }
+
+    #[test]
+    fn key_gen_with_persisted_primes() {
+        let mut rng = ChaCha8Rng::from_entropy();
+        let signers = (0..3)
+            .map(|_| SigningKey::random(&mut rng))
+            .collect::<Vec<_>>();
+        let verifiers = signers
+            .iter()
+            .map(|signer| *signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+
+        // The primes are generated and persisted before the ceremony starts...
+        let primes = signers
+            .iter()
+            .map(|_| PaillierPrimes::<TestParams>::random(&mut rng))
+            .collect::<Vec<_>>();
+        let config = bincode::config::standard();
+        let checkpoints = primes
+            .iter()
+            .map(|primes| bincode::serde::encode_to_vec(primes, config).unwrap())
+            .collect::<Vec<_>>();
+
+        // ...and after an interruption the ceremony is restarted using the persisted primes.
+        let session_id = SessionId::from_seed(b"key gen");
+        let sessions = signers
+            .iter()
+            .zip(checkpoints.iter())
+            .map(|(signer, checkpoint)| {
+                let primes: PaillierPrimes<TestParams> =
+                    bincode::serde::decode_borrowed_from_slice(checkpoint, config).unwrap();
+                make_key_gen_session_with_primes::<TestParams, Signature, SigningKey, VerifyingKey>(
+                    &mut rng,
+                    session_id,
+                    signer.clone(),
+                    &verifiers,
+                    primes,
+                )
+                .unwrap()
+            })
+            .collect();
+        let results = InProcessNetwork::new().run(&mut rng, sessions).unwrap();
+
+        for ((_key_share, aux_info), primes) in results.iter().zip(primes.iter()) {
+            assert_eq!(aux_info.secret_aux.paillier_sk, primes.0);
+        }
+    }
+
+    #[test]
+    fn sign_with_refreshed_key_shares() {
+        let mut rng = ChaCha8Rng::from_entropy();
+        let signers = (0..3)
+            .map(|_| SigningKey::random(&mut rng))
+            .collect::<Vec<_>>();
+        let verifiers = signers
+            .iter()
+            .map(|signer| *signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+        let network = InProcessNetwork::new();
+
+        let session_id = SessionId::from_seed(b"key gen");
+        let sessions = signers
+            .iter()
+            .map(|signer| {
+                make_key_gen_session::<TestParams, Signature, SigningKey, VerifyingKey>(
+                    &mut rng,
+                    session_id,
+                    signer.clone(),
+                    &verifiers,
+                )
+                .unwrap()
+            })
+            .collect();
+        let key_gen_results = network.run(&mut rng, sessions).unwrap();
+        let vkey = key_gen_results[0].0.verifying_key();
+
+        let session_id = SessionId::from_seed(b"key refresh");
+        let sessions = signers
+            .iter()
+            .map(|signer| {
+                make_key_refresh_session::<TestParams, Signature, SigningKey, VerifyingKey>(
+                    &mut rng,
+                    session_id,
+                    signer.clone(),
+                    &verifiers,
+                )
+                .unwrap()
+            })
+            .collect();
+        let refresh_results = network.run(&mut rng, sessions).unwrap();
+
+        // The refresh produces new auxiliary information replacing the old one.
+        let refreshed = key_gen_results
+            .into_iter()
+            .zip(refresh_results)
+            .map(|((key_share, _old_aux_info), (change, aux_info))| {
+                let new_key_share = KeyShare::try_from((key_share.clone(), change)).unwrap();
+                assert_eq!(new_key_share.verifying_key(), vkey);
+                assert_ne!(
+                    new_key_share.secret_share.expose_secret(),
+                    key_share.secret_share.expose_secret()
+                );
+                (new_key_share, aux_info)
+            })
+            .collect::<Vec<_>>();
+
+        let message = [1u8; 32];
+        let session_id = SessionId::from_seed(b"signing");
+        let sessions = signers
+            .iter()
+            .zip(refreshed.iter())
+            .map(|(signer, (key_share, aux_info))| {
+                make_interactive_signing_session::<_, Signature, _, _>(
+                    &mut rng,
+                    session_id,
+                    signer.clone(),
+                    &verifiers,
+                    key_share,
+                    aux_info,
+                    &message,
+                )
+                .unwrap()
+            })
+            .collect();
+        let signatures = network.run(&mut rng, sessions).unwrap();
+
+        for signature in signatures {
+            let (signature, _recovery_id) = signature.to_backend();
+            vkey.verify_prehash(&message, &signature).unwrap();
+        }
+    }
+}
@@ -113,19 +113,42 @@ impl<Sig> MessageBundle<Sig> {
         self.is_echo
     }
 
-    pub(crate) fn verify(
+    /// Applies `f` to each of the bundled messages.
+    #[cfg(test)]
+    pub(crate) fn map_messages(self, f: impl Fn(SignedMessage<Sig>) -> SignedMessage<Sig>) -> Self {
+        let bundle = match self.bundle {
+            MessageBundleEnum::Broadcast(msg) => MessageBundleEnum::Broadcast(f(msg)),
+            MessageBundleEnum::Direct(msg) => MessageBundleEnum::Direct(f(msg)),
+            MessageBundleEnum::Echo(msg) => MessageBundleEnum::Echo(f(msg)),
+            MessageBundleEnum::Both { broadcast, direct } => MessageBundleEnum::Both {
+                broadcast: f(broadcast),
+                direct: f(direct),
+            },
+        };
+        Self { bundle, ..self }
+    }
+
+    /// Verifies the signatures of the bundled messages.
+    ///
+    /// `my_id` is the identity of the receiving party, to which the direct messages are bound.
+    pub(crate) fn verify<I: Serialize>(
         self,
         verifier: &impl PrehashVerifier<Sig>,
+        my_id: &I,
     ) -> Result<VerifiedMessageBundle<Sig>, String> {
         let verified_messages = match self.bundle {
             MessageBundleEnum::Broadcast(msg) => {
-                MessageBundleEnum::Broadcast(msg.verify(verifier)?)
+                MessageBundleEnum::Broadcast(msg.verify::<I>(verifier, None)?)
+            }
+            MessageBundleEnum::Direct(msg) => {
+                MessageBundleEnum::Direct(msg.verify(verifier, Some(my_id))?)
+            }
+            MessageBundleEnum::Echo(msg) => {
+                MessageBundleEnum::Echo(msg.verify::<I>(verifier, None)?)
             }
-            MessageBundleEnum::Direct(msg) => MessageBundleEnum::Direct(msg.verify(verifier)?),
-            MessageBundleEnum::Echo(msg) => MessageBundleEnum::Echo(msg.verify(verifier)?),
             MessageBundleEnum::Both { broadcast, direct } => MessageBundleEnum::Both {
-                broadcast: broadcast.verify(verifier)?,
-                direct: direct.verify(verifier)?,
+                broadcast: broadcast.verify::<I>(verifier, None)?,
+                direct: direct.verify(verifier, Some(my_id))?,
             },
         };
         Ok(VerifiedMessageBundle(verified_messages))
@@ -170,4 +193,50 @@ impl<Sig> VerifiedMessageBundle<Sig> {
     pub fn is_echo(&self) -> bool {
         matches!(&self.0, MessageBundleEnum::Echo(_))
     }
+
+    /// Checks that all the bundled messages have timestamps within `max_skew` seconds of `now`.
+    ///
+    /// Only done after the signatures are verified, so that the sender could not be blamed
+    /// for a timestamp altered in transit.
+    pub fn is_fresh(&self, now: u64, max_skew: u64) -> bool {
+        match &self.0 {
+            MessageBundleEnum::Broadcast(msg)
+            | MessageBundleEnum::Direct(msg)
+            | MessageBundleEnum::Echo(msg) => msg.as_unverified().is_fresh(now, max_skew),
+            MessageBundleEnum::Both { broadcast, direct } => {
+                broadcast.as_unverified().is_fresh(now, max_skew)
+                    && direct.as_unverified().is_fresh(now, max_skew)
+            }
+        }
+    }
+
+    pub fn round(&self) -> u8 {
+        match &self.0 {
+            MessageBundleEnum::Broadcast(msg)
+            | MessageBundleEnum::Direct(msg)
+            | MessageBundleEnum::Echo(msg) => msg.as_unverified().round(),
+            MessageBundleEnum::Both { broadcast, .. } => broadcast.as_unverified().round(),
+        }
+    }
+
+    /// Returns the original signed messages, e.g. to pass them to a third party.
+    pub fn to_unverified(&self) -> Result<MessageBundle<Sig>, LocalError>
+    where
+        Sig: Clone,
+    {
+        let unverified = match &self.0 {
+            MessageBundleEnum::Broadcast(msg) => {
+                MessageBundleEnum::Broadcast(msg.clone().into_unverified())
+            }
+            MessageBundleEnum::Direct(msg) => {
+                MessageBundleEnum::Direct(msg.clone().into_unverified())
+            }
+            MessageBundleEnum::Echo(msg) => MessageBundleEnum::Echo(msg.clone().into_unverified()),
+            MessageBundleEnum::Both { broadcast, direct } => MessageBundleEnum::Both {
+                broadcast: broadcast.clone().into_unverified(),
+                direct: direct.clone().into_unverified(),
+            },
+        };
+        MessageBundle::try_from(unverified)
+    }
 }
@@ -0,0 +1,65 @@
+use core::fmt::Debug;
+
+use serde::{Deserialize, Serialize};
+
+/// The requirements for a type identifying a party in a session.
+///
+/// The sessions use the verifiers of the message signatures as party identifiers,
+/// so any verifier satisfying these bounds (e.g. [`k256::ecdsa::VerifyingKey`])
+/// can be used to key the party set directly, without a separate identifier type.
+pub trait PartyId: Debug + Clone + Ord + Serialize + for<'de> Deserialize<'de> {}
+
+impl<T> PartyId for T where T: Debug + Clone + Ord + Serialize + for<'de> Deserialize<'de> {}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+    use alloc::vec::Vec;
+
+    use impls::impls;
+    use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+    use rand_core::OsRng;
+
+    use super::PartyId;
+    use crate::sessions::run_protocol;
+    use crate::{make_key_gen_session, SessionId, TestParams};
+
+    fn owners<Id: PartyId>(ids: impl IntoIterator<Item = Id>) -> BTreeSet<Id> {
+        ids.into_iter().collect()
+    }
+
+    #[test]
+    fn verifying_key_is_party_id() {
+        assert!(impls!(VerifyingKey: PartyId));
+    }
+
+    #[test]
+    fn session_keyed_by_verifying_keys() {
+        let signers = (0..3)
+            .map(|_| SigningKey::random(&mut OsRng))
+            .collect::<Vec<_>>();
+        let ids = owners(signers.iter().map(|signer| *signer.verifying_key()));
+
+        let session_id = SessionId::from_seed(b"party ids");
+        let sessions = signers
+            .iter()
+            .map(|signer| {
+                make_key_gen_session::<TestParams, Signature, SigningKey, VerifyingKey>(
+                    &mut OsRng,
+                    session_id,
+                    signer.clone(),
+                    &ids,
+                )
+                .unwrap()
+            })
+            .collect();
+        let results = run_protocol(&mut OsRng, sessions).unwrap();
+
+        let result_owners = owners(results.iter().map(|(key_share, _)| *key_share.owner()));
+        assert_eq!(result_owners, ids);
+        for ((key_share, aux_info), signer) in results.iter().zip(signers.iter()) {
+            assert_eq!(key_share.owner(), signer.verifying_key());
+            assert_eq!(aux_info.owner(), signer.verifying_key());
+        }
+    }
+}
@@ -0,0 +1,620 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use bincode::de::read::Reader;
+use bincode::error::DecodeError;
+use serde::{Deserialize, Serialize};
+
+use super::error::LocalError;
+use super::message_bundle::MessageBundle;
+use super::signed_message::SessionId;
+use super::wire_format::WireFormat;
+
+/// The length of the header prepended to each fragment:
+/// the session ID (32 bytes), the round (1 byte), the echo flag (1 byte),
+/// the total length of the message (4 bytes, big-endian),
+/// and the offset of the fragment in the message (4 bytes, big-endian).
+pub const FRAGMENT_HEADER_LEN: usize = 42;
+
+/// The maximum length of a message decoded by [`read_message`].
+pub const MAX_STREAMED_MESSAGE_LEN: usize = 1 << 24;
+
+const SESSION_ID_LEN: usize = 32;
+
+/// Errors that can occur during the reassembly of a message.
+#[derive(Debug, Clone)]
+pub enum ReassemblyError {
+    /// The fragment is too short to contain a header, or the header is malformed.
+    InvalidHeader,
+    /// The declared total length of the message exceeds the limit of the reassembler.
+    MessageTooLarge,
+    /// The sender already has the maximum number of incomplete messages in the reassembler.
+    TooManyPendingMessages,
+    /// The fragment does not fit into the declared message, overlaps a previously received one,
+    /// or declares a different total length than the previous fragments.
+    InvalidFragment,
+    /// The reassembled message cannot be deserialized.
+    CannotDeserialize(String),
+    /// The stream passed to [`read_message`] returned an error.
+    StreamError(String),
+    /// The session ID, the round or the echo flag of the reassembled message
+    /// differ from the ones in the fragment headers.
+    MismatchedHeader,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct FragmentKey {
+    session_id: SessionId,
+    round: u8,
+    is_echo: bool,
+}
+
+struct PartialMessage {
+    total_len: usize,
+    received_len: usize,
+    fragments: BTreeMap<usize, Box<[u8]>>,
+}
+
+/// Splits a message into fragments of at most `max_fragment_len` bytes (including the header)
+/// to be sent over a transport with a limited frame size.
+///
+/// The message is serialized in `wire_format`, which must match the one
+/// the receiving side uses in [`MessageReassembler::new`].
+/// The fragments can be delivered in any order;
+/// the receiving side puts them together with [`MessageReassembler`].
+pub fn fragment_message<Sig: Serialize>(
+    message: &MessageBundle<Sig>,
+    wire_format: WireFormat,
+    max_fragment_len: usize,
+) -> Result<Vec<Box<[u8]>>, LocalError> {
+    if max_fragment_len <= FRAGMENT_HEADER_LEN {
+        return Err(LocalError(format!(
+            "The fragment length must be greater than the header length ({FRAGMENT_HEADER_LEN})"
+        )));
+    }
+
+    let serialized = wire_format
+        .serialize(message)
+        .map_err(|err| LocalError(format!("Failed to serialize the message: {err}")))?;
+    let total_len = u32::try_from(serialized.len())
+        .map_err(|_| LocalError("The message is too large to be fragmented".into()))?;
+
+    let chunk_len = max_fragment_len - FRAGMENT_HEADER_LEN;
+    let fragments = serialized
+        .chunks(chunk_len)
+        .enumerate()
+        .map(|(idx, chunk)| {
+            let offset = (idx * chunk_len) as u32;
+            let mut fragment = Vec::with_capacity(FRAGMENT_HEADER_LEN + chunk.len());
+            fragment.extend(message.session_id().as_ref());
+            fragment.push(message.round());
+            fragment.push(message.is_echo().into());
+            fragment.extend(total_len.to_be_bytes());
+            fragment.extend(offset.to_be_bytes());
+            fragment.extend(chunk);
+            fragment.into_boxed_slice()
+        })
+        .collect();
+    Ok(fragments)
+}
+
+/// Buffers the fragments created by [`fragment_message`]
+/// until a complete message from a party can be passed to the session.
+///
+/// The fragments are grouped by the sender, the session ID, the round, and the echo flag,
+/// so the fragments of messages from different rounds or sessions can be interleaved.
+pub struct MessageReassembler<Sig, Verifier> {
+    wire_format: WireFormat,
+    max_message_len: usize,
+    max_pending_messages: usize,
+    pending: BTreeMap<(Verifier, FragmentKey), PartialMessage>,
+    phantom: PhantomData<Sig>,
+}
+
+impl<Sig, Verifier> MessageReassembler<Sig, Verifier>
+where
+    Sig: for<'de> Deserialize<'de>,
+    Verifier: Clone + Ord,
+{
+    /// Creates a new reassembler for the messages serialized in `wire_format`
+    /// that will reject messages with the total length greater than `max_message_len`,
+    /// and will keep at most `max_pending_messages` incomplete messages from each sender.
+    ///
+    /// The memory for a message is only released when it is complete
+    /// (or when its session is cleared with [`Self::clear_session`]),
+    /// so a misbehaving party can make this node hold up to
+    /// `max_pending_messages * max_message_len` bytes of fragment data.
+    /// The bound is per sender, so the fragments must only be passed here
+    /// if they come from the parties of a known session.
+    pub fn new(
+        wire_format: WireFormat,
+        max_message_len: usize,
+        max_pending_messages: usize,
+    ) -> Self {
+        Self {
+            wire_format,
+            max_message_len,
+            max_pending_messages,
+            pending: BTreeMap::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Adds a fragment received from `from`.
+    ///
+    /// Returns the message if this fragment completes it, and `None` otherwise.
+    pub fn add_fragment(
+        &mut self,
+        from: &Verifier,
+        fragment: &[u8],
+    ) -> Result<Option<MessageBundle<Sig>>, ReassemblyError> {
+        if fragment.len() <= FRAGMENT_HEADER_LEN {
+            return Err(ReassemblyError::InvalidHeader);
+        }
+        let (header, data) = fragment.split_at(FRAGMENT_HEADER_LEN);
+
+        let session_id = SessionId::try_from_bytes(&header[..SESSION_ID_LEN])
+            .ok_or(ReassemblyError::InvalidHeader)?;
+        let round = header[SESSION_ID_LEN];
+        let is_echo = match header[SESSION_ID_LEN + 1] {
+            0 => false,
+            1 => true,
+            _ => return Err(ReassemblyError::InvalidHeader),
+        };
+        let total_len = read_u32(&header[SESSION_ID_LEN + 2..SESSION_ID_LEN + 6]);
+        let offset = read_u32(&header[SESSION_ID_LEN + 6..SESSION_ID_LEN + 10]);
+
+        if total_len > self.max_message_len {
+            return Err(ReassemblyError::MessageTooLarge);
+        }
+        if offset
+            .checked_add(data.len())
+            .is_none_or(|end| end > total_len)
+        {
+            return Err(ReassemblyError::InvalidFragment);
+        }
+
+        let key = FragmentKey {
+            session_id,
+            round,
+            is_echo,
+        };
+        if !self.pending.contains_key(&(from.clone(), key)) {
+            let pending_messages = self
+                .pending
+                .keys()
+                .filter(|(sender, _key)| sender == from)
+                .count();
+            if pending_messages >= self.max_pending_messages {
+                return Err(ReassemblyError::TooManyPendingMessages);
+            }
+        }
+        let partial = self
+            .pending
+            .entry((from.clone(), key))
+            .or_insert_with(|| PartialMessage {
+                total_len,
+                received_len: 0,
+                fragments: BTreeMap::new(),
+            });
+
+        if partial.total_len != total_len || overlaps(&partial.fragments, offset, data.len()) {
+            return Err(ReassemblyError::InvalidFragment);
+        }
+        partial.fragments.insert(offset, data.into());
+        partial.received_len += data.len();
+
+        if partial.received_len < partial.total_len {
+            return Ok(None);
+        }
+
+        let partial = self
+            .pending
+            .remove(&(from.clone(), key))
+            .expect("the entry was just accessed");
+        let serialized = partial.fragments.into_values().collect::<Vec<_>>().concat();
+
+        let message: MessageBundle<Sig> = self
+            .wire_format
+            .deserialize(&serialized)
+            .map_err(ReassemblyError::CannotDeserialize)?;
+        if message.session_id() != &session_id
+            || message.round() != round
+            || message.is_echo() != is_echo
+        {
+            return Err(ReassemblyError::MismatchedHeader);
+        }
+
+        Ok(Some(message))
+    }
+
+    /// Drops the incomplete messages from the given session
+    /// (e.g. when the session is finished or aborted).
+    pub fn clear_session(&mut self, session_id: &SessionId) {
+        self.pending
+            .retain(|(_from, key), _partial| &key.session_id != session_id);
+    }
+}
+
+/// Deserializes a message directly from a byte stream,
+/// without buffering it in full first.
+///
+/// `read` must fill the whole given buffer with the next bytes of the stream,
+/// or return an error if it cannot (e.g. if the stream ended).
+/// The message is expected in the same format that [`fragment_message`] uses
+/// (that is, a [`MessageBundle`] serialized in `wire_format`).
+/// The parts of the message are decoded as they are read from the stream,
+/// so a malformed message is rejected as soon as the offending part arrives.
+/// Messages longer than [`MAX_STREAMED_MESSAGE_LEN`] are rejected.
+pub fn read_message<Sig>(
+    wire_format: WireFormat,
+    read: &mut dyn FnMut(&mut [u8]) -> Result<(), String>,
+) -> Result<MessageBundle<Sig>, ReassemblyError>
+where
+    Sig: for<'de> Deserialize<'de>,
+{
+    let mut failure = None;
+    let reader = StreamReader {
+        read,
+        remaining: MAX_STREAMED_MESSAGE_LEN,
+        failure: &mut failure,
+    };
+
+    let result = match wire_format {
+        WireFormat::Bincode => {
+            let config = bincode::config::standard().with_limit::<MAX_STREAMED_MESSAGE_LEN>();
+            bincode::serde::decode_from_reader(reader, config).map_err(|err| match err {
+                DecodeError::LimitExceeded => ReassemblyError::MessageTooLarge,
+                err => ReassemblyError::CannotDeserialize(err.to_string()),
+            })
+        }
+        #[cfg(feature = "cbor")]
+        WireFormat::Cbor => ciborium::from_reader(reader)
+            .map_err(|err| ReassemblyError::CannotDeserialize(format!("{err:?}"))),
+    };
+
+    match failure {
+        Some(StreamFailure::Read(error)) => Err(ReassemblyError::StreamError(error)),
+        Some(StreamFailure::TooLarge) => Err(ReassemblyError::MessageTooLarge),
+        None => result,
+    }
+}
+
+enum StreamFailure {
+    Read(String),
+    TooLarge,
+}
+
+// Adapts the reading function to the deserializers,
+// keeping the original error and limiting the total length of the message.
+struct StreamReader<'a> {
+    read: &'a mut dyn FnMut(&mut [u8]) -> Result<(), String>,
+    remaining: usize,
+    failure: &'a mut Option<StreamFailure>,
+}
+
+impl StreamReader<'_> {
+    fn fill(&mut self, bytes: &mut [u8]) -> Result<(), &'static str> {
+        self.remaining = match self.remaining.checked_sub(bytes.len()) {
+            Some(remaining) => remaining,
+            None => {
+                *self.failure = Some(StreamFailure::TooLarge);
+                return Err("The message is too large");
+            }
+        };
+        (self.read)(bytes).map_err(|err| {
+            *self.failure = Some(StreamFailure::Read(err));
+            "Failed to read from the stream"
+        })
+    }
+}
+
+impl Reader for StreamReader<'_> {
+    fn read(&mut self, bytes: &mut [u8]) -> Result<(), DecodeError> {
+        self.fill(bytes).map_err(DecodeError::Other)
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl ciborium_io::Read for StreamReader<'_> {
+    type Error = &'static str;
+
+    fn read_exact(&mut self, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        self.fill(bytes)
+    }
+}
+
+fn read_u32(bytes: &[u8]) -> usize {
+    let mut buf = [0u8; 4];
+    buf.copy_from_slice(bytes);
+    u32::from_be_bytes(buf) as usize
+}
+
+fn overlaps(fragments: &BTreeMap<usize, Box<[u8]>>, offset: usize, len: usize) -> bool {
+    // The closest fragment starting at or before `offset` must end before it,
+    // and the closest fragment starting after `offset` must start after the end of this one.
+    let before = fragments
+        .range(..=offset)
+        .next_back()
+        .is_some_and(|(start, data)| start + data.len() > offset);
+    let after = fragments
+        .range(offset + 1..)
+        .next()
+        .is_some_and(|(start, _data)| *start < offset + len);
+    before || after
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+    use rand_core::OsRng;
+
+    use super::{
+        fragment_message, read_message, MessageReassembler, ReassemblyError, FRAGMENT_HEADER_LEN,
+    };
+    use crate::sessions::{SessionId, WireFormat};
+    use crate::{make_key_gen_session, make_presigning_session, AuxInfo, KeyShare, TestParams};
+
+    #[test]
+    fn fragmented_message_is_reassembled() {
+        let signers = (0..3)
+            .map(|_| SigningKey::random(&mut OsRng))
+            .collect::<Vec<_>>();
+        let ids = signers
+            .iter()
+            .map(|signer| *signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+        let session_id = SessionId::from_seed(b"session");
+
+        let key_shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids);
+
+        let sessions = signers
+            .iter()
+            .map(|signer| {
+                let id = signer.verifying_key();
+                make_presigning_session::<_, Signature, _, _>(
+                    &mut OsRng,
+                    session_id,
+                    signer.clone(),
+                    &ids,
+                    &key_shares[id],
+                    &aux_infos[id],
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let sender = &sessions[0];
+        let receiver = &sessions[1];
+
+        // The presigning messages carry Paillier ciphertexts and ZK proofs,
+        // so they take a lot of fragments with a small frame size.
+        let (message, _artifact) = sender
+            .make_message(&mut OsRng, &receiver.verifier())
+            .unwrap();
+        let mut fragments =
+            fragment_message(&message, WireFormat::Bincode, FRAGMENT_HEADER_LEN + 64).unwrap();
+        assert!(fragments.len() > 10);
+
+        // The fragments may arrive out of order.
+        fragments.reverse();
+        let (last, rest) = fragments.split_last().unwrap();
+
+        let mut reassembler =
+            MessageReassembler::<Signature, VerifyingKey>::new(WireFormat::Bincode, 1 << 20, 1);
+        for fragment in rest {
+            assert!(reassembler
+                .add_fragment(&sender.verifier(), fragment)
+                .unwrap()
+                .is_none());
+        }
+
+        // A repeated fragment is rejected
+        assert!(matches!(
+            reassembler.add_fragment(&sender.verifier(), &rest[0]),
+            Err(ReassemblyError::InvalidFragment)
+        ));
+
+        let reassembled = reassembler
+            .add_fragment(&sender.verifier(), last)
+            .unwrap()
+            .unwrap();
+
+        // The reassembled message is accepted by the session
+        let mut accum = receiver.make_accumulator();
+        let preprocessed = receiver
+            .preprocess_message(&mut accum, &sender.verifier(), reassembled)
+            .unwrap()
+            .unwrap();
+        let processed = receiver.process_message(&mut OsRng, preprocessed).unwrap();
+        accum.add_processed_message(processed).unwrap().unwrap();
+        assert!(!receiver
+            .missing_messages(&accum)
+            .unwrap()
+            .contains(&sender.verifier()));
+
+        // Messages exceeding the limit are rejected right away
+        let mut reassembler =
+            MessageReassembler::<Signature, VerifyingKey>::new(WireFormat::Bincode, 64, 1);
+        assert!(matches!(
+            reassembler.add_fragment(&sender.verifier(), &fragments[0]),
+            Err(ReassemblyError::MessageTooLarge)
+        ));
+    }
+
+    #[test]
+    fn pending_messages_are_limited_per_sender() {
+        let signers = (0..2)
+            .map(|_| SigningKey::random(&mut OsRng))
+            .collect::<Vec<_>>();
+        let ids = signers
+            .iter()
+            .map(|signer| *signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+
+        // The messages from the same round of several sessions
+        let messages = [b"session 1", b"session 2", b"session 3"].map(|seed| {
+            let session = make_key_gen_session::<TestParams, Signature, _, _>(
+                &mut OsRng,
+                SessionId::from_seed(seed),
+                signers[0].clone(),
+                &ids,
+            )
+            .unwrap();
+            let (message, _artifact) = session
+                .make_message(&mut OsRng, signers[1].verifying_key())
+                .unwrap();
+            fragment_message(&message, WireFormat::Bincode, FRAGMENT_HEADER_LEN + 16).unwrap()
+        });
+        let sender = signers[0].verifying_key();
+        let other_sender = signers[1].verifying_key();
+
+        let mut reassembler =
+            MessageReassembler::<Signature, VerifyingKey>::new(WireFormat::Bincode, 1 << 20, 2);
+        for fragments in &messages[..2] {
+            assert!(reassembler
+                .add_fragment(sender, &fragments[0])
+                .unwrap()
+                .is_none());
+        }
+
+        // A third incomplete message from the same sender is not accepted,
+        // while the existing ones can still be completed.
+        assert!(matches!(
+            reassembler.add_fragment(sender, &messages[2][0]),
+            Err(ReassemblyError::TooManyPendingMessages)
+        ));
+        assert!(reassembler
+            .add_fragment(sender, &messages[0][1])
+            .unwrap()
+            .is_none());
+
+        // Other senders are not affected.
+        assert!(reassembler
+            .add_fragment(other_sender, &messages[2][0])
+            .unwrap()
+            .is_none());
+
+        // Once a session is cleared, its slot is freed.
+        reassembler.clear_session(&SessionId::from_seed(b"session 1"));
+        assert!(reassembler
+            .add_fragment(sender, &messages[2][0])
+            .unwrap()
+            .is_none());
+    }
+
+    /// A stream delivering the data in separate chunks.
+    struct ChunkedReader<'a> {
+        chunks: Vec<&'a [u8]>,
+    }
+
+    impl ChunkedReader<'_> {
+        fn read(&mut self, bytes: &mut [u8]) -> Result<(), String> {
+            let mut filled = 0;
+            while filled < bytes.len() {
+                let chunk = self
+                    .chunks
+                    .first_mut()
+                    .ok_or_else(|| String::from("Unexpected end of stream"))?;
+                let len = core::cmp::min(chunk.len(), bytes.len() - filled);
+                bytes[filled..filled + len].copy_from_slice(&chunk[..len]);
+                *chunk = &chunk[len..];
+                if chunk.is_empty() {
+                    self.chunks.remove(0);
+                }
+                filled += len;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn message_is_read_from_stream() {
+        let signers = (0..2)
+            .map(|_| SigningKey::random(&mut OsRng))
+            .collect::<Vec<_>>();
+        let ids = signers
+            .iter()
+            .map(|signer| *signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+        let session_id = SessionId::from_seed(b"session");
+
+        let key_shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids);
+
+        let sessions = signers
+            .iter()
+            .map(|signer| {
+                let id = signer.verifying_key();
+                make_presigning_session::<_, Signature, _, _>(
+                    &mut OsRng,
+                    session_id,
+                    signer.clone(),
+                    &ids,
+                    &key_shares[id],
+                    &aux_infos[id],
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let sender = &sessions[0];
+        let receiver = &sessions[1];
+
+        let (message, _artifact) = sender
+            .make_message(&mut OsRng, &receiver.verifier())
+            .unwrap();
+        let serialized =
+            bincode::serde::encode_to_vec(&message, bincode::config::standard()).unwrap();
+
+        // The message arrives in two chunks split in an arbitrary place.
+        let (first, second) = serialized.split_at(serialized.len() / 3);
+        let mut reader = ChunkedReader {
+            chunks: [first, second].into(),
+        };
+        let streamed =
+            read_message::<Signature>(WireFormat::Bincode, &mut |bytes| reader.read(bytes))
+                .unwrap();
+
+        let mut accum = receiver.make_accumulator();
+        let preprocessed = receiver
+            .preprocess_message(&mut accum, &sender.verifier(), streamed)
+            .unwrap()
+            .unwrap();
+        let processed = receiver.process_message(&mut OsRng, preprocessed).unwrap();
+        accum.add_processed_message(processed).unwrap().unwrap();
+        assert!(receiver.missing_messages(&accum).unwrap().is_empty());
+
+        // A truncated stream is an error.
+        let mut reader = ChunkedReader {
+            chunks: [first].into(),
+        };
+        assert!(matches!(
+            read_message::<Signature>(WireFormat::Bincode, &mut |bytes| reader.read(bytes)),
+            Err(ReassemblyError::StreamError(_))
+        ));
+
+        // The CBOR encoding can be streamed as well.
+        #[cfg(feature = "cbor")]
+        {
+            let serialized = WireFormat::Cbor.serialize(&message).unwrap();
+            let (first, second) = serialized.split_at(serialized.len() / 3);
+            let mut reader = ChunkedReader {
+                chunks: [first, second].into(),
+            };
+            let streamed =
+                read_message::<Signature>(WireFormat::Cbor, &mut |bytes| reader.read(bytes))
+                    .unwrap();
+            assert_eq!(WireFormat::Cbor.serialize(&streamed).unwrap(), serialized);
+        }
+    }
+}
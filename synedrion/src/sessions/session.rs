@@ -11,19 +11,70 @@ use signature::{
     Keypair,
 };
 
+use super::audit::{NonParticipation, RoundEvidence};
+use super::budget::{Budgeted, SessionBudget};
+use super::clock::Clock;
 use super::echo::{EchoAccum, EchoRound};
 use super::error::{Error, LocalError, ProvableError, RemoteError, RemoteErrorEnum};
 use super::message_bundle::{MessageBundle, MessageBundleEnum, VerifiedMessageBundle};
+use super::party_id::PartyId;
 use super::signed_message::{MessageType, SessionId, SignedMessage, VerifiedMessage};
 use super::type_erased::{
     self, AccumAddError, DynArtifact, DynFinalizable, DynPayload, DynRoundAccum, ReceiveError,
 };
+use super::wire_format::WireFormat;
 use crate::rounds::{self, FirstRound, ProtocolResult, Round};
+#[cfg(feature = "parallel")]
+use crate::tools::parallel;
 
 struct Context<Signer, Verifier> {
     signer: Signer,
     my_id: Verifier,
+    verifiers: BTreeSet<Verifier>,
     session_id: SessionId,
+    include_self_in_broadcast: bool,
+    freshness: Option<Freshness>,
+    compress: bool,
+    wire_format: WireFormat,
+}
+
+struct Freshness {
+    clock: Box<dyn Clock + Send + Sync>,
+    max_skew: u64,
+}
+
+impl<Signer, Verifier> Context<Signer, Verifier>
+where
+    Verifier: Serialize,
+{
+    fn sign_message<Sig>(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        round: u8,
+        message_type: MessageType,
+        recipient: Option<&Verifier>,
+        payload: &[u8],
+    ) -> Result<SignedMessage<Sig>, LocalError>
+    where
+        Signer: RandomizedPrehashSigner<Sig>,
+    {
+        let timestamp = self
+            .freshness
+            .as_ref()
+            .map(|freshness| freshness.clock.now());
+        Ok(VerifiedMessage::new(
+            rng,
+            &self.signer,
+            &self.session_id,
+            round,
+            message_type,
+            recipient,
+            timestamp,
+            payload,
+        )?
+        .into_unverified()
+        .with_compression(self.compress))
+    }
 }
 
 enum SessionType<Verifier, Res, Sig> {
@@ -40,7 +91,13 @@ enum SessionType<Verifier, Res, Sig> {
 /// The session state where it is ready to send messages.
 pub struct Session<Res, Sig, Signer, Verifier> {
     tp: SessionType<Verifier, Res, Sig>,
-    context: Context<Signer, Verifier>,
+    // Boxed to keep the session (and `FinalizeOutcome::AnotherRound` holding it) small.
+    context: Box<Context<Signer, Verifier>>,
+    deadline: Option<u64>,
+    // The messages for this round cached during the previous one
+    // (identified the same way as in `RoundAccumulator::accepted`).
+    // They have already been accepted, so their copies must be rejected.
+    cached_accepted: BTreeSet<(u8, bool, Verifier)>,
 }
 
 enum MessageFor {
@@ -111,6 +168,10 @@ fn wrap_receive_result<Res: ProtocolResult, Verifier: Clone, T>(
             party: from.clone(),
             error: ProvableError::CannotDeserialize(msg),
         },
+        ReceiveError::InvalidStructure(msg) => Error::Provable {
+            party: from.clone(),
+            error: ProvableError::InvalidStructure(msg),
+        },
         ReceiveError::Protocol(err) => Error::Provable {
             party: from.clone(),
             error: ProvableError::Protocol(err),
@@ -121,7 +182,12 @@ fn wrap_receive_result<Res: ProtocolResult, Verifier: Clone, T>(
 /// Possible outcomes of successfully finalizing a round.
 pub enum FinalizeOutcome<Res: ProtocolResult, Sig, Signer, Verifier> {
     /// The protocol result is available.
-    Success(Res::Success),
+    Success {
+        /// The result of the protocol.
+        result: Res::Success,
+        /// The information about the parties that contributed to the result.
+        meta: ResultMeta<Verifier>,
+    },
     /// Starting the next round.
     AnotherRound {
         /// The new session object.
@@ -131,11 +197,29 @@ pub enum FinalizeOutcome<Res: ProtocolResult, Sig, Signer, Verifier> {
     },
 }
 
+/// Metadata of a successfully finalized session.
+#[derive(Debug, Clone)]
+pub struct ResultMeta<Verifier> {
+    participants: BTreeSet<Verifier>,
+}
+
+impl<Verifier> ResultMeta<Verifier> {
+    /// Returns the parties that contributed to the result, including this one.
+    ///
+    /// A round cannot be finalized until messages from all the expected parties are received,
+    /// so in the threshold case these are the members of the quorum the session was created with
+    /// (see [`ThresholdKeyShare::to_key_share`](`crate::ThresholdKeyShare::to_key_share`)),
+    /// and the holders of the key outside of it are not listed.
+    pub fn participants(&self) -> &BTreeSet<Verifier> {
+        &self.participants
+    }
+}
+
 impl<Res, Sig, Signer, Verifier> Session<Res, Sig, Signer, Verifier>
 where
     Res: ProtocolResult,
     Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier>,
-    Verifier: Debug + Clone + PrehashVerifier<Sig> + Ord + Serialize + for<'de> Deserialize<'de>,
+    Verifier: PartyId + PrehashVerifier<Sig>,
     Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq,
 {
     pub(crate) fn new<
@@ -151,6 +235,16 @@ where
         inputs: R::Inputs,
     ) -> Result<Self, LocalError> {
         let my_id = signer.verifying_key();
+
+        // The round is created for the verifiers without this party,
+        // so if it is not among them, the round would have a different set of parties
+        // than the other sessions.
+        if !verifiers.contains(&my_id) {
+            return Err(LocalError(
+                "The verifiers must include the verifier of this party".into(),
+            ));
+        }
+
         let mut other_parties = verifiers.clone();
         other_parties.remove(&my_id);
         let typed_round = R::new(
@@ -165,30 +259,42 @@ where
         let context = Context {
             my_id,
             signer,
+            verifiers: verifiers.clone(),
             session_id,
+            include_self_in_broadcast: false,
+            freshness: None,
+            compress: false,
+            wire_format: WireFormat::default(),
         };
-        Self::new_internal(rng, context, round)
+        Self::new_internal(rng, Box::new(context), round)
     }
 
     fn new_internal(
         rng: &mut impl CryptoRngCore,
-        context: Context<Signer, Verifier>,
+        context: Box<Context<Signer, Verifier>>,
         round: Box<dyn DynFinalizable<Verifier, Res>>,
     ) -> Result<Self, LocalError> {
-        let broadcast = round.make_broadcast_message(rng)?;
+        // The messages from the parties outside of `verifiers` are rejected,
+        // so a round expecting them would never be finalized.
+        let is_known = |id: &Verifier| id != &context.my_id && context.verifiers.contains(id);
+        if !round.message_destinations().iter().all(is_known)
+            || !round.expecting_messages_from().iter().all(is_known)
+        {
+            return Err(LocalError(
+                "The parties of the round do not match the verifiers of the session".into(),
+            ));
+        }
+
+        let broadcast = round.make_broadcast_message(rng, context.wire_format)?;
 
         let signed_broadcast = if let Some(payload) = broadcast {
-            Some(
-                VerifiedMessage::new(
-                    rng,
-                    &context.signer,
-                    &context.session_id,
-                    round.round_num(),
-                    MessageType::Broadcast,
-                    &payload,
-                )?
-                .into_unverified(),
-            )
+            Some(context.sign_message(
+                rng,
+                round.round_num(),
+                MessageType::Broadcast,
+                None,
+                &payload,
+            )?)
         } else {
             None
         };
@@ -199,6 +305,8 @@ where
                 broadcast: signed_broadcast,
             },
             context,
+            deadline: None,
+            cached_accepted: BTreeSet::new(),
         })
     }
 
@@ -212,6 +320,198 @@ where
         self.context.session_id
     }
 
+    /// Sets whether the broadcast messages should also be addressed to this party itself.
+    ///
+    /// Some transports (e.g. a reliable broadcast via a shared log) deliver every broadcast
+    /// to all the nodes, including the sender. If this is enabled, in rounds that only send
+    /// broadcasts [`Self::message_destinations`] includes this party's own ID,
+    /// and the broadcasts (including the echo broadcasts) received from this party
+    /// are verified and then ignored (since they are already known)
+    /// instead of resulting in an error.
+    /// The setting is kept for all the subsequent rounds of the session.
+    pub fn include_self_in_broadcast(self, include: bool) -> Self {
+        Self {
+            context: Box::new(Context {
+                include_self_in_broadcast: include,
+                ..*self.context
+            }),
+            ..self
+        }
+    }
+
+    /// Enables timestamping of the outgoing messages and the freshness check of the incoming ones.
+    ///
+    /// The messages created by this session are signed together with the current time
+    /// given by `clock`, and the received messages with the timestamp missing or outside of
+    /// `[now - max_skew, now + max_skew]` (in seconds) are rejected
+    /// with [`RemoteErrorEnum::StaleMessage`]. This limits the time window
+    /// in which captured messages can be replayed.
+    /// All the parties of the session must enable this option.
+    /// The setting is kept for all the subsequent rounds of the session.
+    pub fn with_max_skew(
+        self,
+        rng: &mut impl CryptoRngCore,
+        clock: impl Clock + Send + Sync + 'static,
+        max_skew: u64,
+    ) -> Result<Self, LocalError> {
+        let context = Box::new(Context {
+            freshness: Some(Freshness {
+                clock: Box::new(clock),
+                max_skew,
+            }),
+            ..*self.context
+        });
+
+        // The broadcast of the current round has already been signed, so it needs a timestamp.
+        let tp = match self.tp {
+            SessionType::Normal {
+                this_round,
+                broadcast,
+            } => {
+                let broadcast = broadcast
+                    .map(|broadcast| {
+                        context.sign_message(
+                            rng,
+                            this_round.round_num(),
+                            MessageType::Broadcast,
+                            None,
+                            broadcast.payload(),
+                        )
+                    })
+                    .transpose()?;
+                SessionType::Normal {
+                    this_round,
+                    broadcast,
+                }
+            }
+            tp => tp,
+        };
+
+        Ok(Self {
+            tp,
+            context,
+            deadline: self.deadline,
+            cached_accepted: self.cached_accepted,
+        })
+    }
+
+    /// Sets whether the payloads of the outgoing messages should be compressed on the wire.
+    ///
+    /// The messages always carry a header indicating whether the payload is compressed,
+    /// so each party can make this choice independently; receiving compressed messages
+    /// requires the `compression` feature to be enabled on the receiving side.
+    /// The signatures cover the uncompressed payloads and do not depend on this setting.
+    /// The setting is kept for all the subsequent rounds of the session.
+    #[cfg(feature = "compression")]
+    pub fn with_compression(self, compress: bool) -> Self {
+        // The broadcast of the current round has already been created,
+        // but the compression is only applied on serialization, so it does not need re-signing.
+        let tp = match self.tp {
+            SessionType::Normal {
+                this_round,
+                broadcast,
+            } => SessionType::Normal {
+                this_round,
+                broadcast: broadcast.map(|broadcast| broadcast.with_compression(compress)),
+            },
+            tp => tp,
+        };
+
+        Self {
+            tp,
+            context: Box::new(Context {
+                compress,
+                ..*self.context
+            }),
+            ..self
+        }
+    }
+
+    /// Sets the serialization format of the messages created and accepted by this session.
+    ///
+    /// All the parties of the session must use the same format.
+    /// The broadcast of the current round is recreated in the new format.
+    /// The setting is kept for all the subsequent rounds of the session.
+    pub fn with_wire_format(
+        self,
+        rng: &mut impl CryptoRngCore,
+        wire_format: WireFormat,
+    ) -> Result<Self, LocalError> {
+        let context = Box::new(Context {
+            wire_format,
+            ..*self.context
+        });
+
+        let tp = match self.tp {
+            SessionType::Normal {
+                this_round,
+                broadcast: _,
+            } => {
+                let broadcast = this_round
+                    .make_broadcast_message(rng, context.wire_format)?
+                    .map(|payload| {
+                        context.sign_message(
+                            rng,
+                            this_round.round_num(),
+                            MessageType::Broadcast,
+                            None,
+                            &payload,
+                        )
+                    })
+                    .transpose()?;
+                SessionType::Normal {
+                    this_round,
+                    broadcast,
+                }
+            }
+            tp => tp,
+        };
+
+        Ok(Self {
+            tp,
+            context,
+            deadline: self.deadline,
+            cached_accepted: self.cached_accepted,
+        })
+    }
+
+    /// Sets the deadline for receiving the messages of the current round,
+    /// to be checked with [`Self::check_deadline`].
+    ///
+    /// The deadline is in the same units as the values passed to [`Self::check_deadline`]
+    /// (e.g. seconds since the Unix epoch, as given by a [`Clock`]).
+    /// It only applies to the current round; the session returned on finalization
+    /// starts without a deadline.
+    pub fn with_round_deadline(self, deadline: u64) -> Self {
+        Self {
+            deadline: Some(deadline),
+            ..self
+        }
+    }
+
+    /// Returns [`Error::RoundTimedOut`] if the deadline set with [`Self::with_round_deadline`]
+    /// has passed by the time `now` and some messages of the current round are still missing.
+    ///
+    /// Does nothing if no deadline is set.
+    /// The missing parties can then be reported with [`Self::finalize_with_missing`].
+    pub fn check_deadline(
+        &self,
+        accum: &RoundAccumulator<Sig, Verifier>,
+        now: u64,
+    ) -> Result<(), Error<Res, Verifier>> {
+        if self.deadline.is_none_or(|deadline| now <= deadline) {
+            return Ok(());
+        }
+
+        let missing = self.missing_messages(accum).map_err(Error::Local)?;
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let (round, _is_echo_round) = self.current_round();
+        Err(Error::RoundTimedOut { round, missing })
+    }
+
     /// Returns a pair of the current round index and whether it is an echo round.
     pub fn current_round(&self) -> (u8, bool) {
         match &self.tp {
@@ -220,9 +520,23 @@ where
         }
     }
 
+    /// Returns a digest of the public state of the current round.
+    ///
+    /// Given the same randomness, the digests can be compared with the ones produced
+    /// by another implementation to find the round where the executions diverge.
+    /// Returns `None` if the round does not support it.
+    #[cfg(any(test, feature = "state-digest"))]
+    pub fn state_digest(&self) -> Option<[u8; 32]> {
+        let round = match &self.tp {
+            SessionType::Normal { this_round, .. } => this_round,
+            SessionType::Echo { next_round, .. } => next_round,
+        };
+        round.state_digest().map(|digest| digest.0)
+    }
+
     /// Create an accumulator to store message creation and processing results of this round.
     pub fn make_accumulator(&self) -> RoundAccumulator<Sig, Verifier> {
-        RoundAccumulator::new(self.is_echo_round())
+        RoundAccumulator::new(self.is_echo_round(), self.cached_accepted.clone())
     }
 
     /// Returns `true` if the round can be finalized.
@@ -259,6 +573,177 @@ where
         }
     }
 
+    /// Checks that nothing belonging to the current round is left pending in the accumulator.
+    ///
+    /// The accumulator only caches the messages for the next round
+    /// (they are returned on finalization as a part of [`FinalizeOutcome::AnotherRound`]),
+    /// and every message for the current round accepted by [`Self::preprocess_message`]
+    /// is expected to be processed and added to the accumulator before the round is finalized.
+    /// This method returns an error if either of these does not hold,
+    /// for the callers that want to make sure every round is completed in full.
+    pub fn assert_round_drained(
+        &self,
+        accum: &RoundAccumulator<Sig, Verifier>,
+    ) -> Result<(), LocalError> {
+        let (round, is_echo_round) = self.current_round();
+
+        if let Some(from) = accum
+            .cached_messages
+            .values()
+            .find(|cached| {
+                cached.message.round() == round && cached.message.is_echo() == is_echo_round
+            })
+            .map(|cached| &cached.from)
+        {
+            return Err(LocalError(format!(
+                "A message from {from:?} for the current round was cached for the next one"
+            )));
+        }
+
+        let processed = match &self.tp {
+            SessionType::Normal { .. } => accum.processed.received(),
+            SessionType::Echo { .. } => accum
+                .echo_accum
+                .as_ref()
+                .ok_or(LocalError(
+                    "This is an echo round, but the accumulator is in an invalid state".into(),
+                ))?
+                .received(),
+        };
+        if let Some((_round, _is_echo, from)) =
+            accum
+                .accepted
+                .iter()
+                .find(|(accepted_round, accepted_is_echo, from)| {
+                    *accepted_round == round
+                        && *accepted_is_echo == is_echo_round
+                        && !processed.contains(from)
+                })
+        {
+            return Err(LocalError(format!(
+                "A message from {from:?} was accepted for processing, but not added to the accumulator"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Abandons the session, producing a signed statement that the messages
+    /// of the parties in `missing` for the current round have not been received.
+    ///
+    /// The decision to stop waiting (e.g. on a timeout) is up to the caller.
+    /// Every party in `missing` must be among the ones returned by [`Self::missing_messages`].
+    /// The returned statement can be passed on to other parties.
+    pub fn finalize_with_missing(
+        self,
+        rng: &mut impl CryptoRngCore,
+        accum: &RoundAccumulator<Sig, Verifier>,
+        missing: &BTreeSet<Verifier>,
+    ) -> Result<NonParticipation<Sig, Verifier>, LocalError> {
+        if missing.is_empty() {
+            return Err(LocalError(
+                "The set of parties that have not sent their messages is empty".into(),
+            ));
+        }
+
+        let not_received = self.missing_messages(accum)?;
+        if let Some(party) = missing.difference(&not_received).next() {
+            return Err(LocalError(format!(
+                "The message from {party:?} has been received or is not expected"
+            )));
+        }
+
+        let (round, _is_echo_round) = self.current_round();
+        let payload = type_erased::serialize_message(self.context.wire_format, missing)?;
+        let message =
+            self.context
+                .sign_message(rng, round, MessageType::NonParticipation, None, &payload)?;
+
+        Ok(NonParticipation {
+            attester: self.context.my_id,
+            message,
+        })
+    }
+
+    /// Returns the signed messages of the current round sent and received by this party,
+    /// so that they could be checked by a third party (e.g. in case of a dispute).
+    ///
+    /// Includes the messages received from other parties that were added to the accumulator,
+    /// and this party's own broadcast, if the round has one.
+    /// Direct messages sent by this party are not retained by the session,
+    /// so they must be exported by their recipients.
+    pub fn export_round_evidence(
+        &self,
+        accum: &RoundAccumulator<Sig, Verifier>,
+    ) -> Result<RoundEvidence<Sig, Verifier>, LocalError> {
+        let (round, _is_echo_round) = self.current_round();
+        let my_id = &self.context.my_id;
+
+        let mut messages = Vec::new();
+        if let SessionType::Normal {
+            this_round,
+            broadcast: Some(broadcast),
+        } = &self.tp
+        {
+            let bundle = MessageBundle::try_from(MessageBundleEnum::Broadcast(broadcast.clone()))?;
+            for destination in this_round.message_destinations() {
+                messages.push((my_id.clone(), destination.clone(), bundle.clone()));
+            }
+        }
+        for (from, message) in accum.received_messages.iter() {
+            messages.push((from.clone(), my_id.clone(), message.to_unverified()?));
+        }
+
+        Ok(RoundEvidence {
+            session_id: self.context.session_id,
+            round,
+            messages,
+        })
+    }
+
+    /// Returns a snapshot of the session state, intended to be attached to bug reports.
+    ///
+    /// The result only contains public metadata and no secret material.
+    pub fn diagnostic(
+        &self,
+        accum: &RoundAccumulator<Sig, Verifier>,
+    ) -> Result<SessionDiagnostic<Verifier>, LocalError> {
+        let (round, is_echo_round) = self.current_round();
+        let received_from = match &self.tp {
+            SessionType::Normal { .. } => accum.processed.received().clone(),
+            SessionType::Echo { .. } => accum
+                .echo_accum
+                .as_ref()
+                .ok_or(LocalError(
+                    "This is an echo round, but the accumulator is in an invalid state".into(),
+                ))?
+                .received()
+                .clone(),
+        };
+        Ok(SessionDiagnostic {
+            session_id: self.context.session_id,
+            my_id: self.context.my_id.clone(),
+            round,
+            is_echo_round,
+            received_from,
+            missing_from: self.missing_messages(accum)?,
+            cached_messages: accum.cached_messages.len(),
+        })
+    }
+
+    /// Returns a span identifying the current round of this session.
+    #[cfg(feature = "tracing")]
+    fn span(&self) -> tracing::Span {
+        let (round, is_echo_round) = self.current_round();
+        tracing::debug_span!(
+            "round",
+            session_id = ?self.context.session_id,
+            round,
+            is_echo_round,
+            party = ?self.context.my_id,
+        )
+    }
+
     fn is_echo_round(&self) -> bool {
         match &self.tp {
             SessionType::Normal { .. } => false,
@@ -267,10 +752,33 @@ where
     }
 
     /// Returns the party indices to which the messages of this round should be sent.
-    pub fn message_destinations(&self) -> &BTreeSet<Verifier> {
+    ///
+    /// See [`Self::include_self_in_broadcast`] for the cases when this party's own ID is included.
+    pub fn message_destinations(&self) -> BTreeSet<Verifier> {
         match &self.tp {
-            SessionType::Normal { this_round, .. } => this_round.message_destinations(),
-            SessionType::Echo { echo_round, .. } => echo_round.message_destinations(),
+            SessionType::Normal { this_round, .. } => {
+                let mut destinations = this_round.message_destinations().clone();
+                if self.broadcasts_to_self() {
+                    destinations.insert(self.context.my_id.clone());
+                }
+                destinations
+            }
+            SessionType::Echo { echo_round, .. } => echo_round.message_destinations().clone(),
+        }
+    }
+
+    fn broadcasts_to_self(&self) -> bool {
+        match &self.tp {
+            SessionType::Normal {
+                this_round,
+                broadcast,
+            } => {
+                self.context.include_self_in_broadcast
+                    && broadcast.is_some()
+                    && !this_round.sends_direct_messages()
+                    && !this_round.message_destinations().is_empty()
+            }
+            SessionType::Echo { .. } => false,
         }
     }
 
@@ -283,31 +791,60 @@ where
 
     /// Returns the message for the given destination
     /// (must be one of those returned by [`Self::message_destinations`].
+    ///
+    /// This must be called only once per destination per round, since the created messages
+    /// are randomized, and the corresponding artifact is saved in the accumulator.
+    /// If the destination reports that the message was lost
+    /// (see [`Self::is_retransmit_requested`]), the same message should be sent again;
+    /// it will be accepted as long as the destination has not processed it before.
+    ///
+    /// The messages for different destinations are independent, so, if the generic parameters
+    /// are `Send`/`Sync`, they can be created in parallel (each thread using its own RNG),
+    /// with the artifacts added to the accumulator afterwards.
+    /// This is where the per-recipient proofs (e.g. in Round 2 of Presigning) are generated;
+    /// with the `parallel` feature, [`Self::make_messages`] does it on the `rayon` thread pool.
     pub fn make_message(
         &self,
         rng: &mut impl CryptoRngCore,
         destination: &Verifier,
     ) -> Result<(MessageBundle<Sig>, Artifact<Verifier>), LocalError> {
+        #[cfg(feature = "tracing")]
+        let _span = self.span().entered();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(?destination, "Message created");
+
         match &self.tp {
             SessionType::Normal {
                 this_round,
                 broadcast,
             } => {
+                if destination == &self.context.my_id {
+                    return match broadcast {
+                        Some(broadcast) if self.broadcasts_to_self() => Ok((
+                            MessageBundle::try_from(MessageBundleEnum::Broadcast(
+                                broadcast.clone(),
+                            ))?,
+                            Artifact {
+                                destination: destination.clone(),
+                                artifact: None,
+                            },
+                        )),
+                        _ => Err(LocalError("Cannot send a message to myself".into())),
+                    };
+                }
+
                 let round_num = this_round.round_num();
-                let (payload, artifact) = this_round.make_direct_message(rng, destination)?;
+                let (payload, artifact) =
+                    this_round.make_direct_message(rng, self.context.wire_format, destination)?;
 
                 let direct_message = if let Some(payload) = payload {
-                    Some(
-                        VerifiedMessage::new(
-                            rng,
-                            &self.context.signer,
-                            &self.context.session_id,
-                            round_num,
-                            MessageType::Direct,
-                            &payload,
-                        )?
-                        .into_unverified(),
-                    )
+                    Some(self.context.sign_message(
+                        rng,
+                        round_num,
+                        MessageType::Direct,
+                        Some(destination),
+                        &payload,
+                    )?)
                 } else {
                     None
                 };
@@ -326,7 +863,7 @@ where
                     message,
                     Artifact {
                         destination: destination.clone(),
-                        artifact,
+                        artifact: Some(artifact),
                     },
                 ))
             }
@@ -337,20 +874,14 @@ where
                 let round_num = next_round.round_num() - 1;
                 let payload = echo_round.make_broadcast();
                 let artifact = DynArtifact::null();
-                let message = VerifiedMessage::new(
-                    rng,
-                    &self.context.signer,
-                    &self.context.session_id,
-                    round_num,
-                    MessageType::Echo,
-                    &payload,
-                )?
-                .into_unverified();
+                let message =
+                    self.context
+                        .sign_message(rng, round_num, MessageType::Echo, None, &payload)?;
                 Ok((
                     MessageBundle::try_from(MessageBundleEnum::Echo(message))?,
                     Artifact {
                         destination: destination.clone(),
-                        artifact,
+                        artifact: Some(artifact),
                     },
                 ))
             }
@@ -394,19 +925,39 @@ where
             }));
         }
 
+        // Messages from unknown parties may be cached for the next round,
+        // so they must be rejected before any further processing.
+        if !self.context.verifiers.contains(from) {
+            return Err(Error::Remote(RemoteError {
+                party: from.clone(),
+                error: RemoteErrorEnum::UnknownSender,
+            }));
+        }
+
+        if from == &self.context.my_id {
+            return self.preprocess_own_message(message);
+        }
+
         let message_for = self.route_message(from, &message)?;
+        let (message_round, message_is_echo) = (message.round(), message.is_echo());
 
-        let verified_message = message.verify(from).map_err(|err| {
+        let verified_message = message.verify(from, &self.context.my_id).map_err(|err| {
             Error::Remote(RemoteError {
                 party: from.clone(),
                 error: RemoteErrorEnum::InvalidSignature(err),
             })
         })?;
 
-        if from == &self.context.my_id {
-            return Err(Error::Local(LocalError(
-                "Cannot take a message from myself".into(),
-            )));
+        // This is an unprovable fault too (the message may be replayed, or the clocks are off).
+        // The timestamp is signed, so checking it after the signature
+        // ensures it was set by the sender and not altered in transit.
+        if let Some(freshness) = &self.context.freshness {
+            if !verified_message.is_fresh(freshness.clock.now(), freshness.max_skew) {
+                return Err(Error::Remote(RemoteError {
+                    party: from.clone(),
+                    error: RemoteErrorEnum::StaleMessage,
+                }));
+            }
         }
 
         let preprocessed = PreprocessedMessage {
@@ -422,7 +973,9 @@ where
                     )));
                 }
 
-                if accum.is_already_processed(&preprocessed) {
+                if accum.is_already_processed(&preprocessed)
+                    || !accum.mark_accepted(message_round, message_is_echo, from)
+                {
                     return Err(Error::Remote(RemoteError {
                         party: from.clone(),
                         error: RemoteErrorEnum::DuplicateMessage,
@@ -443,6 +996,92 @@ where
         })
     }
 
+    fn preprocess_own_message(
+        &self,
+        message: MessageBundle<Sig>,
+    ) -> Result<Option<PreprocessedMessage<Sig, Verifier>>, Error<Res, Verifier>> {
+        if !self.context.include_self_in_broadcast {
+            return Err(Error::Local(LocalError(
+                "Cannot take a message from myself".into(),
+            )));
+        }
+
+        let my_id = &self.context.my_id;
+        let verified_message = message.verify(my_id, my_id).map_err(|err| {
+            Error::Remote(RemoteError {
+                party: my_id.clone(),
+                error: RemoteErrorEnum::InvalidSignature(err),
+            })
+        })?;
+
+        if verified_message.direct_payload().is_some() {
+            return Err(Error::Local(LocalError(
+                "Only broadcasts can be taken from myself".into(),
+            )));
+        }
+
+        // Our own broadcast (or echo broadcast) is already known, so there is nothing to process.
+        // Since the transport may deliver it at any time, this is done regardless of the round.
+        Ok(None)
+    }
+
+    /// Discards a message that passed [`Self::preprocess_message`] without processing it
+    /// (e.g. if it was dropped because the node was overloaded),
+    /// and returns the request that should be sent to its sender.
+    ///
+    /// A retransmission of the message from the same sender will be accepted afterwards.
+    pub fn discard_message(
+        &self,
+        accum: &mut RoundAccumulator<Sig, Verifier>,
+        preprocessed: PreprocessedMessage<Sig, Verifier>,
+    ) -> RetransmitRequest {
+        let round = preprocessed.message.round();
+        let is_echo = preprocessed.message.is_echo();
+        accum
+            .accepted
+            .remove(&(round, is_echo, preprocessed.from.clone()));
+        RetransmitRequest {
+            session_id: self.context.session_id,
+            round,
+            is_echo,
+        }
+    }
+
+    /// Returns `true` if the request received from another party refers to the current round
+    /// of this session, in which case the message created for that party
+    /// by [`Self::make_message`] should be sent to it again.
+    pub fn is_retransmit_requested(&self, request: &RetransmitRequest) -> bool {
+        request.session_id == self.context.session_id
+            && (request.round, request.is_echo) == self.current_round()
+    }
+
+    /// Process a received message from another party if the budget allows it.
+    ///
+    /// The message is charged by the number of expensive operations
+    /// its verification takes in the current round.
+    /// If what is left of `budget` is not enough, the message is returned as is,
+    /// and can be processed once the budget is refilled.
+    #[allow(clippy::type_complexity)]
+    pub fn process_message_within(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        preprocessed: PreprocessedMessage<Sig, Verifier>,
+        budget: &mut SessionBudget,
+    ) -> Result<
+        Budgeted<ProcessedMessage<Sig, Verifier>, PreprocessedMessage<Sig, Verifier>>,
+        Error<Res, Verifier>,
+    > {
+        let cost = match &self.tp {
+            SessionType::Normal { this_round, .. } => this_round.verification_cost(),
+            // Echo messages only contain signatures on the broadcasts.
+            SessionType::Echo { .. } => 0,
+        };
+        if !budget.try_spend(cost) {
+            return Ok(Budgeted::WouldBlock(preprocessed));
+        }
+        self.process_message(rng, preprocessed).map(Budgeted::Done)
+    }
+
     /// Process a received message from another party.
     pub fn process_message(
         &self,
@@ -451,10 +1090,17 @@ where
     ) -> Result<ProcessedMessage<Sig, Verifier>, Error<Res, Verifier>> {
         let from = preprocessed.from;
         let message = preprocessed.message;
+
+        #[cfg(feature = "tracing")]
+        let _span = self.span().entered();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(?from, "Message processed");
+
         match &self.tp {
             SessionType::Normal { this_round, .. } => {
                 let result = this_round.verify_message(
                     rng,
+                    self.context.wire_format,
                     &from,
                     message.broadcast_payload(),
                     message.direct_payload(),
@@ -486,7 +1132,10 @@ where
         rng: &mut impl CryptoRngCore,
         accum: RoundAccumulator<Sig, Verifier>,
     ) -> Result<FinalizeOutcome<Res, Sig, Signer, Verifier>, Error<Res, Verifier>> {
-        match self.tp {
+        #[cfg(feature = "tracing")]
+        let _span = self.span().entered();
+
+        let result = match self.tp {
             SessionType::Normal { this_round, .. } => {
                 Self::finalize_regular_round(self.context, this_round, rng, accum)
             }
@@ -494,17 +1143,43 @@ where
                 echo_round,
                 next_round,
             } => Self::finalize_echo_round(self.context, echo_round, next_round, rng, accum),
+        };
+
+        #[cfg(feature = "tracing")]
+        match &result {
+            Ok(FinalizeOutcome::Success { meta, .. }) => tracing::debug!(
+                participants = ?meta.participants(),
+                "Round finalized with a result"
+            ),
+            Ok(FinalizeOutcome::AnotherRound { .. }) => {
+                tracing::debug!("Round finalized, starting the next round")
+            }
+            Err(err) => tracing::debug!(error = ?err, "Round finalization failed"),
         }
+
+        result
     }
 
     fn finalize_regular_round(
-        context: Context<Signer, Verifier>,
+        context: Box<Context<Signer, Verifier>>,
         round: Box<dyn DynFinalizable<Verifier, Res>>,
         rng: &mut impl CryptoRngCore,
         accum: RoundAccumulator<Sig, Verifier>,
     ) -> Result<FinalizeOutcome<Res, Sig, Signer, Verifier>, Error<Res, Verifier>> {
         let requires_echo = round.requires_echo();
 
+        // Rounds assume that they have all the messages they need on finalization,
+        // so this cannot be left to them.
+        if !round.can_finalize(&accum.processed) {
+            return Err(Error::Local(LocalError(format!(
+                "Cannot finalize the round yet, missing messages from {:?}",
+                round.missing_messages(&accum.processed)
+            ))));
+        }
+
+        let mut participants = accum.processed.received().clone();
+        participants.insert(context.my_id.clone());
+
         let outcome = round
             .finalize(rng, accum.processed)
             .map_err(|err| match err {
@@ -513,14 +1188,20 @@ where
                         "Failed to initialize the protocol: {err:?}"
                     ))),
                     rounds::FinalizeError::Proof(proof) => Error::Proof { proof },
+                    rounds::FinalizeError::Retry(reason) => Error::Retry { reason },
                 },
-                type_erased::FinalizeError::Accumulator(err) => {
-                    Error::Local(LocalError(format!("Failed to finalize: {err:?}")))
-                }
+                // The accumulator was checked above, so if this happens,
+                // there is a mismatch between the stored payloads/artifacts and the round types.
+                type_erased::FinalizeError::Accumulator(err) => Error::Local(LocalError(format!(
+                    "Internal error: the accumulated data does not match the round ({err})"
+                ))),
             })?;
 
         match outcome {
-            type_erased::FinalizeOutcome::Success(res) => Ok(FinalizeOutcome::Success(res)),
+            type_erased::FinalizeOutcome::Success(res) => Ok(FinalizeOutcome::Success {
+                result: res,
+                meta: ResultMeta { participants },
+            }),
             type_erased::FinalizeOutcome::AnotherRound(next_round) => {
                 if requires_echo {
                     let broadcasts = accum
@@ -531,32 +1212,28 @@ where
                         })
                         .collect();
 
-                    let echo_round = EchoRound::new(broadcasts);
+                    let echo_round = EchoRound::new(broadcasts, context.wire_format);
                     let session = Session {
                         tp: SessionType::Echo {
                             next_round,
                             echo_round,
                         },
                         context,
+                        deadline: None,
+                        cached_accepted: BTreeSet::new(),
                     };
-                    Ok(FinalizeOutcome::AnotherRound {
-                        session,
-                        cached_messages: accum.cached_messages.into_values().collect(),
-                    })
+                    Ok(Self::another_round(session, accum.cached_messages))
                 } else {
                     let session =
                         Session::new_internal(rng, context, next_round).map_err(Error::Local)?;
-                    Ok(FinalizeOutcome::AnotherRound {
-                        session,
-                        cached_messages: accum.cached_messages.into_values().collect(),
-                    })
+                    Ok(Self::another_round(session, accum.cached_messages))
                 }
             }
         }
     }
 
     fn finalize_echo_round(
-        context: Context<Signer, Verifier>,
+        context: Box<Context<Signer, Verifier>>,
         echo_round: EchoRound<Verifier, Sig>,
         next_round: Box<dyn DynFinalizable<Verifier, Res>>,
         rng: &mut impl CryptoRngCore,
@@ -570,15 +1247,90 @@ where
 
         let session = Session::new_internal(rng, context, next_round).map_err(Error::Local)?;
 
-        Ok(FinalizeOutcome::AnotherRound {
-            session,
-            cached_messages: accum.cached_messages.into_values().collect(),
+        Ok(Self::another_round(session, accum.cached_messages))
+    }
+
+    /// Passes the messages cached during the previous round to the session for the next one,
+    /// marking them as already accepted.
+    fn another_round(
+        session: Self,
+        cached_messages: BTreeMap<Verifier, PreprocessedMessage<Sig, Verifier>>,
+    ) -> FinalizeOutcome<Res, Sig, Signer, Verifier> {
+        let cached_accepted = cached_messages
+            .values()
+            .map(|cached| {
+                (
+                    cached.message.round(),
+                    cached.message.is_echo(),
+                    cached.from.clone(),
+                )
+            })
+            .collect();
+        FinalizeOutcome::AnotherRound {
+            session: Self {
+                cached_accepted,
+                ..session
+            },
+            cached_messages: cached_messages.into_values().collect(),
+        }
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl<Res, Sig, Signer, Verifier> Session<Res, Sig, Signer, Verifier>
+where
+    Res: ProtocolResult,
+    Signer: RandomizedPrehashSigner<Sig> + Keypair<VerifyingKey = Verifier> + Sync,
+    Verifier: PartyId + PrehashVerifier<Sig> + Send + Sync,
+    Sig: Clone + Serialize + for<'de> Deserialize<'de> + PartialEq + Eq + Send + Sync,
+{
+    /// Returns the messages for all the destinations returned by [`Self::message_destinations`],
+    /// created in parallel on the `rayon` thread pool.
+    ///
+    /// Each message is created with its own RNG seeded from `rng` in the order of the destinations,
+    /// so the result is the same as of calling [`Self::make_message`] serially with the same seeds.
+    /// The artifacts must be added to the accumulator as usual.
+    #[allow(clippy::type_complexity)]
+    pub fn make_messages(
+        &self,
+        rng: &mut impl CryptoRngCore,
+    ) -> Result<Vec<(Verifier, MessageBundle<Sig>, Artifact<Verifier>)>, LocalError> {
+        let destinations = self.message_destinations().into_iter().collect::<Vec<_>>();
+        parallel::map_with_rng(rng, &destinations, |rng, destination| {
+            self.make_message(rng, destination)
+                .map(|(message, artifact)| (destination.clone(), message, artifact))
         })
+        .into_iter()
+        .collect()
     }
 }
 
+/// A snapshot of the session state returned by [`Session::diagnostic`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionDiagnostic<Verifier: Ord> {
+    /// The session ID.
+    pub session_id: SessionId,
+    /// The ID of this party.
+    pub my_id: Verifier,
+    /// The current round number.
+    pub round: u8,
+    /// Whether the current round is an echo round.
+    pub is_echo_round: bool,
+    /// The parties whose messages for the current round were processed.
+    pub received_from: BTreeSet<Verifier>,
+    /// The parties whose messages for the current round have not been received yet.
+    pub missing_from: BTreeSet<Verifier>,
+    /// The number of messages for the next round cached during the current one.
+    pub cached_messages: usize,
+}
+
 /// A mutable accumulator created for each round to assemble processed messages from other parties.
 pub struct RoundAccumulator<Sig, Verifier> {
+    // The messages that passed preprocessing for the current round
+    // (including the ones cached during the previous round),
+    // identified by the round number, whether it is an echo message, and the sender.
+    // Allows rejecting duplicates before the first copy is processed.
+    accepted: BTreeSet<(u8, bool, Verifier)>,
     received_messages: BTreeMap<Verifier, VerifiedMessageBundle<Sig>>,
     processed: DynRoundAccum<Verifier>,
     cached_messages: BTreeMap<Verifier, PreprocessedMessage<Sig, Verifier>>,
@@ -586,8 +1338,9 @@ pub struct RoundAccumulator<Sig, Verifier> {
 }
 
 impl<Sig, Verifier: Ord + Clone + Debug> RoundAccumulator<Sig, Verifier> {
-    fn new(is_echo_round: bool) -> Self {
+    fn new(is_echo_round: bool, accepted: BTreeSet<(u8, bool, Verifier)>) -> Self {
         Self {
+            accepted,
             received_messages: BTreeMap::new(),
             processed: DynRoundAccum::new(),
             cached_messages: BTreeMap::new(),
@@ -601,8 +1354,13 @@ impl<Sig, Verifier: Ord + Clone + Debug> RoundAccumulator<Sig, Verifier> {
 
     /// Save an artifact produced by [`Session::make_message`].
     pub fn add_artifact(&mut self, artifact: Artifact<Verifier>) -> Result<(), LocalError> {
+        // Broadcasts sent to ourselves do not produce artifacts.
+        let dyn_artifact = match artifact.artifact {
+            Some(dyn_artifact) => dyn_artifact,
+            None => return Ok(()),
+        };
         self.processed
-            .add_artifact(&artifact.destination, artifact.artifact)
+            .add_artifact(&artifact.destination, dyn_artifact)
             .map_err(|err| match err {
                 AccumAddError::SlotTaken => LocalError(format!(
                     "Artifact for the destination {:?} was already added",
@@ -653,6 +1411,12 @@ impl<Sig, Verifier: Ord + Clone + Debug> RoundAccumulator<Sig, Verifier> {
         }
     }
 
+    /// Records that a message was accepted for processing,
+    /// returning `false` if it had already been accepted.
+    fn mark_accepted(&mut self, round: u8, is_echo: bool, from: &Verifier) -> bool {
+        self.accepted.insert((round, is_echo, from.clone()))
+    }
+
     fn is_already_cached(&self, preprocessed: &PreprocessedMessage<Sig, Verifier>) -> bool {
         self.cached_messages.contains_key(&preprocessed.from)
     }
@@ -663,11 +1427,27 @@ impl<Sig, Verifier: Ord + Clone + Debug> RoundAccumulator<Sig, Verifier> {
     }
 }
 
+/// A request to send the message for the current round again,
+/// created by [`Session::discard_message`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetransmitRequest {
+    session_id: SessionId,
+    round: u8,
+    is_echo: bool,
+}
+
+impl RetransmitRequest {
+    /// Returns the number of the round the requested message belongs to.
+    pub fn round(&self) -> u8 {
+        self.round
+    }
+}
+
 /// Data produced when creating a direct message to another party
 /// that has to be preserved for further processing.
 pub struct Artifact<Verifier> {
     destination: Verifier,
-    artifact: DynArtifact,
+    artifact: Option<DynArtifact>,
 }
 
 /// A message that passed initial validity checks.
@@ -692,11 +1472,69 @@ enum ProcessedMessageEnum<Sig> {
 
 #[cfg(test)]
 mod tests {
+    use alloc::collections::{BTreeMap, BTreeSet};
+    use alloc::vec::Vec;
+
     use impls::impls;
     use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+    use rand_core::OsRng;
+    use serde::Serialize;
+    use serde_assert::Token;
+
+    use super::{
+        Artifact, Budgeted, Clock, FinalizeOutcome, MessageBundle, PreprocessedMessage,
+        ProcessedMessage, Session, SessionBudget, SessionDiagnostic,
+    };
+    use crate::sessions::{
+        audit_quorum, Error, NonParticipation, RemoteErrorEnum, RoundEvidence, SessionId,
+        WireFormat,
+    };
+    use crate::{
+        make_key_gen_session, make_presigning_session, AuxInfo, KeyGenResult, KeyShare,
+        ProtocolResult, TestParams,
+    };
+
+    type KeyGenSession =
+        Session<KeyGenResult<TestParams, VerifyingKey>, Signature, SigningKey, VerifyingKey>;
+
+    /// Creates random signers and the set of their verifying keys.
+    /// The signers are sorted so that they are in the same order as the verifying keys.
+    fn make_signers(num_parties: usize) -> (Vec<SigningKey>, BTreeSet<VerifyingKey>) {
+        let mut signers = (0..num_parties)
+            .map(|_| SigningKey::random(&mut OsRng))
+            .collect::<Vec<_>>();
+        signers.sort_by_key(|signer| *signer.verifying_key());
+        let ids = signers
+            .iter()
+            .map(|signer| *signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+        (signers, ids)
+    }
+
+    fn key_gen_session(
+        session_id: SessionId,
+        signer: &SigningKey,
+        ids: &BTreeSet<VerifyingKey>,
+    ) -> KeyGenSession {
+        make_key_gen_session::<TestParams, Signature, _, _>(
+            &mut OsRng,
+            session_id,
+            signer.clone(),
+            ids,
+        )
+        .unwrap()
+    }
 
-    use super::{Artifact, MessageBundle, PreprocessedMessage, ProcessedMessage, Session};
-    use crate::ProtocolResult;
+    fn key_gen_sessions(
+        session_id: SessionId,
+        signers: &[SigningKey],
+        ids: &BTreeSet<VerifyingKey>,
+    ) -> Vec<KeyGenSession> {
+        signers
+            .iter()
+            .map(|signer| key_gen_session(session_id, signer, ids))
+            .collect()
+    }
 
     #[test]
     fn test_concurrency_bounds() {
@@ -715,6 +1553,7 @@ mod tests {
             type Success = ();
             type ProvableError = ();
             type CorrectnessProof = ();
+            type RetryReason = ();
         }
 
         assert!(impls!(Session<DummyResult, Signature, SigningKey, VerifyingKey>: Sync));
@@ -723,4 +1562,1173 @@ mod tests {
         assert!(impls!(PreprocessedMessage<Signature, VerifyingKey>: Send));
         assert!(impls!(ProcessedMessage<Signature, VerifyingKey>: Send));
     }
+
+    #[test]
+    fn message_from_unknown_sender() {
+        let (signers, ids) = make_signers(3);
+        let ids = ids.into_iter().collect::<Vec<_>>();
+        let session_id = SessionId::from_seed(b"session");
+
+        // The first two parties run a session, and the third one is an outsider
+        // creating a session with the same ID.
+        let session = key_gen_session(session_id, &signers[0], &BTreeSet::from([ids[0], ids[1]]));
+        let outsider_session =
+            key_gen_session(session_id, &signers[2], &BTreeSet::from([ids[0], ids[2]]));
+
+        let (message, _artifact) = outsider_session.make_message(&mut OsRng, &ids[0]).unwrap();
+
+        let mut accum = session.make_accumulator();
+        let result = session.preprocess_message(&mut accum, &ids[2], message);
+        assert!(matches!(
+            result,
+            Err(Error::Remote(err)) if err.party == ids[2] && matches!(err.error, RemoteErrorEnum::UnknownSender)
+        ));
+    }
+
+    #[test]
+    fn own_verifier_is_required() {
+        let (signers, ids) = make_signers(3);
+        let ids = ids.into_iter().collect::<Vec<_>>();
+        let session_id = SessionId::from_seed(b"session");
+
+        // The signer of the session is not in the set of verifiers.
+        let result = make_key_gen_session::<TestParams, Signature, _, _>(
+            &mut OsRng,
+            session_id,
+            signers[2].clone(),
+            &BTreeSet::from([ids[0], ids[1]]),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn discarded_message_is_retransmitted() {
+        let (signers, ids) = make_signers(2);
+        let session_id = SessionId::from_seed(b"session");
+
+        let mut sessions = key_gen_sessions(session_id, &signers, &ids);
+        let receiver = sessions.pop().unwrap();
+        let sender = sessions.pop().unwrap();
+
+        let (message, _artifact) = sender
+            .make_message(&mut OsRng, &receiver.verifier())
+            .unwrap();
+
+        // The receiver accepts the message, but has to drop it before processing.
+        let mut accum = receiver.make_accumulator();
+        let preprocessed = receiver
+            .preprocess_message(&mut accum, &sender.verifier(), message.clone())
+            .unwrap()
+            .unwrap();
+        let request = receiver.discard_message(&mut accum, preprocessed);
+        assert_eq!(
+            receiver.missing_messages(&accum).unwrap(),
+            BTreeSet::from([sender.verifier()])
+        );
+
+        // The sender serves the request by sending the same message again.
+        assert_eq!(request.round(), 1);
+        assert!(sender.is_retransmit_requested(&request));
+        let preprocessed = receiver
+            .preprocess_message(&mut accum, &sender.verifier(), message.clone())
+            .unwrap()
+            .unwrap();
+        let processed = receiver.process_message(&mut OsRng, preprocessed).unwrap();
+        accum.add_processed_message(processed).unwrap().unwrap();
+        assert!(receiver.missing_messages(&accum).unwrap().is_empty());
+
+        // Further copies are rejected
+        let result = receiver.preprocess_message(&mut accum, &sender.verifier(), message);
+        assert!(matches!(
+            result,
+            Err(Error::Remote(err)) if matches!(err.error, RemoteErrorEnum::DuplicateMessage)
+        ));
+
+        // The round is completed with the retransmitted message.
+        let (_message, artifact) = receiver
+            .make_message(&mut OsRng, &sender.verifier())
+            .unwrap();
+        accum.add_artifact(artifact).unwrap();
+        assert!(receiver.can_finalize(&accum).unwrap());
+        let next_session = match receiver.finalize_round(&mut OsRng, accum).unwrap() {
+            FinalizeOutcome::AnotherRound { session, .. } => session,
+            FinalizeOutcome::Success { .. } => panic!("Expected another round"),
+        };
+
+        // A request for a previous round is not served.
+        assert!(!next_session.is_retransmit_requested(&request));
+    }
+
+    #[test]
+    fn duplicate_is_rejected_before_processing() {
+        let (signers, ids) = make_signers(2);
+        let session_id = SessionId::from_seed(b"session");
+
+        let sessions = key_gen_sessions(session_id, &signers, &ids);
+        let sender = &sessions[0];
+        let receiver = &sessions[1];
+
+        let (message, _artifact) = sender
+            .make_message(&mut OsRng, &receiver.verifier())
+            .unwrap();
+
+        // The transport delivers the same message twice,
+        // and the second copy arrives while the first one is still being processed.
+        let mut accum = receiver.make_accumulator();
+        let preprocessed = receiver
+            .preprocess_message(&mut accum, &sender.verifier(), message.clone())
+            .unwrap()
+            .unwrap();
+
+        let result = receiver.preprocess_message(&mut accum, &sender.verifier(), message);
+        assert!(matches!(
+            result,
+            Err(Error::Remote(err)) if matches!(err.error, RemoteErrorEnum::DuplicateMessage)
+        ));
+
+        // The first copy is still processed normally.
+        let processed = receiver.process_message(&mut OsRng, preprocessed).unwrap();
+        accum.add_processed_message(processed).unwrap().unwrap();
+        assert!(receiver.missing_messages(&accum).unwrap().is_empty());
+    }
+
+    #[test]
+    fn cached_message_duplicate_is_rejected() {
+        let (signers, ids) = make_signers(2);
+        let session_id = SessionId::from_seed(b"session");
+
+        let mut sessions = key_gen_sessions(session_id, &signers, &ids);
+        let receiver = sessions.pop().unwrap();
+        let sender = sessions.pop().unwrap();
+
+        // The sender gets to the next round first.
+        let (message, _artifact) = sender
+            .make_message(&mut OsRng, &receiver.verifier())
+            .unwrap();
+        let mut receiver_accum = receiver.make_accumulator();
+        let preprocessed = receiver
+            .preprocess_message(&mut receiver_accum, &sender.verifier(), message)
+            .unwrap()
+            .unwrap();
+        let processed = receiver.process_message(&mut OsRng, preprocessed).unwrap();
+        receiver_accum
+            .add_processed_message(processed)
+            .unwrap()
+            .unwrap();
+
+        let (message, artifact) = receiver
+            .make_message(&mut OsRng, &sender.verifier())
+            .unwrap();
+        receiver_accum.add_artifact(artifact).unwrap();
+        let mut sender_accum = sender.make_accumulator();
+        let preprocessed = sender
+            .preprocess_message(&mut sender_accum, &receiver.verifier(), message)
+            .unwrap()
+            .unwrap();
+        let processed = sender.process_message(&mut OsRng, preprocessed).unwrap();
+        sender_accum
+            .add_processed_message(processed)
+            .unwrap()
+            .unwrap();
+        let (_message, artifact) = sender
+            .make_message(&mut OsRng, &receiver.verifier())
+            .unwrap();
+        sender_accum.add_artifact(artifact).unwrap();
+        let sender = match sender.finalize_round(&mut OsRng, sender_accum).unwrap() {
+            FinalizeOutcome::AnotherRound { session, .. } => session,
+            FinalizeOutcome::Success { .. } => panic!("Expected another round"),
+        };
+
+        // Its message for the next round is cached by the receiver.
+        let (message, _artifact) = sender
+            .make_message(&mut OsRng, &receiver.verifier())
+            .unwrap();
+        assert!(receiver
+            .preprocess_message(&mut receiver_accum, &sender.verifier(), message.clone())
+            .unwrap()
+            .is_none());
+        let (receiver, cached_messages) =
+            match receiver.finalize_round(&mut OsRng, receiver_accum).unwrap() {
+                FinalizeOutcome::AnotherRound {
+                    session,
+                    cached_messages,
+                } => (session, cached_messages),
+                FinalizeOutcome::Success { .. } => panic!("Expected another round"),
+            };
+        assert_eq!(cached_messages.len(), 1);
+
+        // A copy of the cached message delivered in the next round is a duplicate.
+        let mut accum = receiver.make_accumulator();
+        let result = receiver.preprocess_message(&mut accum, &sender.verifier(), message);
+        assert!(matches!(
+            result,
+            Err(Error::Remote(err)) if matches!(err.error, RemoteErrorEnum::DuplicateMessage)
+        ));
+
+        // The cached message itself is processed normally.
+        for preprocessed in cached_messages {
+            let processed = receiver.process_message(&mut OsRng, preprocessed).unwrap();
+            accum.add_processed_message(processed).unwrap().unwrap();
+        }
+        assert!(receiver.missing_messages(&accum).unwrap().is_empty());
+        receiver.assert_round_drained(&accum).unwrap();
+    }
+
+    #[test]
+    fn messages_are_created_on_demand() {
+        let (signers, ids) = make_signers(3);
+        let session_id = SessionId::from_seed(b"session");
+
+        let sessions = key_gen_sessions(session_id, &signers, &ids);
+        let sender = &sessions[0];
+        let mut sender_accum = sender.make_accumulator();
+        let mut accums = sessions
+            .iter()
+            .map(|session| session.make_accumulator())
+            .collect::<Vec<_>>();
+
+        // Each message is created only when it is about to be sent,
+        // and is delivered and processed before the next one is created,
+        // so there is never more than one outgoing message held in memory.
+        for destination in sender.message_destinations() {
+            let (message, artifact) = sender.make_message(&mut OsRng, &destination).unwrap();
+            sender_accum.add_artifact(artifact).unwrap();
+
+            let idx = sessions
+                .iter()
+                .position(|session| session.verifier() == destination)
+                .unwrap();
+            let receiver = &sessions[idx];
+            let accum = &mut accums[idx];
+            assert!(receiver
+                .missing_messages(accum)
+                .unwrap()
+                .contains(&sender.verifier()));
+
+            let preprocessed = receiver
+                .preprocess_message(accum, &sender.verifier(), message)
+                .unwrap()
+                .unwrap();
+            let processed = receiver.process_message(&mut OsRng, preprocessed).unwrap();
+            accum.add_processed_message(processed).unwrap().unwrap();
+            assert!(!receiver
+                .missing_messages(accum)
+                .unwrap()
+                .contains(&sender.verifier()));
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_messages_match_serial() {
+        use rand_chacha::ChaCha8Rng;
+        use rand_core::{RngCore, SeedableRng};
+
+        let (signers, ids) = make_signers(3);
+        let session_id = SessionId::from_seed(b"session");
+        let key_shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids);
+
+        // The first round of presigning has per-recipient proofs.
+        let id = signers[0].verifying_key();
+        let session = make_presigning_session::<_, Signature, _, _>(
+            &mut OsRng,
+            session_id,
+            signers[0].clone(),
+            &ids,
+            &key_shares[id],
+            &aux_infos[id],
+        )
+        .unwrap();
+
+        let encode = |message: &MessageBundle<Signature>| {
+            bincode::serde::encode_to_vec(message, bincode::config::standard()).unwrap()
+        };
+
+        let parallel = session
+            .make_messages(&mut ChaCha8Rng::from_seed([1; 32]))
+            .unwrap()
+            .into_iter()
+            .map(|(destination, message, _artifact)| (destination, encode(&message)))
+            .collect::<Vec<_>>();
+
+        // The same seeds drawn in the order of the destinations.
+        let mut rng = ChaCha8Rng::from_seed([1; 32]);
+        let serial = session
+            .message_destinations()
+            .into_iter()
+            .map(|destination| {
+                let mut seed = [0u8; 32];
+                rng.fill_bytes(&mut seed);
+                let (message, _artifact) = session
+                    .make_message(&mut ChaCha8Rng::from_seed(seed), &destination)
+                    .unwrap();
+                (destination, encode(&message))
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(parallel.len(), 2);
+        assert_eq!(parallel, serial);
+    }
+
+    #[test]
+    fn processing_yields_when_budget_is_exhausted() {
+        let (signers, ids) = make_signers(3);
+        let session_id = SessionId::from_seed(b"session");
+
+        let mut sessions = key_gen_sessions(session_id, &signers, &ids);
+        let receiver = sessions.remove(0);
+        let my_id = receiver.verifier();
+
+        let mut accum = receiver.make_accumulator();
+        for destination in receiver.message_destinations().iter() {
+            let (_message, artifact) = receiver.make_message(&mut OsRng, destination).unwrap();
+            accum.add_artifact(artifact).unwrap();
+        }
+
+        let mut pending = sessions
+            .iter()
+            .map(|sender| {
+                let (message, _artifact) = sender.make_message(&mut OsRng, &my_id).unwrap();
+                receiver
+                    .preprocess_message(&mut accum, &sender.verifier(), message)
+                    .unwrap()
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        // With a budget of one message per slice, the session yields after every message.
+        let mut budget = SessionBudget::new(1);
+        let mut slices = 0;
+        while !pending.is_empty() {
+            slices += 1;
+            let mut postponed = Vec::new();
+            for preprocessed in pending {
+                match receiver
+                    .process_message_within(&mut OsRng, preprocessed, &mut budget)
+                    .unwrap()
+                {
+                    Budgeted::Done(processed) => {
+                        accum.add_processed_message(processed).unwrap().unwrap()
+                    }
+                    Budgeted::WouldBlock(preprocessed) => postponed.push(preprocessed),
+                }
+            }
+            assert!(budget.is_exhausted());
+            budget.refill();
+            pending = postponed;
+        }
+
+        assert_eq!(slices, 2);
+        assert!(receiver.can_finalize(&accum).unwrap());
+    }
+
+    /// Runs the current round of all the sessions and returns the sessions for the next round.
+    fn step_sessions<Res: ProtocolResult>(
+        sessions: Vec<Session<Res, Signature, SigningKey, VerifyingKey>>,
+    ) -> Vec<Session<Res, Signature, SigningKey, VerifyingKey>> {
+        let mut accums = sessions
+            .iter()
+            .map(|session| session.make_accumulator())
+            .collect::<Vec<_>>();
+        for (i, sender) in sessions.iter().enumerate() {
+            for destination in sender.message_destinations() {
+                let (message, artifact) = sender.make_message(&mut OsRng, &destination).unwrap();
+                accums[i].add_artifact(artifact).unwrap();
+                let j = sessions
+                    .iter()
+                    .position(|session| session.verifier() == destination)
+                    .unwrap();
+                let preprocessed = sessions[j]
+                    .preprocess_message(&mut accums[j], &sender.verifier(), message)
+                    .unwrap()
+                    .unwrap();
+                let processed = sessions[j]
+                    .process_message(&mut OsRng, preprocessed)
+                    .unwrap();
+                accums[j].add_processed_message(processed).unwrap().unwrap();
+            }
+        }
+        sessions
+            .into_iter()
+            .zip(accums)
+            .map(
+                |(session, accum)| match session.finalize_round(&mut OsRng, accum).unwrap() {
+                    FinalizeOutcome::AnotherRound { session, .. } => session,
+                    FinalizeOutcome::Success { .. } => panic!("Expected another round"),
+                },
+            )
+            .collect()
+    }
+
+    #[test]
+    fn budget_is_charged_by_verification_cost() {
+        let (signers, ids) = make_signers(3);
+        let session_id = SessionId::from_seed(b"session");
+
+        let key_shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids);
+
+        let sessions = signers
+            .iter()
+            .map(|signer| {
+                let id = signer.verifying_key();
+                make_presigning_session::<_, Signature, _, _>(
+                    &mut OsRng,
+                    session_id,
+                    signer.clone(),
+                    &ids,
+                    &key_shares[id],
+                    &aux_infos[id],
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        // Skip the first round of presigning and its echo round.
+        let mut sessions = step_sessions(step_sessions(sessions));
+        let receiver = sessions.remove(0);
+        let my_id = receiver.verifier();
+
+        let mut accum = receiver.make_accumulator();
+        for destination in receiver.message_destinations().iter() {
+            let (_message, artifact) = receiver.make_message(&mut OsRng, destination).unwrap();
+            accum.add_artifact(artifact).unwrap();
+        }
+
+        let mut pending = sessions
+            .iter()
+            .map(|sender| {
+                let (message, _artifact) = sender.make_message(&mut OsRng, &my_id).unwrap();
+                receiver
+                    .preprocess_message(&mut accum, &sender.verifier(), message)
+                    .unwrap()
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        // A message of the second round of presigning takes six expensive operations to verify,
+        // so a budget that would fit both messages if they were charged one each
+        // only lets one through per slice.
+        let mut budget = SessionBudget::new(pending.len() + 1);
+        let mut slices = 0;
+        while !pending.is_empty() {
+            slices += 1;
+            let mut postponed = Vec::new();
+            for preprocessed in pending {
+                match receiver
+                    .process_message_within(&mut OsRng, preprocessed, &mut budget)
+                    .unwrap()
+                {
+                    Budgeted::Done(processed) => {
+                        accum.add_processed_message(processed).unwrap().unwrap()
+                    }
+                    Budgeted::WouldBlock(preprocessed) => postponed.push(preprocessed),
+                }
+            }
+            assert!(budget.is_exhausted());
+            budget.refill();
+            pending = postponed;
+        }
+
+        assert_eq!(slices, 2);
+        assert!(receiver.can_finalize(&accum).unwrap());
+    }
+
+    #[test]
+    fn own_broadcast_is_tolerated() {
+        let (signers, ids) = make_signers(2);
+        let session_id = SessionId::from_seed(b"session");
+
+        let sessions = signers
+            .iter()
+            .map(|signer| key_gen_session(session_id, signer, &ids).include_self_in_broadcast(true))
+            .collect::<Vec<_>>();
+
+        // The first round of KeyGen only sends broadcasts, so every party is a destination.
+        for session in sessions.iter() {
+            assert_eq!(session.message_destinations(), ids);
+        }
+
+        let session = &sessions[0];
+        let other = &sessions[1];
+        let my_id = session.verifier();
+
+        let mut accum = session.make_accumulator();
+        for destination in session.message_destinations().iter() {
+            let (_message, artifact) = session.make_message(&mut OsRng, destination).unwrap();
+            accum.add_artifact(artifact).unwrap();
+        }
+
+        // Our own broadcast delivered back to us, possibly several times, is not an error.
+        let (own_message, _artifact) = session.make_message(&mut OsRng, &my_id).unwrap();
+        for _ in 0..2 {
+            let result = session
+                .preprocess_message(&mut accum, &my_id, own_message.clone())
+                .unwrap();
+            assert!(result.is_none());
+        }
+        assert_eq!(
+            session.missing_messages(&accum).unwrap(),
+            BTreeSet::from([other.verifier()])
+        );
+
+        // The messages from the other party are processed as usual.
+        let (message, _artifact) = other.make_message(&mut OsRng, &my_id).unwrap();
+        let preprocessed = session
+            .preprocess_message(&mut accum, &other.verifier(), message)
+            .unwrap()
+            .unwrap();
+        let processed = session.process_message(&mut OsRng, preprocessed).unwrap();
+        accum.add_processed_message(processed).unwrap().unwrap();
+        assert!(session.can_finalize(&accum).unwrap());
+
+        // Without the option, own messages are rejected.
+        let plain_session = key_gen_session(session_id, &signers[0], &ids);
+        assert!(!plain_session.message_destinations().contains(&my_id));
+        let mut accum = plain_session.make_accumulator();
+        let result = plain_session.preprocess_message(&mut accum, &my_id, own_message);
+        assert!(matches!(result, Err(Error::Local(_))));
+    }
+
+    #[test]
+    fn own_echo_is_tolerated() {
+        let (signers, ids) = make_signers(2);
+        let session_id = SessionId::from_seed(b"session");
+
+        let key_shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids);
+
+        let mut sessions = signers
+            .iter()
+            .map(|signer| {
+                let id = signer.verifying_key();
+                make_presigning_session::<_, Signature, _, _>(
+                    &mut OsRng,
+                    session_id,
+                    signer.clone(),
+                    &ids,
+                    &key_shares[id],
+                    &aux_infos[id],
+                )
+                .unwrap()
+                .include_self_in_broadcast(true)
+            })
+            .collect::<Vec<_>>();
+        let other = sessions.pop().unwrap();
+        let session = sessions.pop().unwrap();
+        let my_id = session.verifier();
+
+        // The first round of presigning is followed by an echo round.
+        let mut accum = session.make_accumulator();
+        for destination in session.message_destinations().iter() {
+            let (_message, artifact) = session.make_message(&mut OsRng, destination).unwrap();
+            accum.add_artifact(artifact).unwrap();
+        }
+        let (message, _artifact) = other.make_message(&mut OsRng, &my_id).unwrap();
+        let preprocessed = session
+            .preprocess_message(&mut accum, &other.verifier(), message)
+            .unwrap()
+            .unwrap();
+        let processed = session.process_message(&mut OsRng, preprocessed).unwrap();
+        accum.add_processed_message(processed).unwrap().unwrap();
+        let session = match session.finalize_round(&mut OsRng, accum).unwrap() {
+            FinalizeOutcome::AnotherRound { session, .. } => session,
+            FinalizeOutcome::Success { .. } => panic!("Expected another round"),
+        };
+        assert_eq!(session.current_round(), (1, true));
+
+        // Our own echo broadcast delivered back to us is ignored.
+        let (own_echo, _artifact) = session.make_message(&mut OsRng, &other.verifier()).unwrap();
+        let mut accum = session.make_accumulator();
+        let result = session
+            .preprocess_message(&mut accum, &my_id, own_echo)
+            .unwrap();
+        assert!(result.is_none());
+        assert_eq!(
+            session.missing_messages(&accum).unwrap(),
+            BTreeSet::from([other.verifier()])
+        );
+    }
+
+    #[test]
+    fn finalize_requires_all_messages() {
+        let (signers, ids) = make_signers(3);
+        let session_id = SessionId::from_seed(b"session");
+
+        for num_received in [1, 2] {
+            let mut sessions = key_gen_sessions(session_id, &signers, &ids);
+            let receiver = sessions.remove(0);
+            let my_id = receiver.verifier();
+
+            let mut accum = receiver.make_accumulator();
+            for destination in receiver.message_destinations().iter() {
+                let (_message, artifact) = receiver.make_message(&mut OsRng, destination).unwrap();
+                accum.add_artifact(artifact).unwrap();
+            }
+
+            for sender in sessions.iter().take(num_received) {
+                let (message, _artifact) = sender.make_message(&mut OsRng, &my_id).unwrap();
+                let preprocessed = receiver
+                    .preprocess_message(&mut accum, &sender.verifier(), message)
+                    .unwrap()
+                    .unwrap();
+                let processed = receiver.process_message(&mut OsRng, preprocessed).unwrap();
+                accum.add_processed_message(processed).unwrap().unwrap();
+            }
+
+            let all_received = num_received == sessions.len();
+            assert_eq!(receiver.can_finalize(&accum).unwrap(), all_received);
+
+            // The parties that have not sent their messages are reported precisely.
+            let not_received = sessions
+                .iter()
+                .skip(num_received)
+                .map(|sender| sender.verifier())
+                .collect::<BTreeSet<_>>();
+            assert_eq!(receiver.missing_messages(&accum).unwrap(), not_received);
+
+            // Finalizing early is an error rather than a panic in the round,
+            // and with exactly all the messages received it succeeds.
+            let result = receiver.finalize_round(&mut OsRng, accum);
+            if all_received {
+                assert!(matches!(result, Ok(FinalizeOutcome::AnotherRound { .. })));
+            } else {
+                assert!(matches!(result, Err(Error::Local(_))));
+            }
+        }
+    }
+
+    #[test]
+    fn round_deadline() {
+        let (signers, ids) = make_signers(3);
+        let session_id = SessionId::from_seed(b"session");
+
+        let mut sessions = key_gen_sessions(session_id, &signers, &ids);
+        let deadline = 1_700_000_000;
+        let receiver = sessions.remove(0).with_round_deadline(deadline);
+        let my_id = receiver.verifier();
+        let sender = &sessions[0];
+        let late = &sessions[1];
+
+        let mut accum = receiver.make_accumulator();
+        let (message, _artifact) = sender.make_message(&mut OsRng, &my_id).unwrap();
+        let preprocessed = receiver
+            .preprocess_message(&mut accum, &sender.verifier(), message)
+            .unwrap()
+            .unwrap();
+        let processed = receiver.process_message(&mut OsRng, preprocessed).unwrap();
+        accum.add_processed_message(processed).unwrap().unwrap();
+
+        // Before the deadline, waiting for the late party is fine.
+        assert!(receiver.check_deadline(&accum, deadline).is_ok());
+
+        // After the deadline, the late party is reported.
+        let result = receiver.check_deadline(&accum, deadline + 1);
+        assert!(matches!(
+            result,
+            Err(Error::RoundTimedOut { round: 1, missing })
+                if missing == BTreeSet::from([late.verifier()])
+        ));
+
+        // Once the late message arrives, the deadline no longer matters.
+        let (message, _artifact) = late.make_message(&mut OsRng, &my_id).unwrap();
+        let preprocessed = receiver
+            .preprocess_message(&mut accum, &late.verifier(), message)
+            .unwrap()
+            .unwrap();
+        let processed = receiver.process_message(&mut OsRng, preprocessed).unwrap();
+        accum.add_processed_message(processed).unwrap().unwrap();
+        assert!(receiver.check_deadline(&accum, deadline + 1).is_ok());
+
+        // A session without a deadline never times out.
+        let no_deadline = &sessions[0];
+        let accum = no_deadline.make_accumulator();
+        assert!(no_deadline.check_deadline(&accum, u64::MAX).is_ok());
+    }
+
+    #[test]
+    fn round_drained_assertion() {
+        let (signers, ids) = make_signers(3);
+        let session_id = SessionId::from_seed(b"session");
+
+        let mut sessions = key_gen_sessions(session_id, &signers, &ids);
+        let receiver = sessions.remove(0);
+        let my_id = receiver.verifier();
+
+        let mut accum = receiver.make_accumulator();
+        assert!(receiver.assert_round_drained(&accum).is_ok());
+
+        // Everyone sends out the messages of the first round.
+        let mut to_receiver = Vec::new();
+        let mut to_senders = Vec::new();
+        for destination in receiver.message_destinations().iter() {
+            let (message, artifact) = receiver.make_message(&mut OsRng, destination).unwrap();
+            accum.add_artifact(artifact).unwrap();
+            to_senders.push((my_id, *destination, message));
+        }
+        let mut senders = BTreeMap::new();
+        for sender in sessions {
+            let mut sender_accum = sender.make_accumulator();
+            for destination in sender.message_destinations().iter() {
+                let (message, artifact) = sender.make_message(&mut OsRng, destination).unwrap();
+                sender_accum.add_artifact(artifact).unwrap();
+                if destination == &my_id {
+                    to_receiver.push((sender.verifier(), message));
+                } else {
+                    to_senders.push((sender.verifier(), *destination, message));
+                }
+            }
+            senders.insert(sender.verifier(), (sender, sender_accum));
+        }
+
+        let mut preprocessed = to_receiver
+            .into_iter()
+            .map(|(from, message)| {
+                receiver
+                    .preprocess_message(&mut accum, &from, message)
+                    .unwrap()
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        // The messages were accepted, but not processed yet
+        assert!(receiver.assert_round_drained(&accum).is_err());
+
+        let pending = preprocessed.pop().unwrap();
+        for message in preprocessed {
+            let processed = receiver.process_message(&mut OsRng, message).unwrap();
+            accum.add_processed_message(processed).unwrap().unwrap();
+        }
+        assert!(receiver.assert_round_drained(&accum).is_err());
+
+        let processed = receiver.process_message(&mut OsRng, pending).unwrap();
+        accum.add_processed_message(processed).unwrap().unwrap();
+        assert!(receiver.assert_round_drained(&accum).is_ok());
+
+        // The other parties finish the round and send out the messages of the next one,
+        // which the receiver caches without affecting the current round.
+        for (from, to, message) in to_senders {
+            let (sender, sender_accum) = senders.get_mut(&to).unwrap();
+            let preprocessed = sender
+                .preprocess_message(sender_accum, &from, message)
+                .unwrap()
+                .unwrap();
+            let processed = sender.process_message(&mut OsRng, preprocessed).unwrap();
+            sender_accum
+                .add_processed_message(processed)
+                .unwrap()
+                .unwrap();
+        }
+        for (sender, sender_accum) in senders.into_values() {
+            let sender = match sender.finalize_round(&mut OsRng, sender_accum).unwrap() {
+                FinalizeOutcome::AnotherRound { session, .. } => session,
+                FinalizeOutcome::Success { .. } => panic!("Expected another round"),
+            };
+            let (message, _artifact) = sender.make_message(&mut OsRng, &my_id).unwrap();
+            assert!(receiver
+                .preprocess_message(&mut accum, &sender.verifier(), message)
+                .unwrap()
+                .is_none());
+        }
+        assert!(receiver.assert_round_drained(&accum).is_ok());
+
+        match receiver.finalize_round(&mut OsRng, accum).unwrap() {
+            FinalizeOutcome::AnotherRound {
+                cached_messages, ..
+            } => assert_eq!(cached_messages.len(), 2),
+            FinalizeOutcome::Success { .. } => panic!("Expected another round"),
+        };
+    }
+
+    #[test]
+    fn non_participation_names_silent_parties() {
+        let (signers, ids) = make_signers(3);
+        let session_id = SessionId::from_seed(b"session");
+
+        let sender_id = *signers[1].verifying_key();
+        let silent_id = *signers[2].verifying_key();
+
+        // Naming nobody, or a party whose message has been received, is an error.
+        for (missing, is_valid) in [
+            (BTreeSet::new(), false),
+            (BTreeSet::from([sender_id, silent_id]), false),
+            (BTreeSet::from([silent_id]), true),
+        ] {
+            let mut sessions = key_gen_sessions(session_id, &signers, &ids);
+            let receiver = sessions.remove(0);
+            let my_id = receiver.verifier();
+            let sender = &sessions[0];
+
+            let mut accum = receiver.make_accumulator();
+            for destination in receiver.message_destinations().iter() {
+                let (_message, artifact) = receiver.make_message(&mut OsRng, destination).unwrap();
+                accum.add_artifact(artifact).unwrap();
+            }
+
+            // Only one of the two other parties sends its message.
+            let (message, _artifact) = sender.make_message(&mut OsRng, &my_id).unwrap();
+            let preprocessed = receiver
+                .preprocess_message(&mut accum, &sender_id, message)
+                .unwrap()
+                .unwrap();
+            let processed = receiver.process_message(&mut OsRng, preprocessed).unwrap();
+            accum.add_processed_message(processed).unwrap().unwrap();
+
+            let result = receiver.finalize_with_missing(&mut OsRng, &accum, &missing);
+            if !is_valid {
+                assert!(result.is_err());
+                continue;
+            }
+
+            // The statement survives serialization and names exactly the silent party.
+            let attestation = result.unwrap();
+            let serialized =
+                bincode::serde::encode_to_vec(&attestation, bincode::config::standard()).unwrap();
+            let attestation: NonParticipation<Signature, VerifyingKey> =
+                bincode::serde::decode_borrowed_from_slice(
+                    &serialized,
+                    bincode::config::standard(),
+                )
+                .unwrap();
+
+            assert_eq!(attestation.attester(), &my_id);
+            assert_eq!(attestation.session_id(), &session_id);
+            assert_eq!(attestation.round(), 1);
+            assert_eq!(
+                attestation.verify(WireFormat::default()).unwrap(),
+                BTreeSet::from([silent_id])
+            );
+        }
+    }
+
+    #[test]
+    fn diagnostic_reflects_round_state() {
+        let (signers, ids) = make_signers(3);
+        let session_id = SessionId::from_seed(b"session");
+
+        let mut sessions = key_gen_sessions(session_id, &signers, &ids);
+        let receiver = sessions.remove(0);
+        let my_id = receiver.verifier();
+        let sender = &sessions[0];
+        let absent = &sessions[1];
+
+        let mut accum = receiver.make_accumulator();
+        let (message, _artifact) = sender.make_message(&mut OsRng, &my_id).unwrap();
+        let preprocessed = receiver
+            .preprocess_message(&mut accum, &sender.verifier(), message)
+            .unwrap()
+            .unwrap();
+        let processed = receiver.process_message(&mut OsRng, preprocessed).unwrap();
+        accum.add_processed_message(processed).unwrap().unwrap();
+
+        let diagnostic = receiver.diagnostic(&accum).unwrap();
+        assert_eq!(
+            diagnostic,
+            SessionDiagnostic {
+                session_id,
+                my_id,
+                round: 1,
+                is_echo_round: false,
+                received_from: BTreeSet::from([sender.verifier()]),
+                missing_from: BTreeSet::from([absent.verifier()]),
+                cached_messages: 0,
+            }
+        );
+
+        // Only the public metadata is serialized.
+        let serializer = serde_assert::Serializer::builder().build();
+        let tokens = diagnostic.serialize(&serializer).unwrap();
+        let fields = tokens
+            .into_iter()
+            .filter_map(|token| match token {
+                Token::Field(name) => Some(name),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            [
+                "session_id",
+                "my_id",
+                "round",
+                "is_echo_round",
+                "received_from",
+                "missing_from",
+                "cached_messages"
+            ]
+        );
+    }
+
+    #[test]
+    fn round_evidence_is_verifiable() {
+        let (signers, ids) = make_signers(3);
+        let session_id = SessionId::from_seed(b"session");
+
+        let mut sessions = key_gen_sessions(session_id, &signers, &ids);
+        let receiver = sessions.remove(0);
+        let my_id = receiver.verifier();
+
+        let mut accum = receiver.make_accumulator();
+        for sender in sessions.iter() {
+            let (message, _artifact) = sender.make_message(&mut OsRng, &my_id).unwrap();
+            let preprocessed = receiver
+                .preprocess_message(&mut accum, &sender.verifier(), message)
+                .unwrap()
+                .unwrap();
+            let processed = receiver.process_message(&mut OsRng, preprocessed).unwrap();
+            accum.add_processed_message(processed).unwrap().unwrap();
+        }
+
+        let evidence = receiver.export_round_evidence(&accum).unwrap();
+        assert_eq!(evidence.session_id, session_id);
+        assert_eq!(evidence.round, 1);
+        // Two received messages, and the own broadcast sent to two parties.
+        assert_eq!(evidence.messages.len(), 4);
+
+        // The evidence can be passed to a third party and checked there.
+        let serialized =
+            bincode::serde::encode_to_vec(&evidence, bincode::config::standard()).unwrap();
+        let evidence: RoundEvidence<Signature, VerifyingKey> =
+            bincode::serde::decode_borrowed_from_slice(&serialized, bincode::config::standard())
+                .unwrap();
+        let verified = audit_quorum(&evidence.session_id, &ids, &evidence.messages).unwrap();
+        assert_eq!(verified, ids);
+
+        // Tampered evidence is rejected.
+        let other_session_id = SessionId::from_seed(b"another session");
+        assert!(audit_quorum(&other_session_id, &ids, &evidence.messages).is_err());
+    }
+
+    #[test]
+    fn stale_message_is_rejected() {
+        struct FixedClock(u64);
+
+        impl Clock for FixedClock {
+            fn now(&self) -> u64 {
+                self.0
+            }
+        }
+
+        let (signers, ids) = make_signers(2);
+        let session_id = SessionId::from_seed(b"session");
+
+        let make_session = |signer: &SigningKey| key_gen_session(session_id, signer, &ids);
+
+        let now = 1_700_000_000;
+        let max_skew = 60;
+        let receiver = make_session(&signers[1])
+            .with_max_skew(&mut OsRng, FixedClock(now), max_skew)
+            .unwrap();
+        let my_id = receiver.verifier();
+
+        // A message created an hour ago (e.g. captured and replayed by an attacker) is rejected.
+        let stale_sender = make_session(&signers[0])
+            .with_max_skew(&mut OsRng, FixedClock(now - 3600), max_skew)
+            .unwrap();
+        let (message, _artifact) = stale_sender.make_message(&mut OsRng, &my_id).unwrap();
+        let mut accum = receiver.make_accumulator();
+        let result = receiver.preprocess_message(&mut accum, &stale_sender.verifier(), message);
+        assert!(matches!(
+            result,
+            Err(Error::Remote(err)) if matches!(err.error, RemoteErrorEnum::StaleMessage)
+        ));
+
+        // If the timestamp is altered in transit, the signature check fails first,
+        // so the sender is not blamed for a stale message.
+        let (message, _artifact) = stale_sender.make_message(&mut OsRng, &my_id).unwrap();
+        let message = message.map_messages(|msg| msg.with_timestamp(Some(now)));
+        let result = receiver.preprocess_message(&mut accum, &stale_sender.verifier(), message);
+        assert!(matches!(
+            result,
+            Err(Error::Remote(err)) if matches!(err.error, RemoteErrorEnum::InvalidSignature(_))
+        ));
+
+        // A message without a timestamp is rejected as stale too.
+        let plain_sender = make_session(&signers[0]);
+        let (message, _artifact) = plain_sender.make_message(&mut OsRng, &my_id).unwrap();
+        let result = receiver.preprocess_message(&mut accum, &plain_sender.verifier(), message);
+        assert!(matches!(
+            result,
+            Err(Error::Remote(err)) if matches!(err.error, RemoteErrorEnum::StaleMessage)
+        ));
+
+        // A message within the allowed skew is accepted.
+        let sender = make_session(&signers[0])
+            .with_max_skew(&mut OsRng, FixedClock(now + 30), max_skew)
+            .unwrap();
+        let (message, _artifact) = sender.make_message(&mut OsRng, &my_id).unwrap();
+        let preprocessed = receiver
+            .preprocess_message(&mut accum, &sender.verifier(), message)
+            .unwrap()
+            .unwrap();
+        let processed = receiver.process_message(&mut OsRng, preprocessed).unwrap();
+        accum.add_processed_message(processed).unwrap().unwrap();
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn sessions_with_cbor_format() {
+        use crate::sessions::run_protocol;
+
+        let (signers, ids) = make_signers(3);
+        let session_id = SessionId::from_seed(b"session");
+
+        let sessions = key_gen_sessions(session_id, &signers, &ids)
+            .into_iter()
+            .map(|session| {
+                session
+                    .with_wire_format(&mut OsRng, WireFormat::Cbor)
+                    .unwrap()
+            })
+            .collect();
+        let results = run_protocol(&mut OsRng, sessions).unwrap();
+        let (key_share, _aux_info) = &results[0];
+        assert!(results
+            .iter()
+            .all(|(other, _)| other.verifying_key() == key_share.verifying_key()));
+
+        // A message in a different format is rejected.
+        let receiver = key_gen_session(session_id, &signers[1], &ids)
+            .with_wire_format(&mut OsRng, WireFormat::Cbor)
+            .unwrap();
+        let sender = key_gen_session(session_id, &signers[0], &ids);
+        let (message, _artifact) = sender
+            .make_message(&mut OsRng, &receiver.verifier())
+            .unwrap();
+        let mut accum = receiver.make_accumulator();
+        let preprocessed = receiver
+            .preprocess_message(&mut accum, &sender.verifier(), message)
+            .unwrap()
+            .unwrap();
+        assert!(receiver.process_message(&mut OsRng, preprocessed).is_err());
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn tracing_spans() {
+        use alloc::string::{String, ToString};
+        use core::fmt::Debug;
+        use core::sync::atomic::{AtomicU64, Ordering};
+        use std::sync::{Arc, Mutex};
+
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        use crate::make_interactive_signing_session;
+        use crate::sessions::InProcessNetwork;
+
+        #[derive(Default)]
+        struct Fields(BTreeMap<&'static str, String>);
+
+        impl Visit for Fields {
+            fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+                self.0.insert(field.name(), format!("{value:?}"));
+            }
+        }
+
+        // Records the fields of all the spans and events.
+        #[derive(Default, Clone)]
+        struct Recorder {
+            next_id: Arc<AtomicU64>,
+            spans: Arc<Mutex<Vec<Fields>>>,
+            events: Arc<Mutex<Vec<Fields>>>,
+        }
+
+        impl Subscriber for Recorder {
+            fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let mut fields = Fields::default();
+                span.record(&mut fields);
+                self.spans.lock().unwrap().push(fields);
+                Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+            }
+
+            fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+            fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+            fn event(&self, event: &Event<'_>) {
+                let mut fields = Fields::default();
+                event.record(&mut fields);
+                self.events.lock().unwrap().push(fields);
+            }
+
+            fn enter(&self, _span: &Id) {}
+
+            fn exit(&self, _span: &Id) {}
+        }
+
+        let (signers, ids) = make_signers(3);
+        let session_id = SessionId::from_seed(b"session");
+
+        let key_shares =
+            KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &ids, None);
+        let aux_infos = AuxInfo::new_centralized(&mut OsRng, &ids);
+        let message = [1u8; 32];
+
+        let sessions = signers
+            .iter()
+            .map(|signer| {
+                let id = signer.verifying_key();
+                make_interactive_signing_session::<_, Signature, _, _>(
+                    &mut OsRng,
+                    session_id,
+                    signer.clone(),
+                    &ids,
+                    &key_shares[id],
+                    &aux_infos[id],
+                    &message,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            InProcessNetwork::new().run(&mut OsRng, sessions).unwrap();
+        });
+
+        // A span is opened for every round, including the echo round after the first one.
+        let spans = recorder.spans.lock().unwrap();
+        let rounds = spans
+            .iter()
+            .map(|fields| (fields.0["round"].clone(), fields.0["is_echo_round"].clone()))
+            .collect::<BTreeSet<_>>();
+        let expected_rounds = [
+            ("1", "false"),
+            ("1", "true"),
+            ("2", "false"),
+            ("3", "false"),
+            ("4", "false"),
+        ]
+        .into_iter()
+        .map(|(round, is_echo)| (round.to_string(), is_echo.to_string()))
+        .collect::<BTreeSet<_>>();
+        assert_eq!(rounds, expected_rounds);
+        assert!(spans
+            .iter()
+            .all(|fields| fields.0["session_id"] == format!("{session_id:?}")));
+
+        let events = recorder.events.lock().unwrap();
+        let messages = events
+            .iter()
+            .map(|fields| fields.0["message"].clone())
+            .collect::<BTreeSet<_>>();
+        for message in [
+            "Message created",
+            "Message processed",
+            "Round finalized, starting the next round",
+            "Round finalized with a result",
+        ] {
+            assert!(messages.contains(message));
+        }
+    }
 }
@@ -3,7 +3,7 @@ use alloc::format;
 use alloc::string::{String, ToString};
 
 use rand_core::CryptoRngCore;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use signature::hazmat::{PrehashVerifier, RandomizedPrehashSigner};
 
 use super::error::LocalError;
@@ -23,6 +23,12 @@ impl SessionId {
                 .finalize(),
         )
     }
+
+    /// Restores a session ID from its byte representation (see the [`AsRef`] impl).
+    #[cfg(any(test, feature = "reassembly"))]
+    pub(crate) fn try_from_bytes(bytes: &[u8]) -> Option<Self> {
+        Some(Self(HashOutput(bytes.try_into().ok()?)))
+    }
 }
 
 impl AsRef<[u8]> for SessionId {
@@ -31,18 +37,28 @@ impl AsRef<[u8]> for SessionId {
     }
 }
 
-fn message_hash(
+fn message_hash<I: Serialize>(
     session_id: &SessionId,
     round: u8,
     message_type: MessageType,
+    recipient: Option<&I>,
+    timestamp: Option<u64>,
     payload: &[u8],
 ) -> HashOutput {
-    FofHasher::new_with_dst(b"SignedMessage")
+    let mut digest = FofHasher::new_with_dst(b"SignedMessage")
         .chain(session_id)
         .chain(&round)
         .chain(&message_type)
-        .chain(&payload)
-        .finalize()
+        .chain(&payload);
+    // The optional fields are only hashed (each with its own tag) when they are set,
+    // so that the signed bytes of the messages without them stay the same.
+    if let Some(recipient) = recipient {
+        digest = digest.chain_bytes(b"recipient").chain(recipient);
+    }
+    if let Some(timestamp) = timestamp {
+        digest = digest.chain_bytes(b"timestamp").chain(&timestamp);
+    }
+    digest.finalize()
 }
 
 /// Protocol message type.
@@ -53,6 +69,8 @@ pub enum MessageType {
     Direct,
     /// A service message for echo-broadcast.
     Echo,
+    /// A statement listing the parties whose messages for the round were not received.
+    NonParticipation,
 }
 
 /// A (yet) unverified message from a round that includes the payload signature.
@@ -61,15 +79,53 @@ pub struct SignedMessage<Sig> {
     session_id: SessionId,
     round: u8,
     message_type: MessageType,
-    #[serde(with = "serde_bytes::as_base64")]
-    payload: Box<[u8]>,
+    timestamp: Option<u64>,
+    payload: Payload,
     signature: Sig,
 }
 
+/// The message payload along with the sender's choice of whether to compress it on the wire.
+// The payload is signed before compression,
+// so the signature does not depend on whether the sender compresses it.
+#[derive(Debug, Clone)]
+struct Payload {
+    bytes: Box<[u8]>,
+    compress: bool,
+}
+
+impl PartialEq for Payload {
+    fn eq(&self, other: &Self) -> bool {
+        // The compression is only a property of the encoding
+        self.bytes == other.bytes
+    }
+}
+
+impl Eq for Payload {}
+
+impl Serialize for Payload {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serde_bytes::as_base64_with_header::serialize(&self.bytes, self.compress, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Payload {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self {
+            bytes: serde_bytes::as_base64_with_header::deserialize(deserializer)?,
+            compress: false,
+        })
+    }
+}
+
 impl<Sig> SignedMessage<Sig> {
-    pub(crate) fn verify(
+    /// Verifies the message signature.
+    ///
+    /// `recipient` must be set for direct messages (and only for them),
+    /// since those are signed together with their intended recipient.
+    pub(crate) fn verify<I: Serialize>(
         self,
         verifier: &impl PrehashVerifier<Sig>,
+        recipient: Option<&I>,
     ) -> Result<VerifiedMessage<Sig>, String> {
         verifier
             .verify_prehash(
@@ -77,7 +133,9 @@ impl<Sig> SignedMessage<Sig> {
                     &self.session_id,
                     self.round,
                     self.message_type,
-                    &self.payload,
+                    recipient,
+                    self.timestamp,
+                    &self.payload.bytes,
                 )
                 .as_ref(),
                 &self.signature,
@@ -101,11 +159,45 @@ impl<Sig> SignedMessage<Sig> {
         self.message_type
     }
 
+    /// The time of the message creation (in seconds since the UNIX epoch), if it was set.
+    pub fn timestamp(&self) -> Option<u64> {
+        self.timestamp
+    }
+
+    pub(crate) fn payload(&self) -> &[u8] {
+        &self.payload.bytes
+    }
+
+    /// Sets whether the payload will be compressed when the message is serialized.
+    pub(crate) fn with_compression(mut self, compress: bool) -> Self {
+        self.payload.compress = compress;
+        self
+    }
+
+    /// Replaces the timestamp without re-signing the message (as a relay could do).
+    #[cfg(test)]
+    pub(crate) fn with_timestamp(mut self, timestamp: Option<u64>) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Checks that the timestamp of the message is set and lies within `max_skew` seconds of `now`.
+    pub(crate) fn is_fresh(&self, now: u64, max_skew: u64) -> bool {
+        match self.timestamp {
+            Some(timestamp) => {
+                timestamp >= now.saturating_sub(max_skew)
+                    && timestamp <= now.saturating_add(max_skew)
+            }
+            None => false,
+        }
+    }
+
     /// Compares the "significant" part of the messages (that is, everything but signatures)
     pub fn is_same_as(&self, other: &Self) -> bool {
         self.session_id == other.session_id
             && self.round == other.round
             && self.message_type == other.message_type
+            && self.timestamp == other.timestamp
             && self.payload == other.payload
     }
 }
@@ -114,31 +206,50 @@ impl<Sig> SignedMessage<Sig> {
 pub(crate) struct VerifiedMessage<Sig>(SignedMessage<Sig>);
 
 impl<Sig> VerifiedMessage<Sig> {
-    pub(crate) fn new(
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new<I: Serialize>(
         rng: &mut impl CryptoRngCore,
         signer: &impl RandomizedPrehashSigner<Sig>,
         session_id: &SessionId,
         round: u8,
         message_type: MessageType,
+        recipient: Option<&I>,
+        timestamp: Option<u64>,
         message_bytes: &[u8],
     ) -> Result<Self, LocalError> {
         // In order for the messages be impossible to reuse by a malicious third party,
         // we need to sign, besides the message itself, the session and the round in this session
         // it belongs to.
+        // Direct messages are also bound to their recipient,
+        // so that they could not be forwarded to another party.
+        // If the timestamp is set, it is signed too, so that the receiver could limit
+        // the time window in which the message can be replayed.
         // We also need the exact way we sign this to be a part of the public ABI,
         // so that these signatures could be verified by a third party.
 
         let signature = signer
             .sign_prehash_with_rng(
                 rng,
-                message_hash(session_id, round, message_type, message_bytes).as_ref(),
+                message_hash(
+                    session_id,
+                    round,
+                    message_type,
+                    recipient,
+                    timestamp,
+                    message_bytes,
+                )
+                .as_ref(),
             )
             .map_err(|err| LocalError(err.to_string()))?;
         Ok(Self(SignedMessage {
             session_id: *session_id,
             round,
             message_type,
-            payload: message_bytes.into(),
+            timestamp,
+            payload: Payload {
+                bytes: message_bytes.into(),
+                compress: false,
+            },
             signature,
         }))
     }
@@ -152,6 +263,195 @@ impl<Sig> VerifiedMessage<Sig> {
     }
 
     pub fn payload(&self) -> &[u8] {
-        &self.0.payload
+        self.0.payload()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+    use rand_core::{OsRng, RngCore};
+    use serde::Serialize;
+    use serde_assert::Token;
+
+    use super::{message_hash, MessageType, SessionId, SignedMessage, VerifiedMessage};
+    use crate::tools::hashing::{Chain, FofHasher};
+
+    #[test]
+    fn direct_message_is_bound_to_recipient() {
+        let sender = SigningKey::random(&mut OsRng);
+        let recipient = *SigningKey::random(&mut OsRng).verifying_key();
+        let other = *SigningKey::random(&mut OsRng).verifying_key();
+        let session_id = SessionId::from_seed(b"session");
+
+        let message = VerifiedMessage::<Signature>::new(
+            &mut OsRng,
+            &sender,
+            &session_id,
+            1,
+            MessageType::Direct,
+            Some(&recipient),
+            None,
+            b"payload",
+        )
+        .unwrap()
+        .into_unverified();
+
+        assert!(message
+            .clone()
+            .verify(sender.verifying_key(), Some(&other))
+            .is_err());
+        assert!(message
+            .clone()
+            .verify::<VerifyingKey>(sender.verifying_key(), None)
+            .is_err());
+        assert!(message
+            .verify(sender.verifying_key(), Some(&recipient))
+            .is_ok());
+    }
+
+    #[test]
+    fn payload_serialization() {
+        let signer = SigningKey::random(&mut OsRng);
+        let session_id = SessionId::from_seed(b"session");
+
+        let make_message = |payload: &[u8]| {
+            VerifiedMessage::<Signature>::new::<VerifyingKey>(
+                &mut OsRng,
+                &signer,
+                &session_id,
+                1,
+                MessageType::Broadcast,
+                None,
+                None,
+                payload,
+            )
+            .unwrap()
+            .into_unverified()
+        };
+
+        // In binary formats the payload is serialized as plain bytes,
+        // without the size overhead of a text encoding.
+        let mut payload = [0u8; 1024];
+        OsRng.fill_bytes(&mut payload);
+        let message = make_message(&payload);
+
+        let config = bincode::config::standard();
+        let serialized = bincode::serde::encode_to_vec(&message, config).unwrap();
+        assert!(serialized.len() < payload.len() + 128);
+        assert!(serialized
+            .windows(payload.len())
+            .any(|window| window == payload));
+
+        let deserialized: SignedMessage<Signature> =
+            bincode::serde::decode_borrowed_from_slice(&serialized, config).unwrap();
+        assert_eq!(deserialized, message);
+
+        // In human-readable formats base64 is used.
+        let message = make_message(b"payload");
+        let serializer = serde_assert::Serializer::builder().build();
+        let tokens = message.serialize(&serializer).unwrap();
+        assert!(tokens
+            .into_iter()
+            .any(|token| matches!(token, Token::Str(value) if value == "cGF5bG9hZA")));
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn compressed_payload_round_trip() {
+        let signer = SigningKey::random(&mut OsRng);
+        let session_id = SessionId::from_seed(b"session");
+
+        let payload = [1u8; 4096];
+        let message = VerifiedMessage::<Signature>::new::<VerifyingKey>(
+            &mut OsRng,
+            &signer,
+            &session_id,
+            1,
+            MessageType::Broadcast,
+            None,
+            None,
+            &payload,
+        )
+        .unwrap()
+        .into_unverified()
+        .with_compression(true);
+
+        let config = bincode::config::standard();
+        let serialized = bincode::serde::encode_to_vec(&message, config).unwrap();
+        assert!(serialized.len() < payload.len() / 10);
+
+        // The signature covers the uncompressed payload
+        let deserialized: SignedMessage<Signature> =
+            bincode::serde::decode_borrowed_from_slice(&serialized, config).unwrap();
+        assert_eq!(deserialized, message);
+        let verified = deserialized
+            .verify::<VerifyingKey>(signer.verifying_key(), None)
+            .unwrap();
+        assert_eq!(verified.payload(), payload);
+    }
+
+    #[test]
+    fn optional_fields_are_only_hashed_when_set() {
+        let session_id = SessionId::from_seed(b"session");
+        let payload: &[u8] = b"payload";
+        let plain_hash = FofHasher::new_with_dst(b"SignedMessage")
+            .chain(&session_id)
+            .chain(&1u8)
+            .chain(&MessageType::Broadcast)
+            .chain(&payload)
+            .finalize();
+
+        let hash = |recipient: Option<&u32>, timestamp: Option<u64>| {
+            message_hash(
+                &session_id,
+                1,
+                MessageType::Broadcast,
+                recipient,
+                timestamp,
+                payload,
+            )
+        };
+
+        assert_eq!(hash(None, None), plain_hash);
+        assert_ne!(hash(Some(&1), None), plain_hash);
+        assert_ne!(hash(None, Some(1)), plain_hash);
+        // The tags keep a recipient from being confused with a timestamp of the same value
+        assert_ne!(hash(Some(&1), None), hash(None, Some(1)));
+    }
+
+    #[test]
+    fn timestamp_is_signed() {
+        let signer = SigningKey::random(&mut OsRng);
+        let session_id = SessionId::from_seed(b"session");
+
+        let message = VerifiedMessage::<Signature>::new::<VerifyingKey>(
+            &mut OsRng,
+            &signer,
+            &session_id,
+            1,
+            MessageType::Broadcast,
+            None,
+            Some(1000),
+            b"payload",
+        )
+        .unwrap()
+        .into_unverified();
+
+        assert_eq!(message.timestamp(), Some(1000));
+        assert!(message.is_fresh(1010, 10));
+        assert!(message.is_fresh(990, 10));
+        assert!(!message.is_fresh(1011, 10));
+        assert!(!message.is_fresh(989, 10));
+
+        // Changing the timestamp invalidates the signature
+        let mut replayed = message.clone();
+        replayed.timestamp = Some(2000);
+        assert!(replayed
+            .verify::<VerifyingKey>(signer.verifying_key(), None)
+            .is_err());
+        assert!(message
+            .verify::<VerifyingKey>(signer.verifying_key(), None)
+            .is_ok());
     }
 }
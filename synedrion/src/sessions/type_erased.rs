@@ -6,28 +6,34 @@ This way they can be used in a state machine loop without code repetition.
 use alloc::boxed::Box;
 use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::format;
-use alloc::string::{String, ToString};
+use alloc::string::String;
 use core::any::{Any, TypeId};
 
 use rand_core::CryptoRngCore;
 use serde::{Deserialize, Serialize};
 
 use super::error::LocalError;
+use super::wire_format::WireFormat;
 use crate::rounds::{
     self, FinalizableToNextRound, FinalizableToResult, ProtocolResult, Round, ToNextRound, ToResult,
 };
-
-pub(crate) fn serialize_message(message: &impl Serialize) -> Result<Box<[u8]>, LocalError> {
-    bincode::serde::encode_to_vec(message, bincode::config::standard())
-        .map(|serialized| serialized.into_boxed_slice())
-        .map_err(|err| LocalError(format!("Failed to serialize: {err:?}")))
+#[cfg(any(test, feature = "state-digest"))]
+use crate::tools::hashing::HashOutput;
+
+pub(crate) fn serialize_message(
+    wire_format: WireFormat,
+    message: &impl Serialize,
+) -> Result<Box<[u8]>, LocalError> {
+    wire_format
+        .serialize(message)
+        .map_err(|err| LocalError(format!("Failed to serialize: {err}")))
 }
 
 pub(crate) fn deserialize_message<M: for<'de> Deserialize<'de>>(
+    wire_format: WireFormat,
     message_bytes: &[u8],
 ) -> Result<M, String> {
-    bincode::serde::decode_borrowed_from_slice(message_bytes, bincode::config::standard())
-        .map_err(|err| err.to_string())
+    wire_format.deserialize(message_bytes)
 }
 
 pub(crate) enum FinalizeOutcome<I, Res: ProtocolResult> {
@@ -46,6 +52,8 @@ pub(crate) enum ReceiveError<Res: ProtocolResult> {
     InvalidContents(String),
     /// Error while deserializing the given message.
     CannotDeserialize(String),
+    /// The message was deserialized, but is malformed.
+    InvalidStructure(&'static str),
     /// An error from the protocol level
     Protocol(Res::ProvableError),
 }
@@ -96,27 +104,34 @@ pub(crate) trait DynRound<I, Res: ProtocolResult>: Send + Sync {
     fn next_round_num(&self) -> Option<u8>;
 
     fn requires_echo(&self) -> bool;
+    fn sends_direct_messages(&self) -> bool;
     fn message_destinations(&self) -> &BTreeSet<I>;
     fn expecting_messages_from(&self) -> &BTreeSet<I>;
     fn make_broadcast_message(
         &self,
         rng: &mut dyn CryptoRngCore,
+        wire_format: WireFormat,
     ) -> Result<Option<Box<[u8]>>, LocalError>;
     #[allow(clippy::type_complexity)]
     fn make_direct_message(
         &self,
         rng: &mut dyn CryptoRngCore,
+        wire_format: WireFormat,
         destination: &I,
     ) -> Result<(Option<Box<[u8]>>, DynArtifact), LocalError>;
     fn verify_message(
         &self,
         rng: &mut dyn CryptoRngCore,
+        wire_format: WireFormat,
         from: &I,
         broadcast_data: Option<&[u8]>,
         direct_data: Option<&[u8]>,
     ) -> Result<DynPayload, ReceiveError<Res>>;
+    fn verification_cost(&self) -> usize;
     fn can_finalize(&self, accum: &DynRoundAccum<I>) -> bool;
     fn missing_messages(&self, accum: &DynRoundAccum<I>) -> BTreeSet<I>;
+    #[cfg(any(test, feature = "state-digest"))]
+    fn state_digest(&self) -> Option<HashOutput>;
 }
 
 fn is_null_type<T: 'static>() -> bool {
@@ -151,6 +166,7 @@ where
     fn make_broadcast_message(
         &self,
         rng: &mut dyn CryptoRngCore,
+        wire_format: WireFormat,
     ) -> Result<Option<Box<[u8]>>, LocalError> {
         if is_null_type::<R::BroadcastMessage>() {
             return Ok(None);
@@ -159,7 +175,7 @@ where
         let mut boxed_rng = BoxedRng(rng);
         let typed_message = self.make_broadcast_message(&mut boxed_rng);
         let serialized = typed_message
-            .map(|message| serialize_message(&message))
+            .map(|message| serialize_message(wire_format, &message))
             .transpose()?;
         Ok(serialized)
     }
@@ -167,6 +183,7 @@ where
     fn make_direct_message(
         &self,
         rng: &mut dyn CryptoRngCore,
+        wire_format: WireFormat,
         destination: &I,
     ) -> Result<(Option<Box<[u8]>>, DynArtifact), LocalError> {
         let null_message = is_null_type::<R::DirectMessage>();
@@ -182,7 +199,7 @@ where
         let message = if null_message {
             None
         } else {
-            Some(serialize_message(&typed_message)?)
+            Some(serialize_message(wire_format, &typed_message)?)
         };
 
         Ok((message, DynArtifact(Box::new(typed_artifact))))
@@ -191,6 +208,7 @@ where
     fn verify_message(
         &self,
         rng: &mut dyn CryptoRngCore,
+        wire_format: WireFormat,
         from: &I,
         broadcast_data: Option<&[u8]>,
         direct_data: Option<&[u8]>,
@@ -198,39 +216,47 @@ where
         let null_broadcast = is_null_type::<R::BroadcastMessage>();
         let null_direct = is_null_type::<R::DirectMessage>();
 
-        let broadcast_data = if let Some(data) = broadcast_data {
-            data
+        let (broadcast_format, broadcast_data) = if let Some(data) = broadcast_data {
+            (wire_format, data)
         } else {
             if !null_broadcast {
                 return Err(ReceiveError::InvalidContents(
                     "Expected a non-null broadcast message".into(),
                 ));
             }
-            b""
+            // Null messages are not sent, and `()` is encoded as zero bytes in `bincode`
+            // (but not in every other format).
+            (WireFormat::Bincode, b"".as_slice())
         };
 
         let broadcast_message: <R as Round<I>>::BroadcastMessage =
-            match deserialize_message(broadcast_data) {
+            match deserialize_message(broadcast_format, broadcast_data) {
                 Ok(message) => message,
                 Err(err) => return Err(ReceiveError::CannotDeserialize(err)),
             };
 
-        let direct_data = if let Some(data) = direct_data {
-            data
+        let (direct_format, direct_data) = if let Some(data) = direct_data {
+            (wire_format, data)
         } else {
             if !null_direct {
                 return Err(ReceiveError::InvalidContents(
                     "Expected a non-null direct message".into(),
                 ));
             }
-            b""
+            // Same as for the broadcast above.
+            (WireFormat::Bincode, b"".as_slice())
         };
 
-        let direct_message: <R as Round<I>>::DirectMessage = match deserialize_message(direct_data)
-        {
-            Ok(message) => message,
-            Err(err) => return Err(ReceiveError::CannotDeserialize(err)),
-        };
+        let direct_message: <R as Round<I>>::DirectMessage =
+            match deserialize_message(direct_format, direct_data) {
+                Ok(message) => message,
+                Err(err) => return Err(ReceiveError::CannotDeserialize(err)),
+            };
+
+        self.validate_structure(from, &broadcast_message, &direct_message)
+            .map_err(ReceiveError::InvalidStructure)?;
+        self.check_consistency(from, &broadcast_message, &direct_message)
+            .map_err(|msg| ReceiveError::InvalidContents(msg.into()))?;
 
         let mut boxed_rng = BoxedRng(rng);
 
@@ -241,10 +267,18 @@ where
         Ok(DynPayload(Box::new(payload)))
     }
 
+    fn verification_cost(&self) -> usize {
+        Round::verification_cost(self)
+    }
+
     fn requires_echo(&self) -> bool {
         <R as Round<I>>::REQUIRES_ECHO
     }
 
+    fn sends_direct_messages(&self) -> bool {
+        !is_null_type::<R::DirectMessage>()
+    }
+
     fn can_finalize(&self, accum: &DynRoundAccum<I>) -> bool {
         self.can_finalize(&accum.received)
     }
@@ -252,6 +286,11 @@ where
     fn missing_messages(&self, accum: &DynRoundAccum<I>) -> BTreeSet<I> {
         self.missing_messages(&accum.received)
     }
+
+    #[cfg(any(test, feature = "state-digest"))]
+    fn state_digest(&self) -> Option<HashOutput> {
+        Round::state_digest(self)
+    }
 }
 
 pub(crate) struct DynRoundAccum<I> {
@@ -278,6 +317,10 @@ impl<I: Ord + Clone> DynRoundAccum<I> {
         self.received.contains(from)
     }
 
+    pub fn received(&self) -> &BTreeSet<I> {
+        &self.received
+    }
+
     pub fn add_payload(&mut self, from: &I, payload: DynPayload) -> Result<(), AccumAddError> {
         if self.received.contains(from) {
             return Err(AccumAddError::SlotTaken);
@@ -419,3 +462,30 @@ const _: () = {
         }
     }
 };
+
+#[cfg(test)]
+mod tests {
+    use alloc::boxed::Box;
+
+    use super::{AccumAddError, DynArtifact, DynPayload, DynRoundAccum};
+
+    #[test]
+    fn accumulator_rejects_filled_slots() {
+        let mut accum = DynRoundAccum::<u32>::new();
+
+        accum.add_payload(&1, DynPayload(Box::new(1u8))).unwrap();
+        assert!(matches!(
+            accum.add_payload(&1, DynPayload(Box::new(2u8))),
+            Err(AccumAddError::SlotTaken)
+        ));
+        accum.add_payload(&2, DynPayload(Box::new(2u8))).unwrap();
+        assert!(accum.contains(&1) && accum.contains(&2) && !accum.contains(&3));
+
+        // Artifacts are tracked separately from payloads
+        accum.add_artifact(&1, DynArtifact::null()).unwrap();
+        assert!(matches!(
+            accum.add_artifact(&1, DynArtifact::null()),
+            Err(AccumAddError::SlotTaken)
+        ));
+    }
+}
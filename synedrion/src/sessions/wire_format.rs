@@ -0,0 +1,105 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+#[cfg(feature = "cbor")]
+use alloc::{vec, vec::Vec};
+
+use serde::{Deserialize, Serialize};
+
+/// A serialization format of the messages.
+///
+/// The sessions use [`WireFormat::Bincode`] unless another format is chosen
+/// with [`Session::with_wire_format`](`crate::Session::with_wire_format`).
+/// All the parties of a session must use the same format;
+/// a payload in a different format is rejected as undeserializable.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WireFormat {
+    /// The `bincode` format with the standard configuration.
+    #[default]
+    Bincode,
+    /// The CBOR format (RFC 8949), for interoperability with implementations in other languages
+    /// (requires the `cbor` feature).
+    #[cfg(feature = "cbor")]
+    Cbor,
+}
+
+impl WireFormat {
+    /// Serializes a value.
+    pub(crate) fn serialize(self, value: &impl Serialize) -> Result<Box<[u8]>, String> {
+        match self {
+            Self::Bincode => bincode::serde::encode_to_vec(value, bincode::config::standard())
+                .map(|serialized| serialized.into_boxed_slice())
+                .map_err(|err| format!("{err:?}")),
+            #[cfg(feature = "cbor")]
+            Self::Cbor => {
+                let mut serialized = Vec::new();
+                ciborium::into_writer(value, &mut serialized).map_err(|err| format!("{err:?}"))?;
+                Ok(serialized.into_boxed_slice())
+            }
+        }
+    }
+
+    /// Deserializes a value serialized by [`Self::serialize`].
+    pub(crate) fn deserialize<T: for<'de> Deserialize<'de>>(
+        self,
+        bytes: &[u8],
+    ) -> Result<T, String> {
+        match self {
+            Self::Bincode => {
+                bincode::serde::decode_borrowed_from_slice(bytes, bincode::config::standard())
+                    .map_err(|err| err.to_string())
+            }
+            #[cfg(feature = "cbor")]
+            Self::Cbor => {
+                // `ciborium::from_reader()` cannot read the byte strings longer than its
+                // fixed-size scratch buffer, and none of them can be longer than the input.
+                let mut scratch = vec![0u8; bytes.len()];
+                ciborium::de::from_reader_with_buffer(bytes, &mut scratch)
+                    .map_err(|err| format!("{err:?}"))
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "cbor"))]
+mod tests {
+    use alloc::vec::Vec;
+
+    use serde::{Deserialize, Serialize};
+
+    use super::WireFormat;
+
+    #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+    struct Payload {
+        flag: bool,
+        data: Vec<u8>,
+    }
+
+    fn payload() -> Payload {
+        Payload {
+            flag: true,
+            data: [1, 2, 3].into(),
+        }
+    }
+
+    #[test]
+    fn cbor_round_trip() {
+        let serialized = WireFormat::Cbor.serialize(&payload()).unwrap();
+        let deserialized: Payload = WireFormat::Cbor.deserialize(&serialized).unwrap();
+        assert_eq!(deserialized, payload());
+    }
+
+    #[test]
+    fn mixed_formats_are_rejected() {
+        let serialized = WireFormat::Cbor.serialize(&payload()).unwrap();
+        assert!(WireFormat::Bincode
+            .deserialize::<Payload>(&serialized)
+            .is_err());
+
+        let serialized = WireFormat::Bincode.serialize(&payload()).unwrap();
+        assert!(WireFormat::Cbor
+            .deserialize::<Payload>(&serialized)
+            .is_err());
+    }
+}
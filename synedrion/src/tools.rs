@@ -1,4 +1,7 @@
 pub(crate) mod bitvec;
+pub(crate) mod compression;
 pub(crate) mod hashing;
+#[cfg(feature = "parallel")]
+pub(crate) mod parallel;
 pub(crate) mod serde_bytes;
 pub(crate) mod sss;
@@ -17,6 +17,23 @@ impl BitVec {
         rng.fill_bytes(&mut bytes);
         Self(bytes.into())
     }
+
+    /// Creates a vector from the given bytes.
+    #[cfg(test)]
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self(bytes.into())
+    }
+
+    /// Returns the length of the vector in bytes.
+    pub fn byte_len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl AsRef<[u8]> for BitVec {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
 }
 
 impl BitXorAssign<&BitVec> for BitVec {
@@ -0,0 +1,127 @@
+//! Compression of the message payloads on the wire.
+//!
+//! The payload is prefixed with a one-byte header
+//! indicating whether the rest of it is compressed or stored as is.
+//! The header is always written and accepted, so whether to compress the outgoing messages
+//! is a runtime choice of each party; compressing and decompressing the payloads
+//! requires the `compression` feature.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+#[cfg(feature = "compression")]
+use miniz_oxide::{deflate::compress_to_vec, inflate::decompress_to_vec_with_limit};
+
+const UNCOMPRESSED: u8 = 0;
+const DEFLATE: u8 = 1;
+
+// The default level of `zlib`, a reasonable trade-off between the speed and the ratio.
+#[cfg(feature = "compression")]
+const COMPRESSION_LEVEL: u8 = 6;
+
+/// The maximum size of a decompressed payload.
+///
+/// A small compressed payload can expand into an arbitrarily large one,
+/// so this bounds the amount of memory a misbehaving party can make this node allocate.
+#[cfg(feature = "compression")]
+pub(crate) const MAX_DECOMPRESSED_LEN: usize = 1 << 24;
+
+/// Prefixes `data` with the header, compressing it if `compress` is set
+/// and that makes it shorter.
+///
+/// Without the `compression` feature the data is always stored as is.
+pub(crate) fn encode(data: &[u8], compress: bool) -> Vec<u8> {
+    #[cfg(feature = "compression")]
+    if compress {
+        let compressed = compress_to_vec(data, COMPRESSION_LEVEL);
+        if compressed.len() < data.len() {
+            return with_header(DEFLATE, &compressed);
+        }
+    }
+    #[cfg(not(feature = "compression"))]
+    let _ = compress; // Only the uncompressed encoding is available
+
+    with_header(UNCOMPRESSED, data)
+}
+
+/// Restores the data serialized by [`encode`].
+pub(crate) fn decode(data: &[u8]) -> Result<Vec<u8>, String> {
+    let (header, body) = data
+        .split_first()
+        .ok_or_else(|| String::from("The compression header is missing"))?;
+    match *header {
+        UNCOMPRESSED => Ok(body.into()),
+        #[cfg(feature = "compression")]
+        DEFLATE => decompress_to_vec_with_limit(body, MAX_DECOMPRESSED_LEN)
+            .map_err(|err| format!("Failed to decompress: {err:?}")),
+        #[cfg(not(feature = "compression"))]
+        DEFLATE => Err("Decompression requires the `compression` feature".into()),
+        _ => Err(format!("Unknown compression header: {header}")),
+    }
+}
+
+fn with_header(header: u8, body: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(body.len() + 1);
+    result.push(header);
+    result.extend(body);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::{decode, encode, DEFLATE};
+
+    #[test]
+    fn header_is_always_written() {
+        let data = vec![7u8; 4096];
+        let encoded = encode(&data, false);
+        assert_eq!(encoded.len(), data.len() + 1);
+        assert_eq!(decode(&encoded).unwrap(), data);
+
+        assert!(decode(&[]).is_err());
+        assert!(decode(&[2, 0, 0]).is_err());
+    }
+
+    #[cfg(not(feature = "compression"))]
+    #[test]
+    fn compressed_data_requires_feature() {
+        // Compression is not available, so the data is stored as is
+        let data = vec![7u8; 4096];
+        assert_eq!(encode(&data, true), encode(&data, false));
+
+        assert!(decode(&[DEFLATE, 0, 0]).is_err());
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn round_trip() {
+        use rand_core::{OsRng, RngCore};
+
+        // Repetitive data is compressed
+        let data = vec![7u8; 4096];
+        let encoded = encode(&data, true);
+        assert_eq!(encoded[0], DEFLATE);
+        assert!(encoded.len() < data.len() / 10);
+        assert_eq!(decode(&encoded).unwrap(), data);
+
+        // Random data is stored as is, with only the header added
+        let mut data = vec![0u8; 4096];
+        OsRng.fill_bytes(&mut data);
+        let encoded = encode(&data, true);
+        assert_eq!(encoded.len(), data.len() + 1);
+        assert_eq!(decode(&encoded).unwrap(), data);
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn decompression_is_bounded() {
+        use super::MAX_DECOMPRESSED_LEN;
+
+        let data = vec![0u8; MAX_DECOMPRESSED_LEN + 1];
+        let encoded = encode(&data, true);
+        assert!(decode(&encoded).is_err());
+    }
+}
@@ -5,7 +5,7 @@ use sha3::{Shake256, Shake256Reader};
 
 use hashing_serializer::HashingSerializer;
 
-use crate::curve::Scalar;
+use crate::curve::{Curve, Scalar};
 use crate::tools::serde_bytes;
 use crypto_bigint::{Encoding, Integer, NonZero};
 
@@ -79,11 +79,19 @@ impl FofHasher {
         HashOutput(self.0.finalize().into())
     }
 
-    pub fn finalize_to_scalar(self) -> Scalar {
+    pub fn finalize_to_scalar<C: Curve>(self) -> Scalar<C> {
         Scalar::from_digest(self.0)
     }
 }
 
+/// Derives the randomness for one phase of a composite protocol from the shared randomness
+/// of the whole session, so that each phase gets an independent transcript seed.
+pub(crate) fn derive_phase_randomness(phase_dst: &[u8], shared_randomness: &[u8]) -> HashOutput {
+    FofHasher::new_with_dst(phase_dst)
+        .chain(&shared_randomness)
+        .finalize()
+}
+
 /// Wraps an extendable output hash for easier replacement, and standardizes the use of DST.
 pub struct XofHasher(Shake256);
 
@@ -157,7 +165,7 @@ where
     let backend_modulus = modulus.as_ref();
 
     let n_bits = backend_modulus.bits_vartime();
-    let n_bytes = (n_bits + 7) / 8; // ceiling division by 8
+    let n_bytes = n_bits.div_ceil(8);
 
     // If the number of bits is not a multiple of 8,
     // use a mask to zeroize the high bits in the gererated random bytestring,
@@ -0,0 +1,34 @@
+//! Running independent randomized tasks on the `rayon` thread pool.
+
+use alloc::vec::Vec;
+
+use rand_chacha::ChaCha8Rng;
+use rand_core::{CryptoRngCore, SeedableRng};
+use rayon::prelude::*;
+
+/// Applies `f` to each of `items` in parallel, giving each call its own RNG.
+///
+/// The RNGs are seeded from `rng` in the order of `items` before any of the calls are made,
+/// so the results are the same as of the calls made serially with the same seeds,
+/// regardless of the order the calls are scheduled in.
+pub(crate) fn map_with_rng<T, U, F>(rng: &mut impl CryptoRngCore, items: &[T], f: F) -> Vec<U>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&mut ChaCha8Rng, &T) -> U + Sync,
+{
+    let seeds = items
+        .iter()
+        .map(|_| {
+            let mut seed = <ChaCha8Rng as SeedableRng>::Seed::default();
+            rng.fill_bytes(&mut seed);
+            seed
+        })
+        .collect::<Vec<_>>();
+
+    items
+        .par_iter()
+        .zip(seeds.into_par_iter())
+        .map(|(item, seed)| f(&mut ChaCha8Rng::from_seed(seed), item))
+        .collect()
+}
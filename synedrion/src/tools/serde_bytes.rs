@@ -3,6 +3,7 @@
 //! TODO (#83): make a separate crate.
 
 use alloc::format;
+use alloc::vec::Vec;
 use core::any::type_name;
 use core::fmt;
 use core::marker::PhantomData;
@@ -10,6 +11,9 @@ use core::marker::PhantomData;
 use base64::{engine::general_purpose, Engine as _};
 use serde::{de, Deserializer, Serializer};
 
+/// The maximum number of bytes preallocated when deserializing a sequence of bytes.
+const MAX_PREALLOCATED_LEN: usize = 4096;
+
 enum Encoding {
     /// Use base64 representation for byte arrays.
     Base64,
@@ -100,6 +104,20 @@ where
     {
         T::try_from(v).map_err(de::Error::custom)
     }
+
+    // Some binary formats do not have a dedicated bytestring type
+    // and encode bytes as a sequence of integers.
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        // The size hint comes from the untrusted input, so it is only used up to a limit.
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(MAX_PREALLOCATED_LEN));
+        while let Some(byte) = seq.next_element::<u8>()? {
+            bytes.push(byte);
+        }
+        T::try_from(&bytes).map_err(de::Error::custom)
+    }
 }
 
 /// A helper function that will serialize a byte array efficiently
@@ -181,7 +199,7 @@ pub(crate) mod as_hex {
 
 pub(crate) mod as_base64 {
     //! A module containing serialization and deserialization function
-    //! that use hex (`0x`-prefixed) representation for bytestrings.
+    //! that use base64 representation for bytestrings in human-readable formats.
     //!
     //! To be used in `[serde(with)]` field attribute.
 
@@ -208,3 +226,53 @@ pub(crate) mod as_base64 {
         deserialize_with_encoding(deserializer, Encoding::Base64)
     }
 }
+
+pub(crate) mod as_base64_with_header {
+    //! A module containing serialization and deserialization functions
+    //! that prefix bytestrings in binary formats with a compression header
+    //! (see [`crate::tools::compression`]),
+    //! and use uncompressed base64 representation in human-readable formats.
+    //!
+    //! Since whether to compress the bytes is chosen at runtime,
+    //! these are to be called from manual `Serialize`/`Deserialize` impls.
+
+    use alloc::boxed::Box;
+    use core::convert::Infallible;
+
+    use super::*;
+    use crate::tools::compression::{decode, encode};
+
+    /// Serialize bytes using `base64` encoding if the target format is human-readable,
+    /// and the bytes with the compression header (compressed if `compress` is set) otherwise.
+    pub(crate) fn serialize<S>(
+        bytes: &[u8],
+        compress: bool,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            serialize_with_encoding(&bytes, serializer, Encoding::Base64)
+        } else {
+            serializer.serialize_bytes(&encode(bytes, compress))
+        }
+    }
+
+    /// Deserialize bytes assuming `base64` encoding if the source format is human-readable,
+    /// and the bytes with the compression header otherwise.
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Box<[u8]>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserialize_with_encoding::<Box<[u8]>, Infallible, D>(deserializer, Encoding::Base64)
+        } else {
+            let encoded = deserializer.deserialize_bytes(BytesVisitor::<Box<[u8]>, Infallible>(
+                PhantomData,
+                PhantomData,
+            ))?;
+            decode(&encoded).map(Into::into).map_err(de::Error::custom)
+        }
+    }
+}
@@ -5,18 +5,20 @@ use core::ops::{Add, Mul};
 use rand_core::CryptoRngCore;
 use serde::{Deserialize, Serialize};
 
-use crate::curve::{Point, Scalar};
+use crate::curve::{Curve, Point, Scalar};
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
-pub struct ShareId(Scalar);
+#[serde(bound(serialize = "Scalar<C>: Serialize"))]
+#[serde(bound(deserialize = "Scalar<C>: for <'x> Deserialize<'x>"))]
+pub struct ShareId<C: Curve>(Scalar<C>);
 
-impl ShareId {
+impl<C: Curve> ShareId<C> {
     pub fn new(idx: usize) -> Self {
         Self(Scalar::from(idx))
     }
 }
 
-pub(crate) fn shamir_evaluation_points(num_shares: usize) -> Vec<ShareId> {
+pub(crate) fn shamir_evaluation_points<C: Curve>(num_shares: usize) -> Vec<ShareId<C>> {
     // For now we are hardcoding the points to be 1, 2, ..., n.
     // Potentially we can derive them from Session ID.
     (1..=u32::try_from(num_shares).expect("The number of shares cannot be over 2^32-1"))
@@ -24,9 +26,9 @@ pub(crate) fn shamir_evaluation_points(num_shares: usize) -> Vec<ShareId> {
         .collect()
 }
 
-fn evaluate_polynomial<T>(coeffs: &[T], x: &Scalar) -> T
+fn evaluate_polynomial<C: Curve, T>(coeffs: &[T], x: &Scalar<C>) -> T
 where
-    T: Copy + Add<T, Output = T> + for<'a> Mul<&'a Scalar, Output = T>,
+    T: Copy + Add<T, Output = T> + for<'a> Mul<&'a Scalar<C>, Output = T>,
 {
     // Evaluate in reverse to save on multiplications.
     // Basically: a0 + a1 x + a2 x^2 + a3 x^3 == (((a3 x) + a2) x + a1) x + a0
@@ -37,10 +39,10 @@ where
     res
 }
 
-pub(crate) struct Polynomial(Vec<Scalar>);
+pub(crate) struct Polynomial<C: Curve>(Vec<Scalar<C>>);
 
-impl Polynomial {
-    pub fn random(rng: &mut impl CryptoRngCore, coeff0: &Scalar, degree: usize) -> Self {
+impl<C: Curve> Polynomial<C> {
+    pub fn random(rng: &mut impl CryptoRngCore, coeff0: &Scalar<C>, degree: usize) -> Self {
         let mut coeffs = Vec::with_capacity(degree);
         coeffs.push(*coeff0);
         for _ in 1..degree {
@@ -49,11 +51,11 @@ impl Polynomial {
         Self(coeffs)
     }
 
-    pub fn evaluate(&self, x: &ShareId) -> Scalar {
+    pub fn evaluate(&self, x: &ShareId<C>) -> Scalar<C> {
         evaluate_polynomial(&self.0, &x.0)
     }
 
-    pub fn public(&self) -> PublicPolynomial {
+    pub fn public(&self) -> PublicPolynomial<C> {
         PublicPolynomial(
             self.0
                 .iter()
@@ -64,24 +66,26 @@ impl Polynomial {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct PublicPolynomial(Vec<Point>);
+#[serde(bound(serialize = "Point<C>: Serialize"))]
+#[serde(bound(deserialize = "Point<C>: for <'x> Deserialize<'x>"))]
+pub(crate) struct PublicPolynomial<C: Curve>(Vec<Point<C>>);
 
-impl PublicPolynomial {
-    pub fn evaluate(&self, x: &ShareId) -> Point {
+impl<C: Curve> PublicPolynomial<C> {
+    pub fn evaluate(&self, x: &ShareId<C>) -> Point<C> {
         evaluate_polynomial(&self.0, &x.0)
     }
 
-    pub fn coeff0(&self) -> Point {
+    pub fn coeff0(&self) -> Point<C> {
         self.0[0]
     }
 }
 
-pub(crate) fn shamir_split(
+pub(crate) fn shamir_split<C: Curve>(
     rng: &mut impl CryptoRngCore,
-    secret: &Scalar,
+    secret: &Scalar<C>,
     threshold: usize,
-    indices: &[ShareId],
-) -> BTreeMap<ShareId, Scalar> {
+    indices: &[ShareId<C>],
+) -> BTreeMap<ShareId<C>, Scalar<C>> {
     let polynomial = Polynomial::random(rng, secret, threshold);
     indices
         .iter()
@@ -89,19 +93,19 @@ pub(crate) fn shamir_split(
         .collect()
 }
 
-pub(crate) fn interpolation_coeff<'a>(
-    share_ids: impl Iterator<Item = &'a ShareId>,
-    share_id: &ShareId,
-) -> Scalar {
+pub(crate) fn interpolation_coeff<'a, C: Curve>(
+    share_ids: impl Iterator<Item = &'a ShareId<C>>,
+    share_id: &ShareId<C>,
+) -> Scalar<C> {
     share_ids
         .filter(|id| id != &share_id)
         .map(|id| id.0 * (id.0 - share_id.0).invert().unwrap())
         .product()
 }
 
-pub(crate) fn shamir_join_scalars<'a>(
-    pairs: impl Iterator<Item = (&'a ShareId, &'a Scalar)>,
-) -> Scalar {
+pub(crate) fn shamir_join_scalars<'a, C: Curve>(
+    pairs: impl Iterator<Item = (&'a ShareId<C>, &'a Scalar<C>)>,
+) -> Scalar<C> {
     let (share_ids, values): (Vec<_>, Vec<_>) = pairs.map(|(k, v)| (*k, *v)).unzip();
     values
         .iter()
@@ -110,9 +114,9 @@ pub(crate) fn shamir_join_scalars<'a>(
         .sum()
 }
 
-pub(crate) fn shamir_join_points<'a>(
-    pairs: impl Iterator<Item = (&'a ShareId, &'a Point)>,
-) -> Point {
+pub(crate) fn shamir_join_points<'a, C: Curve>(
+    pairs: impl Iterator<Item = (&'a ShareId<C>, &'a Point<C>)>,
+) -> Point<C> {
     let (share_ids, values): (Vec<_>, Vec<_>) = pairs.map(|(k, v)| (*k, *v)).unzip();
     values
         .iter()
@@ -126,7 +130,9 @@ mod tests {
     use rand_core::OsRng;
 
     use super::{evaluate_polynomial, shamir_evaluation_points, shamir_join_scalars, shamir_split};
-    use crate::curve::Scalar;
+    use crate::curve::Secp256k1;
+
+    type Scalar = crate::curve::Scalar<Secp256k1>;
 
     #[test]
     fn evaluate() {
@@ -146,7 +152,7 @@ mod tests {
         let threshold = 3;
         let num_shares = 5;
         let secret = Scalar::random(&mut OsRng);
-        let points = shamir_evaluation_points(num_shares);
+        let points = shamir_evaluation_points::<Secp256k1>(num_shares);
         let mut shares = shamir_split(&mut OsRng, &secret, threshold, &points);
 
         shares.remove(&points[0]);
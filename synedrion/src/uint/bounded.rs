@@ -27,7 +27,7 @@ where
 {
     fn from(val: Bounded<T>) -> Self {
         let repr = val.as_ref().to_be_bytes();
-        let bound_bytes = (val.bound() + 7) / 8;
+        let bound_bytes = val.bound().div_ceil(8);
         let slice = &repr.as_ref()[(repr.as_ref().len() - bound_bytes as usize)..];
         Self {
             bound: val.bound(),
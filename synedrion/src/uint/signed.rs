@@ -297,18 +297,35 @@ where
 
     /// Returns a random value in range `[-2^bound_bits, 2^bound_bits]`.
     ///
+    /// Panics if `bound_bits` does not fit in `T` (see [`Self::try_random_bounded_bits`]).
+    ///
     /// Note: variable time in `bound_bits`.
     pub fn random_bounded_bits(rng: &mut impl CryptoRngCore, bound_bits: usize) -> Self {
-        assert!(
-            bound_bits < (T::BITS - 1) as usize,
-            "Out of bounds: bound_bits was {} but must be smaller than {}",
-            bound_bits,
-            T::BITS - 1
-        );
+        Self::try_random_bounded_bits(rng, bound_bits).unwrap_or_else(|| {
+            panic!(
+                "Out of bounds: bound_bits was {} but must be smaller than {}",
+                bound_bits,
+                T::BITS - 1
+            )
+        })
+    }
+
+    /// Returns a random value in range `[-2^bound_bits, 2^bound_bits]`,
+    /// or `None` if `bound_bits` is too large for the result to fit in `T`
+    /// (one bit of `T` is reserved for the sign, so `bound_bits` must be smaller than `T::BITS - 1`).
+    ///
+    /// Note: variable time in `bound_bits`.
+    pub fn try_random_bounded_bits(
+        rng: &mut impl CryptoRngCore,
+        bound_bits: usize,
+    ) -> Option<Self> {
+        if bound_bits >= (T::BITS - 1) as usize {
+            return None;
+        }
 
         let bound =
             NonZero::new(T::one() << bound_bits).expect("Checked bound_bits just above; qed");
-        Self::random_bounded(rng, &bound)
+        Some(Self::random_bounded(rng, &bound))
     }
 }
 
@@ -332,6 +349,11 @@ where
 
 impl<T> secrecy::CloneableSecret for Signed<T> where T: Clone + Integer + Zeroize {}
 
+impl<T> secrecy::SerializableSecret for Signed<T> where
+    T: Integer + Encoding + crypto_bigint::Bounded + ConditionallySelectable
+{
+}
+
 impl<T> From<Signed<T>> for SecretBox<Signed<T>>
 where
     T: Integer + Zeroize,
@@ -372,7 +394,7 @@ where
     }
 }
 
-impl<'a, T> Neg for &'a Signed<T>
+impl<T> Neg for &Signed<T>
 where
     T: Integer + crypto_bigint::Bounded + ConditionallySelectable + Encoding,
 {
@@ -769,6 +791,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn try_random_bounded_bits_rejects_too_large_bounds() {
+        let mut rng = ChaCha8Rng::seed_from_u64(SEED);
+        // One bit is reserved for the sign
+        let max_bits = (U1024::BITS - 2) as usize;
+        assert!(Signed::<U1024>::try_random_bounded_bits(&mut rng, max_bits).is_some());
+        assert!(Signed::<U1024>::try_random_bounded_bits(&mut rng, max_bits + 1).is_none());
+        assert!(Signed::<U1024>::try_random_bounded_bits(&mut rng, 2 * max_bits).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Out of bounds: bound_bits was 1024 but must be smaller than 1023")]
+    fn random_bounded_bits_panics_on_too_large_bounds() {
+        let mut rng = ChaCha8Rng::seed_from_u64(SEED);
+        let _ = Signed::<U1024>::random_bounded_bits(&mut rng, U1024::BITS as usize);
+    }
+
     #[test]
     fn signed_with_low_bounds() {
         // a 2 bit bound means numbers must be smaller or equal to 3
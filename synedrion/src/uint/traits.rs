@@ -1,6 +1,5 @@
 use crypto_bigint::{
-    modular::MontyForm, nlimbs, Encoding, Integer, RandomMod, Uint, Zero, U1024, U2048, U4096,
-    U512, U8192,
+    modular::MontyForm, Encoding, Integer, RandomMod, Uint, Zero, U1024, U2048, U4096, U512, U8192,
 };
 
 pub(crate) const fn upcast_uint<const N1: usize, const N2: usize>(value: Uint<N1>) -> Uint<N2> {
@@ -116,10 +115,10 @@ impl HasWide for U4096 {
     }
 }
 
-pub type U512Mod = MontyForm<{ nlimbs!(512) }>;
-pub type U1024Mod = MontyForm<{ nlimbs!(1024) }>;
-pub type U2048Mod = MontyForm<{ nlimbs!(2048) }>;
-pub type U4096Mod = MontyForm<{ nlimbs!(4096) }>;
+pub type U512Mod = MontyForm<{ U512::LIMBS }>;
+pub type U1024Mod = MontyForm<{ U1024::LIMBS }>;
+pub type U2048Mod = MontyForm<{ U2048::LIMBS }>;
+pub type U4096Mod = MontyForm<{ U4096::LIMBS }>;
 
 impl ToMontgomery for U512 {}
 impl ToMontgomery for U1024 {}
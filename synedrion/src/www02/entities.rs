@@ -2,7 +2,6 @@ use alloc::boxed::Box;
 use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::vec::Vec;
 use core::fmt::Debug;
-use core::marker::PhantomData;
 
 use bip32::{DerivationPath, PrivateKey, PrivateKeyBytes, PublicKey};
 use k256::ecdsa::{SigningKey, VerifyingKey};
@@ -11,7 +10,7 @@ use secrecy::{ExposeSecret, SecretBox};
 use serde::{Deserialize, Serialize};
 
 use crate::cggmp21::{KeyShare, SchemeParams};
-use crate::curve::{Point, Scalar};
+use crate::curve::{Curve, Point, Scalar, Secp256k1};
 use crate::tools::hashing::{Chain, FofHasher};
 use crate::tools::sss::{
     interpolation_coeff, shamir_evaluation_points, shamir_join_points, shamir_split, ShareId,
@@ -23,16 +22,14 @@ use crate::tools::sss::{
 pub struct ThresholdKeyShare<P: SchemeParams, I: Ord> {
     pub(crate) owner: I,
     pub(crate) threshold: u32,
-    pub(crate) secret_share: SecretBox<Scalar>,
-    pub(crate) share_ids: BTreeMap<I, ShareId>,
-    pub(crate) public_shares: BTreeMap<I, Point>,
-    // TODO (#27): this won't be needed when Scalar/Point are a part of `P`
-    pub(crate) phantom: PhantomData<P>,
+    pub(crate) secret_share: SecretBox<Scalar<P::Curve>>,
+    pub(crate) share_ids: BTreeMap<I, ShareId<P::Curve>>,
+    pub(crate) public_shares: BTreeMap<I, Point<P::Curve>>,
 }
 
 impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> ThresholdKeyShare<P, I> {
     /// Threshold share ID.
-    pub fn share_id(&self) -> ShareId {
+    pub fn share_id(&self) -> ShareId<P::Curve> {
         self.share_ids[&self.owner]
     }
 
@@ -46,13 +43,13 @@ impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> ThresholdKeyShare<P, I
         rng: &mut impl CryptoRngCore,
         ids: &BTreeSet<I>,
         threshold: usize,
-        signing_key: Option<&SigningKey>,
+        signing_key: Option<&<P::Curve as Curve>::SigningKey>,
     ) -> BTreeMap<I, Self> {
         debug_assert!(threshold <= ids.len()); // TODO (#68): make the method fallible
 
         let secret = match signing_key {
-            None => Scalar::random(rng),
-            Some(sk) => Scalar::from(sk.as_nonzero_scalar()),
+            None => Scalar::<P::Curve>::random(rng),
+            Some(sk) => Scalar::<P::Curve>::from_signing_key(sk),
         };
 
         let share_ids = shamir_evaluation_points(ids.len());
@@ -78,14 +75,13 @@ impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> ThresholdKeyShare<P, I
                         secret_share: SecretBox::new(Box::new(secret_shares[&share_ids[id]])),
                         share_ids: share_ids.clone(),
                         public_shares: public_shares.clone(),
-                        phantom: PhantomData,
                     },
                 )
             })
             .collect()
     }
 
-    pub(crate) fn verifying_key_as_point(&self) -> Point {
+    pub(crate) fn verifying_key_as_point(&self) -> Point<P::Curve> {
         shamir_join_points(
             self.share_ids
                 .iter()
@@ -95,7 +91,7 @@ impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> ThresholdKeyShare<P, I
     }
 
     /// Return the verifying key to which this set of shares corresponds.
-    pub fn verifying_key(&self) -> VerifyingKey {
+    pub fn verifying_key(&self) -> <P::Curve as Curve>::VerifyingKey {
         // TODO (#5): need to ensure on creation of the share that the verifying key actually exists
         // (that is, the sum of public keys does not evaluate to the infinity point)
         self.verifying_key_as_point().to_verifying_key().unwrap()
@@ -132,7 +128,6 @@ impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> ThresholdKeyShare<P, I
             owner: self.owner.clone(),
             secret_share,
             public_shares,
-            phantom: PhantomData,
         }
     }
 
@@ -169,10 +164,15 @@ impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> ThresholdKeyShare<P, I
             share_ids,
             secret_share,
             public_shares,
-            phantom: PhantomData,
         }
     }
+}
 
+impl<P, I> ThresholdKeyShare<P, I>
+where
+    P: SchemeParams<Curve = Secp256k1>,
+    I: Clone + Ord + PartialEq + Debug,
+{
     /// Deterministically derives a child share using BIP-32 standard.
     pub fn derive_bip32(&self, derivation_path: &DerivationPath) -> Result<Self, bip32::Error> {
         let tweaks = derive_tweaks(self.verifying_key(), derivation_path)?;
@@ -183,7 +183,7 @@ impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> ThresholdKeyShare<P, I
             .expose_secret()
             .to_signing_key()
             .ok_or(bip32::Error::Crypto)?;
-        let secret_share = SecretBox::new(Box::new(Scalar::from_signing_key(
+        let secret_share = SecretBox::new(Box::new(Scalar::<Secp256k1>::from_signing_key(
             &apply_tweaks_private(secret_share, &tweaks)?,
         )));
 
@@ -195,7 +195,7 @@ impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> ThresholdKeyShare<P, I
                 // Will fail here if the final or one of the intermediate points is an identity
                 point.to_verifying_key().ok_or(bip32::Error::Crypto)
                     .and_then(|vkey| apply_tweaks_public(vkey, &tweaks))
-                    .map(|vkey| (id, Point::from_verifying_key(&vkey))))
+                    .map(|vkey| (id, Point::<Secp256k1>::from_verifying_key(&vkey))))
             .collect::<Result<_, _>>()?;
 
         Ok(Self {
@@ -204,7 +204,6 @@ impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> ThresholdKeyShare<P, I
             share_ids: self.share_ids.clone(),
             secret_share,
             public_shares,
-            phantom: PhantomData,
         })
     }
 }
@@ -218,8 +217,10 @@ pub trait DeriveChildKey {
     ) -> Result<VerifyingKey, bip32::Error>;
 }
 
-impl<P: SchemeParams, I: Clone + Ord + PartialEq + Debug> DeriveChildKey
-    for ThresholdKeyShare<P, I>
+impl<P, I> DeriveChildKey for ThresholdKeyShare<P, I>
+where
+    P: SchemeParams<Curve = Secp256k1>,
+    I: Clone + Ord + PartialEq + Debug,
 {
     fn derive_verifying_key_bip32(
         &self,
@@ -249,7 +250,7 @@ fn derive_tweaks(
 
     // Note: deriving the initial chain code from public information. Is this okay?
     let mut chain_code = FofHasher::new_with_dst(b"chain-code-derivation")
-        .chain_bytes(&Point::from_verifying_key(&public_key).to_compressed_array())
+        .chain_bytes(&Point::<Secp256k1>::from_verifying_key(&public_key).to_compressed_array())
         .finalize()
         .0;
 
@@ -289,15 +290,20 @@ fn apply_tweaks_private(
 #[cfg(test)]
 mod tests {
     use alloc::collections::BTreeSet;
+    use alloc::vec::Vec;
 
-    use k256::ecdsa::SigningKey;
+    use k256::ecdsa::{signature::hazmat::PrehashVerifier, Signature, SigningKey, VerifyingKey};
     use rand_core::OsRng;
     use secrecy::ExposeSecret;
 
     use super::ThresholdKeyShare;
-    use crate::cggmp21::TestParams;
-    use crate::curve::Scalar;
+    use crate::cggmp21::{AuxInfo, TestParams};
+    use crate::curve::Secp256k1;
+    use crate::make_interactive_signing_session;
     use crate::rounds::test_utils::Id;
+    use crate::sessions::{InProcessNetwork, SessionId};
+
+    type Scalar = crate::curve::Scalar<Secp256k1>;
 
     #[test]
     fn threshold_key_share_centralized() {
@@ -325,4 +331,59 @@ mod tests {
         assert_eq!(&nt_share0.verifying_key(), sk.verifying_key());
         assert_eq!(&nt_share1.verifying_key(), sk.verifying_key());
     }
+
+    #[test]
+    fn signing_with_a_quorum() {
+        let sk = SigningKey::random(&mut OsRng);
+        let signers = (0..3)
+            .map(|_| SigningKey::random(&mut OsRng))
+            .collect::<Vec<_>>();
+        let ids = signers
+            .iter()
+            .map(|signer| *signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+
+        let shares = ThresholdKeyShare::<TestParams, VerifyingKey>::new_centralized(
+            &mut OsRng,
+            &ids,
+            2,
+            Some(&sk),
+        );
+
+        // Any two of the three parties can sign.
+        let quorum_signers = [&signers[0], &signers[2]];
+        let quorum = quorum_signers
+            .iter()
+            .map(|signer| *signer.verifying_key())
+            .collect::<BTreeSet<_>>();
+        let aux_infos = AuxInfo::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &quorum);
+
+        let message = [7u8; 32];
+        let session_id = SessionId::from_seed(b"threshold signing");
+        let sessions = quorum_signers
+            .iter()
+            .map(|signer| {
+                let id = signer.verifying_key();
+                let key_share = shares[id].to_key_share(&quorum);
+                make_interactive_signing_session::<_, Signature, _, _>(
+                    &mut OsRng,
+                    session_id,
+                    (*signer).clone(),
+                    &quorum,
+                    &key_share,
+                    &aux_infos[id],
+                    &message,
+                )
+                .unwrap()
+            })
+            .collect();
+        let signatures = InProcessNetwork::new().run(&mut OsRng, sessions).unwrap();
+
+        for signature in signatures {
+            let (signature, _recovery_id) = signature.to_backend();
+            sk.verifying_key()
+                .verify_prehash(&message, &signature)
+                .unwrap();
+        }
+    }
 }
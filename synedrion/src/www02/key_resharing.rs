@@ -8,16 +8,16 @@
 use alloc::boxed::Box;
 use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::vec::Vec;
+use core::convert::Infallible;
 use core::fmt::Debug;
 use core::marker::PhantomData;
 
-use k256::ecdsa::VerifyingKey;
 use rand_core::CryptoRngCore;
 use secrecy::{ExposeSecret, SecretBox};
 use serde::{Deserialize, Serialize};
 
 use super::ThresholdKeyShare;
-use crate::curve::{Point, Scalar};
+use crate::curve::{Curve, Point, Scalar};
 use crate::rounds::{
     FinalizableToResult, FinalizationRequirement, FinalizeError, FirstRound, InitError,
     ProtocolResult, Round, ToResult,
@@ -36,6 +36,7 @@ impl<P: SchemeParams, I: Ord + Debug> ProtocolResult for KeyResharingResult<P, I
     type Success = Option<ThresholdKeyShare<P, I>>;
     type ProvableError = KeyResharingError;
     type CorrectnessProof = ();
+    type RetryReason = Infallible;
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -53,9 +54,9 @@ pub struct OldHolder<P: SchemeParams, I: Ord> {
 
 /// New share data.
 #[derive(Clone)]
-pub struct NewHolder<I: Ord> {
+pub struct NewHolder<P: SchemeParams, I: Ord> {
     /// The verifying key the old shares add up to.
-    pub verifying_key: VerifyingKey,
+    pub verifying_key: <P::Curve as Curve>::VerifyingKey,
     /// The old threshold.
     pub old_threshold: usize,
     /// Some of the holders of the old shares (at least `old_threshold` of them).
@@ -68,32 +69,31 @@ pub struct KeyResharingInputs<P: SchemeParams, I: Ord> {
     /// Old share data if the node holds it, or `None`.
     pub old_holder: Option<OldHolder<P, I>>,
     /// New share data if the node is one of the new holders, or `None`.
-    pub new_holder: Option<NewHolder<I>>,
+    pub new_holder: Option<NewHolder<P, I>>,
     /// The new holders of the shares.
     pub new_holders: BTreeSet<I>,
     /// The new threshold.
     pub new_threshold: usize,
 }
 
-struct OldHolderData {
-    share_id: ShareId,
-    polynomial: Polynomial,
-    public_polynomial: PublicPolynomial,
+struct OldHolderData<C: Curve> {
+    share_id: ShareId<C>,
+    polynomial: Polynomial<C>,
+    public_polynomial: PublicPolynomial<C>,
 }
 
-struct NewHolderData<I: Ord> {
-    inputs: NewHolder<I>,
+struct NewHolderData<P: SchemeParams, I: Ord> {
+    inputs: NewHolder<P, I>,
 }
 
 pub struct Round1<P: SchemeParams, I: Ord> {
-    old_holder: Option<OldHolderData>,
-    new_holder: Option<NewHolderData<I>>,
-    new_share_ids: BTreeMap<I, ShareId>,
+    old_holder: Option<OldHolderData<P::Curve>>,
+    new_holder: Option<NewHolderData<P, I>>,
+    new_share_ids: BTreeMap<I, ShareId<P::Curve>>,
     new_threshold: usize,
     other_ids: BTreeSet<I>,
     my_id: I,
     message_destinations: BTreeSet<I>,
-    phantom: PhantomData<P>,
 }
 
 impl<P: SchemeParams, I: Clone + Ord + Debug> FirstRound<I> for Round1<P, I> {
@@ -156,26 +156,29 @@ impl<P: SchemeParams, I: Clone + Ord + Debug> FirstRound<I> for Round1<P, I> {
             other_ids,
             my_id,
             message_destinations,
-            phantom: PhantomData,
         })
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Round1BroadcastMessage {
-    public_polynomial: PublicPolynomial,
-    old_share_id: ShareId,
+#[serde(bound(serialize = "Point<C>: Serialize"))]
+#[serde(bound(deserialize = "Point<C>: for <'x> Deserialize<'x>"))]
+pub struct Round1BroadcastMessage<C: Curve> {
+    public_polynomial: PublicPolynomial<C>,
+    old_share_id: ShareId<C>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Round1DirectMessage {
-    subshare: Scalar,
+#[serde(bound(serialize = "Scalar<C>: Serialize"))]
+#[serde(bound(deserialize = "Scalar<C>: for <'x> Deserialize<'x>"))]
+pub struct Round1DirectMessage<C: Curve> {
+    subshare: Scalar<C>,
 }
 
-pub struct Round1Payload {
-    subshare: Scalar,
-    public_polynomial: PublicPolynomial,
-    old_share_id: ShareId,
+pub struct Round1Payload<C: Curve> {
+    subshare: Scalar<C>,
+    public_polynomial: PublicPolynomial<C>,
+    old_share_id: ShareId<C>,
 }
 
 impl<P: SchemeParams, I: Clone + Ord + Debug> Round<I> for Round1<P, I> {
@@ -193,9 +196,9 @@ impl<P: SchemeParams, I: Clone + Ord + Debug> Round<I> for Round1<P, I> {
     }
 
     const REQUIRES_ECHO: bool = true;
-    type BroadcastMessage = Round1BroadcastMessage;
-    type DirectMessage = Round1DirectMessage;
-    type Payload = Round1Payload;
+    type BroadcastMessage = Round1BroadcastMessage<P::Curve>;
+    type DirectMessage = Round1DirectMessage<P::Curve>;
+    type Payload = Round1Payload<P::Curve>;
     type Artifact = ();
 
     fn message_destinations(&self) -> &BTreeSet<I> {
@@ -336,7 +339,7 @@ impl<P: SchemeParams, I: Ord + Clone + Debug> FinalizableToResult<I> for Round1<
                     * interpolation_coeff(old_share_ids.iter(), &payload.old_share_id)
             })
             .sum();
-        if Point::from_verifying_key(&new_holder.inputs.verifying_key) != vkey {
+        if Point::<P::Curve>::from_verifying_key(&new_holder.inputs.verifying_key) != vkey {
             // TODO (#113): this is unattributable.
             // Should we add an enum variant to `FinalizeError`?
             // or take the public shares as an input (assuming the nodes published those previously)
@@ -373,7 +376,6 @@ impl<P: SchemeParams, I: Ord + Clone + Debug> FinalizableToResult<I> for Round1<
             secret_share,
             share_ids: self.new_share_ids,
             public_shares,
-            phantom: PhantomData,
         }))
     }
 }
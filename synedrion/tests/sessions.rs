@@ -7,8 +7,9 @@ use tokio::sync::mpsc;
 use tokio::time::{sleep, Duration};
 
 use synedrion::{
-    make_interactive_signing_session, make_key_gen_session, AuxInfo, FinalizeOutcome, KeyShare,
-    MessageBundle, ProtocolResult, Session, SessionId, TestParams,
+    make_aux_gen_session, make_interactive_signing_session, make_key_gen_session,
+    make_presigning_session, make_signing_session, AuxInfo, FinalizeOutcome, InitError, KeyShare,
+    MessageBundle, ProtocolResult, Session, SessionId, SigningSessionBuilder, TestParams,
 };
 
 type MessageOut = (VerifyingKey, VerifyingKey, MessageBundle<Signature>);
@@ -97,7 +98,7 @@ async fn run_session<Res: ProtocolResult>(
         println!("{key_str}: finalizing the round");
 
         match session.finalize_round(&mut OsRng, accum).unwrap() {
-            FinalizeOutcome::Success(res) => break res,
+            FinalizeOutcome::Success { result, .. } => break result,
             FinalizeOutcome::AnotherRound {
                 session: new_session,
                 cached_messages: new_cached_messages,
@@ -172,7 +173,7 @@ where
     let tx_map = sessions
         .iter()
         .map(|session| session.verifier())
-        .zip(txs.into_iter())
+        .zip(txs)
         .collect();
 
     let dispatcher_task = message_dispatcher(tx_map, dispatcher_rx);
@@ -180,7 +181,7 @@ where
 
     let handles: Vec<tokio::task::JoinHandle<Res::Success>> = rxs
         .into_iter()
-        .zip(sessions.into_iter())
+        .zip(sessions)
         .map(|(rx, session)| {
             let node_task = run_session(dispatcher_tx.clone(), rx, session);
             tokio::spawn(node_task)
@@ -230,6 +231,68 @@ async fn keygen_and_aux() {
     }
 }
 
+#[tokio::test]
+async fn aux_rotation_keeps_key_shares() {
+    let num_parties = 3;
+    let (signers, verifiers) = make_signers(num_parties);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+
+    let key_shares =
+        KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &verifiers_set, None);
+    let old_aux_infos =
+        AuxInfo::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &verifiers_set);
+    let vkey = key_shares[&verifiers[0]].verifying_key();
+
+    // Only the auxiliary data is regenerated.
+    let session_id = SessionId::from_seed(b"aux rotation");
+    let sessions = signers
+        .iter()
+        .map(|signer| {
+            make_aux_gen_session::<TestParams, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signer.clone(),
+                &verifiers_set,
+            )
+            .unwrap()
+        })
+        .collect();
+    let aux_infos = run_nodes(sessions).await;
+
+    let config = bincode::config::standard();
+    for (verifier, aux_info) in verifiers.iter().zip(aux_infos.iter()) {
+        assert_eq!(aux_info.owner(), verifier);
+        assert_ne!(
+            bincode::serde::encode_to_vec(aux_info, config).unwrap(),
+            bincode::serde::encode_to_vec(&old_aux_infos[verifier], config).unwrap()
+        );
+    }
+
+    // The existing key shares can be used with the new auxiliary data.
+    let message = b"abcdefghijklmnopqrstuvwxyz123456";
+    let session_id = SessionId::from_seed(b"signing after aux rotation");
+    let sessions = (0..num_parties)
+        .map(|idx| {
+            make_interactive_signing_session::<_, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signers[idx].clone(),
+                &verifiers_set,
+                &key_shares[&verifiers[idx]],
+                &aux_infos[idx],
+                message,
+            )
+            .unwrap()
+        })
+        .collect();
+
+    let signatures = run_nodes(sessions).await;
+    for signature in signatures {
+        let (sig, _rec_id) = signature.to_backend();
+        vkey.verify_prehash(message, &sig).unwrap();
+    }
+}
+
 #[tokio::test]
 async fn interactive_signing() {
     let num_parties = 3;
@@ -273,3 +336,296 @@ async fn interactive_signing() {
         assert_eq!(recovered_key, vkey);
     }
 }
+
+#[test]
+fn signing_parties_must_match_key_share() {
+    let num_parties = 3;
+    let (signers, verifiers) = make_signers(num_parties);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+
+    // The key shares are held by the parties identified by `verifiers`,
+    // but the shared ECDSA key itself is unrelated to the keys in `signers`.
+    let key_shares =
+        KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &verifiers_set, None);
+    let aux_infos =
+        AuxInfo::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &verifiers_set);
+    assert!(verifiers
+        .iter()
+        .all(|verifier| key_shares[verifier].verifying_key() != *verifier));
+
+    let session_id = SessionId::from_seed(b"abcde");
+    let message = b"abcdefghijklmnopqrstuvwxyz123456";
+
+    // One of the key holders is missing
+    let fewer_verifiers = BTreeSet::from_iter(verifiers[..2].iter().cloned());
+    let result = make_interactive_signing_session::<_, Signature, _, _>(
+        &mut OsRng,
+        session_id,
+        signers[0].clone(),
+        &fewer_verifiers,
+        &key_shares[&verifiers[0]],
+        &aux_infos[&verifiers[0]],
+        message,
+    );
+    let error = result.err().unwrap().to_string();
+    assert!(error.contains(
+        "The number of verifiers (2) does not match the number of parties in the key share (3)"
+    ));
+
+    // The same number of parties, but one of them does not hold a key share
+    let mut other_verifiers = fewer_verifiers.clone();
+    other_verifiers.insert(*SigningKey::random(&mut OsRng).verifying_key());
+    let result = make_interactive_signing_session::<_, Signature, _, _>(
+        &mut OsRng,
+        session_id,
+        signers[0].clone(),
+        &other_verifiers,
+        &key_shares[&verifiers[0]],
+        &aux_infos[&verifiers[0]],
+        message,
+    );
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn interactive_signing_with_builder() {
+    let num_parties = 3;
+    let (signers, verifiers) = make_signers(num_parties);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+
+    let key_shares =
+        KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &verifiers_set, None);
+    let aux_infos =
+        AuxInfo::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &verifiers_set);
+
+    let message = b"abcdefghijklmnopqrstuvwxyz123456";
+
+    // The session ID must be set
+    assert!(SigningSessionBuilder::new(
+        signers[0].clone(),
+        &key_shares[&verifiers[0]],
+        &aux_infos[&verifiers[0]],
+        message,
+    )
+    .build::<Signature>(&mut OsRng)
+    .is_err());
+
+    // The key share must match the expected verifying key, if one is given
+    let wrong_key = *SigningKey::random(&mut OsRng).verifying_key();
+    let result = SigningSessionBuilder::new(
+        signers[0].clone(),
+        &key_shares[&verifiers[0]],
+        &aux_infos[&verifiers[0]],
+        message,
+    )
+    .deterministic_session_id()
+    .expected_verifying_key(&wrong_key)
+    .build::<Signature>(&mut OsRng);
+    assert!(matches!(result, Err(InitError::VerifyingKeyMismatch)));
+
+    let sessions = (0..num_parties)
+        .map(|idx| {
+            SigningSessionBuilder::new(
+                signers[idx].clone(),
+                &key_shares[&verifiers[idx]],
+                &aux_infos[&verifiers[idx]],
+                message,
+            )
+            .with_verifiers(&verifiers_set)
+            .deterministic_session_id()
+            .expected_verifying_key(&key_shares[&verifiers[idx]].verifying_key())
+            .build::<Signature>(&mut OsRng)
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    // All the parties derived the same session ID
+    for session in sessions.iter() {
+        assert_eq!(session.session_id(), sessions[0].session_id());
+    }
+
+    let signatures = run_nodes(sessions).await;
+
+    let vkey = key_shares[&verifiers[0]].verifying_key();
+    for signature in signatures {
+        let (sig, _rec_id) = signature.to_backend();
+        vkey.verify_prehash(message, &sig).unwrap();
+    }
+}
+
+#[tokio::test]
+async fn signing_with_cached_presignatures() {
+    let num_parties = 3;
+    let (signers, verifiers) = make_signers(num_parties);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+
+    let key_shares =
+        KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &verifiers_set, None);
+    let aux_infos =
+        AuxInfo::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &verifiers_set);
+
+    // Create a pool of presignatures before the messages are known.
+    let mut pools = (0..num_parties).map(|_| Vec::new()).collect::<Vec<_>>();
+    for pool_idx in 0..2u8 {
+        let session_id = SessionId::from_seed(&[b'p', pool_idx]);
+        let sessions = (0..num_parties)
+            .map(|idx| {
+                make_presigning_session::<_, Signature, _, _>(
+                    &mut OsRng,
+                    session_id,
+                    signers[idx].clone(),
+                    &verifiers_set,
+                    &key_shares[&verifiers[idx]],
+                    &aux_infos[&verifiers[idx]],
+                )
+                .unwrap()
+            })
+            .collect();
+
+        let presigning_datas = run_nodes(sessions).await;
+        for (pool, data) in pools.iter_mut().zip(presigning_datas.into_iter()) {
+            pool.push(data);
+        }
+    }
+
+    // Each message uses up one presignature from the pool.
+    let vkey = key_shares[&verifiers[0]].verifying_key();
+    for message in [
+        b"abcdefghijklmnopqrstuvwxyz123456",
+        b"0123456789abcdefghijklmnopqrstuv",
+    ] {
+        let session_id = SessionId::from_seed(message);
+        let sessions = (0..num_parties)
+            .map(|idx| {
+                // The presignature is moved into the session, so it cannot be used twice.
+                let presigning = pools[idx].pop().unwrap();
+                make_signing_session::<_, Signature, _, _>(
+                    &mut OsRng,
+                    session_id,
+                    signers[idx].clone(),
+                    &verifiers_set,
+                    &key_shares[&verifiers[idx]],
+                    &aux_infos[&verifiers[idx]],
+                    presigning,
+                    message,
+                )
+                .unwrap()
+            })
+            .collect();
+
+        let signatures = run_nodes(sessions).await;
+        for signature in signatures {
+            let (sig, _rec_id) = signature.to_backend();
+            vkey.verify_prehash(message, &sig).unwrap();
+        }
+    }
+}
+
+#[cfg(feature = "experimental")]
+#[tokio::test]
+async fn nonce_only_presigning() {
+    use synedrion::make_nonce_presigning_session;
+
+    let num_parties = 3;
+    let (signers, verifiers) = make_signers(num_parties);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+
+    let key_shares =
+        KeyShare::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &verifiers_set, None);
+    let aux_infos =
+        AuxInfo::<TestParams, VerifyingKey>::new_centralized(&mut OsRng, &verifiers_set);
+
+    let session_id = SessionId::from_seed(b"nonce");
+    let sessions = (0..num_parties)
+        .map(|idx| {
+            make_nonce_presigning_session::<_, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signers[idx].clone(),
+                &verifiers_set,
+                &key_shares[&verifiers[idx]],
+                &aux_infos[&verifiers[idx]],
+            )
+            .unwrap()
+        })
+        .collect();
+
+    let presigning_datas = run_nodes(sessions).await;
+
+    // All the parties get the same nonce, corresponding to the sum of the ephemeral scalar shares.
+    let nonce_point = presigning_datas[0].experimental_nonce_point();
+    let k = presigning_datas
+        .iter()
+        .map(|data| data.experimental_ephemeral_scalar_share())
+        .sum::<k256::Scalar>();
+    assert_eq!(
+        k256::ProjectivePoint::GENERATOR * k.invert().unwrap(),
+        nonce_point
+    );
+    for data in presigning_datas.iter() {
+        assert_eq!(data.experimental_nonce_point(), nonce_point);
+    }
+
+    // The data cannot be used for signing.
+    let presigning = presigning_datas.into_iter().next().unwrap();
+    let result = make_signing_session::<_, Signature, _, _>(
+        &mut OsRng,
+        SessionId::from_seed(b"signing"),
+        signers[0].clone(),
+        &verifiers_set,
+        &key_shares[&verifiers[0]],
+        &aux_infos[&verifiers[0]],
+        presigning,
+        b"abcdefghijklmnopqrstuvwxyz123456",
+    );
+    assert!(result.is_err());
+}
+
+#[cfg(feature = "p256")]
+#[tokio::test]
+async fn keygen_and_signing_on_p256() {
+    use synedrion::TestParamsP256;
+
+    let num_parties = 3;
+    let (signers, verifiers) = make_signers(num_parties);
+    let verifiers_set = BTreeSet::from_iter(verifiers.iter().cloned());
+
+    let session_id = SessionId::from_seed(b"P-256 keygen");
+    let sessions = signers
+        .iter()
+        .map(|signer| {
+            make_key_gen_session::<TestParamsP256, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signer.clone(),
+                &verifiers_set,
+            )
+            .unwrap()
+        })
+        .collect();
+    let (key_shares, aux_infos): (Vec<_>, Vec<_>) = run_nodes(sessions).await.into_iter().unzip();
+
+    let message = b"abcdefghijklmnopqrstuvwxyz123456";
+    let session_id = SessionId::from_seed(b"P-256 signing");
+    let sessions = (0..num_parties)
+        .map(|idx| {
+            make_interactive_signing_session::<_, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signers[idx].clone(),
+                &verifiers_set,
+                &key_shares[idx],
+                &aux_infos[idx],
+                message,
+            )
+            .unwrap()
+        })
+        .collect();
+    let signatures = run_nodes(sessions).await;
+
+    let vkey: synedrion::p256::ecdsa::VerifyingKey = key_shares[0].verifying_key();
+    for signature in signatures {
+        let (sig, _rec_id) = signature.to_backend();
+        vkey.verify_prehash(message, &sig).unwrap();
+    }
+}
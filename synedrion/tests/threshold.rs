@@ -6,10 +6,12 @@ use rand_core::OsRng;
 use tokio::sync::mpsc;
 use tokio::time::{sleep, Duration};
 
+use synedrion::sessions::WireFormat;
 use synedrion::{
     make_aux_gen_session, make_interactive_signing_session, make_key_init_session,
     make_key_resharing_session, DeriveChildKey, FinalizeOutcome, KeyResharingInputs, MessageBundle,
-    NewHolder, OldHolder, ProtocolResult, Session, SessionId, TestParams, ThresholdKeyShare,
+    NewHolder, OldHolder, ProtocolResult, ResultMeta, Session, SessionId, TestParams,
+    ThresholdKeyShare,
 };
 
 type MessageOut = (VerifyingKey, VerifyingKey, MessageBundle<Signature>);
@@ -23,7 +25,7 @@ async fn run_session<Res: ProtocolResult>(
     tx: mpsc::Sender<MessageOut>,
     rx: mpsc::Receiver<MessageIn>,
     session: Session<Res, Signature, SigningKey, VerifyingKey>,
-) -> Res::Success {
+) -> (Res::Success, ResultMeta<VerifyingKey>) {
     let mut rx = rx;
 
     let mut session = session;
@@ -98,7 +100,7 @@ async fn run_session<Res: ProtocolResult>(
         println!("{key_str}: finalizing the round");
 
         match session.finalize_round(&mut OsRng, accum).unwrap() {
-            FinalizeOutcome::Success(res) => break res,
+            FinalizeOutcome::Success { result, meta } => break (result, meta),
             FinalizeOutcome::AnotherRound {
                 session: new_session,
                 cached_messages: new_cached_messages,
@@ -159,6 +161,20 @@ fn make_signers(num_parties: usize) -> (Vec<SigningKey>, Vec<VerifyingKey>) {
 async fn run_nodes<Res>(
     sessions: Vec<Session<Res, Signature, SigningKey, VerifyingKey>>,
 ) -> Vec<Res::Success>
+where
+    Res: ProtocolResult + Send + 'static,
+    Res::Success: Send,
+{
+    run_nodes_with_meta(sessions)
+        .await
+        .into_iter()
+        .map(|(result, _meta)| result)
+        .collect()
+}
+
+async fn run_nodes_with_meta<Res>(
+    sessions: Vec<Session<Res, Signature, SigningKey, VerifyingKey>>,
+) -> Vec<(Res::Success, ResultMeta<VerifyingKey>)>
 where
     Res: ProtocolResult + Send + 'static,
     Res::Success: Send,
@@ -173,15 +189,15 @@ where
     let tx_map = sessions
         .iter()
         .map(|session| session.verifier())
-        .zip(txs.into_iter())
+        .zip(txs)
         .collect();
 
     let dispatcher_task = message_dispatcher(tx_map, dispatcher_rx);
     let dispatcher = tokio::spawn(dispatcher_task);
 
-    let handles: Vec<tokio::task::JoinHandle<Res::Success>> = rxs
+    let handles: Vec<tokio::task::JoinHandle<(Res::Success, ResultMeta<VerifyingKey>)>> = rxs
         .into_iter()
-        .zip(sessions.into_iter())
+        .zip(sessions)
         .map(|(rx, session)| {
             let node_task = run_session(dispatcher_tx.clone(), rx, session);
             tokio::spawn(node_task)
@@ -337,34 +353,107 @@ async fn full_sequence() {
 
     // For signing, we select `t` parties and these parties:
     // - derive child key shares
-    // - convert their threshold key shares into regular key shares.
+    // - convert their threshold key shares into regular key shares
+    // - restrict their auxiliary data to the selected parties.
 
-    let selected_signers = vec![signers[0].clone(), signers[2].clone(), signers[4].clone()];
-    let selected_parties = BTreeSet::from([verifiers[0], verifiers[2], verifiers[4]]);
-    let selected_key_shares = vec![
+    let message = b"abcdefghijklmnopqrstuvwxyz123456";
+
+    // The first selection includes a party that goes offline before signing.
+    let offline_party = verifiers[4];
+    let first_selection = BTreeSet::from([verifiers[0], verifiers[2], offline_party]);
+    let sessions = [0, 2]
+        .into_iter()
+        .map(|idx| {
+            make_interactive_signing_session::<_, Signature, _, _>(
+                &mut OsRng,
+                session_id,
+                signers[idx].clone(),
+                &first_selection,
+                &new_t_key_shares[idx]
+                    .derive_bip32(&path)
+                    .unwrap()
+                    .to_key_share(&first_selection),
+                &aux_infos[idx].to_subset(&first_selection).unwrap(),
+                message,
+            )
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    // The online parties exchange the messages of the first round,
+    // but cannot finalize it without the offline one.
+    let mut accums = sessions
+        .iter()
+        .map(|session| session.make_accumulator())
+        .collect::<Vec<_>>();
+    let mut messages = Vec::new();
+    for (session, accum) in sessions.iter().zip(accums.iter_mut()) {
+        for destination in session.message_destinations() {
+            let (message, artifact) = session.make_message(&mut OsRng, &destination).unwrap();
+            accum.add_artifact(artifact).unwrap();
+            if destination != offline_party {
+                messages.push((session.verifier(), destination, message));
+            }
+        }
+    }
+    for (from, to, message) in messages {
+        let idx = sessions
+            .iter()
+            .position(|session| session.verifier() == to)
+            .unwrap();
+        let preprocessed = sessions[idx]
+            .preprocess_message(&mut accums[idx], &from, message)
+            .unwrap()
+            .unwrap();
+        let processed = sessions[idx]
+            .process_message(&mut OsRng, preprocessed)
+            .unwrap();
+        accums[idx]
+            .add_processed_message(processed)
+            .unwrap()
+            .unwrap();
+    }
+
+    // Each of them gives up on the offline party, attesting that it did not participate.
+    let missing = BTreeSet::from([offline_party]);
+    for (session, accum) in sessions.into_iter().zip(accums.iter()) {
+        assert!(!session.can_finalize(accum).unwrap());
+        assert_eq!(session.missing_messages(accum).unwrap(), missing);
+
+        let attester = session.verifier();
+        let statement = session
+            .finalize_with_missing(&mut OsRng, accum, &missing)
+            .unwrap();
+        assert_eq!(statement.attester(), &attester);
+        assert_eq!(statement.verify(WireFormat::default()).unwrap(), missing);
+    }
+
+    // Signing is retried with another holder of the key in place of the offline party.
+
+    let selected_signers = [signers[0].clone(), signers[1].clone(), signers[2].clone()];
+    let selected_parties = BTreeSet::from([verifiers[0], verifiers[1], verifiers[2]]);
+    let selected_key_shares = [
         new_t_key_shares[0]
             .derive_bip32(&path)
             .unwrap()
             .to_key_share(&selected_parties),
-        new_t_key_shares[2]
+        new_t_key_shares[1]
             .derive_bip32(&path)
             .unwrap()
             .to_key_share(&selected_parties),
-        new_t_key_shares[4]
+        new_t_key_shares[2]
             .derive_bip32(&path)
             .unwrap()
             .to_key_share(&selected_parties),
     ];
-    let selected_aux_infos = vec![
-        aux_infos[0].clone(),
-        aux_infos[2].clone(),
-        aux_infos[4].clone(),
+    let selected_aux_infos = [
+        aux_infos[0].to_subset(&selected_parties).unwrap(),
+        aux_infos[1].to_subset(&selected_parties).unwrap(),
+        aux_infos[2].to_subset(&selected_parties).unwrap(),
     ];
 
     // Perform signing with the key shares
 
-    let message = b"abcdefghijklmnopqrstuvwxyz123456";
-
     let sessions = (0..3)
         .map(|idx| {
             make_interactive_signing_session::<_, Signature, _, _>(
@@ -381,9 +470,16 @@ async fn full_sequence() {
         .collect();
 
     println!("\nRunning InteractiveSigning\n");
-    let signatures = run_nodes(sessions).await;
+    let outputs = run_nodes_with_meta(sessions).await;
+
+    // Only the selected parties contributed to the signature.
+    for (_signature, meta) in outputs.iter() {
+        assert_eq!(meta.participants(), &selected_parties);
+        assert!(!meta.participants().contains(&offline_party));
+        assert!(!meta.participants().contains(&verifiers[3]));
+    }
 
-    for signature in signatures {
+    for (signature, _meta) in outputs {
         let (sig, rec_id) = signature.to_backend();
 
         // Check that the signature can be verified